@@ -0,0 +1,193 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use super::question_detect::{self, QuestionSignal};
+use super::streaming::StreamEvent;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::manager::SessionManager;
+use crate::session::storage_actor::StorageActor;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// How often the clipboard is checked in watch mode
+const WATCH_INTERVAL_MS: u64 = 1000;
+/// Longer copies are documents, not questions
+const MAX_QUESTION_CHARS: usize = 1000;
+
+/// Last clipboard text seen or written here, so watch mode only reacts to new copies
+static LAST_TEXT: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// Emitted as "clipboard-answer" while the answer to a copied question streams in
+#[derive(Debug, Clone, Serialize)]
+struct ClipboardAnswerEvent {
+    stream_id: String,
+    question: String,
+    /// "start", "token", "end" or "error", as with "quick-ask"
+    kind: &'static str,
+    text: String,
+}
+
+/// Put `text` on the clipboard without watch mode taking it for a question
+pub fn copy_text(app: &AppHandle, text: &str) -> Result<(), VenkyError> {
+    *LAST_TEXT.lock() = Some(text.trim().to_string());
+    app.clipboard()
+        .write_text(text.to_string())
+        .map_err(|e| VenkyError::Other(format!("Failed to copy to the clipboard: {}", e)))
+}
+
+/// Copy a new suggestion when auto-copy is on
+pub fn copy_if_enabled(app: &AppHandle, text: &str) {
+    if !app.state::<ConfigState>().lock().auto_copy_suggestions {
+        return;
+    }
+    if let Err(e) = copy_text(app, text) {
+        log::warn!("{}", e);
+    }
+}
+
+/// Answer a question copied in another app, with the focused session's context if there is
+/// one, streaming it as "clipboard-answer" events
+async fn answer(app: &AppHandle, question: String) -> Result<String, VenkyError> {
+    let cfg = app.state::<ConfigState>().lock().clone();
+    let session = {
+        let state = app.state::<SessionState>();
+        let mgr = state.lock();
+        mgr.focused()
+            .map(|s| (s.id.clone(), s.purpose.as_str(), s.context.clone()))
+    };
+
+    let mut system_prompt = "You are VenkyAI. The user copied this question from a chat or \
+         document and needs a reply they can paste back. Answer it directly, with no \
+         prefixes like 'Answer:'. Be concise (max 3 sentences)."
+        .to_string();
+    if let Some((_, purpose, ref context)) = session {
+        system_prompt.push_str(&format!("\n\nThe user is in a {}.", purpose));
+        if let Some(context) = context {
+            system_prompt.push_str(&format!("\n\n## User's Context:\n{}", context));
+        }
+    }
+    let messages = vec![super::AIMessage {
+        role: "user".to_string(),
+        content: question.clone(),
+    }];
+
+    let stream_id = uuid::Uuid::new_v4().to_string();
+    let emit = |kind: &'static str, text: &str| {
+        let _ = app.emit(
+            "clipboard-answer",
+            ClipboardAnswerEvent {
+                stream_id: stream_id.clone(),
+                question: question.clone(),
+                kind,
+                text: text.to_string(),
+            },
+        );
+    };
+    let on_event = |event: StreamEvent<'_>| match event {
+        StreamEvent::Start => emit("start", ""),
+        StreamEvent::Token(token) => emit("token", token),
+        StreamEvent::End(full) => emit("end", full),
+    };
+    let on_event = crate::events::batch_tokens(app, "clipboard-answer", on_event);
+    let stream = super::streaming::stream_llm_with(cfg, messages, Some(system_prompt), on_event);
+    let result = match session {
+        Some((ref session_id, _, _)) => crate::redaction::for_session(session_id, stream).await,
+        None => stream.await,
+    };
+
+    match result {
+        Ok(answer) => {
+            let answer = answer.trim().to_string();
+            match session {
+                _ if answer.is_empty() => {}
+                // Recording it copies it too, when auto-copy is on
+                Some((ref session_id, _, _)) => {
+                    super::live_engine::record_suggestion(app, session_id, &answer).await
+                }
+                None => copy_if_enabled(app, &answer),
+            }
+            Ok(answer)
+        }
+        Err(e) => {
+            emit("error", e.message());
+            Err(e)
+        }
+    }
+}
+
+/// Whether newly copied text is a question worth answering
+async fn is_question(app: &AppHandle, text: &str) -> bool {
+    if text.chars().count() > MAX_QUESTION_CHARS || crate::capture::privacy::is_paused(app) {
+        return false;
+    }
+    if crate::capture::privacy::check_text(app, "clipboard", text) {
+        return false;
+    }
+    match question_detect::classify(text) {
+        QuestionSignal::Question => true,
+        QuestionSignal::Possible => {
+            let cfg = app.state::<ConfigState>().lock().clone();
+            question_detect::confirm(&cfg, text).await
+        }
+        QuestionSignal::None => false,
+    }
+}
+
+/// In watch mode, answer questions copied in any app
+pub async fn clipboard_watch_loop(app: AppHandle) {
+    let mut interval = tokio::time::interval(Duration::from_millis(WATCH_INTERVAL_MS));
+
+    loop {
+        interval.tick().await;
+
+        if !app.state::<ConfigState>().lock().clipboard_watch {
+            // Whatever is on the clipboard when watching starts isn't a new copy
+            *LAST_TEXT.lock() = None;
+            continue;
+        }
+        let Ok(text) = app.clipboard().read_text() else {
+            continue;
+        };
+        let text = text.trim().to_string();
+        {
+            let mut last = LAST_TEXT.lock();
+            let first_check = last.is_none();
+            if last.as_deref() == Some(text.as_str()) {
+                continue;
+            }
+            *last = Some(text.clone());
+            if first_check || text.is_empty() {
+                continue;
+            }
+        }
+
+        if is_question(&app, &text).await {
+            if let Err(e) = answer(&app, text).await {
+                log::warn!("Clipboard answer failed: {}", e);
+            }
+        }
+    }
+}
+
+/// Copy a suggestion's text and count it as used
+#[tauri::command]
+pub async fn copy_suggestion_to_clipboard(
+    app: AppHandle,
+    storage: tauri::State<'_, StorageActor>,
+    id: String,
+) -> CommandResult<()> {
+    let text = storage
+        .call(move |s| {
+            s.set_suggestion_accepted(&id)?;
+            s.get_suggestion_text(&id)
+        })
+        .await?;
+    copy_text(&app, &text)
+}
@@ -2,24 +2,156 @@ use std::sync::Arc;
 use parking_lot::Mutex;
 use tauri::{AppHandle, Manager, Emitter};
 use regex::Regex;
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 use tokio::time;
+use tokio_util::sync::CancellationToken;
 
-use crate::session::manager::SessionManager;
+use crate::ai::question_detect::{self, QuestionSignal};
+use crate::ai::streaming::StreamEvent;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::manager::{Session, SessionManager, SessionPurpose};
+use crate::session::storage_actor::StorageActor;
 use crate::capture::audio;
 
-pub struct LiveEngine;
+/// The background loops that follow a live meeting
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveLoop {
+    Monitoring,
+    Transcription,
+    Suggestions,
+    Coaching,
+    Sentiment,
+}
+
+/// Owns the live loops so they can be stopped and restarted with new settings
+#[derive(Default)]
+pub struct LiveEngine {
+    cancel: Option<CancellationToken>,
+    loops: Vec<LiveLoop>,
+    started_at: Option<String>,
+    restarts: u32,
+}
+
+pub type LiveEngineState = Arc<Mutex<LiveEngine>>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveEngineStatus {
+    pub running: bool,
+    pub loops: Vec<LiveLoop>,
+    pub started_at: Option<String>,
+    pub restarts: u32,
+}
+
+/// Meeting detection state shared by the monitor loop and `confirm_auto_session`
+#[derive(Debug, Default)]
+pub struct AutoSession {
+    /// Detected meeting waiting for the user to confirm recording
+    pub pending_title: Option<String>,
+    /// Session started from a detected meeting, ended when its window closes
+    pub session_id: Option<String>,
+}
+
+pub type AutoSessionState = Arc<Mutex<AutoSession>>;
 
 impl LiveEngine {
-    pub async fn start_monitoring(app: AppHandle) {
-        let mut interval = tokio::time::interval(Duration::from_secs(5));
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn the loops enabled in the config, stopping any that are already running
+    pub fn start(&mut self, app: &AppHandle) {
+        if self.cancel.is_some() {
+            self.stop();
+            self.restarts += 1;
+        }
+
+        let cfg = app
+            .state::<Arc<Mutex<crate::config::AppConfig>>>()
+            .lock()
+            .clone();
+        let cancel = CancellationToken::new();
+        let mut loops = Vec::new();
+
+        if cfg.meeting_detection {
+            let (app, cancel) = (app.clone(), cancel.clone());
+            tauri::async_runtime::spawn(async move {
+                LiveEngine::start_monitoring(app, cancel).await;
+            });
+            loops.push(LiveLoop::Monitoring);
+        }
+        if cfg.live_transcription {
+            let (app, cancel) = (app.clone(), cancel.clone());
+            tauri::async_runtime::spawn(async move {
+                transcription_loop(app, cancel).await;
+            });
+            loops.push(LiveLoop::Transcription);
+        }
+        if cfg.live_suggestions {
+            let (app, cancel) = (app.clone(), cancel.clone());
+            tauri::async_runtime::spawn(async move {
+                suggestion_loop(app, cancel).await;
+            });
+            loops.push(LiveLoop::Suggestions);
+        }
+        if cfg.live_coaching {
+            let (app, cancel) = (app.clone(), cancel.clone());
+            tauri::async_runtime::spawn(async move {
+                crate::session::coaching::coaching_loop(app, cancel).await;
+            });
+            loops.push(LiveLoop::Coaching);
+        }
+        if cfg.live_sentiment {
+            let (app, cancel) = (app.clone(), cancel.clone());
+            tauri::async_runtime::spawn(async move {
+                crate::session::sentiment::sentiment_loop(app, cancel).await;
+            });
+            loops.push(LiveLoop::Sentiment);
+        }
+
+        log::info!("Live engine started: {:?}", loops);
+        self.cancel = Some(cancel);
+        self.loops = loops;
+        self.started_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+
+    /// Cancel the running loops; each exits at its next tick
+    pub fn stop(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            cancel.cancel();
+        }
+        self.loops.clear();
+        self.started_at = None;
+    }
+
+    pub fn status(&self) -> LiveEngineStatus {
+        LiveEngineStatus {
+            running: self.cancel.is_some(),
+            loops: self.loops.clone(),
+            started_at: self.started_at.clone(),
+            restarts: self.restarts,
+        }
+    }
+
+    pub async fn start_monitoring(app: AppHandle, cancel: CancellationToken) {
+        let secs = app
+            .state::<Arc<Mutex<crate::config::AppConfig>>>()
+            .lock()
+            .monitoring_interval_secs;
+        let mut interval = tokio::time::interval(Duration::from_secs(secs.max(1) as u64));
         // Common meeting window titles
         let meeting_regex = Regex::new(r"(?i)(Meet -|Zoom Meeting|Microsoft Teams|Webex|GoToMeeting)").unwrap();
         let mut last_detected_title: Option<String> = None;
+        // When the auto-started session's meeting window was last seen missing
+        let mut window_gone_since: Option<Instant> = None;
 
         loop {
-            interval.tick().await;
-            
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
             let windows = xcap::Window::all().unwrap_or_default();
             let mut current_detected_title: Option<String> = None;
 
@@ -37,56 +169,252 @@ impl LiveEngine {
                 if last_detected_title.as_ref() != Some(title) {
                     log::info!("Meeting detected: {}", title);
                     let _ = app.emit("meeting-detected", title.clone());
+                    crate::notifications::meeting_detected(&app, title);
                     last_detected_title = Some(title.clone());
                 }
             } else {
                 last_detected_title = None;
             }
 
+            let auto_state = app.state::<AutoSessionState>();
             let session_manager = app.state::<Arc<Mutex<SessionManager>>>();
             let should_start = {
                 let mgr = session_manager.lock();
-                mgr.current_session.is_none()
+                let mut auto = auto_state.lock();
+                // Forget an auto-started session the user already ended by hand
+                if let Some(ref id) = auto.session_id {
+                    if !mgr.sessions.contains_key(id) {
+                        auto.session_id = None;
+                        window_gone_since = None;
+                    }
+                }
+                if current_detected_title.is_none() {
+                    auto.pending_title = None;
+                }
+                mgr.sessions.is_empty()
             };
 
+            let auto_session_id = auto_state.lock().session_id.clone();
+            if let Some(id) = auto_session_id {
+                if current_detected_title.is_some() {
+                    window_gone_since = None;
+                } else {
+                    let gone_since = *window_gone_since.get_or_insert_with(Instant::now);
+                    let grace = app
+                        .state::<Arc<Mutex<crate::config::AppConfig>>>()
+                        .lock()
+                        .meeting_end_grace_secs;
+                    if gone_since.elapsed() >= Duration::from_secs(grace as u64) {
+                        auto_state.lock().session_id = None;
+                        window_gone_since = None;
+                        Self::auto_end_session(&app, &id);
+                    }
+                }
+            }
+
             if let Some(title) = current_detected_title {
-                if should_start {
-                    log::info!("Meeting detected: {}. Auto-starting session and audio capture.", title);
-                    
-                    // Create session - This also acquires the lock, so we must not hold it here!
-                    if let Ok(session) = crate::session::manager::create_session(app.state(), title.clone(), "meeting".to_string(), None) {
-                        let _ = app.emit("session-auto-started", session);
-                        
-                        // Start system audio capture (hearing others)
-                        let _ = audio::start_system_audio_capture();
-                        // Start mic capture (hearing you)
-                        let _ = audio::start_audio_capture(app.state());
+                let (auto_start, confirm) = {
+                    let cfg = app.state::<Arc<Mutex<crate::config::AppConfig>>>();
+                    let cfg = cfg.lock();
+                    (cfg.auto_start_session, cfg.confirm_auto_start)
+                };
+                if should_start && auto_start {
+                    if confirm {
+                        // Ask once per detected meeting; recording waits for confirm_auto_session
+                        let mut auto = auto_state.lock();
+                        if auto.pending_title.as_ref() != Some(&title) {
+                            log::info!("Meeting detected: {}. Asking before recording.", title);
+                            auto.pending_title = Some(title.clone());
+                            let _ = app.emit("auto-session-prompt", title);
+                        }
+                    } else {
+                        log::info!(
+                            "Meeting detected: {}. Auto-starting session and audio capture.",
+                            title
+                        );
+                        if Self::start_auto_session(&app, &title).is_ok() {
+                            window_gone_since = None;
+                        }
                     }
                 }
             }
         }
     }
+
+    /// Start a session for a detected meeting, with the audio capture the user opted into
+    fn start_auto_session(app: &AppHandle, title: &str) -> Result<Session, VenkyError> {
+        // Create session - This also acquires the lock, so we must not hold it here!
+        let mut session = crate::session::manager::create_session(
+            app.clone(),
+            app.state(),
+            title.to_string(),
+            SessionPurpose::Meeting,
+            None,
+            None,
+        )?;
+        session.meeting_window = Some(title.to_string());
+        if let Some(live) = app
+            .state::<Arc<Mutex<SessionManager>>>()
+            .lock()
+            .sessions
+            .get_mut(&session.id)
+        {
+            live.meeting_window = Some(title.to_string());
+        }
+        // Queued behind the row `create_session` saved
+        let (id, window) = (session.id.clone(), title.to_string());
+        app.state::<StorageActor>().send(move |s| {
+            if let Err(e) = s.set_session_meeting_window(&id, &window) {
+                log::error!("Failed to save meeting window of {}: {}", id, e);
+            }
+        });
+        {
+            let mut auto = app.state::<AutoSessionState>().inner().lock();
+            auto.session_id = Some(session.id.clone());
+            auto.pending_title = None;
+        }
+        let _ = app.emit("session-auto-started", session.clone());
+        crate::notifications::session_auto_started(app, &session.title);
+        if title.to_lowercase().contains("zoom") {
+            crate::integrations::zoom::enrich_if_connected(app, &session.id);
+        }
+
+        start_auto_capture(app, Some(title));
+        Ok(session)
+    }
+
+    /// End a session whose meeting window went away and summarize it. Ending the last
+    /// session stops audio capture.
+    fn auto_end_session(app: &AppHandle, session_id: &str) {
+        log::info!("Meeting window closed. Auto-ending session {}.", session_id);
+
+        let app = app.clone();
+        let session_id = session_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            let ended =
+                match crate::session::manager::end_session(app.clone(), Some(session_id.clone()))
+                    .await
+                {
+                    Ok(session) => session,
+                    Err(e) => {
+                        log::error!("Failed to auto-end session {}: {}", session_id, e);
+                        return;
+                    }
+                };
+
+            let _ = app.emit("session-auto-ended", &ended);
+
+            if let Err(e) = crate::session::summary::summarize_session(&app, &ended.id, None).await
+            {
+                log::warn!("Summary for auto-ended session {} failed: {}", ended.id, e);
+            }
+        });
+    }
+}
+
+/// Start recording the meeting announced by "auto-session-prompt"
+#[tauri::command]
+pub fn confirm_auto_session(
+    app: AppHandle,
+    auto_state: tauri::State<'_, AutoSessionState>,
+) -> CommandResult<Session> {
+    let title = auto_state
+        .lock()
+        .pending_title
+        .clone()
+        .ok_or_else(|| {
+            VenkyError::Other("No detected meeting is waiting to be recorded".to_string())
+        })?;
+    LiveEngine::start_auto_session(&app, &title)
 }
 
-pub async fn transcription_loop(app: AppHandle) {
-    let mut interval = time::interval(Duration::from_millis(1500)); // Reduced from 4s to 1.5s
+/// Start the audio capture the user opted into for automatically started sessions
+pub fn start_auto_capture(app: &AppHandle, window_title: Option<&str>) {
+    let (start_mic, start_system) = {
+        let cfg = app.state::<Arc<Mutex<crate::config::AppConfig>>>();
+        let cfg = cfg.lock();
+        (cfg.auto_start_mic, cfg.auto_start_system_audio)
+    };
+    if !start_mic && !start_system {
+        return;
+    }
+    {
+        let consent = app.state::<crate::session::consent::ConsentState>();
+        let mut gate = consent.lock();
+        if gate.check().is_err() {
+            // Started by `confirm_consent` instead
+            gate.defer_capture(window_title);
+            return;
+        }
+    }
+
+    if start_system {
+        // Start system audio capture (hearing others), scoped to the meeting window
+        // so music and notification sounds stay out of the transcript
+        let target = window_title.map(str::to_string);
+        if target.is_none()
+            || audio::start_system_audio_capture(app.state(), app.state(), app.state(), target)
+                .is_err()
+        {
+            let _ = audio::start_system_audio_capture(app.state(), app.state(), app.state(), None);
+        }
+    }
+    if start_mic {
+        // Start mic capture (hearing you)
+        let _ = audio::start_audio_capture(app.state(), app.state(), app.state());
+    }
+}
+
+/// Restart the live engine so config changes take effect
+pub fn restart(app: &AppHandle) {
+    app.state::<LiveEngineState>().lock().start(app);
+}
+
+#[tauri::command]
+pub fn get_live_engine_status(engine: tauri::State<'_, LiveEngineState>) -> LiveEngineStatus {
+    engine.lock().status()
+}
+
+#[tauri::command]
+pub fn restart_live_engine(
+    app: AppHandle,
+    engine: tauri::State<'_, LiveEngineState>,
+) -> LiveEngineStatus {
+    let mut engine = engine.lock();
+    engine.start(&app);
+    engine.status()
+}
+
+pub async fn transcription_loop(app: AppHandle, cancel: CancellationToken) {
+    let ms = app
+        .state::<Arc<Mutex<crate::config::AppConfig>>>()
+        .lock()
+        .transcription_interval_ms;
+    let mut interval = time::interval(Duration::from_millis(ms.max(100) as u64));
     
     loop {
-        interval.tick().await;
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
 
         let session_manager = app.state::<Arc<Mutex<SessionManager>>>();
-        let is_active = session_manager.lock().current_session.is_some();
+        let is_active = session_manager.lock().focused_id.is_some();
 
         if is_active {
+            let config_state = app.state::<Arc<Mutex<crate::config::AppConfig>>>();
+            let cfg = config_state.lock().clone();
+
             // 1. Get current audio chunks and clear the buffer
-            let wav_bytes = match audio::get_and_clear_audio_wav_bytes() {
-                Ok(bytes) => bytes,
-                Err(_) => continue,
-            };
+            let dsp = crate::capture::dsp::DspOptions::from_config(&cfg);
+            let engine = app.state::<audio::AudioEngineState>();
+            let wav_bytes =
+                match audio::get_and_clear_audio_wav_bytes(&engine, cfg.stt_sample_rate, &dsp) {
+                    Ok(bytes) => bytes,
+                    Err(_) => continue,
+                };
 
             // 2. Transcribe
-            let config_state = app.state::<Arc<Mutex<crate::config::AppConfig>>>();
-            let cfg = config_state.lock().clone();
 
             if !cfg.openai_api_key.is_empty() {
                 let app_handle = app.clone();
@@ -96,7 +424,7 @@ pub async fn transcription_loop(app: AppHandle) {
                         Ok(text) => {
                             if !text.trim().is_empty() {
                                 log::debug!("Transcription chunk: {}", text);
-                                let _ = app_handle.emit("transcription-chunk", text);
+                                crate::events::emit_text(&app_handle, "transcription-chunk", text);
                             }
                         }
                         Err(e) => {
@@ -109,40 +437,145 @@ pub async fn transcription_loop(app: AppHandle) {
     }
 }
 
-pub async fn suggestion_loop(app: AppHandle) {
-    let mut interval = time::interval(Duration::from_secs(2)); // Reduced from 8s to 2s
+pub async fn suggestion_loop(app: AppHandle, cancel: CancellationToken) {
+    let ms = app
+        .state::<Arc<Mutex<crate::config::AppConfig>>>()
+        .lock()
+        .suggestion_interval_ms;
+    let mut interval = time::interval(Duration::from_millis(ms.max(100) as u64));
     let mut last_processed_count = 0;
+    let mut last_session_id = String::new();
 
     loop {
-        interval.tick().await;
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+
+        if crate::capture::privacy::is_paused(&app) {
+            continue;
+        }
+
+        let config_state = app.state::<Arc<Mutex<crate::config::AppConfig>>>();
+        let cfg = config_state.lock().clone();
 
         let session_manager = app.state::<Arc<Mutex<SessionManager>>>();
-        let (transcript_text, current_count, purpose, context) = {
+        let (
+            transcript_text,
+            current_count,
+            new_words,
+            new_speech,
+            purpose,
+            context,
+            template_id,
+            session_id,
+        ) = {
             let mgr = session_manager.lock();
-            if let Some(ref session) = mgr.current_session {
+            if let Some(session) = mgr.focused() {
+                let new_words = session
+                    .transcript
+                    .iter()
+                    .skip(last_processed_count)
+                    .filter(|e| e.speaker != crate::session::manager::TIMELINE_SPEAKER)
+                    .map(|e| e.text.split_whitespace().count())
+                    .sum::<usize>();
+                // What the other side said since the last check; the user's own questions need no answer
+                let new_speech = session
+                    .transcript
+                    .iter()
+                    .skip(last_processed_count)
+                    .filter(|e| e.speaker != crate::session::manager::TIMELINE_SPEAKER)
+                    .filter(|e| e.source.as_deref() != Some("mic"))
+                    .filter(|e| {
+                        !matches!(
+                            e.speaker.to_lowercase().as_str(),
+                            "you" | "user" | "assistant"
+                        )
+                    })
+                    .map(|e| e.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
                 let text = session.transcript
                     .iter()
+                    .filter(|e| e.speaker != crate::session::manager::TIMELINE_SPEAKER)
                     .rev()
                     .take(15) // Take last 15 entries for more context
                     .rev()
                     .map(|e| format!("{}: {}", e.speaker, e.text))
                     .collect::<Vec<_>>()
                     .join("\n");
-                (text, session.transcript.len(), session.purpose.clone(), session.context.clone())
+                (
+                    text,
+                    session.transcript.len(),
+                    new_words,
+                    new_speech,
+                    session.purpose,
+                    session.context.clone(),
+                    session.prompt_template_id.clone(),
+                    session.id.clone(),
+                )
             } else {
-                (String::new(), 0, String::new(), None)
+                (
+                    String::new(),
+                    0,
+                    0,
+                    String::new(),
+                    SessionPurpose::Meeting,
+                    None,
+                    None,
+                    String::new(),
+                )
             }
         };
 
-        if !transcript_text.is_empty() && current_count > last_processed_count {
+        // Focus moved to another session: only answer what's said from here on
+        if session_id != last_session_id {
+            last_session_id = session_id.clone();
             last_processed_count = current_count;
+        }
 
-            let config_state = app.state::<Arc<Mutex<crate::config::AppConfig>>>();
-            let cfg = config_state.lock().clone();
+        // Wait for enough new speech rather than answering every fragment
+        if !transcript_text.is_empty()
+            && current_count > last_processed_count
+            && new_words >= cfg.suggestion_min_new_words as usize
+        {
+            last_processed_count = current_count;
+
+            let signal = if cfg.gate_suggestions_on_questions {
+                question_detect::classify(&new_speech)
+            } else {
+                QuestionSignal::Question
+            };
+            if signal == QuestionSignal::None {
+                continue;
+            }
+
+            let template = match template_id {
+                Some(id) => app
+                    .state::<StorageActor>()
+                    .call(move |s| s.get_prompt_template(&id))
+                    .await
+                    .ok()
+                    .flatten(),
+                None => None,
+            };
+            let slide_text = app
+                .state::<Arc<Mutex<crate::capture::CaptureState>>>()
+                .lock()
+                .current_slide_text
+                .clone();
 
             if !cfg.openai_api_key.is_empty() {
                 let app_handle = app.clone();
                 tokio::spawn(async move {
+                    // Borderline cases go past the cheap model before the expensive answer
+                    if signal == QuestionSignal::Possible
+                        && !cfg.question_classifier_model.is_empty()
+                        && !question_detect::confirm(&cfg, &new_speech).await
+                    {
+                        return;
+                    }
+
                     log::debug!("Generating automated answer...");
                     
                     let mut system_prompt = format!(
@@ -152,13 +585,24 @@ pub async fn suggestion_loop(app: AppHandle) {
                          Be direct. NO prefixes like 'Answer:'. \
                          Respond with '[SILENCE]' if no response is needed right now. \
                          Be concise (max 3 sentences).",
-                        purpose.to_uppercase()
+                        purpose.as_str().to_uppercase()
                     );
 
+                    if let Some(ref template) = template {
+                        system_prompt.push_str(&format!(
+                            "\n\n## Instructions ({}):\n{}",
+                            template.name, template.template
+                        ));
+                    }
+
                     if let Some(ref ctx) = context {
                         system_prompt.push_str(&format!("\n\n## User's Resume/Context:\n{}", ctx));
                     }
 
+                    if let Some(ref slide) = slide_text {
+                        system_prompt.push_str(&format!("\n\n## Current Slide:\n{}", slide));
+                    }
+
                     system_prompt.push_str(&format!("\n\n## Recent Transcript:\n{}", transcript_text));
 
                     let messages = vec![crate::ai::AIMessage {
@@ -166,10 +610,38 @@ pub async fn suggestion_loop(app: AppHandle) {
                         content: "What is the best answer or talking point for the current moment?".to_string(),
                     }];
 
-                    match crate::ai::streaming::stream_llm_internal(app_handle.clone(), cfg, messages, Some(system_prompt)).await {
+                    let stream_id = uuid::Uuid::new_v4().to_string();
+                    let emitter = app_handle.clone();
+                    let on_event = move |event: StreamEvent<'_>| {
+                        let (kind, text) = match event {
+                            StreamEvent::Start => ("start", ""),
+                            StreamEvent::Token(token) => ("token", token),
+                            StreamEvent::End(full) => ("end", full),
+                        };
+                        let _ = emitter.emit(
+                            "live-suggestion",
+                            LiveSuggestionEvent {
+                                stream_id: stream_id.clone(),
+                                kind,
+                                text: text.to_string(),
+                            },
+                        );
+                    };
+                    let on_event =
+                        crate::events::batch_tokens(&app_handle, "live-suggestion", on_event);
+
+                    match crate::ai::streaming::stream_llm_with(
+                        cfg,
+                        messages,
+                        Some(system_prompt),
+                        on_event,
+                    )
+                    .await
+                    {
                         Ok(full_response) => {
                             if !full_response.contains("[SILENCE]") && !full_response.trim().is_empty() {
                                 log::debug!("Automated streaming response complete.");
+                                record_suggestion(&app_handle, &session_id, full_response.trim()).await;
                             }
                         }
                         Err(e) => {
@@ -181,3 +653,70 @@ pub async fn suggestion_loop(app: AppHandle) {
         }
     }
 }
+
+/// Emitted as "live-suggestion" while an automatic answer streams in
+#[derive(Clone, serde::Serialize)]
+struct LiveSuggestionEvent {
+    stream_id: String,
+    /// "start", "token" or "end"; `text` is the token, or the full answer at the end
+    kind: &'static str,
+    text: String,
+}
+
+/// Turn live suggestions on or off, saving the setting and restarting the live engine
+pub fn set_live_suggestions(app: &AppHandle, enabled: bool) -> Result<(), VenkyError> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))?;
+    {
+        let config_state = app.state::<Arc<Mutex<crate::config::AppConfig>>>();
+        let mut cfg = config_state.lock();
+        cfg.live_suggestions = enabled;
+        cfg.save(&app_data)?;
+    }
+    restart(app);
+    let _ = app.emit("live-suggestions-changed", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn enable_live_suggestions(app: AppHandle) -> CommandResult<()> {
+    set_live_suggestions(&app, true)
+}
+
+#[tauri::command]
+pub fn disable_live_suggestions(app: AppHandle) -> CommandResult<()> {
+    set_live_suggestions(&app, false)
+}
+
+#[derive(Clone, serde::Serialize)]
+struct RecordedSuggestion {
+    id: String,
+    text: String,
+}
+
+/// Keep a shown suggestion so acceptance can be tracked; emits "suggestion-recorded" with its id
+pub async fn record_suggestion(app: &AppHandle, session_id: &str, text: &str) {
+    let id = uuid::Uuid::new_v4().to_string();
+    let (stored_id, session_id, stored_text) = (id.clone(), session_id.to_string(), text.to_string());
+    let result = app
+        .state::<StorageActor>()
+        .call(move |s| s.add_suggestion(&stored_id, &session_id, &stored_text))
+        .await;
+
+    match result {
+        Ok(()) => {
+            let _ = app.emit(
+                "suggestion-recorded",
+                RecordedSuggestion {
+                    id,
+                    text: text.to_string(),
+                },
+            );
+            super::clipboard::copy_if_enabled(app, text);
+            super::tts::speak_if_enabled(app, text);
+        }
+        Err(e) => log::error!("Failed to record suggestion: {}", e),
+    }
+}
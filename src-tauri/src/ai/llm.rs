@@ -2,8 +2,11 @@ use serde::{Deserialize, Serialize};
 
 use super::{AIContext, AIResponse};
 use crate::config::{AppConfig, LLMProvider};
+use crate::error::{CommandResult, VenkyError};
+use crate::integrations::ConnectionTest;
 
 type ConfigState = std::sync::Arc<parking_lot::Mutex<AppConfig>>;
+type CaptureStateHandle = std::sync::Arc<parking_lot::Mutex<crate::capture::CaptureState>>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderInfo {
@@ -45,13 +48,20 @@ fn build_system_prompt(context: &AIContext) -> String {
     prompt
 }
 
-#[tauri::command]
-pub async fn ask_ai(
-    config: tauri::State<'_, ConfigState>,
-    question: String,
-) -> Result<AIResponse, String> {
-    let cfg = config.lock().clone();
+/// Run a prompt on the configured provider in JSON mode and return the raw JSON text
+pub async fn generate_json(
+    cfg: &AppConfig,
+    system_prompt: &str,
+    question: &str,
+) -> Result<String, VenkyError> {
+    match cfg.llm_provider {
+        LLMProvider::OpenAI => super::openai::generate_json(cfg, system_prompt, question).await,
+        LLMProvider::Ollama => super::ollama::generate_json(cfg, system_prompt, question).await,
+    }
+}
 
+/// Answer a standalone question with the configured provider
+pub async fn ask(cfg: &AppConfig, question: &str) -> Result<AIResponse, VenkyError> {
     let context = AIContext {
         transcript: None,
         screen_description: None,
@@ -60,33 +70,77 @@ pub async fn ask_ai(
     };
 
     match cfg.llm_provider {
-        LLMProvider::OpenAI => {
-            super::openai::generate(&cfg, &question, &context).await
-        }
-        LLMProvider::Ollama => {
-            super::ollama::generate(&cfg, &question, &context).await
-        }
+        LLMProvider::OpenAI => super::openai::generate(cfg, question, &context).await,
+        LLMProvider::Ollama => super::ollama::generate(cfg, question, &context).await,
     }
 }
 
+#[tauri::command]
+pub async fn ask_ai(
+    config: tauri::State<'_, ConfigState>,
+    question: String,
+) -> CommandResult<AIResponse> {
+    let cfg = config.lock().clone();
+    Ok(ask(&cfg, &question).await?)
+}
+
 #[tauri::command]
 pub async fn ask_ai_with_context(
     config: tauri::State<'_, ConfigState>,
+    capture: tauri::State<'_, CaptureStateHandle>,
     question: String,
-    context: AIContext,
-) -> Result<AIResponse, String> {
+    mut context: AIContext,
+) -> CommandResult<AIResponse> {
     let cfg = config.lock().clone();
 
+    // Fall back to whatever the screen-context loop last saw
+    if context.screen_base64.is_none() {
+        context.screen_base64 = capture.lock().last_screen_capture.clone();
+    }
+
     let system_prompt = build_system_prompt(&context);
 
-    match cfg.llm_provider {
+    let response = match cfg.llm_provider {
         LLMProvider::OpenAI => {
             super::openai::generate_with_system(&cfg, &system_prompt, &question, &context).await
         }
         LLMProvider::Ollama => {
             super::ollama::generate_with_system(&cfg, &system_prompt, &question).await
         }
-    }
+    };
+    Ok(response?)
+}
+
+/// Check an OpenAI key against the configured model; `api_key` tests a key before it's saved
+#[tauri::command]
+pub async fn test_openai_key(
+    config: tauri::State<'_, ConfigState>,
+    api_key: Option<String>,
+) -> CommandResult<ConnectionTest> {
+    let cfg = config.lock().clone();
+    let api_key = api_key.unwrap_or(cfg.openai_api_key);
+    let model = cfg.openai_model;
+    Ok(ConnectionTest::run(async {
+        super::openai::check_model(&api_key, &model).await?;
+        Ok(format!("Key works with {}", model))
+    })
+    .await)
+}
+
+/// Check that Ollama is running with the configured model; `url` tests one before it's saved
+#[tauri::command]
+pub async fn test_ollama_connection(
+    config: tauri::State<'_, ConfigState>,
+    url: Option<String>,
+) -> CommandResult<ConnectionTest> {
+    let cfg = config.lock().clone();
+    let url = url.unwrap_or(cfg.ollama_url);
+    let model = cfg.ollama_model;
+    Ok(ConnectionTest::run(async {
+        super::ollama::check_model(&url, &model).await?;
+        Ok(format!("Ollama is running with {}", model))
+    })
+    .await)
 }
 
 #[tauri::command]
@@ -1,8 +1,12 @@
+pub mod clipboard;
 pub mod llm;
 pub mod ollama;
 pub mod openai;
+pub mod question_detect;
+pub mod quick_ask;
 pub mod stt;
 pub mod streaming;
+pub mod tts;
 pub mod live_engine;
 
 use serde::{Deserialize, Serialize};
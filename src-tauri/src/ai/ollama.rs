@@ -1,14 +1,16 @@
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
 use super::AIResponse;
 use crate::config::AppConfig;
+use crate::error::VenkyError;
 
 #[derive(Debug, Serialize)]
 struct OllamaRequest {
     model: String,
     messages: Vec<OllamaMessage>,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -20,6 +22,10 @@ struct OllamaMessage {
 #[derive(Debug, Deserialize)]
 struct OllamaResponse {
     message: Option<OllamaMessageResponse>,
+    #[serde(default)]
+    prompt_eval_count: u64,
+    #[serde(default)]
+    eval_count: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,12 +33,60 @@ struct OllamaMessageResponse {
     content: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct OllamaTags {
+    #[serde(default)]
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
+/// Check that Ollama is reachable at `url` and `model` has been pulled
+pub async fn check_model(url: &str, model: &str) -> Result<(), VenkyError> {
+    let response = crate::http::client()
+        .get(format!("{}/api/tags", url.trim_end_matches('/')))
+        .send()
+        .await
+        .map_err(|e| {
+            VenkyError::Network(format!("Ollama request failed: {}. Is Ollama running?", e))
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "Ollama API error ({}): {}",
+            status, body
+        )));
+    }
+
+    let tags: OllamaTags = response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Failed to parse Ollama response: {}", e)))?;
+    // Untagged names resolve to ":latest"
+    let pulled = tags
+        .models
+        .iter()
+        .any(|m| m.name == model || m.name == format!("{}:latest", model));
+    if !pulled {
+        return Err(VenkyError::Provider(format!(
+            "Ollama is running but {} isn't pulled; run `ollama pull {}`",
+            model, model
+        )));
+    }
+    Ok(())
+}
+
 pub async fn generate(
     config: &AppConfig,
     question: &str,
     _context: &super::AIContext,
-) -> Result<AIResponse, String> {
-    let client = Client::new();
+) -> Result<AIResponse, VenkyError> {
+    let client = crate::http::client();
 
     let request = OllamaRequest {
         model: config.ollama_model.clone(),
@@ -49,27 +103,29 @@ pub async fn generate(
             },
         ],
         stream: false,
+        format: None,
     };
 
     let url = format!("{}/api/chat", config.ollama_url);
 
-    let response = client
-        .post(&url)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Ollama request failed: {}. Is Ollama running?", e))?;
+    let response = client.post(&url).json(&request).send().await.map_err(|e| {
+        VenkyError::Network(format!("Ollama request failed: {}. Is Ollama running?", e))
+    })?;
 
     let status = response.status();
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
-        return Err(format!("Ollama API error ({}): {}", status, body));
+        return Err(VenkyError::Provider(format!(
+            "Ollama API error ({}): {}",
+            status, body
+        )));
     }
 
     let body: OllamaResponse = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+        .map_err(|e| VenkyError::Provider(format!("Failed to parse Ollama response: {}", e)))?;
+    crate::metrics::record_tokens("ollama", body.prompt_eval_count + body.eval_count);
 
     let content = body
         .message
@@ -88,8 +144,8 @@ pub async fn generate_with_system(
     config: &AppConfig,
     system_prompt: &str,
     question: &str,
-) -> Result<AIResponse, String> {
-    let client = Client::new();
+) -> Result<AIResponse, VenkyError> {
+    let client = crate::http::client();
 
     let request = OllamaRequest {
         model: config.ollama_model.clone(),
@@ -104,27 +160,29 @@ pub async fn generate_with_system(
             },
         ],
         stream: false,
+        format: None,
     };
 
     let url = format!("{}/api/chat", config.ollama_url);
 
-    let response = client
-        .post(&url)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Ollama request failed: {}. Is Ollama running?", e))?;
+    let response = client.post(&url).json(&request).send().await.map_err(|e| {
+        VenkyError::Network(format!("Ollama request failed: {}. Is Ollama running?", e))
+    })?;
 
     let status = response.status();
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
-        return Err(format!("Ollama API error ({}): {}", status, body));
+        return Err(VenkyError::Provider(format!(
+            "Ollama API error ({}): {}",
+            status, body
+        )));
     }
 
     let body: OllamaResponse = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+        .map_err(|e| VenkyError::Provider(format!("Failed to parse Ollama response: {}", e)))?;
+    crate::metrics::record_tokens("ollama", body.prompt_eval_count + body.eval_count);
 
     let content = body
         .message
@@ -138,3 +196,56 @@ pub async fn generate_with_system(
         timestamp: chrono::Utc::now().to_rfc3339(),
     })
 }
+
+/// Constrain the reply to valid JSON
+pub async fn generate_json(
+    config: &AppConfig,
+    system_prompt: &str,
+    question: &str,
+) -> Result<String, VenkyError> {
+    let request = OllamaRequest {
+        model: config.ollama_model.clone(),
+        messages: vec![
+            OllamaMessage {
+                role: "system".to_string(),
+                content: system_prompt.to_string(),
+            },
+            OllamaMessage {
+                role: "user".to_string(),
+                content: question.to_string(),
+            },
+        ],
+        stream: false,
+        format: Some("json".to_string()),
+    };
+
+    let url = format!("{}/api/chat", config.ollama_url);
+
+    let response = crate::http::client()
+        .post(&url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| {
+            VenkyError::Network(format!("Ollama request failed: {}. Is Ollama running?", e))
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "Ollama API error ({}): {}",
+            status, body
+        )));
+    }
+
+    let body: OllamaResponse = response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Failed to parse Ollama response: {}", e)))?;
+    crate::metrics::record_tokens("ollama", body.prompt_eval_count + body.eval_count);
+
+    body.message
+        .map(|m| m.content)
+        .ok_or_else(|| VenkyError::Provider("No response from Ollama".to_string()))
+}
@@ -1,8 +1,9 @@
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
 use super::{AIContext, AIResponse};
 use crate::config::AppConfig;
+use crate::error::VenkyError;
+use crate::redaction::Redactor;
 
 #[derive(Debug, Serialize)]
 struct OpenAIRequest {
@@ -10,6 +11,8 @@ struct OpenAIRequest {
     messages: Vec<OpenAIMessage>,
     max_tokens: u32,
     temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -21,6 +24,13 @@ struct OpenAIMessage {
 #[derive(Debug, Deserialize)]
 struct OpenAIResponse {
     choices: Vec<OpenAIChoice>,
+    #[serde(default)]
+    usage: Option<OpenAIUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIUsage {
+    total_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -37,12 +47,18 @@ pub async fn generate(
     config: &AppConfig,
     question: &str,
     _context: &AIContext,
-) -> Result<AIResponse, String> {
+) -> Result<AIResponse, VenkyError> {
     if config.openai_api_key.is_empty() {
-        return Err("OpenAI API key not configured. Go to Settings to add your key.".to_string());
+        return Err(VenkyError::Provider(
+            "OpenAI API key not configured. Go to Settings to add your key.".to_string(),
+        ));
     }
 
-    let client = Client::new();
+    let client = crate::http::client();
+
+    let mut redactor = Redactor::new(config).await;
+    let question = redactor.redact(question);
+    redactor.report();
 
     let request = OpenAIRequest {
         model: config.openai_model.clone(),
@@ -57,11 +73,12 @@ pub async fn generate(
             },
             OpenAIMessage {
                 role: "user".to_string(),
-                content: serde_json::Value::String(question.to_string()),
+                content: serde_json::Value::String(question),
             },
         ],
         max_tokens: 1024,
         temperature: 0.7,
+        response_format: None,
     };
 
     let response = client
@@ -71,23 +88,29 @@ pub async fn generate(
         .json(&request)
         .send()
         .await
-        .map_err(|e| format!("OpenAI request failed: {}", e))?;
+        .map_err(|e| VenkyError::Network(format!("OpenAI request failed: {}", e)))?;
 
     let status = response.status();
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error ({}): {}", status, body));
+        return Err(VenkyError::Provider(format!(
+            "OpenAI API error ({}): {}",
+            status, body
+        )));
     }
 
     let body: OpenAIResponse = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse OpenAI response: {}", e))?;
+        .map_err(|e| VenkyError::Provider(format!("Failed to parse OpenAI response: {}", e)))?;
+    if let Some(ref usage) = body.usage {
+        crate::metrics::record_tokens("openai", usage.total_tokens);
+    }
 
     let content = body
         .choices
         .first()
-        .map(|c| c.message.content.clone())
+        .map(|c| redactor.restore(&c.message.content))
         .unwrap_or_else(|| "No response from OpenAI".to_string());
 
     Ok(AIResponse {
@@ -103,19 +126,25 @@ pub async fn generate_with_system(
     system_prompt: &str,
     question: &str,
     context: &AIContext,
-) -> Result<AIResponse, String> {
+) -> Result<AIResponse, VenkyError> {
     if config.openai_api_key.is_empty() {
-        return Err("OpenAI API key not configured. Go to Settings to add your key.".to_string());
+        return Err(VenkyError::Provider(
+            "OpenAI API key not configured. Go to Settings to add your key.".to_string(),
+        ));
     }
 
-    let client = Client::new();
+    let client = crate::http::client();
+
+    // Screenshots go as they are; only text can be redacted
+    let mut redactor = Redactor::new(config).await;
+    let system_prompt = redactor.redact(system_prompt);
+    let question = redactor.redact(question);
+    redactor.report();
 
-    let mut messages = vec![
-        OpenAIMessage {
-            role: "system".to_string(),
-            content: serde_json::Value::String(system_prompt.to_string()),
-        },
-    ];
+    let mut messages = vec![OpenAIMessage {
+        role: "system".to_string(),
+        content: serde_json::Value::String(system_prompt),
+    }];
 
     // If we have a screen capture, use vision
     if let Some(ref base64_img) = context.screen_base64 {
@@ -129,7 +158,11 @@ pub async fn generate_with_system(
                 {
                     "type": "image_url",
                     "image_url": {
-                        "url": format!("data:image/png;base64,{}", base64_img),
+                        "url": format!(
+                            "data:{};base64,{}",
+                            crate::capture::screen::mime_from_base64(base64_img),
+                            base64_img
+                        ),
                         "detail": "low"
                     }
                 }
@@ -138,7 +171,7 @@ pub async fn generate_with_system(
     } else {
         messages.push(OpenAIMessage {
             role: "user".to_string(),
-            content: serde_json::Value::String(question.to_string()),
+            content: serde_json::Value::String(question),
         });
     }
 
@@ -147,6 +180,7 @@ pub async fn generate_with_system(
         messages,
         max_tokens: 1024,
         temperature: 0.7,
+        response_format: None,
     };
 
     let response = client
@@ -156,23 +190,29 @@ pub async fn generate_with_system(
         .json(&request)
         .send()
         .await
-        .map_err(|e| format!("OpenAI request failed: {}", e))?;
+        .map_err(|e| VenkyError::Network(format!("OpenAI request failed: {}", e)))?;
 
     let status = response.status();
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error ({}): {}", status, body));
+        return Err(VenkyError::Provider(format!(
+            "OpenAI API error ({}): {}",
+            status, body
+        )));
     }
 
     let body: OpenAIResponse = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse OpenAI response: {}", e))?;
+        .map_err(|e| VenkyError::Provider(format!("Failed to parse OpenAI response: {}", e)))?;
+    if let Some(ref usage) = body.usage {
+        crate::metrics::record_tokens("openai", usage.total_tokens);
+    }
 
     let content = body
         .choices
         .first()
-        .map(|c| c.message.content.clone())
+        .map(|c| redactor.restore(&c.message.content))
         .unwrap_or_else(|| "No response".to_string());
 
     Ok(AIResponse {
@@ -182,3 +222,105 @@ pub async fn generate_with_system(
         timestamp: chrono::Utc::now().to_rfc3339(),
     })
 }
+
+/// Check that `api_key` is valid and can use `model`, without spending any tokens
+pub async fn check_model(api_key: &str, model: &str) -> Result<(), VenkyError> {
+    if api_key.trim().is_empty() {
+        return Err(VenkyError::Provider(
+            "OpenAI API key not configured".to_string(),
+        ));
+    }
+
+    let response = crate::http::client()
+        .get(format!("https://api.openai.com/v1/models/{}", model))
+        .header("Authorization", format!("Bearer {}", api_key.trim()))
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("OpenAI request failed: {}", e)))?;
+
+    let status = response.status();
+    match status.as_u16() {
+        200..=299 => Ok(()),
+        401 => Err(VenkyError::Provider(
+            "OpenAI rejected the API key".to_string(),
+        )),
+        404 => Err(VenkyError::Provider(format!(
+            "This key has no access to {}",
+            model
+        ))),
+        _ => {
+            let body = response.text().await.unwrap_or_default();
+            Err(VenkyError::Provider(format!(
+                "OpenAI API error ({}): {}",
+                status, body
+            )))
+        }
+    }
+}
+
+/// JSON mode: the reply is guaranteed to be a single JSON object
+pub async fn generate_json(
+    config: &AppConfig,
+    system_prompt: &str,
+    question: &str,
+) -> Result<String, VenkyError> {
+    if config.openai_api_key.is_empty() {
+        return Err(VenkyError::Provider(
+            "OpenAI API key not configured. Go to Settings to add your key.".to_string(),
+        ));
+    }
+
+    let mut redactor = Redactor::new(config).await;
+    let system_prompt = redactor.redact(system_prompt);
+    let question = redactor.redact(question);
+    redactor.report();
+
+    let request = OpenAIRequest {
+        model: config.openai_model.clone(),
+        messages: vec![
+            OpenAIMessage {
+                role: "system".to_string(),
+                content: serde_json::Value::String(system_prompt),
+            },
+            OpenAIMessage {
+                role: "user".to_string(),
+                content: serde_json::Value::String(question),
+            },
+        ],
+        max_tokens: 2048,
+        temperature: 0.2,
+        response_format: Some(serde_json::json!({ "type": "json_object" })),
+    };
+
+    let response = crate::http::client()
+        .post("https://api.openai.com/v1/chat/completions")
+        .header("Authorization", format!("Bearer {}", config.openai_api_key))
+        .header("Content-Type", "application/json")
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("OpenAI request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "OpenAI API error ({}): {}",
+            status, body
+        )));
+    }
+
+    let body: OpenAIResponse = response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Failed to parse OpenAI response: {}", e)))?;
+    if let Some(ref usage) = body.usage {
+        crate::metrics::record_tokens("openai", usage.total_tokens);
+    }
+
+    body.choices
+        .into_iter()
+        .next()
+        .map(|c| redactor.restore_json(&c.message.content))
+        .ok_or_else(|| VenkyError::Provider("No response from OpenAI".to_string()))
+}
@@ -0,0 +1,80 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::config::{AppConfig, LLMProvider};
+
+/// Requests that expect an answer even without a question mark
+static PROMPT_PHRASE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)^(tell (me|us) (about|more)|walk (me|us) through|describe|explain|talk (me|us) through|give (me|us) an example|share)\b",
+    )
+    .unwrap()
+});
+
+/// Openers of a question whose question mark the transcription may have dropped
+static QUESTION_OPENER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)^(what|why|how|when|where|who|whom|which|can|could|would|will|do|does|did|have|has|are|is|were|should)\b",
+    )
+    .unwrap()
+});
+
+const CONFIRM_PROMPT: &str =
+    "You check live meeting transcripts. Decide whether the text ends with \
+    a question or request that the listener is expected to answer now. \
+    Respond with a JSON object of the form {\"question\": true or false}.";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuestionSignal {
+    /// Clearly asks for an answer
+    Question,
+    /// Reads like a question but might not be one; worth a cheap model check
+    Possible,
+    None,
+}
+
+/// Cheap local check of what was just said by the other side
+pub fn classify(text: &str) -> QuestionSignal {
+    // Only the last few sentences matter; earlier questions were already handled
+    let sentences = text
+        .split_inclusive(['.', '!', '?', '\n'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut signal = QuestionSignal::None;
+    for sentence in sentences.iter().rev().take(3) {
+        if sentence.ends_with('?') || PROMPT_PHRASE.is_match(sentence) {
+            return QuestionSignal::Question;
+        }
+        if QUESTION_OPENER.is_match(sentence) {
+            signal = QuestionSignal::Possible;
+        }
+    }
+    signal
+}
+
+#[derive(Debug, Deserialize)]
+struct Verdict {
+    #[serde(default)]
+    question: bool,
+}
+
+/// Ask the fast classifier model whether `text` needs an answer; errors count as "no"
+pub async fn confirm(cfg: &AppConfig, text: &str) -> bool {
+    let mut cfg = cfg.clone();
+    if cfg.llm_provider == LLMProvider::OpenAI && !cfg.question_classifier_model.is_empty() {
+        cfg.openai_model = cfg.question_classifier_model.clone();
+    }
+
+    match super::llm::generate_json(&cfg, CONFIRM_PROMPT, text).await {
+        Ok(raw) => serde_json::from_str::<Verdict>(&raw)
+            .map(|v| v.question)
+            .unwrap_or(false),
+        Err(e) => {
+            log::debug!("Question check failed: {}", e);
+            false
+        }
+    }
+}
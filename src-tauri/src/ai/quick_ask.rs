@@ -0,0 +1,188 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::streaming::StreamEvent;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::manager::{SessionManager, TIMELINE_SPEAKER};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Emitted as "quick-ask" while the answer streams in
+#[derive(Debug, Clone, Serialize)]
+struct QuickAskEvent {
+    stream_id: String,
+    /// "start", "token", "end" or "error"; `text` is the token, the full answer at the end,
+    /// or what went wrong
+    kind: &'static str,
+    text: String,
+}
+
+/// What was said in the focused session over the last `seconds`
+fn recent_transcript(app: &AppHandle, seconds: u32) -> Option<(String, String)> {
+    let state = app.state::<SessionState>();
+    let mgr = state.lock();
+    let session = mgr.focused()?;
+    let since = chrono::Utc::now() - chrono::Duration::seconds(seconds as i64);
+    let lines: Vec<String> = session
+        .transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER)
+        .filter(|e| {
+            chrono::DateTime::parse_from_rfc3339(&e.timestamp)
+                .map(|t| t >= since)
+                .unwrap_or(false)
+        })
+        .map(|e| format!("{}: {}", e.speaker, e.text))
+        .collect();
+    Some((session.id.clone(), lines.join("\n")))
+}
+
+/// OCR of a fresh screenshot, unless capture is paused or sensitive text is showing
+async fn screen_text(app: &AppHandle, cfg: &AppConfig) -> Option<String> {
+    if crate::capture::privacy::is_paused(app) {
+        return None;
+    }
+    let lines = tokio::task::spawn_blocking(|| {
+        let image = crate::capture::screen::capture_primary_monitor()?;
+        crate::capture::ocr::recognize(&image)
+    })
+    .await
+    .ok()?;
+    let text = match lines {
+        Ok(lines) => lines
+            .iter()
+            .map(|l| l.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Err(e) => {
+            log::debug!("Quick ask screen capture skipped: {}", e);
+            return None;
+        }
+    };
+    if text.trim().is_empty() || crate::capture::privacy::check_text(app, "screen", &text) {
+        return None;
+    }
+    Some(crate::capture::redact::redact_text(&text, &cfg.redaction))
+}
+
+/// Ask "what should I say right now?" about the last `seconds` of the focused session (and
+/// the screen, when enabled), streaming the answer to the overlay as "quick-ask" events
+pub async fn ask_now(
+    app: &AppHandle,
+    seconds: Option<u32>,
+    include_screen: Option<bool>,
+) -> Result<String, VenkyError> {
+    let cfg = app.state::<ConfigState>().lock().clone();
+    let seconds = seconds.unwrap_or(cfg.quick_ask_seconds);
+    let (session_id, transcript) = recent_transcript(app, seconds)
+        .ok_or_else(|| VenkyError::Other("No active session".to_string()))?;
+    let screen = if include_screen.unwrap_or(cfg.quick_ask_screen) {
+        screen_text(app, &cfg).await
+    } else {
+        None
+    };
+    if transcript.is_empty() && screen.is_none() {
+        return Err(VenkyError::Other(format!(
+            "Nothing was said in the last {} seconds",
+            seconds
+        )));
+    }
+
+    let (purpose, context) = {
+        let state = app.state::<SessionState>();
+        let mgr = state.lock();
+        let session = mgr.sessions.get(&session_id);
+        (
+            session.map(|s| s.purpose.as_str()).unwrap_or("meeting"),
+            session.and_then(|s| s.context.clone()),
+        )
+    };
+
+    let mut system_prompt = format!(
+        "You are VenkyAI, helping the user live during a {}. The user just asked for help: \
+         tell them exactly what to say next, based on the last few moments of the conversation. \
+         Be direct, with no prefixes like 'Answer:'. Be concise (max 3 sentences).",
+        purpose
+    );
+    if let Some(ref ctx) = context {
+        system_prompt.push_str(&format!("\n\n## User's Context:\n{}", ctx));
+    }
+    if let Some(ref screen) = screen {
+        system_prompt.push_str(&format!("\n\n## On Screen Now:\n{}", screen));
+    }
+    system_prompt.push_str(&format!(
+        "\n\n## Last {} Seconds of Transcript:\n{}",
+        seconds, transcript
+    ));
+    let messages = vec![super::AIMessage {
+        role: "user".to_string(),
+        content: "What should I say right now?".to_string(),
+    }];
+
+    // Surface the overlay so the answer is seen as it streams in
+    if let Some(window) = app.get_webview_window("overlay") {
+        if !window.is_visible().unwrap_or(false) {
+            let _ = window.show();
+            let _ = app.emit("overlay-visibility", true);
+        }
+    }
+
+    let stream_id = uuid::Uuid::new_v4().to_string();
+    let emit = |kind: &'static str, text: &str| {
+        let _ = app.emit(
+            "quick-ask",
+            QuickAskEvent {
+                stream_id: stream_id.clone(),
+                kind,
+                text: text.to_string(),
+            },
+        );
+    };
+    let on_event = |event: StreamEvent<'_>| match event {
+        StreamEvent::Start => emit("start", ""),
+        StreamEvent::Token(token) => emit("token", token),
+        StreamEvent::End(full) => emit("end", full),
+    };
+    let on_event = crate::events::batch_tokens(app, "quick-ask", on_event);
+    let stream = super::streaming::stream_llm_with(cfg, messages, Some(system_prompt), on_event);
+    let result = crate::redaction::for_session(&session_id, stream).await;
+
+    match result {
+        Ok(answer) => {
+            let answer = answer.trim().to_string();
+            if !answer.is_empty() {
+                super::live_engine::record_suggestion(app, &session_id, &answer).await;
+            }
+            Ok(answer)
+        }
+        Err(e) => {
+            emit("error", e.message());
+            Err(e)
+        }
+    }
+}
+
+/// Run `ask_now` in the background; bound to the push-to-ask hotkey
+pub fn ask_in_background(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = ask_now(&app, None, None).await {
+            log::warn!("Quick ask failed: {}", e);
+        }
+    });
+}
+
+/// What to say right now, from the last `seconds` of transcript (default from settings)
+/// and optionally a fresh screen capture
+#[tauri::command]
+pub async fn ask_about_recent(
+    app: AppHandle,
+    seconds: Option<u32>,
+    include_screen: Option<bool>,
+) -> CommandResult<String> {
+    ask_now(&app, seconds, include_screen).await
+}
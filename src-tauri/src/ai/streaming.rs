@@ -1,9 +1,9 @@
-use reqwest::Client;
 use serde::Deserialize;
 use tauri::{Emitter, AppHandle};
 use futures_util::StreamExt;
 
 use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
 
 type ConfigState = std::sync::Arc<parking_lot::Mutex<AppConfig>>;
 
@@ -15,12 +15,19 @@ struct StreamDelta {
 #[derive(Debug, Deserialize)]
 struct StreamChoice {
     delta: StreamDelta,
-    finish_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct StreamChunk {
     choices: Vec<StreamChoice>,
+    /// Only on the last chunk, since the request asks for it
+    #[serde(default)]
+    usage: Option<StreamUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamUsage {
+    total_tokens: u64,
 }
 
 /// Send a streaming chat request to OpenAI — emits "llm-token" events as tokens arrive
@@ -30,43 +37,71 @@ pub async fn stream_chat(
     config: tauri::State<'_, ConfigState>,
     messages: Vec<crate::ai::AIMessage>,
     system_prompt: Option<String>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let cfg = config.lock().clone();
     stream_llm_internal(app, cfg, messages, system_prompt).await
 }
 
+/// Progress of a streamed completion
+pub enum StreamEvent<'a> {
+    Start,
+    Token(&'a str),
+    End(&'a str),
+}
+
 pub async fn stream_llm_internal(
     app: AppHandle,
     cfg: crate::config::AppConfig,
     messages: Vec<crate::ai::AIMessage>,
     system_prompt: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, VenkyError> {
+    let on_event = |event: StreamEvent<'_>| {
+        let _ = match event {
+            StreamEvent::Start => app.emit("llm-stream-start", ()),
+            StreamEvent::Token(token) => app.emit("llm-token", token),
+            StreamEvent::End(full) => app.emit("llm-stream-end", full),
+        };
+    };
+    let on_event = crate::events::batch_tokens(&app, "llm-token", on_event);
+    stream_llm_with(cfg, messages, system_prompt, on_event).await
+}
+
+/// Stream a chat completion, reporting progress to `on_event` instead of the shared llm-* events
+pub async fn stream_llm_with(
+    cfg: crate::config::AppConfig,
+    messages: Vec<crate::ai::AIMessage>,
+    system_prompt: Option<String>,
+    mut on_event: impl FnMut(StreamEvent<'_>),
+) -> Result<String, VenkyError> {
     if cfg.openai_api_key.is_empty() {
-        return Err("OpenAI API key not configured".to_string());
+        return Err(VenkyError::Provider("OpenAI API key not configured".to_string()));
     }
 
-    let client = Client::new();
+    let client = crate::http::client();
 
+    let mut redactor = crate::redaction::Redactor::new(&cfg).await;
     let mut api_messages = Vec::new();
 
     if let Some(sys) = system_prompt {
         api_messages.push(serde_json::json!({
             "role": "system",
-            "content": sys
+            "content": redactor.redact(&sys)
         }));
     }
 
     for msg in &messages {
         api_messages.push(serde_json::json!({
             "role": msg.role,
-            "content": msg.content
+            "content": redactor.redact(&msg.content)
         }));
     }
+    redactor.report();
 
     let body = serde_json::json!({
         "model": cfg.openai_model,
         "messages": api_messages,
-        "stream": true
+        "stream": true,
+        "stream_options": { "include_usage": true }
     });
 
     let response = client
@@ -76,23 +111,22 @@ pub async fn stream_llm_internal(
         .json(&body)
         .send()
         .await
-        .map_err(|e| format!("Stream request failed: {}", e))?;
+        .map_err(|e| VenkyError::Network(format!("Stream request failed: {}", e)))?;
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI error ({}): {}", status, body));
+        return Err(VenkyError::Provider(format!("OpenAI error ({}): {}", status, body)));
     }
 
     let mut stream = response.bytes_stream();
     let mut full_response = String::new();
     let mut buffer = String::new();
 
-    // Emit stream-start event
-    let _ = app.emit("llm-stream-start", ());
+    on_event(StreamEvent::Start);
 
     while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Stream error: {}", e))?;
+        let chunk = chunk.map_err(|e| VenkyError::Network(format!("Stream error: {}", e)))?;
         let text = String::from_utf8_lossy(&chunk);
         buffer.push_str(&text);
 
@@ -108,26 +142,28 @@ pub async fn stream_llm_internal(
             let data = &line[6..];
 
             if data == "[DONE]" {
-                let _ = app.emit("llm-stream-end", &full_response);
+                // Tokens stream with their placeholders; the full reply gets the originals back
+                let full_response = redactor.restore(&full_response);
+                on_event(StreamEvent::End(&full_response));
                 return Ok(full_response);
             }
 
             if let Ok(chunk) = serde_json::from_str::<StreamChunk>(data) {
+                // The usage chunk follows the finishing one, so keep reading until [DONE]
                 for choice in &chunk.choices {
                     if let Some(content) = &choice.delta.content {
                         full_response.push_str(content);
-                        // Emit each token as it arrives
-                        let _ = app.emit("llm-token", content);
-                    }
-                    if choice.finish_reason.is_some() {
-                        let _ = app.emit("llm-stream-end", &full_response);
-                        return Ok(full_response);
+                        on_event(StreamEvent::Token(content));
                     }
                 }
+                if let Some(usage) = chunk.usage {
+                    crate::metrics::record_tokens("openai", usage.total_tokens);
+                }
             }
         }
     }
 
-    let _ = app.emit("llm-stream-end", &full_response);
+    let full_response = redactor.restore(&full_response);
+    on_event(StreamEvent::End(&full_response));
     Ok(full_response)
 }
@@ -1,9 +1,21 @@
-use reqwest::Client;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
 
+use crate::capture::audio::AudioEngineState;
+use crate::capture::dsp::DspOptions;
 use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+use crate::integrations::ConnectionTest;
+use crate::session::manager::TranscriptEntry;
+use crate::session::storage_actor::StorageActor;
 
-type ConfigState = std::sync::Arc<parking_lot::Mutex<AppConfig>>;
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+/// Whisper rejects uploads larger than 25 MB, keep each chunk comfortably below that
+pub const MAX_UPLOAD_BYTES: usize = 24 * 1024 * 1024;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptSegment {
@@ -12,60 +24,421 @@ pub struct TranscriptSegment {
     pub end: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum STTProvider {
+    OpenAIWhisper,
+    OpenAIGpt4oTranscribe,
+}
+
+impl STTProvider {
+    fn model(&self) -> &'static str {
+        match self {
+            STTProvider::OpenAIWhisper => "whisper-1",
+            STTProvider::OpenAIGpt4oTranscribe => "gpt-4o-transcribe",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DiffOp {
+    Equal,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptDiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetranscriptionResult {
+    pub session_id: String,
+    pub provider: STTProvider,
+    pub old_transcript: Vec<TranscriptEntry>,
+    pub new_transcript: Vec<TranscriptEntry>,
+    pub diff: Vec<TranscriptDiffLine>,
+    pub applied: bool,
+    /// Spans that couldn't be transcribed; the stored lines are kept for them
+    pub failed_chunks: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct WhisperResponse {
     text: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct VerboseWhisperResponse {
+    #[serde(default)]
+    segments: Vec<TranscriptSegment>,
+}
+
 /// Transcribe audio using OpenAI Whisper API
 pub async fn transcribe_with_openai(
     config: &AppConfig,
     audio_wav: Vec<u8>,
-) -> Result<String, String> {
-    if config.openai_api_key.is_empty() {
-        return Err("OpenAI API key not configured".to_string());
-    }
-
-    let client = Client::new();
+) -> Result<String, VenkyError> {
+    transcribe_with_openai_model(config, audio_wav, STTProvider::OpenAIWhisper.model()).await
+}
 
+async fn transcribe_with_openai_model(
+    config: &AppConfig,
+    audio_wav: Vec<u8>,
+    model: &str,
+) -> Result<String, VenkyError> {
     let part = reqwest::multipart::Part::bytes(audio_wav)
         .file_name("audio.wav")
         .mime_str("audio/wav")
-        .map_err(|e| format!("MIME error: {}", e))?;
+        .map_err(|e| VenkyError::Other(format!("MIME error: {}", e)))?;
+    let response = whisper_request(config, part, model, "json").await?;
+
+    let result: WhisperResponse = response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Failed to parse Whisper response: {}", e)))?;
+
+    Ok(result.text)
+}
+
+/// Transcribe an encoded audio file (m4a, mp3, ...) with Whisper, keeping segment timings
+pub async fn transcribe_file_segments(
+    config: &AppConfig,
+    audio: Vec<u8>,
+    file_name: &str,
+    mime: &str,
+) -> Result<Vec<TranscriptSegment>, VenkyError> {
+    if audio.len() > MAX_UPLOAD_BYTES {
+        return Err(VenkyError::Other(
+            "Audio file is too large to transcribe in one upload".to_string(),
+        ));
+    }
+    let part = reqwest::multipart::Part::bytes(audio)
+        .file_name(file_name.to_string())
+        .mime_str(mime)
+        .map_err(|e| VenkyError::Other(format!("MIME error: {}", e)))?;
+    // Segment timings are only returned by whisper-1
+    let response = whisper_request(
+        config,
+        part,
+        STTProvider::OpenAIWhisper.model(),
+        "verbose_json",
+    )
+    .await?;
+
+    let result: VerboseWhisperResponse = response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Failed to parse Whisper response: {}", e)))?;
+
+    Ok(result.segments)
+}
+
+async fn whisper_request(
+    config: &AppConfig,
+    file: reqwest::multipart::Part,
+    model: &str,
+    response_format: &str,
+) -> Result<reqwest::Response, VenkyError> {
+    if config.openai_api_key.is_empty() {
+        return Err(VenkyError::Provider(
+            "OpenAI API key not configured".to_string(),
+        ));
+    }
 
     let form = reqwest::multipart::Form::new()
-        .text("model", "whisper-1")
+        .text("model", model.to_string())
         .text("language", "en")
-        .text("response_format", "json")
-        .part("file", part);
+        .text("response_format", response_format.to_string())
+        .part("file", file);
 
-    let response = client
+    let response = crate::http::client()
         .post("https://api.openai.com/v1/audio/transcriptions")
         .header("Authorization", format!("Bearer {}", config.openai_api_key))
         .multipart(form)
         .send()
         .await
-        .map_err(|e| format!("Whisper API request failed: {}", e))?;
+        .map_err(|e| VenkyError::Network(format!("Whisper API request failed: {}", e)))?;
 
     let status = response.status();
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
-        return Err(format!("Whisper API error ({}): {}", status, body));
+        return Err(VenkyError::Provider(format!(
+            "Whisper API error ({}): {}",
+            status, body
+        )));
     }
-
-    let result: WhisperResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Whisper response: {}", e))?;
-
-    Ok(result.text)
+    Ok(response)
 }
 
 #[tauri::command]
 pub async fn transcribe_audio(
     config: tauri::State<'_, ConfigState>,
-) -> Result<String, String> {
-    let audio_wav = crate::capture::audio::get_audio_wav_bytes()?;
+    engine: tauri::State<'_, AudioEngineState>,
+) -> CommandResult<String> {
     let cfg = config.lock().clone();
+    let dsp = DspOptions::from_config(&cfg);
+    let audio_wav = crate::capture::audio::get_audio_wav_bytes(&engine, cfg.stt_sample_rate, &dsp)?;
     transcribe_with_openai(&cfg, audio_wav).await
 }
+
+/// Check that the OpenAI key can use `stt_provider`'s model, without uploading any audio
+#[tauri::command]
+pub async fn test_stt_provider(
+    config: tauri::State<'_, ConfigState>,
+    stt_provider: STTProvider,
+) -> CommandResult<ConnectionTest> {
+    let api_key = config.lock().openai_api_key.clone();
+    let model = stt_provider.model();
+    Ok(ConnectionTest::run(async {
+        super::openai::check_model(&api_key, model).await?;
+        Ok(format!("{} is available", model))
+    })
+    .await)
+}
+
+/// Re-run STT over a session's persisted recordings and diff it against the stored transcript.
+/// The stored transcript is only replaced when `apply` is set.
+#[tauri::command]
+pub async fn retranscribe_session(
+    config: tauri::State<'_, ConfigState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+    stt_provider: STTProvider,
+    apply: Option<bool>,
+) -> CommandResult<RetranscriptionResult> {
+    let id = session_id.clone();
+    let (recordings, session) = storage
+        .call(move |s| Ok((s.get_session_audio(&id)?, s.get_session(&id)?)))
+        .await?;
+    if recordings.is_empty() {
+        return Err(VenkyError::Other(
+            "No saved audio for this session".to_string(),
+        ));
+    }
+    let old_transcript = session.transcript;
+
+    let cfg = config.lock().clone();
+
+    let mut timed_entries = Vec::new();
+    let mut kept = HashSet::new();
+    let mut failed_chunks = Vec::new();
+    for recording in recordings {
+        // Only the mic gets the denoise/AGC stage, system audio is already clean
+        let (speaker, dsp) = match recording.source.as_str() {
+            "mic" => ("You", DspOptions::from_config(&cfg)),
+            _ => ("Others", DspOptions::default()),
+        };
+        let started_at = recording
+            .started_at
+            .as_deref()
+            .unwrap_or(session.start_time.as_str());
+        let started_at = parse_timestamp(started_at).ok_or_else(|| {
+            VenkyError::Other(format!("Invalid recording start time: {}", started_at))
+        })?;
+        let chunks = read_recording_chunks(
+            std::path::Path::new(&recording.path),
+            cfg.stt_sample_rate,
+            &dsp,
+        )?;
+        for (offset_secs, duration_secs, wav) in chunks {
+            let start = started_at + chrono::Duration::seconds(offset_secs as i64);
+            let text = match transcribe_with_openai_model(&cfg, wav, stt_provider.model()).await {
+                Ok(text) => text,
+                Err(e) => {
+                    log::warn!(
+                        "Failed to re-transcribe {} audio at +{}s: {}",
+                        recording.source,
+                        offset_secs,
+                        e
+                    );
+                    failed_chunks.push(format!("{} at +{}s: {}", recording.source, offset_secs, e));
+                    // Keep what was transcribed live for the span instead
+                    let end = start + chrono::Duration::seconds(duration_secs as i64);
+                    for (at, entry) in
+                        entries_between(&old_transcript, &recording.source, start, end)
+                    {
+                        if kept.insert(entry.id.clone()) {
+                            timed_entries.push((at, entry.clone()));
+                        }
+                    }
+                    continue;
+                }
+            };
+            if text.trim().is_empty() {
+                continue;
+            }
+            timed_entries.push((
+                start,
+                TranscriptEntry {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    timestamp: start.to_rfc3339(),
+                    speaker: speaker.to_string(),
+                    text: text.trim().to_string(),
+                    source: Some(recording.source.clone()),
+                },
+            ));
+        }
+    }
+    timed_entries.sort_by_key(|(at, _)| *at);
+    let new_transcript: Vec<TranscriptEntry> =
+        timed_entries.into_iter().map(|(_, entry)| entry).collect();
+
+    let diff = diff_transcripts(&old_transcript, &new_transcript);
+
+    let applied = apply.unwrap_or(false);
+    if applied {
+        let (id, transcript) = (session_id.clone(), new_transcript.clone());
+        storage
+            .call(move |s| s.update_transcript(&id, &transcript))
+            .await?;
+    }
+
+    Ok(RetranscriptionResult {
+        session_id,
+        provider: stt_provider,
+        old_transcript,
+        new_transcript,
+        diff,
+        applied,
+        failed_chunks,
+    })
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Entries from `source` (or with no source recorded) timestamped in `[start, end)`
+fn entries_between<'a>(
+    transcript: &'a [TranscriptEntry],
+    source: &'a str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> impl Iterator<Item = (DateTime<Utc>, &'a TranscriptEntry)> + 'a {
+    transcript.iter().filter_map(move |entry| {
+        let at = parse_timestamp(&entry.timestamp)?;
+        let same_source = entry.source.as_deref().map_or(true, |s| s == source);
+        (at >= start && at < end && same_source).then_some((at, entry))
+    })
+}
+
+/// Split a recording into mono WAV chunks that fit the upload limit, tagged with their start
+/// offset and length in seconds
+fn read_recording_chunks(
+    path: &std::path::Path,
+    target_rate: u32,
+    dsp: &DspOptions,
+) -> Result<Vec<(u32, u32, Vec<u8>)>, VenkyError> {
+    let (samples, sample_rate) = crate::capture::recording::read_recording(path)?;
+    let samples = crate::capture::dsp::process_clip(samples, sample_rate, dsp);
+    let (mono, rate) =
+        crate::capture::audio::prepare_for_stt(&samples, 1, sample_rate, target_rate);
+
+    // Encoded as 16-bit mono, so two bytes per sample
+    let samples_per_chunk = MAX_UPLOAD_BYTES / 2;
+    let mut chunks = Vec::new();
+    for (i, chunk) in mono.chunks(samples_per_chunk).enumerate() {
+        let offset_secs = (i * samples_per_chunk) as u32 / rate;
+        let duration_secs = (chunk.len() as u32).div_ceil(rate);
+        let wav = crate::capture::audio::encode_wav(chunk, rate)?;
+        chunks.push((offset_secs, duration_secs, wav));
+    }
+
+    Ok(chunks)
+}
+
+fn split_sentences(transcript: &[TranscriptEntry]) -> Vec<String> {
+    let mut sentences = Vec::new();
+    // Pause/resume markers aren't speech and never show up in a re-transcription
+    for entry in transcript
+        .iter()
+        .filter(|e| e.speaker != crate::session::manager::TIMELINE_SPEAKER)
+    {
+        let mut current = String::new();
+        for ch in entry.text.chars() {
+            current.push(ch);
+            if matches!(ch, '.' | '?' | '!') {
+                let sentence = current.trim().to_string();
+                if !sentence.is_empty() {
+                    sentences.push(sentence);
+                }
+                current.clear();
+            }
+        }
+        let rest = current.trim();
+        if !rest.is_empty() {
+            sentences.push(rest.to_string());
+        }
+    }
+    sentences
+}
+
+fn normalize_sentence(sentence: &str) -> String {
+    sentence
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Sentence-level LCS diff between two transcripts
+fn diff_transcripts(old: &[TranscriptEntry], new: &[TranscriptEntry]) -> Vec<TranscriptDiffLine> {
+    let a = split_sentences(old);
+    let b = split_sentences(new);
+    let na: Vec<String> = a.iter().map(|s| normalize_sentence(s)).collect();
+    let nb: Vec<String> = b.iter().map(|s| normalize_sentence(s)).collect();
+
+    let (n, m) = (a.len(), b.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if na[i] == nb[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if na[i] == nb[j] {
+            diff.push(TranscriptDiffLine {
+                op: DiffOp::Equal,
+                text: b[j].clone(),
+            });
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(TranscriptDiffLine {
+                op: DiffOp::Removed,
+                text: a[i].clone(),
+            });
+            i += 1;
+        } else {
+            diff.push(TranscriptDiffLine {
+                op: DiffOp::Added,
+                text: b[j].clone(),
+            });
+            j += 1;
+        }
+    }
+    diff.extend(a[i..].iter().map(|s| TranscriptDiffLine {
+        op: DiffOp::Removed,
+        text: s.clone(),
+    }));
+    diff.extend(b[j..].iter().map(|s| TranscriptDiffLine {
+        op: DiffOp::Added,
+        text: s.clone(),
+    }));
+
+    diff
+}
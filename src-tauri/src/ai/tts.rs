@@ -0,0 +1,321 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::capture::audio::resample_linear;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+const OPENAI_TTS_MODEL: &str = "tts-1";
+/// OpenAI's "pcm" output: 16-bit mono at this rate
+const OPENAI_SAMPLE_RATE: u32 = 24_000;
+/// Used when a Piper voice has no readable .onnx.json next to it
+const PIPER_DEFAULT_SAMPLE_RATE: u32 = 22_050;
+/// OpenAI rejects longer input
+const MAX_CHARS: usize = 4096;
+
+/// Bumped whenever speech starts or stops, so older speech notices and goes quiet
+static PLAYBACK: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum TtsProvider {
+    #[default]
+    OpenAI,
+    /// Local Piper voices, nothing leaves the machine
+    Piper,
+}
+
+/// Mono speech samples
+struct Speech {
+    samples: Vec<f32>,
+    sample_rate: u32,
+}
+
+fn pcm16(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+        .collect()
+}
+
+/// Markdown from the suggestion, and the line breaks Piper would read as separate
+/// utterances, taken out
+fn speakable(text: &str) -> String {
+    let text = text
+        .replace(['*', '#', '`'], "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    text.chars().take(MAX_CHARS).collect()
+}
+
+async fn synthesize_openai(cfg: &AppConfig, text: &str, voice: &str) -> Result<Speech, VenkyError> {
+    if cfg.openai_api_key.is_empty() {
+        return Err(VenkyError::Provider(
+            "OpenAI API key not configured".to_string(),
+        ));
+    }
+
+    let response = crate::http::client()
+        .post("https://api.openai.com/v1/audio/speech")
+        .header("Authorization", format!("Bearer {}", cfg.openai_api_key))
+        .json(&serde_json::json!({
+            "model": OPENAI_TTS_MODEL,
+            "input": text,
+            "voice": voice,
+            "response_format": "pcm",
+            "speed": cfg.tts_speed,
+        }))
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("TTS request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "TTS API error ({}): {}",
+            status, body
+        )));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| VenkyError::Network(format!("Failed to read TTS audio: {}", e)))?;
+    Ok(Speech {
+        samples: pcm16(&bytes),
+        sample_rate: OPENAI_SAMPLE_RATE,
+    })
+}
+
+/// The sample rate in a Piper voice's .onnx.json
+fn piper_sample_rate(model: &str) -> u32 {
+    std::fs::read_to_string(format!("{}.json", model))
+        .ok()
+        .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+        .and_then(|config| config.pointer("/audio/sample_rate")?.as_u64())
+        .map(|rate| rate as u32)
+        .unwrap_or(PIPER_DEFAULT_SAMPLE_RATE)
+}
+
+fn synthesize_piper(cfg: &AppConfig, text: &str, model: &str) -> Result<Speech, VenkyError> {
+    if !Path::new(model).exists() {
+        return Err(VenkyError::Other(format!(
+            "Piper voice model {} not found",
+            model
+        )));
+    }
+    let program = match cfg.piper_path.trim() {
+        "" => "piper",
+        path => path,
+    };
+
+    let mut child = Command::new(program)
+        .args(["--model", model, "--output_raw"])
+        .args(["--length_scale", &format!("{:.2}", 1.0 / cfg.tts_speed)])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| VenkyError::Audio(format!("Failed to start Piper ({}): {}", program, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .and_then(|_| stdin.write_all(b"\n"))
+            .map_err(|e| VenkyError::Audio(format!("Failed to send text to Piper: {}", e)))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| VenkyError::Audio(format!("Piper failed: {}", e)))?;
+    if !output.status.success() {
+        return Err(VenkyError::Audio(format!(
+            "Piper failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(Speech {
+        samples: pcm16(&output.stdout),
+        sample_rate: piper_sample_rate(model),
+    })
+}
+
+/// `voice` is an OpenAI voice name or a Piper .onnx model; None uses the configured one
+async fn synthesize(
+    cfg: &AppConfig,
+    text: &str,
+    voice: Option<&str>,
+) -> Result<Speech, VenkyError> {
+    let voice = voice
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .unwrap_or(cfg.tts_voice.trim())
+        .to_string();
+    match cfg.tts_provider {
+        TtsProvider::OpenAI => synthesize_openai(cfg, text, &voice).await,
+        TtsProvider::Piper => {
+            let cfg = cfg.clone();
+            let text = text.to_string();
+            tokio::task::spawn_blocking(move || synthesize_piper(&cfg, &text, &voice))
+                .await
+                .map_err(|e| VenkyError::Audio(format!("Piper failed: {}", e)))?
+        }
+    }
+}
+
+/// The output device with this name, falling back to the system default
+fn output_device(name: Option<&str>) -> Result<cpal::Device, VenkyError> {
+    let host = cpal::default_host();
+    if let Some(name) = name {
+        let found = host
+            .output_devices()
+            .map_err(|e| VenkyError::Audio(format!("Failed to list output devices: {}", e)))?
+            .find(|d| d.name().ok().as_deref() == Some(name));
+        match found {
+            Some(device) => return Ok(device),
+            None => log::warn!("Output device \"{}\" not found, using the default", name),
+        }
+    }
+    host.default_output_device()
+        .ok_or_else(|| VenkyError::Audio("No output device available".to_string()))
+}
+
+/// Play `speech` and block until it ends or newer speech replaces it. The stream stays on
+/// this thread since streams aren't `Send` on every platform.
+fn play(
+    speech: Speech,
+    device_name: Option<&str>,
+    volume: f32,
+    generation: u64,
+) -> Result<(), VenkyError> {
+    let device = output_device(device_name)?;
+    let config = device
+        .default_output_config()
+        .map_err(|e| VenkyError::Audio(format!("Failed to get output config: {}", e)))?;
+    let channels = config.channels().max(1) as usize;
+    let samples: Arc<Vec<f32>> = Arc::new(
+        resample_linear(&speech.samples, speech.sample_rate, config.sample_rate().0)
+            .into_iter()
+            .map(|s| s * volume)
+            .collect(),
+    );
+    let total = samples.len();
+    let position = Arc::new(AtomicUsize::new(0));
+
+    let next = {
+        let samples = samples.clone();
+        let position = position.clone();
+        move || {
+            let i = position.fetch_add(1, Ordering::Relaxed);
+            samples.get(i).copied().unwrap_or(0.0)
+        }
+    };
+    let on_error = |err: cpal::StreamError| log::error!("Speech output error: {}", err);
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => device
+            .build_output_stream(
+                &config.into(),
+                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                    for frame in data.chunks_mut(channels) {
+                        frame.fill(next());
+                    }
+                },
+                on_error,
+                None,
+            )
+            .map_err(|e| VenkyError::Audio(format!("Failed to build output stream: {}", e)))?,
+        cpal::SampleFormat::I16 => device
+            .build_output_stream(
+                &config.into(),
+                move |data: &mut [i16], _: &cpal::OutputCallbackInfo| {
+                    for frame in data.chunks_mut(channels) {
+                        frame.fill((next() * 32767.0) as i16);
+                    }
+                },
+                on_error,
+                None,
+            )
+            .map_err(|e| VenkyError::Audio(format!("Failed to build output stream: {}", e)))?,
+        format => {
+            return Err(VenkyError::Audio(format!(
+                "Unsupported output format: {:?}",
+                format
+            )));
+        }
+    };
+    stream
+        .play()
+        .map_err(|e| VenkyError::Audio(format!("Failed to start output stream: {}", e)))?;
+
+    while position.load(Ordering::Relaxed) < total && PLAYBACK.load(Ordering::SeqCst) == generation
+    {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    // Let the device drain its last buffer
+    std::thread::sleep(Duration::from_millis(150));
+    Ok(())
+}
+
+/// Speak `text` on the configured output device, cutting off anything still being spoken
+pub async fn speak(app: &AppHandle, text: &str, voice: Option<&str>) -> Result<(), VenkyError> {
+    let cfg = app.state::<ConfigState>().lock().clone();
+    let text = speakable(text);
+    let generation = PLAYBACK.fetch_add(1, Ordering::SeqCst) + 1;
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    let speech = synthesize(&cfg, &text, voice).await?;
+    // Replaced or stopped while the audio was being generated
+    if PLAYBACK.load(Ordering::SeqCst) != generation {
+        return Ok(());
+    }
+    let device = cfg.tts_output_device.clone();
+    tokio::task::spawn_blocking(move || play(speech, device.as_deref(), cfg.tts_volume, generation))
+        .await
+        .map_err(|e| VenkyError::Audio(format!("Speech playback failed: {}", e)))?
+}
+
+/// Read a new suggestion aloud when auto-speak is on
+pub fn speak_if_enabled(app: &AppHandle, text: &str) {
+    if !app.state::<ConfigState>().lock().tts_auto_speak {
+        return;
+    }
+    let app = app.clone();
+    let text = text.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = speak(&app, &text, None).await {
+            log::warn!("Failed to speak suggestion: {}", e);
+        }
+    });
+}
+
+/// Speak `text` with the configured provider; `voice` overrides the configured voice
+#[tauri::command]
+pub async fn speak_text(app: AppHandle, text: String, voice: Option<String>) -> CommandResult<()> {
+    speak(&app, &text, voice.as_deref()).await
+}
+
+/// Stop whatever is being spoken
+#[tauri::command]
+pub fn stop_speaking() {
+    PLAYBACK.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Names of the output devices speech can be routed to
+#[tauri::command]
+pub fn list_output_devices() -> CommandResult<Vec<String>> {
+    let devices = cpal::default_host()
+        .output_devices()
+        .map_err(|e| VenkyError::Audio(format!("Failed to list output devices: {}", e)))?;
+    Ok(devices.filter_map(|d| d.name().ok()).collect())
+}
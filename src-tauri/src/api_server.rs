@@ -0,0 +1,340 @@
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+
+use crate::ai::AIResponse;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+use crate::integrations::keychain;
+use crate::session::manager::{Session, SessionManager};
+use crate::session::storage_actor::StorageActor;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+pub type ApiServerState = Arc<Mutex<ApiServer>>;
+
+/// Keychain account holding the API token
+const TOKEN_KEYCHAIN_ACCOUNT: &str = "api-server-token";
+/// Events a slow WebSocket client can fall behind by before it skips ahead
+const EVENT_BUFFER: usize = 256;
+/// How long a restart waits for the previous server's requests to finish
+const SHUTDOWN_GRACE_SECS: u64 = 5;
+
+/// The optional localhost API for stream decks, OBS overlays and scripts
+pub struct ApiServer {
+    cancel: Option<CancellationToken>,
+    /// The last server started, awaited before its port is bound again
+    task: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// Port the current server is listening on, once it has bound
+    listening: Arc<Mutex<Option<u16>>>,
+    /// JSON events fanned out to every WebSocket client
+    events: broadcast::Sender<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    pub url: Option<String>,
+    /// Sent as `Authorization: Bearer <token>` or `?token=<token>`
+    pub token: String,
+}
+
+#[derive(Clone)]
+struct ApiContext {
+    app: AppHandle,
+    token: String,
+    events: broadcast::Sender<String>,
+    /// Closes WebSocket streams so a stopping server isn't held open by them
+    shutdown: CancellationToken,
+}
+
+#[derive(Serialize)]
+struct StreamEvent<'a, T: Serialize> {
+    event: &'a str,
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct AskRequest {
+    question: String,
+}
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+fn api_token() -> Result<String, VenkyError> {
+    if let Some(token) = keychain::get_secret(TOKEN_KEYCHAIN_ACCOUNT)? {
+        return Ok(token);
+    }
+    regenerate_token()
+}
+
+fn regenerate_token() -> Result<String, VenkyError> {
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    keychain::set_secret(TOKEN_KEYCHAIN_ACCOUNT, Some(&token))?;
+    Ok(token)
+}
+
+impl ApiServer {
+    pub fn new() -> Self {
+        Self {
+            cancel: None,
+            task: None,
+            listening: Arc::new(Mutex::new(None)),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        }
+    }
+
+    /// (Re)start the server if it is enabled in the config, stopping any running one
+    pub fn start(&mut self, app: &AppHandle) {
+        self.stop();
+        let (enabled, port) = {
+            let cfg = app.state::<ConfigState>();
+            let cfg = cfg.lock();
+            (cfg.api_server, cfg.api_server_port)
+        };
+        if !enabled {
+            return;
+        }
+        let token = match api_token() {
+            Ok(token) => token,
+            Err(e) => {
+                log::error!("API server not started: {}", e);
+                return;
+            }
+        };
+
+        let cancel = CancellationToken::new();
+        let shutdown = cancel.clone();
+        let context = ApiContext {
+            app: app.clone(),
+            token,
+            events: self.events.clone(),
+            shutdown: cancel.clone(),
+        };
+        let previous = self.task.take();
+        let listening = self.listening.clone();
+        self.task = Some(tauri::async_runtime::spawn(async move {
+            // The old server has to let go of the port before it can be bound again
+            if let Some(previous) = previous {
+                let grace = std::time::Duration::from_secs(SHUTDOWN_GRACE_SECS);
+                if tokio::time::timeout(grace, previous).await.is_err() {
+                    log::warn!("Previous API server is still finishing requests");
+                }
+            }
+            if shutdown.is_cancelled() {
+                return;
+            }
+            // Loopback only; nothing on the network can reach it
+            let listener = match tokio::net::TcpListener::bind(("127.0.0.1", port)).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    log::error!("API server could not listen on port {}: {}", port, e);
+                    return;
+                }
+            };
+            log::info!("API server listening on http://127.0.0.1:{}", port);
+            *listening.lock() = Some(port);
+            if let Err(e) = axum::serve(listener, router(context))
+                .with_graceful_shutdown(shutdown.cancelled_owned())
+                .await
+            {
+                log::error!("API server stopped: {}", e);
+            }
+            *listening.lock() = None;
+        }));
+        self.cancel = Some(cancel);
+    }
+
+    /// Ask the server to shut down. Its task is kept so the next `start` can wait for it.
+    pub fn stop(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            cancel.cancel();
+        }
+        // A fresh slot, so the old server clearing its own can't touch the next one's
+        self.listening = Arc::new(Mutex::new(None));
+    }
+
+    fn status(&self) -> Result<ApiServerStatus, VenkyError> {
+        let port = *self.listening.lock();
+        Ok(ApiServerStatus {
+            running: port.is_some(),
+            url: port.map(|port| format!("http://127.0.0.1:{}", port)),
+            token: api_token()?,
+        })
+    }
+}
+
+impl Default for ApiServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn restart(app: &AppHandle) {
+    app.state::<ApiServerState>().lock().start(app);
+}
+
+/// Send an event to connected WebSocket clients, if there are any
+pub fn publish<T: Serialize>(app: &AppHandle, event: &str, data: T) {
+    let events = app.state::<ApiServerState>().lock().events.clone();
+    if events.receiver_count() == 0 {
+        return;
+    }
+    if let Ok(json) = serde_json::to_string(&StreamEvent { event, data }) {
+        let _ = events.send(json);
+    }
+}
+
+fn router(context: ApiContext) -> Router {
+    Router::new()
+        .route("/api/sessions", get(active_sessions))
+        .route("/api/sessions/current", get(current_session))
+        .route("/api/sessions/:id", get(session))
+        .route("/api/ask", post(ask))
+        .route("/api/stream", get(stream))
+        .layer(middleware::from_fn_with_state(context.clone(), authorize))
+        .with_state(context)
+}
+
+/// Require the API token as a bearer token, or as `?token=` where headers can't be set
+/// (browser WebSockets, OBS browser sources)
+async fn authorize(
+    State(context): State<ApiContext>,
+    Query(query): Query<HashMap<String, String>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let presented = bearer.or(query.get("token").map(String::as_str));
+    if !presented.is_some_and(|token| tokens_match(token, &context.token)) {
+        return (StatusCode::UNAUTHORIZED, "Invalid or missing API token").into_response();
+    }
+    next.run(request).await
+}
+
+/// Compare every byte instead of stopping at the first difference, so response times
+/// don't give away how much of a guessed token was right
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    let (presented, expected) = (presented.as_bytes(), expected.as_bytes());
+    presented.len() == expected.len()
+        && presented
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn active_sessions(State(context): State<ApiContext>) -> Json<Vec<Session>> {
+    let state = context.app.state::<SessionState>();
+    let mut sessions: Vec<Session> = state.lock().sessions.values().cloned().collect();
+    sessions.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+    Json(sessions)
+}
+
+async fn current_session(State(context): State<ApiContext>) -> ApiResult<Session> {
+    let state = context.app.state::<SessionState>();
+    let focused = state.lock().focused().cloned();
+    focused
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "No active session".to_string()))
+}
+
+/// A live session, or a saved one
+async fn session(State(context): State<ApiContext>, Path(id): Path<String>) -> ApiResult<Session> {
+    let state = context.app.state::<SessionState>();
+    let live = state.lock().sessions.get(&id).cloned();
+    if let Some(session) = live {
+        return Ok(Json(session));
+    }
+    let storage = context.app.state::<StorageActor>().inner().clone();
+    storage
+        .call(move |s| s.get_session(&id))
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))
+}
+
+async fn ask(
+    State(context): State<ApiContext>,
+    Json(request): Json<AskRequest>,
+) -> ApiResult<AIResponse> {
+    if request.question.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Question is empty".to_string()));
+    }
+    let cfg = context.app.state::<ConfigState>().lock().clone();
+    crate::ai::llm::ask(&cfg, &request.question)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))
+}
+
+/// WebSocket of live events: transcript entries, sessions starting and ending
+async fn stream(State(context): State<ApiContext>, ws: WebSocketUpgrade) -> Response {
+    let events = context.events.subscribe();
+    ws.on_upgrade(move |socket| forward_events(socket, events, context.shutdown))
+}
+
+async fn forward_events(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<String>,
+    shutdown: CancellationToken,
+) {
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            event = events.recv() => match event {
+                Ok(json) => {
+                    if socket.send(Message::Text(json)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("API stream client fell behind by {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_api_server_status(
+    server: tauri::State<'_, ApiServerState>,
+) -> CommandResult<ApiServerStatus> {
+    server.lock().status()
+}
+
+/// Replace the API token, disconnecting clients that use the old one
+#[tauri::command]
+pub fn regenerate_api_token(app: AppHandle) -> CommandResult<ApiServerStatus> {
+    regenerate_token()?;
+    let server = app.state::<ApiServerState>();
+    let mut server = server.lock();
+    if server.cancel.is_some() {
+        server.start(&app);
+    }
+    server.status()
+}
@@ -0,0 +1,40 @@
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+/// Passed by the login item so the app comes up in the tray without the overlay
+pub const HIDDEN_FLAG: &str = "--hidden";
+
+/// Make the OS login item match `enabled`
+pub fn sync(app: &AppHandle, enabled: bool) {
+    let launcher = app.autolaunch();
+    let current = launcher.is_enabled().unwrap_or(false);
+    if current == enabled {
+        return;
+    }
+    let result = if enabled {
+        launcher.enable()
+    } else {
+        launcher.disable()
+    };
+    match result {
+        Ok(()) => log::info!("Launch at login {}", if enabled { "on" } else { "off" }),
+        Err(e) => log::error!("Failed to update launch at login: {}", e),
+    }
+}
+
+/// Whether this launch should stay in the tray: started at login, or asked to in settings
+pub fn start_hidden(cfg: &AppConfig) -> bool {
+    cfg.start_hidden || std::env::args().any(|arg| arg == HIDDEN_FLAG)
+}
+
+/// Whether the OS will actually start VenkyAI at login
+#[tauri::command]
+pub fn get_launch_at_login(app: AppHandle) -> CommandResult<bool> {
+    Ok(app
+        .autolaunch()
+        .is_enabled()
+        .map_err(|e| VenkyError::Other(format!("Failed to read launch at login: {}", e)))?)
+}
@@ -0,0 +1,573 @@
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use crate::config::{AppConfig, CONFIG_VERSION};
+use crate::error::{CommandResult, VenkyError};
+use crate::session::manager::SessionManager;
+use crate::session::storage_actor::StorageActor;
+use crate::settings_bundle::{check_kdf_rounds, derive_key, KDF_ROUNDS, MIN_PASSPHRASE_CHARS};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+const FORMAT: &str = "venkyai-backup";
+const BACKUP_VERSION: u32 = 1;
+/// Plaintext bytes per encrypted chunk, so large recordings never sit in memory whole
+const CHUNK_SIZE: usize = 1 << 20;
+const TAG_SIZE: usize = 16;
+const MANIFEST: &str = "manifest.json";
+const DATABASE: &str = "venkyai.db";
+const CONFIG: &str = "config.json";
+/// Folders under app data copied into a backup, with everything in them
+const DIRS: [&str; 3] = ["recordings", "captures", "profiles"];
+/// Where a backup is unpacked under app data before it replaces anything
+const STAGING_DIR: &str = "restore";
+/// Copy of the database taken under app data while a backup is written
+const SNAPSHOT: &str = "backup-snapshot.db";
+
+/// Unencrypted first line of a backup file, everything needed to decrypt the rest
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+    kdf_rounds: u32,
+    salt: String,
+    /// First 7 bytes of every chunk's nonce; the rest is the chunk number and a last-chunk flag
+    nonce_prefix: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSummary {
+    /// Version of VenkyAI that made the backup
+    pub app_version: String,
+    pub created_at: String,
+    pub sessions: usize,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    #[serde(flatten)]
+    summary: BackupSummary,
+    /// App data folder of the machine the backup was made on, to re-point stored file paths
+    app_data: String,
+    /// Random key the archived database is encrypted with; None in backups that stored it
+    /// unencrypted
+    #[serde(default)]
+    database_key: Option<String>,
+}
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, VenkyError> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), VenkyError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(VenkyError::Other(format!(
+            "The passphrase must be at least {} characters",
+            MIN_PASSPHRASE_CHARS
+        )));
+    }
+    Ok(())
+}
+
+/// Whether `version` is a later release than this build
+fn is_newer(version: &str) -> bool {
+    let parse = |v: &str| {
+        v.split('.')
+            .map(|part| part.trim().parse::<u64>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    };
+    parse(version) > parse(env!("CARGO_PKG_VERSION"))
+}
+
+fn nonce(prefix: &[u8; 7], counter: u32, last: bool) -> Nonce {
+    let mut nonce = [0u8; 12];
+    nonce[..7].copy_from_slice(prefix);
+    nonce[7..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    *Nonce::from_slice(&nonce)
+}
+
+/// Encrypts everything written to it in length-prefixed chunks. The last chunk is marked in
+/// its nonce, so a truncated file fails to decrypt instead of restoring part of the data.
+struct Encryptor<W: Write> {
+    out: W,
+    cipher: ChaCha20Poly1305,
+    prefix: [u8; 7],
+    counter: u32,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> Encryptor<W> {
+    fn seal(&mut self, chunk: &[u8], last: bool) -> io::Result<()> {
+        let sealed = self
+            .cipher
+            .encrypt(&nonce(&self.prefix, self.counter, last), chunk)
+            .map_err(|_| io::Error::other("Failed to encrypt backup"))?;
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| io::Error::other("Backup too large"))?;
+        self.out.write_all(&(sealed.len() as u32).to_be_bytes())?;
+        self.out.write_all(&sealed)
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        let rest = std::mem::take(&mut self.buf);
+        self.seal(&rest, true)?;
+        self.out.flush()
+    }
+}
+
+impl<W: Write> Write for Encryptor<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        // Hold back a full chunk until more arrives; only `finish` knows which one is last
+        while self.buf.len() > CHUNK_SIZE {
+            let rest = self.buf.split_off(CHUNK_SIZE);
+            let chunk = std::mem::replace(&mut self.buf, rest);
+            self.seal(&chunk, false)?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Length of the next chunk, or None at the end of the file
+fn read_len(input: &mut impl Read) -> io::Result<Option<u32>> {
+    let mut len = [0u8; 4];
+    let mut read = 0;
+    while read < len.len() {
+        match input.read(&mut len[read..])? {
+            0 if read == 0 => return Ok(None),
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => read += n,
+        }
+    }
+    Ok(Some(u32::from_be_bytes(len)))
+}
+
+struct Decryptor<R: Read> {
+    input: R,
+    cipher: ChaCha20Poly1305,
+    prefix: [u8; 7],
+    counter: u32,
+    next_len: Option<u32>,
+    plain: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read> Decryptor<R> {
+    fn new(mut input: R, cipher: ChaCha20Poly1305, prefix: [u8; 7]) -> io::Result<Self> {
+        let next_len = read_len(&mut input)?;
+        Ok(Self {
+            input,
+            cipher,
+            prefix,
+            counter: 0,
+            next_len,
+            plain: Vec::new(),
+            pos: 0,
+        })
+    }
+
+    /// Decrypt the next chunk; false once the last one has been read
+    fn open_next(&mut self) -> io::Result<bool> {
+        let Some(len) = self.next_len else {
+            return Ok(false);
+        };
+        if len as usize > CHUNK_SIZE + TAG_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Corrupt backup"));
+        }
+        let mut sealed = vec![0u8; len as usize];
+        self.input.read_exact(&mut sealed)?;
+        self.next_len = read_len(&mut self.input)?;
+        let last = self.next_len.is_none();
+        self.plain = self
+            .cipher
+            .decrypt(&nonce(&self.prefix, self.counter, last), sealed.as_slice())
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Wrong passphrase, or the backup is damaged",
+                )
+            })?;
+        self.counter = self.counter.wrapping_add(1);
+        self.pos = 0;
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for Decryptor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos >= self.plain.len() {
+            if !self.open_next()? {
+                return Ok(0);
+            }
+        }
+        let n = buf.len().min(self.plain.len() - self.pos);
+        buf[..n].copy_from_slice(&self.plain[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// One file in the archive: name length, name ('/'-separated, relative to app data), size, bytes
+fn write_entry(
+    out: &mut impl Write,
+    name: &str,
+    size: u64,
+    data: &mut impl Read,
+) -> io::Result<()> {
+    out.write_all(&(name.len() as u16).to_be_bytes())?;
+    out.write_all(name.as_bytes())?;
+    out.write_all(&size.to_be_bytes())?;
+    if io::copy(&mut data.take(size), out)? != size {
+        return Err(io::Error::other(format!(
+            "{} changed during the backup",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Name and size of the next file in the archive, or None after the last
+fn read_entry(input: &mut impl Read) -> io::Result<Option<(String, u64)>> {
+    let mut len = [0u8; 2];
+    input.read_exact(&mut len)?;
+    let len = u16::from_be_bytes(len) as usize;
+    if len == 0 {
+        return Ok(None);
+    }
+    let mut name = vec![0u8; len];
+    input.read_exact(&mut name)?;
+    let name = String::from_utf8(name)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Corrupt backup"))?;
+    let mut size = [0u8; 8];
+    input.read_exact(&mut size)?;
+    Ok(Some((name, u64::from_be_bytes(size))))
+}
+
+/// Every file under `dir`, named `prefix/...`
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), &name, files)?;
+        } else {
+            files.push((name, entry.path()));
+        }
+    }
+    Ok(())
+}
+
+/// Where an archived file is unpacked, refusing names that would escape the staging folder
+fn staged_path(staging: &Path, name: &str) -> Result<PathBuf, VenkyError> {
+    let mut path = staging.to_path_buf();
+    for part in name.split('/') {
+        if part.is_empty() || part == "." || part == ".." || part.contains(['\\', ':']) {
+            return Err(VenkyError::Other(format!(
+                "Corrupt backup: bad file name {}",
+                name
+            )));
+        }
+        path.push(part);
+    }
+    Ok(path)
+}
+
+fn write_archive(
+    path: &Path,
+    passphrase: &str,
+    manifest: &Manifest,
+    config: &[u8],
+    files: &[(String, PathBuf)],
+) -> io::Result<()> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let mut prefix = [0u8; 7];
+    OsRng.fill_bytes(&mut prefix);
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let header = Header {
+        format: FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        kdf_rounds: KDF_ROUNDS,
+        salt: b64.encode(salt),
+        nonce_prefix: b64.encode(prefix),
+    };
+
+    let mut out = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(&mut out, &header)?;
+    out.write_all(b"\n")?;
+
+    let mut archive = Encryptor {
+        out,
+        cipher: ChaCha20Poly1305::new(&derive_key(passphrase, &salt, KDF_ROUNDS)),
+        prefix,
+        counter: 0,
+        buf: Vec::with_capacity(CHUNK_SIZE * 2),
+    };
+    let manifest = serde_json::to_vec(manifest)?;
+    write_entry(
+        &mut archive,
+        MANIFEST,
+        manifest.len() as u64,
+        &mut manifest.as_slice(),
+    )?;
+    write_entry(&mut archive, CONFIG, config.len() as u64, &mut &config[..])?;
+    for (name, file) in files {
+        let mut file = File::open(file)?;
+        let size = file.metadata()?.len();
+        write_entry(&mut archive, name, size, &mut file)?;
+    }
+    archive.write_all(&0u16.to_be_bytes())?;
+    archive.finish()
+}
+
+/// The database snapshot followed by everything in the backed-up folders
+fn archive_files(app_data: &Path, snapshot: &Path) -> Result<Vec<(String, PathBuf)>, VenkyError> {
+    let mut files = vec![(DATABASE.to_string(), snapshot.to_path_buf())];
+    for dir in DIRS {
+        collect_files(&app_data.join(dir), dir, &mut files)
+            .map_err(|e| VenkyError::Storage(format!("Failed to read {}: {}", dir, e)))?;
+    }
+    Ok(files)
+}
+
+fn write_backup(
+    app: &AppHandle,
+    path: &Path,
+    passphrase: &str,
+) -> Result<BackupSummary, VenkyError> {
+    let app_data = app_data_dir(app)?;
+    let snapshot = app_data.join(SNAPSHOT);
+    let target = snapshot.clone();
+    let database_key = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let key = database_key.clone();
+    let sessions = app.state::<StorageActor>().call_blocking(move |s| {
+        s.snapshot(&target, &key)?;
+        Ok(s.get_all_sessions()?.len())
+    })?;
+    let config = serde_json::to_vec_pretty(&*app.state::<ConfigState>().lock())
+        .map_err(|e| VenkyError::Other(format!("Serialize error: {}", e)))?;
+
+    let result = archive_files(&app_data, &snapshot).and_then(|files| {
+        let mut bytes = config.len() as u64;
+        for (_, file) in &files {
+            bytes += std::fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+        }
+        let manifest = Manifest {
+            summary: BackupSummary {
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+                created_at: chrono::Utc::now().to_rfc3339(),
+                sessions,
+                files: files.len(),
+                bytes,
+            },
+            app_data: app_data.to_string_lossy().into_owned(),
+            database_key: Some(database_key),
+        };
+        if let Err(e) = write_archive(path, passphrase, &manifest, &config, &files) {
+            let _ = std::fs::remove_file(path);
+            return Err(VenkyError::Storage(format!(
+                "Failed to write backup: {}",
+                e
+            )));
+        }
+        Ok(manifest.summary)
+    });
+    let _ = std::fs::remove_file(&snapshot);
+    result
+}
+
+/// Decrypt the backup at `path` into `staging`, checking its versions before unpacking
+fn unpack(path: &Path, passphrase: &str, staging: &Path) -> Result<Manifest, VenkyError> {
+    let read_err = |e: io::Error| VenkyError::Storage(format!("Failed to read backup: {}", e));
+    let mut input = BufReader::new(File::open(path).map_err(read_err)?);
+    let mut line = String::new();
+    input.read_line(&mut line).map_err(read_err)?;
+    let header: Header = serde_json::from_str(&line)
+        .map_err(|_| VenkyError::Other("Not a VenkyAI backup".to_string()))?;
+    if header.format != FORMAT {
+        return Err(VenkyError::Other("Not a VenkyAI backup".to_string()));
+    }
+    if header.version > BACKUP_VERSION {
+        return Err(VenkyError::Other(
+            "This backup is from a newer version of VenkyAI".to_string(),
+        ));
+    }
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let salt = b64
+        .decode(&header.salt)
+        .map_err(|e| VenkyError::Other(format!("Corrupt backup: {}", e)))?;
+    let prefix: [u8; 7] = b64
+        .decode(&header.nonce_prefix)
+        .ok()
+        .and_then(|p| p.try_into().ok())
+        .ok_or_else(|| VenkyError::Other("Corrupt backup: bad nonce".to_string()))?;
+    check_kdf_rounds(header.kdf_rounds)?;
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt, header.kdf_rounds));
+    let mut archive = Decryptor::new(input, cipher, prefix).map_err(read_err)?;
+
+    let manifest = match read_entry(&mut archive).map_err(read_err)? {
+        Some((name, size)) if name == MANIFEST && size <= CHUNK_SIZE as u64 => {
+            let mut manifest = vec![0u8; size as usize];
+            archive.read_exact(&mut manifest).map_err(read_err)?;
+            serde_json::from_slice::<Manifest>(&manifest)
+                .map_err(|e| VenkyError::Other(format!("Corrupt backup: {}", e)))?
+        }
+        _ => return Err(VenkyError::Other("Corrupt backup: no manifest".to_string())),
+    };
+    if is_newer(&manifest.summary.app_version) {
+        return Err(VenkyError::Other(format!(
+            "This backup was made with VenkyAI {}; update to restore it",
+            manifest.summary.app_version
+        )));
+    }
+
+    let _ = std::fs::remove_dir_all(staging);
+    while let Some((name, size)) = read_entry(&mut archive).map_err(read_err)? {
+        let dest = staged_path(staging, &name)?;
+        if let Some(dir) = dest.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                VenkyError::Storage(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let mut file = File::create(&dest).map_err(|e| {
+            VenkyError::Storage(format!("Failed to write {}: {}", dest.display(), e))
+        })?;
+        let copied = io::copy(&mut (&mut archive).take(size), &mut file).map_err(read_err)?;
+        if copied != size {
+            return Err(VenkyError::Storage(
+                "Failed to read backup: it ends early".to_string(),
+            ));
+        }
+    }
+    Ok(manifest)
+}
+
+/// Swap this machine's data for what was unpacked into `staging`
+fn apply_backup(app: &AppHandle, staging: &Path, manifest: &Manifest) -> Result<(), VenkyError> {
+    let app_data = app_data_dir(app)?;
+
+    // Checked before anything is replaced, the way config.json is on startup
+    let content = std::fs::read_to_string(staging.join(CONFIG))
+        .map_err(|e| VenkyError::Other(format!("Corrupt backup: no config: {}", e)))?;
+    let (mut config, diagnostics) = AppConfig::from_json(&content)?;
+    config.config_version = CONFIG_VERSION;
+    config.validate()?;
+
+    let (database, database_key, old_root, new_root) = (
+        staging.join(DATABASE),
+        manifest.database_key.clone(),
+        manifest.app_data.clone(),
+        app_data.to_string_lossy().to_string(),
+    );
+    app.state::<StorageActor>().call_blocking(move |s| {
+        s.replace_with(&database, database_key.as_deref())?;
+        s.rebase_file_paths(&old_root, &new_root)
+    })?;
+
+    for dir in DIRS {
+        let target = app_data.join(dir);
+        if let Err(e) = std::fs::remove_dir_all(&target) {
+            if e.kind() != io::ErrorKind::NotFound {
+                return Err(VenkyError::Storage(format!(
+                    "Failed to delete {}: {}",
+                    target.display(),
+                    e
+                )));
+            }
+        }
+        let staged = staging.join(dir);
+        if staged.exists() {
+            std::fs::rename(&staged, &target)
+                .map_err(|e| VenkyError::Storage(format!("Failed to restore {}: {}", dir, e)))?;
+        }
+    }
+
+    for diagnostic in &diagnostics {
+        log::warn!("Restored config: {}", diagnostic.message);
+    }
+    config.save(&app_data)?;
+    crate::config::apply(app, &app.state::<ConfigState>(), config);
+    Ok(())
+}
+
+/// Delete a database snapshot or unpacked backup left behind when the app stopped during
+/// a backup or restore
+pub fn remove_leftovers(app_data: &Path) {
+    for file in [SNAPSHOT, "backup-snapshot.db-journal"] {
+        let _ = std::fs::remove_file(app_data.join(file));
+    }
+    let _ = std::fs::remove_dir_all(app_data.join(STAGING_DIR));
+}
+
+/// Write the database, saved audio and screen recordings, screenshots, profiles and config
+/// (API key included) to one file encrypted under `passphrase`. Credentials kept in the
+/// keychain, such as CRM and calendar sign-ins, are not included.
+#[tauri::command]
+pub async fn create_backup(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+) -> CommandResult<BackupSummary> {
+    check_passphrase(&passphrase)?;
+    Ok(
+        tokio::task::spawn_blocking(move || write_backup(&app, Path::new(&path), &passphrase))
+            .await
+            .map_err(|e| VenkyError::Other(format!("Backup failed: {}", e)))??,
+    )
+}
+
+/// Replace all sessions, recordings, screenshots, profiles and settings with those in a
+/// backup from `create_backup`, e.g. on a new machine. Refused while a session is running.
+#[tauri::command]
+pub async fn restore_backup(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+) -> CommandResult<BackupSummary> {
+    crate::session::retention::ensure_not_active(&app.state::<SessionState>(), None)?;
+    Ok(tokio::task::spawn_blocking(move || {
+        let staging = app_data_dir(&app)?.join(STAGING_DIR);
+        let result = unpack(Path::new(&path), &passphrase, &staging)
+            .and_then(|manifest| apply_backup(&app, &staging, &manifest).map(|_| manifest));
+        let _ = std::fs::remove_dir_all(&staging);
+        let manifest = result?;
+        log::info!(
+            "Restored backup made at {} by VenkyAI {}",
+            manifest.summary.created_at,
+            manifest.summary.app_version
+        );
+        Ok(manifest.summary)
+    })
+    .await
+    .map_err(|e| VenkyError::Other(format!("Restore failed: {}", e)))??)
+}
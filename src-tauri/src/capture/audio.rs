@@ -1,297 +1,865 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use parking_lot::Mutex;
 use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
 use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
 
-use super::CaptureState;
+use super::buffer::AudioRingBuffer;
+use super::dsp::DspOptions;
+use super::permissions::{grant_hint, PermissionKind};
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::consent::ConsentState;
+use crate::session::manager::{self, SessionManager};
 
-type CaptureStateHandle = Arc<Mutex<CaptureState>>;
+pub type AudioEngineState = Arc<Mutex<AudioEngine>>;
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
 
-/// Wrapper to make cpal::Stream Send+Sync (it is safe for our usage pattern)
-struct SendStream(cpal::Stream);
-unsafe impl Send for SendStream {}
-unsafe impl Sync for SendStream {}
+/// Buffer shared between a capture thread and the engine
+pub type SharedBuffer = Arc<Mutex<AudioRingBuffer>>;
 
-/// Shared audio buffer that collects samples during recording
-static AUDIO_BUFFER: once_cell::sync::Lazy<Arc<Mutex<Vec<f32>>>> =
-    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(Vec::new())));
-
-/// Active audio stream handle (kept alive while recording)
-static AUDIO_STREAM: once_cell::sync::Lazy<Arc<Mutex<Option<SendStream>>>> =
-    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(None)));
-
-static SAMPLE_RATE: once_cell::sync::Lazy<Arc<Mutex<u32>>> =
-    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(44100)));
-
-/// System audio buffer (from scab)
-static SYSTEM_AUDIO_BUFFER: once_cell::sync::Lazy<Arc<Mutex<Vec<f32>>>> =
-    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(Vec::new())));
-
-/// Handle for scap recorder thread
-static SYSTEM_AUDIO_THREAD: once_cell::sync::Lazy<Arc<Mutex<Option<std::thread::JoinHandle<()>>>>> =
-    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(None)));
-
-static STOP_SIGNAL: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+/// scap delivers system audio as 48 kHz interleaved stereo
+const SYSTEM_AUDIO_SAMPLE_RATE: u32 = 48000;
+const SYSTEM_AUDIO_CHANNELS: u16 = 2;
 
 #[derive(Debug, Serialize)]
 pub struct AudioStatus {
     pub is_recording: bool,
     pub is_recording_system: bool,
+    pub is_paused: bool,
+    pub is_system_paused: bool,
+    pub is_muted: bool,
     pub buffer_duration_secs: f32,
     pub system_buffer_duration_secs: f32,
     pub sample_rate: u32,
 }
 
-#[tauri::command]
-pub fn start_audio_capture(
-    state: tauri::State<'_, CaptureStateHandle>,
-) -> Result<String, String> {
-    let mut capture_state = state.lock();
-    if capture_state.is_recording_audio {
-        return Err("Already recording".to_string());
+/// A window whose audio can be captured on its own
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioTarget {
+    pub id: u32,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDeviceChange {
+    pub previous_device: Option<String>,
+    pub current_device: Option<String>,
+    pub reason: String,
+}
+
+/// Format of an opened mic stream
+struct MicFormat {
+    device_name: String,
+    sample_rate: u32,
+}
+
+/// A cpal stream parked on its own thread. Streams aren't `Send` on every platform,
+/// so the thread owns it for its whole life and drops it when told to stop.
+struct MicStream {
+    stop_tx: mpsc::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl MicStream {
+    fn stop(self) {
+        let _ = self.stop_tx.send(());
+        let _ = self.thread.join();
+    }
+}
+
+/// The scap polling thread for system audio
+struct SystemCapture {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+/// Owns every audio capture source and its buffer
+pub struct AudioEngine {
+    mic_buffer: SharedBuffer,
+    mic_stream: Option<MicStream>,
+    /// Whether the user wants the mic recorded; stays set while a lost device is being replaced
+    mic_active: bool,
+    mic_device: Option<String>,
+    /// Set by the stream error callback when the input device goes away
+    mic_failed: Arc<AtomicBool>,
+    /// Mirrors the user's mute in the meeting; mic audio is still recorded but not transcribed
+    mic_muted: bool,
+    system_buffer: SharedBuffer,
+    system_capture: Option<SystemCapture>,
+}
+
+impl AudioEngine {
+    pub fn new() -> Self {
+        Self {
+            mic_buffer: Arc::new(Mutex::new(AudioRingBuffer::new(44100, 1))),
+            mic_stream: None,
+            mic_active: false,
+            mic_device: None,
+            mic_failed: Arc::new(AtomicBool::new(false)),
+            mic_muted: false,
+            system_buffer: Arc::new(Mutex::new(AudioRingBuffer::new(
+                SYSTEM_AUDIO_SAMPLE_RATE,
+                SYSTEM_AUDIO_CHANNELS,
+            ))),
+            system_capture: None,
+        }
+    }
+
+    pub fn is_mic_active(&self) -> bool {
+        self.mic_active
+    }
+
+    pub fn is_system_active(&self) -> bool {
+        self.system_capture.is_some()
+    }
+
+    pub fn is_mic_muted(&self) -> bool {
+        self.mic_muted
+    }
+
+    /// Returns whether the mute state actually changed
+    pub fn set_mic_muted(&mut self, muted: bool) -> bool {
+        let changed = self.mic_muted != muted;
+        self.mic_muted = muted;
+        changed
+    }
+
+    pub fn mic_buffer(&self) -> SharedBuffer {
+        self.mic_buffer.clone()
+    }
+
+    /// Start recording the default input device.
+    /// When `spill_path` is set the full recording is also streamed to a WAV file there.
+    pub fn start_mic(&mut self, spill_path: Option<PathBuf>) -> Result<String, VenkyError> {
+        if self.mic_active {
+            return Err(VenkyError::Other("Already recording".to_string()));
+        }
+
+        // Clear previous buffer
+        {
+            let mut ring = self.mic_buffer.lock();
+            ring.clear();
+            ring.set_paused(false);
+        }
+
+        let format = self.open_mic(spill_path)?;
+        self.mic_active = true;
+
+        Ok(format!(
+            "Recording started (device: {}, sample rate: {}Hz)",
+            format.device_name, format.sample_rate
+        ))
+    }
+
+    /// Stop the mic stream. Returns the path of the full recording when spill-to-disk is enabled.
+    pub fn stop_mic(&mut self) -> Result<Option<String>, VenkyError> {
+        if !self.mic_active {
+            return Err(VenkyError::Other("Not recording".to_string()));
+        }
+
+        if let Some(stream) = self.mic_stream.take() {
+            stream.stop();
+        }
+        self.mic_active = false;
+        self.mic_device = None;
+        self.mic_muted = false;
+
+        let mut buffer = self.mic_buffer.lock();
+        if buffer.dropped() > 0 {
+            log::warn!(
+                "Audio ring overflowed, {} samples evicted",
+                buffer.dropped()
+            );
+        }
+        buffer.set_paused(false);
+        let path = buffer.finish_spill()?;
+        Ok(path.map(|p| p.to_string_lossy().to_string()))
+    }
+
+    /// Stop feeding mic samples into the buffer and recording, keeping the stream open
+    pub fn pause_mic(&mut self) -> Result<(), VenkyError> {
+        if !self.mic_active {
+            return Err(VenkyError::Other("Not recording".to_string()));
+        }
+        set_paused(&self.mic_buffer, true)
+    }
+
+    pub fn resume_mic(&mut self) -> Result<(), VenkyError> {
+        if !self.mic_active {
+            return Err(VenkyError::Other("Not recording".to_string()));
+        }
+        set_paused(&self.mic_buffer, false)
+    }
+
+    fn open_mic(&mut self, spill_path: Option<PathBuf>) -> Result<MicFormat, VenkyError> {
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let buffer = self.mic_buffer.clone();
+        let failed = self.mic_failed.clone();
+
+        let thread = std::thread::spawn(move || {
+            let stream = match build_mic_stream(buffer, failed, spill_path) {
+                Ok((stream, format)) => {
+                    let _ = ready_tx.send(Ok(format));
+                    stream
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            // Park until the engine asks us to stop (or goes away)
+            let _ = stop_rx.recv();
+            drop(stream);
+        });
+
+        let format = ready_rx
+            .recv()
+            .map_err(|_| VenkyError::Audio("Audio thread exited unexpectedly".to_string()))??;
+
+        self.mic_stream = Some(MicStream { stop_tx, thread });
+        self.mic_device = Some(format.device_name.clone());
+        self.mic_failed.store(false, Ordering::SeqCst);
+        Ok(format)
+    }
+
+    /// Reopen the mic on the current default device if the active one was lost or the
+    /// OS default changed. Returns the change to report, if any.
+    fn check_mic_device(&mut self) -> Option<AudioDeviceChange> {
+        if !self.mic_active {
+            return None;
+        }
+
+        let failed = self.mic_failed.load(Ordering::SeqCst);
+        let previous = self.mic_device.clone();
+        let current = cpal::default_host()
+            .default_input_device()
+            .and_then(|d| d.name().ok());
+
+        let reason = if failed {
+            "device-lost"
+        } else if current != previous {
+            "default-changed"
+        } else {
+            return None;
+        };
+
+        // Drop the dead or stale stream before opening a new one
+        if let Some(stream) = self.mic_stream.take() {
+            stream.stop();
+        }
+        self.mic_failed.store(false, Ordering::SeqCst);
+
+        let reopened = if current.is_some() {
+            match self.open_mic(None) {
+                Ok(format) => Some(format.device_name),
+                Err(e) => {
+                    log::error!("Failed to reopen audio input: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        if reopened.is_none() {
+            self.mic_device = None;
+            // Already reported the loss, keep waiting for a device to appear
+            if previous.is_none() {
+                return None;
+            }
+        }
+
+        log::info!(
+            "Audio input changed ({}): {:?} -> {:?}",
+            reason,
+            previous,
+            reopened
+        );
+        Some(AudioDeviceChange {
+            previous_device: previous,
+            current_device: reopened,
+            reason: reason.to_string(),
+        })
+    }
+
+    /// Start system audio capture. With `target` set, only audio from the first window whose
+    /// title contains it (case-insensitive) is captured.
+    pub fn start_system(
+        &mut self,
+        spill_path: Option<PathBuf>,
+        target: Option<&str>,
+    ) -> Result<String, VenkyError> {
+        // Check if system audio capture thread is already running
+        if self.system_capture.is_some() {
+            return Err(VenkyError::Other(
+                "System audio recording already active".to_string(),
+            ));
+        }
+
+        // Initialize scap
+        if !scap::has_permission() {
+            return Err(VenkyError::Permission(format!(
+                "System audio capture permission not granted. {}",
+                grant_hint(PermissionKind::SystemAudio)
+            )));
+        }
+
+        let (scap_target, target_title) = match target {
+            Some(name) => {
+                let window = find_window_target(name)?;
+                let title = window.title.clone();
+                (Some(scap::Target::Window(window)), Some(title))
+            }
+            None => (None, None),
+        };
+
+        let options = scap::capturer::Options {
+            fps: 1,
+            show_cursor: false,
+            captures_audio: true,
+            target: scap_target,
+            ..Default::default()
+        };
+
+        let buffer = self.system_buffer.clone();
+        {
+            let mut ring = buffer.lock();
+            ring.clear();
+            ring.set_paused(false);
+        }
+
+        let mut capturer = scap::capturer::Capturer::build(options)
+            .map_err(|e| VenkyError::Audio(format!("Failed to build scap capturer: {:?}", e)))?;
+        if let Some(ref path) = spill_path {
+            buffer.lock().start_spill(path)?;
+        }
+
+        capturer.start_capture();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_signal = stop.clone();
+
+        // Spawn a background thread to poll for audio frames
+        let thread = std::thread::spawn(move || {
+            while !stop_signal.load(Ordering::SeqCst) {
+                match capturer.get_next_frame() {
+                    Ok(frame) => {
+                        if let scap::frame::Frame::Audio(audio_frame) = frame {
+                            let data = audio_frame.raw_data();
+                            if matches!(audio_frame.format(), scap::frame::AudioFormat::F32) {
+                                let floats: Vec<f32> = data
+                                    .chunks_exact(4)
+                                    .map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+                                    .collect();
+                                buffer.lock().push(&floats);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Error getting next frame: {:?}", e);
+                        break;
+                    }
+                }
+            }
+            log::info!("System audio polling loop ended");
+        });
+
+        self.system_capture = Some(SystemCapture { stop, thread });
+
+        Ok(match target_title {
+            Some(title) => format!("System audio capture started (target: {})", title),
+            None => "System audio capture started".to_string(),
+        })
+    }
+
+    /// Stop system audio capture. Returns the path of the full recording when spill-to-disk is enabled.
+    pub fn stop_system(&mut self) -> Result<Option<String>, VenkyError> {
+        let capture = self
+            .system_capture
+            .take()
+            .ok_or_else(|| VenkyError::Other("System audio capture not active".to_string()))?;
+
+        capture.stop.store(true, Ordering::SeqCst);
+        let _ = capture.thread.join();
+
+        let mut buffer = self.system_buffer.lock();
+        buffer.set_paused(false);
+        let path = buffer.finish_spill()?;
+        Ok(path.map(|p| p.to_string_lossy().to_string()))
+    }
+
+    pub fn pause_system(&mut self) -> Result<(), VenkyError> {
+        if self.system_capture.is_none() {
+            return Err(VenkyError::Other(
+                "System audio capture not active".to_string(),
+            ));
+        }
+        set_paused(&self.system_buffer, true)
+    }
+
+    pub fn resume_system(&mut self) -> Result<(), VenkyError> {
+        if self.system_capture.is_none() {
+            return Err(VenkyError::Other(
+                "System audio capture not active".to_string(),
+            ));
+        }
+        set_paused(&self.system_buffer, false)
+    }
+
+    pub fn status(&self) -> AudioStatus {
+        let (buffer_len, (sr, channels)) = {
+            let ring = self.mic_buffer.lock();
+            (ring.len(), ring.format())
+        };
+        let (system_buffer_len, system_paused) = {
+            let ring = self.system_buffer.lock();
+            (ring.len(), ring.is_paused())
+        };
+
+        AudioStatus {
+            is_recording: self.mic_active,
+            is_recording_system: self.system_capture.is_some(),
+            is_paused: self.mic_active && self.mic_buffer.lock().is_paused(),
+            is_system_paused: self.system_capture.is_some() && system_paused,
+            is_muted: self.mic_muted,
+            buffer_duration_secs: buffer_len as f32 / channels as f32 / sr as f32,
+            system_buffer_duration_secs: system_buffer_len as f32
+                / SYSTEM_AUDIO_CHANNELS as f32
+                / SYSTEM_AUDIO_SAMPLE_RATE as f32,
+            sample_rate: sr,
+        }
+    }
+}
+
+/// Find a capturable window by case-insensitive title substring
+fn find_window_target(name: &str) -> Result<scap::Window, VenkyError> {
+    let needle = name.to_lowercase();
+    scap::get_all_targets()
+        .into_iter()
+        .find_map(|target| match target {
+            scap::Target::Window(window) if window.title.to_lowercase().contains(&needle) => {
+                Some(window)
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            VenkyError::Other(format!(
+                "No window matching \"{}\" to capture audio from",
+                name
+            ))
+        })
+}
+
+fn set_paused(buffer: &SharedBuffer, paused: bool) -> Result<(), VenkyError> {
+    let mut ring = buffer.lock();
+    if ring.is_paused() == paused {
+        return Err(if paused {
+            VenkyError::Other("Already paused".to_string())
+        } else {
+            VenkyError::Other("Not paused".to_string())
+        });
+    }
+    ring.set_paused(paused);
+    Ok(())
+}
+
+impl Default for AudioEngine {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
+/// Open the default input device and start pushing its samples into `buffer`
+fn build_mic_stream(
+    buffer: SharedBuffer,
+    failed: Arc<AtomicBool>,
+    spill_path: Option<PathBuf>,
+) -> Result<(cpal::Stream, MicFormat), VenkyError> {
     let host = cpal::default_host();
     let device = host
         .default_input_device()
-        .ok_or_else(|| "No input device available".to_string())?;
+        .ok_or_else(|| VenkyError::Audio("No input device available".to_string()))?;
 
     let config = device
         .default_input_config()
-        .map_err(|e| format!("Failed to get input config: {}", e))?;
+        .map_err(|e| VenkyError::Audio(format!("Failed to get input config: {}", e)))?;
 
     let sr = config.sample_rate().0;
-    *SAMPLE_RATE.lock() = sr;
-
-    // Clear previous buffer
-    AUDIO_BUFFER.lock().clear();
+    let channels = config.channels();
+
+    {
+        let mut ring = buffer.lock();
+        // Samples already buffered at a different rate/layout can't be mixed with the new stream
+        if ring.format() != (sr, channels) && !ring.is_empty() {
+            log::warn!(
+                "Input format changed, dropping {} buffered samples",
+                ring.len()
+            );
+            ring.clear();
+        }
+        ring.set_format(sr, channels);
+    }
+    let ring = buffer.clone();
 
-    let buffer = AUDIO_BUFFER.clone();
+    let on_error = move |err: cpal::StreamError| {
+        log::error!("Audio stream error: {}", err);
+        if let cpal::StreamError::DeviceNotAvailable = err {
+            failed.store(true, Ordering::SeqCst);
+        }
+    };
 
     let stream = match config.sample_format() {
-        cpal::SampleFormat::F32 => {
-            let stream = device
-                .build_input_stream(
-                    &config.into(),
-                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                        buffer.lock().extend_from_slice(data);
-                    },
-                    |err| {
-                        log::error!("Audio stream error: {}", err);
-                    },
-                    None,
-                )
-                .map_err(|e| format!("Failed to build stream: {}", e))?;
-            stream
-        }
-        cpal::SampleFormat::I16 => {
-            let stream = device
-                .build_input_stream(
-                    &config.into(),
-                    move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                        let floats: Vec<f32> = data.iter().map(|&s| s as f32 / 32768.0).collect();
-                        buffer.lock().extend_from_slice(&floats);
-                    },
-                    |err| {
-                        log::error!("Audio stream error: {}", err);
-                    },
-                    None,
-                )
-                .map_err(|e| format!("Failed to build stream: {}", e))?;
-            stream
-        }
+        cpal::SampleFormat::F32 => device
+            .build_input_stream(
+                &config.into(),
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    buffer.lock().push(data);
+                },
+                on_error,
+                None,
+            )
+            .map_err(|e| VenkyError::Audio(format!("Failed to build stream: {}", e)))?,
+        cpal::SampleFormat::I16 => device
+            .build_input_stream(
+                &config.into(),
+                move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                    let floats: Vec<f32> = data.iter().map(|&s| s as f32 / 32768.0).collect();
+                    buffer.lock().push(&floats);
+                },
+                on_error,
+                None,
+            )
+            .map_err(|e| VenkyError::Audio(format!("Failed to build stream: {}", e)))?,
         format => {
-            return Err(format!("Unsupported sample format: {:?}", format));
+            return Err(VenkyError::Audio(format!(
+                "Unsupported sample format: {:?}",
+                format
+            )));
         }
     };
 
-    stream
-        .play()
-        .map_err(|e| format!("Failed to start stream: {}", e))?;
-
-    *AUDIO_STREAM.lock() = Some(SendStream(stream));
-    capture_state.is_recording_audio = true;
+    // The spill file is only opened once the device has a stream, so a failed open leaves
+    // nothing half-written behind
+    if let Some(ref path) = spill_path {
+        ring.lock().start_spill(path)?;
+    }
+    if let Err(e) = stream.play() {
+        if let Ok(Some(path)) = ring.lock().finish_spill() {
+            std::fs::remove_file(path).ok();
+        }
+        return Err(VenkyError::Audio(format!("Failed to start stream: {}", e)));
+    }
 
-    Ok(format!(
-        "Recording started (device: {}, sample rate: {}Hz)",
-        device.name().unwrap_or_default(),
-        sr
+    Ok((
+        stream,
+        MicFormat {
+            device_name: device.name().unwrap_or_default(),
+            sample_rate: sr,
+        },
     ))
 }
 
-#[tauri::command]
-pub fn stop_audio_capture(
-    state: tauri::State<'_, CaptureStateHandle>,
-) -> Result<Vec<f32>, String> {
-    let mut capture_state = state.lock();
-    if !capture_state.is_recording_audio {
-        return Err("Not recording".to_string());
-    }
+/// Temp file the full recording of `source` is spilled to
+fn spill_file_path(source: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join("venkyai")
+        .join(format!("{}-{}.wav", source, uuid::Uuid::new_v4()))
+}
 
-    // Drop the stream to stop recording
-    *AUDIO_STREAM.lock() = None;
-    capture_state.is_recording_audio = false;
+/// Start the mic stream; blocked while a session in compliance mode awaits consent
+#[tauri::command]
+pub fn start_audio_capture(
+    engine: tauri::State<'_, AudioEngineState>,
+    config: tauri::State<'_, ConfigState>,
+    consent: tauri::State<'_, ConsentState>,
+) -> CommandResult<String> {
+    consent.lock().check()?;
+    let spill_path = config.lock().spills_audio().then(|| spill_file_path("mic"));
+    Ok(engine.lock().start_mic(spill_path)?)
+}
 
-    // Return the captured audio buffer
-    let buffer = AUDIO_BUFFER.lock().clone();
-    Ok(buffer)
+/// Stop the mic stream. Returns the path of the full recording when spill-to-disk is enabled.
+#[tauri::command]
+pub fn stop_audio_capture(
+    engine: tauri::State<'_, AudioEngineState>,
+) -> CommandResult<Option<String>> {
+    Ok(engine.lock().stop_mic()?)
 }
 
+/// Start system audio capture, scoped to `target` (or the configured target) when given
 #[tauri::command]
-pub fn start_system_audio_capture() -> Result<String, String> {
-    // Check if system audio capture thread is already running
-    if SYSTEM_AUDIO_THREAD.lock().is_some() {
-        return Err("System audio recording already active".to_string());
-    }
+pub fn start_system_audio_capture(
+    engine: tauri::State<'_, AudioEngineState>,
+    config: tauri::State<'_, ConfigState>,
+    consent: tauri::State<'_, ConsentState>,
+    target: Option<String>,
+) -> CommandResult<String> {
+    consent.lock().check()?;
+    let (spill_path, target) = {
+        let cfg = config.lock();
+        (
+            cfg.spills_audio().then(|| spill_file_path("system")),
+            target.or_else(|| cfg.system_audio_target.clone()),
+        )
+    };
+    Ok(engine.lock().start_system(spill_path, target.as_deref())?)
+}
 
-    // Initialize scap
+/// Windows that system audio capture can be scoped to
+#[tauri::command]
+pub fn list_audio_targets() -> CommandResult<Vec<AudioTarget>> {
     if !scap::has_permission() {
-        return Err("System audio capture permission not granted".to_string());
+        return Err(VenkyError::Permission(format!(
+            "System audio capture permission not granted. {}",
+            grant_hint(PermissionKind::SystemAudio)
+        )));
     }
 
-    let options = scap::capturer::Options {
-        fps: 1, 
-        show_cursor: false,
-        captures_audio: true,
-        ..Default::default()
-    };
+    Ok(scap::get_all_targets()
+        .into_iter()
+        .filter_map(|target| match target {
+            scap::Target::Window(window) if !window.title.is_empty() => Some(AudioTarget {
+                id: window.id,
+                title: window.title,
+            }),
+            _ => None,
+        })
+        .collect())
+}
 
-    let buffer = SYSTEM_AUDIO_BUFFER.clone();
-    buffer.lock().clear();
-
-    let mut capturer = scap::capturer::Capturer::build(options)
-        .map_err(|e| format!("Failed to build scap capturer: {:?}", e))?;
-
-    capturer.start_capture();
-    STOP_SIGNAL.store(false, std::sync::atomic::Ordering::SeqCst);
-
-    // Spawn a background thread to poll for audio frames
-    let handle = std::thread::spawn(move || {
-        while !STOP_SIGNAL.load(std::sync::atomic::Ordering::SeqCst) {
-            match capturer.get_next_frame() {
-                Ok(frame) => {
-                    if let scap::frame::Frame::Audio(audio_frame) = frame {
-                        let data = audio_frame.raw_data();
-                        if matches!(audio_frame.format(), scap::frame::AudioFormat::F32) {
-                            let floats: Vec<f32> = data
-                                .chunks_exact(4)
-                                .map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
-                                .collect();
-                            buffer.lock().extend_from_slice(&floats);
-                        }
-                    }
-                }
-                Err(e) => {
-                    log::error!("Error getting next frame: {:?}", e);
-                    break;
-                }
-            }
-        }
-        log::info!("System audio polling loop ended");
-    });
+/// Stop system audio capture. Returns the path of the full recording when spill-to-disk is enabled.
+#[tauri::command]
+pub fn stop_system_audio_capture(
+    engine: tauri::State<'_, AudioEngineState>,
+) -> CommandResult<Option<String>> {
+    Ok(engine.lock().stop_system()?)
+}
+
+/// Go off the record on the mic without tearing down the stream
+#[tauri::command]
+pub fn pause_audio_capture(
+    engine: tauri::State<'_, AudioEngineState>,
+    session: tauri::State<'_, SessionState>,
+) -> CommandResult<()> {
+    engine.lock().pause_mic()?;
+    manager::mark_timeline(&session, "Microphone capture paused");
+    Ok(())
+}
 
-    *SYSTEM_AUDIO_THREAD.lock() = Some(handle);
+#[tauri::command]
+pub fn resume_audio_capture(
+    engine: tauri::State<'_, AudioEngineState>,
+    session: tauri::State<'_, SessionState>,
+) -> CommandResult<()> {
+    engine.lock().resume_mic()?;
+    manager::mark_timeline(&session, "Microphone capture resumed");
+    Ok(())
+}
 
-    Ok("System audio capture started".to_string())
+#[tauri::command]
+pub fn pause_system_audio_capture(
+    engine: tauri::State<'_, AudioEngineState>,
+    session: tauri::State<'_, SessionState>,
+) -> CommandResult<()> {
+    engine.lock().pause_system()?;
+    manager::mark_timeline(&session, "System audio capture paused");
+    Ok(())
 }
 
 #[tauri::command]
-pub fn stop_system_audio_capture() -> Result<Vec<f32>, String> {
-    STOP_SIGNAL.store(true, std::sync::atomic::Ordering::SeqCst);
-    
-    let mut thread_handle = SYSTEM_AUDIO_THREAD.lock();
-    if let Some(handle) = thread_handle.take() {
-        let _ = handle.join();
-        let buffer = SYSTEM_AUDIO_BUFFER.lock().clone();
-        Ok(buffer)
-    } else {
-        Err("System audio capture not active".to_string())
+pub fn resume_system_audio_capture(
+    engine: tauri::State<'_, AudioEngineState>,
+    session: tauri::State<'_, SessionState>,
+) -> CommandResult<()> {
+    engine.lock().resume_system()?;
+    manager::mark_timeline(&session, "System audio capture resumed");
+    Ok(())
+}
+
+/// Stop (or restart) feeding mic audio to STT while the user is muted in the meeting
+#[tauri::command]
+pub fn set_capture_muted(app: AppHandle, muted: bool) -> CommandResult<bool> {
+    apply_capture_muted(&app, muted);
+    Ok(muted)
+}
+
+/// Flip the mute state; bound to the mute hotkey
+pub fn toggle_capture_muted(app: &AppHandle) {
+    let muted = !app.state::<AudioEngineState>().lock().is_mic_muted();
+    apply_capture_muted(app, muted);
+}
+
+fn apply_capture_muted(app: &AppHandle, muted: bool) {
+    let changed = app.state::<AudioEngineState>().lock().set_mic_muted(muted);
+    if !changed {
+        return;
     }
+
+    let session = app.state::<SessionState>();
+    manager::mark_timeline(
+        &session,
+        if muted {
+            "Microphone muted"
+        } else {
+            "Microphone unmuted"
+        },
+    );
+    let _ = app.emit("capture-muted", muted);
 }
 
 #[tauri::command]
-pub fn get_audio_status(state: tauri::State<'_, CaptureStateHandle>) -> AudioStatus {
-    let capture_state = state.lock();
-    let buffer_len = AUDIO_BUFFER.lock().len();
-    let system_buffer_len = SYSTEM_AUDIO_BUFFER.lock().len();
-    let sr = *SAMPLE_RATE.lock();
-    AudioStatus {
-        is_recording: capture_state.is_recording_audio,
-        is_recording_system: SYSTEM_AUDIO_THREAD.lock().is_some(),
-        buffer_duration_secs: buffer_len as f32 / sr as f32,
-        system_buffer_duration_secs: system_buffer_len as f32 / sr as f32,
-        sample_rate: sr,
+pub fn get_audio_status(engine: tauri::State<'_, AudioEngineState>) -> AudioStatus {
+    engine.lock().status()
+}
+
+/// Watches the mic stream for an unplugged device or a changed OS default input,
+/// reopening capture on the current default device and emitting "audio-device-changed".
+pub async fn device_watch_loop(app: AppHandle) {
+    let mut interval = tokio::time::interval(Duration::from_secs(2));
+
+    loop {
+        interval.tick().await;
+
+        let change = app.state::<AudioEngineState>().lock().check_mic_device();
+        if let Some(change) = change {
+            let _ = app.emit("audio-device-changed", change);
+        }
     }
 }
 
-/// Get the current audio buffer as WAV bytes and CLEAR the buffer
-pub fn get_and_clear_audio_wav_bytes() -> Result<Vec<u8>, String> {
-    let mut buffer_lock = AUDIO_BUFFER.lock();
-    if buffer_lock.is_empty() {
-        return Err("No audio data".to_string());
+/// Get the current mic buffer as WAV bytes and CLEAR the buffer.
+/// Audio is downmixed to mono, run through the enabled DSP stages and
+/// resampled to `target_rate` (0 keeps the device rate).
+pub fn get_and_clear_audio_wav_bytes(
+    engine: &AudioEngineState,
+    target_rate: u32,
+    dsp: &DspOptions,
+) -> Result<Vec<u8>, VenkyError> {
+    let (buffer, muted) = {
+        let engine = engine.lock();
+        (engine.mic_buffer(), engine.is_mic_muted())
+    };
+    let mut ring = buffer.lock();
+    if ring.is_empty() {
+        return Err(VenkyError::Audio("No audio data".to_string()));
     }
-    
-    let buffer = std::mem::take(&mut *buffer_lock);
-    drop(buffer_lock); // Release lock early
 
-    let sr = *SAMPLE_RATE.lock();
+    let samples = ring.drain();
+    if muted {
+        // Whatever was said while muted isn't part of the meeting
+        return Err(VenkyError::Audio("Microphone muted".to_string()));
+    }
+    let (sr, channels) = ring.format();
+    drop(ring); // Release lock early
 
-    let mut cursor = std::io::Cursor::new(Vec::new());
-    let spec = hound::WavSpec {
-        channels: 1,
-        sample_rate: sr,
-        bits_per_sample: 16,
-        sample_format: hound::SampleFormat::Int,
-    };
+    let mono = super::dsp::process_live(downmix_to_mono(&samples, channels), sr, dsp);
+    let (samples, rate) = prepare_for_stt(&mono, 1, sr, target_rate);
+    encode_wav(&samples, rate)
+}
 
-    let mut writer =
-        hound::WavWriter::new(&mut cursor, spec).map_err(|e| format!("WAV error: {}", e))?;
+/// Get the current mic buffer as WAV bytes (for STT processing)
+pub fn get_audio_wav_bytes(
+    engine: &AudioEngineState,
+    target_rate: u32,
+    dsp: &DspOptions,
+) -> Result<Vec<u8>, VenkyError> {
+    let buffer = engine.lock().mic_buffer();
+    let (samples, (sr, channels)) = {
+        let ring = buffer.lock();
+        (ring.to_vec(), ring.format())
+    };
 
-    for &sample in &buffer {
-        let s = (sample * 32767.0).clamp(-32768.0, 32767.0) as i16;
-        writer
-            .write_sample(s)
-            .map_err(|e| format!("WAV write error: {}", e))?;
+    if samples.is_empty() {
+        return Err(VenkyError::Audio("No audio data".to_string()));
     }
 
-    writer
-        .finalize()
-        .map_err(|e| format!("WAV finalize error: {}", e))?;
+    let mono = super::dsp::process_clip(downmix_to_mono(&samples, channels), sr, dsp);
+    let (samples, rate) = prepare_for_stt(&mono, 1, sr, target_rate);
+    encode_wav(&samples, rate)
+}
 
-    Ok(cursor.into_inner())
+/// Downmix interleaved samples to mono and resample to the STT target rate.
+/// Returns the processed samples and their sample rate.
+pub fn prepare_for_stt(
+    samples: &[f32],
+    channels: u16,
+    sample_rate: u32,
+    target_rate: u32,
+) -> (Vec<f32>, u32) {
+    let mono = downmix_to_mono(samples, channels);
+    // Never upsample — it only adds bytes without adding information
+    if target_rate == 0 || target_rate >= sample_rate {
+        return (mono, sample_rate);
+    }
+    (
+        resample_linear(&mono, sample_rate, target_rate),
+        target_rate,
+    )
 }
 
-/// Get the current audio buffer as WAV bytes (for STT processing)
-#[allow(dead_code)]
-pub fn get_audio_wav_bytes() -> Result<Vec<u8>, String> {
-    let buffer = AUDIO_BUFFER.lock().clone();
-    let sr = *SAMPLE_RATE.lock();
+/// Average interleaved frames into a single channel
+pub fn downmix_to_mono(samples: &[f32], channels: u16) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks(channels as usize)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
 
-    if buffer.is_empty() {
-        return Err("No audio data".to_string());
+/// Linear-interpolation resampler, good enough for speech going to STT
+pub fn resample_linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
     }
 
+    let ratio = from_rate as f64 / to_rate as f64;
+    let out_len = (samples.len() as f64 / ratio).floor() as usize;
+    let last = samples.len() - 1;
+
+    (0..out_len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let idx = pos.floor() as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = samples[idx.min(last)];
+            let b = samples[(idx + 1).min(last)];
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+/// Encode mono f32 samples as 16-bit PCM WAV bytes
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>, VenkyError> {
     let mut cursor = std::io::Cursor::new(Vec::new());
     let spec = hound::WavSpec {
         channels: 1,
-        sample_rate: sr,
+        sample_rate,
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
     };
 
-    let mut writer =
-        hound::WavWriter::new(&mut cursor, spec).map_err(|e| format!("WAV error: {}", e))?;
+    let mut writer = hound::WavWriter::new(&mut cursor, spec)
+        .map_err(|e| VenkyError::Audio(format!("WAV error: {}", e)))?;
 
-    for &sample in &buffer {
+    for &sample in samples {
         let s = (sample * 32767.0).clamp(-32768.0, 32767.0) as i16;
         writer
             .write_sample(s)
-            .map_err(|e| format!("WAV write error: {}", e))?;
+            .map_err(|e| VenkyError::Audio(format!("WAV write error: {}", e)))?;
     }
 
     writer
         .finalize()
-        .map_err(|e| format!("WAV finalize error: {}", e))?;
+        .map_err(|e| VenkyError::Audio(format!("WAV finalize error: {}", e)))?;
 
     Ok(cursor.into_inner())
 }
@@ -0,0 +1,165 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use crate::error::VenkyError;
+
+/// Upper bound on audio kept in memory per source; older samples are evicted first
+pub const MAX_BUFFER_SECS: usize = 300;
+
+/// Streams everything pushed into the ring to a mono 16-bit WAV file
+struct WavSpill {
+    writer: hound::WavWriter<BufWriter<File>>,
+    path: PathBuf,
+    sample_rate: u32,
+}
+
+/// Bounded interleaved sample buffer with an optional spill file holding the full recording
+pub struct AudioRingBuffer {
+    samples: VecDeque<f32>,
+    capacity: usize,
+    sample_rate: u32,
+    channels: u16,
+    dropped: u64,
+    /// While set, incoming samples are kept out of the ring and spilled as silence
+    paused: bool,
+    spill: Option<WavSpill>,
+}
+
+impl AudioRingBuffer {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        let mut ring = Self {
+            samples: VecDeque::new(),
+            capacity: 0,
+            sample_rate,
+            channels,
+            dropped: 0,
+            paused: false,
+            spill: None,
+        };
+        ring.set_format(sample_rate, channels);
+        ring
+    }
+
+    /// Update the incoming stream format and resize the ring to hold `MAX_BUFFER_SECS`
+    pub fn set_format(&mut self, sample_rate: u32, channels: u16) {
+        self.sample_rate = sample_rate;
+        self.channels = channels.max(1);
+        self.capacity = sample_rate as usize * self.channels as usize * MAX_BUFFER_SECS;
+        self.evict();
+    }
+
+    /// Sample rate and channel count of the buffered (interleaved) samples
+    pub fn format(&self) -> (u32, u16) {
+        (self.sample_rate, self.channels)
+    }
+
+    pub fn push(&mut self, data: &[f32]) {
+        if let Some(ref mut spill) = self.spill {
+            // Silence rather than nothing while paused, so the recording stays in step with
+            // the session's clock
+            let mono = if self.paused {
+                vec![0.0; data.len() / self.channels as usize]
+            } else {
+                super::audio::downmix_to_mono(data, self.channels)
+            };
+            let mono = super::audio::resample_linear(&mono, self.sample_rate, spill.sample_rate);
+            for &sample in &mono {
+                let s = (sample * 32767.0).clamp(-32768.0, 32767.0) as i16;
+                if let Err(e) = spill.writer.write_sample(s) {
+                    log::error!("Failed to spill audio to {}: {}", spill.path.display(), e);
+                    break;
+                }
+            }
+        }
+
+        if self.paused {
+            return;
+        }
+        self.samples.extend(data.iter().copied());
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        if self.samples.len() > self.capacity {
+            let excess = self.samples.len() - self.capacity;
+            self.samples.drain(..excess);
+            self.dropped += excess as u64;
+        }
+    }
+
+    /// Take everything currently buffered, leaving the ring empty
+    pub fn drain(&mut self) -> Vec<f32> {
+        self.samples.drain(..).collect()
+    }
+
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.samples.iter().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.dropped = 0;
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Samples evicted because the ring was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Start writing every pushed sample to a WAV file at `path` (mono, current sample rate)
+    pub fn start_spill(&mut self, path: &Path) -> Result<(), VenkyError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                VenkyError::Storage(format!("Failed to create recording dir: {}", e))
+            })?;
+        }
+
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let writer = hound::WavWriter::create(path, spec)
+            .map_err(|e| VenkyError::Storage(format!("Failed to create recording file: {}", e)))?;
+
+        self.spill = Some(WavSpill {
+            writer,
+            path: path.to_path_buf(),
+            sample_rate: self.sample_rate,
+        });
+        Ok(())
+    }
+
+    /// Finalize the spill file, returning its path if one was being written
+    pub fn finish_spill(&mut self) -> Result<Option<PathBuf>, VenkyError> {
+        match self.spill.take() {
+            Some(spill) => {
+                spill
+                    .writer
+                    .finalize()
+                    .map_err(|e| VenkyError::Storage(format!("WAV finalize error: {}", e)))?;
+                Ok(Some(spill.path))
+            }
+            None => Ok(None),
+        }
+    }
+}
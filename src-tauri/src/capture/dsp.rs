@@ -0,0 +1,110 @@
+use nnnoiseless::DenoiseState;
+use parking_lot::Mutex;
+
+use super::audio::resample_linear;
+use crate::config::AppConfig;
+
+/// RNNoise is trained on 48 kHz audio in 480-sample frames
+const DENOISE_RATE: u32 = 48000;
+
+/// Target loudness for gain normalization (about -20 dBFS)
+const TARGET_RMS: f32 = 0.1;
+/// Never boost more than this, so near-silence doesn't turn into amplified hiss
+const MAX_GAIN: f32 = 10.0;
+/// Chunks quieter than this are treated as silence and left alone
+const SILENCE_RMS: f32 = 0.001;
+
+/// Denoiser for the live mic stream; kept across chunks so its state carries over
+static LIVE_DENOISER: once_cell::sync::Lazy<Mutex<Box<DenoiseState<'static>>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(DenoiseState::new()));
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DspOptions {
+    pub noise_suppression: bool,
+    pub auto_gain: bool,
+}
+
+impl DspOptions {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            noise_suppression: config.noise_suppression,
+            auto_gain: config.auto_gain,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.noise_suppression || self.auto_gain
+    }
+}
+
+/// Run the enabled stages over live mono mic samples
+pub fn process_live(samples: Vec<f32>, sample_rate: u32, opts: &DspOptions) -> Vec<f32> {
+    if !opts.is_enabled() {
+        return samples;
+    }
+    process(samples, sample_rate, opts, &mut LIVE_DENOISER.lock())
+}
+
+/// Run the enabled stages over a standalone mono clip with a fresh denoiser
+pub fn process_clip(samples: Vec<f32>, sample_rate: u32, opts: &DspOptions) -> Vec<f32> {
+    if !opts.is_enabled() {
+        return samples;
+    }
+    process(samples, sample_rate, opts, &mut DenoiseState::new())
+}
+
+fn process(
+    samples: Vec<f32>,
+    sample_rate: u32,
+    opts: &DspOptions,
+    denoiser: &mut DenoiseState,
+) -> Vec<f32> {
+    let mut samples = if opts.noise_suppression {
+        denoise(&samples, sample_rate, denoiser)
+    } else {
+        samples
+    };
+
+    if opts.auto_gain {
+        normalize_gain(&mut samples);
+    }
+
+    samples
+}
+
+/// RNNoise-based suppression; resamples to 48 kHz and back around the model
+fn denoise(samples: &[f32], sample_rate: u32, state: &mut DenoiseState) -> Vec<f32> {
+    let input = resample_linear(samples, sample_rate, DENOISE_RATE);
+    let mut output = Vec::with_capacity(input.len());
+    let mut frame_in = [0.0f32; DenoiseState::FRAME_SIZE];
+    let mut frame_out = [0.0f32; DenoiseState::FRAME_SIZE];
+
+    for frame in input.chunks(DenoiseState::FRAME_SIZE) {
+        // The model expects i16-range samples; pad the trailing partial frame with silence
+        frame_in.fill(0.0);
+        for (dst, &src) in frame_in.iter_mut().zip(frame) {
+            *dst = src * 32767.0;
+        }
+        state.process_frame(&mut frame_out, &frame_in);
+        output.extend(frame_out[..frame.len()].iter().map(|&s| s / 32767.0));
+    }
+
+    resample_linear(&output, DENOISE_RATE, sample_rate)
+}
+
+/// Scale the chunk towards `TARGET_RMS`, capped at `MAX_GAIN` and hard-limited to [-1, 1]
+fn normalize_gain(samples: &mut [f32]) {
+    if samples.is_empty() {
+        return;
+    }
+
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    if rms < SILENCE_RMS {
+        return;
+    }
+
+    let gain = (TARGET_RMS / rms).min(MAX_GAIN);
+    for sample in samples.iter_mut() {
+        *sample = (*sample * gain).clamp(-1.0, 1.0);
+    }
+}
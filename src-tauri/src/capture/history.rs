@@ -0,0 +1,106 @@
+use parking_lot::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use crate::config::AppConfig;
+use crate::error::VenkyError;
+use crate::session::manager::SessionManager;
+use crate::session::storage_actor::StorageActor;
+
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Directory under app data holding saved screenshots
+pub fn captures_dir(app: &AppHandle) -> Result<PathBuf, VenkyError> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))?
+        .join("captures"))
+}
+
+/// Write an encoded capture under the captures dir with the configured format's extension
+pub fn write_capture_file(
+    app: &AppHandle,
+    bytes: &[u8],
+    cfg: &AppConfig,
+) -> Result<PathBuf, VenkyError> {
+    let dir = captures_dir(app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| VenkyError::Storage(format!("Failed to create captures dir: {}", e)))?;
+
+    let path = dir.join(format!(
+        "{}.{}",
+        uuid::Uuid::new_v4(),
+        cfg.screenshot_format.extension()
+    ));
+    std::fs::write(&path, bytes)
+        .map_err(|e| VenkyError::Storage(format!("Failed to write capture: {}", e)))?;
+    Ok(path)
+}
+
+/// Id of the focused session, if any
+pub fn active_session_id(app: &AppHandle) -> Option<String> {
+    app.state::<SessionState>().lock().focused_id.clone()
+}
+
+/// Save a copy of a capture taken during a session so it can be reviewed later
+pub fn keep_for_session(app: &AppHandle, bytes: &[u8], cfg: &AppConfig) {
+    let Some(session_id) = active_session_id(app) else {
+        return;
+    };
+
+    match write_capture_file(app, bytes, cfg) {
+        Ok(path) => record_capture(app, &session_id, &path, bytes, cfg),
+        Err(e) => log::error!("Failed to keep session capture: {}", e),
+    }
+}
+
+/// Link a capture file to a session, running OCR on it in the background when enabled
+pub fn record_capture(
+    app: &AppHandle,
+    session_id: &str,
+    path: &Path,
+    bytes: &[u8],
+    cfg: &AppConfig,
+) {
+    let storage = app.state::<StorageActor>().inner().clone();
+    let capture_id = uuid::Uuid::new_v4().to_string();
+    let (id, session_id) = (capture_id.clone(), session_id.to_string());
+    let path = path.to_string_lossy().to_string();
+    let taken_at = chrono::Utc::now().to_rfc3339();
+
+    // The OCR text below is queued behind this row
+    storage.send(move |s| {
+        if let Err(e) = s.add_capture(&id, &session_id, &path, &taken_at) {
+            log::error!("Failed to record session capture: {}", e);
+        }
+    });
+
+    if !cfg.ocr_captures {
+        return;
+    }
+
+    let bytes = bytes.to_vec();
+    std::thread::spawn(move || {
+        let text = image::load_from_memory(&bytes)
+            .map_err(|e| VenkyError::Other(format!("Failed to decode capture: {}", e)))
+            .and_then(|img| super::ocr::recognize(&img.to_rgba8()));
+
+        match text {
+            Ok(lines) => {
+                let text = lines
+                    .into_iter()
+                    .map(|line| line.text)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                storage.send(move |s| {
+                    if let Err(e) = s.set_capture_ocr_text(&capture_id, &text) {
+                        log::error!("Failed to store capture OCR text: {}", e);
+                    }
+                });
+            }
+            Err(e) => log::warn!("OCR of capture {} failed: {}", capture_id, e),
+        }
+    });
+}
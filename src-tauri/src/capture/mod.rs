@@ -1,10 +1,24 @@
 pub mod audio;
+pub mod buffer;
+pub mod dsp;
+pub mod history;
+pub mod ocr;
+pub mod permissions;
+pub mod privacy;
+pub mod recording;
+pub mod redact;
 pub mod screen;
+pub mod screen_recording;
+pub mod slides;
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct CaptureState {
-    pub is_recording_audio: bool,
+    /// Base64 PNG of the most recent meaningfully-changed screen, kept by the screen-context loop
     pub last_screen_capture: Option<String>,
+    /// OCR text of the slide currently being presented, kept by the slide watcher
+    pub current_slide_text: Option<String>,
+    /// Set while live AI is suspended because sensitive content was detected
+    pub privacy_pause: Option<privacy::PrivacyPause>,
 }
@@ -0,0 +1,104 @@
+use image::RgbaImage;
+use ocrs::{ImageSource, OcrEngine, OcrEngineParams};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::error::VenkyError;
+
+/// Where the detection/recognition models live, set once at startup
+static MODELS_DIR: OnceCell<PathBuf> = OnceCell::new();
+/// Loaded lazily on first use since the models take a moment to read
+static ENGINE: OnceCell<OcrEngine> = OnceCell::new();
+
+const DETECTION_MODEL: &str = "text-detection.rten";
+const RECOGNITION_MODEL: &str = "text-recognition.rten";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrWord {
+    pub text: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrLine {
+    pub text: String,
+    pub words: Vec<OcrWord>,
+}
+
+impl OcrLine {
+    /// Bounding box covering every word on the line
+    pub fn bounds(&self) -> Option<(i32, i32, u32, u32)> {
+        let left = self.words.iter().map(|w| w.x).min()?;
+        let top = self.words.iter().map(|w| w.y).min()?;
+        let right = self.words.iter().map(|w| w.x + w.width as i32).max()?;
+        let bottom = self.words.iter().map(|w| w.y + w.height as i32).max()?;
+        Some((left, top, (right - left) as u32, (bottom - top) as u32))
+    }
+}
+
+/// Models are expected under app data so they can be downloaded after install
+pub fn init(app_data: &Path) {
+    let _ = MODELS_DIR.set(app_data.join("models"));
+}
+
+fn engine() -> Result<&'static OcrEngine, VenkyError> {
+    ENGINE.get_or_try_init(|| {
+        let dir = MODELS_DIR
+            .get()
+            .ok_or_else(|| VenkyError::Other("OCR not initialized".to_string()))?;
+        let load = |name: &str| {
+            rten::Model::load_file(dir.join(name))
+                .map_err(|e| VenkyError::Other(format!("Failed to load OCR model {}: {}", name, e)))
+        };
+
+        OcrEngine::new(OcrEngineParams {
+            detection_model: Some(load(DETECTION_MODEL)?),
+            recognition_model: Some(load(RECOGNITION_MODEL)?),
+            ..Default::default()
+        })
+        .map_err(|e| VenkyError::Other(format!("Failed to start OCR engine: {}", e)))
+    })
+}
+
+/// Recognize text lines (with word boxes in image pixels) in a screenshot
+pub fn recognize(image: &RgbaImage) -> Result<Vec<OcrLine>, VenkyError> {
+    let engine = engine()?;
+
+    let source = ImageSource::from_bytes(image.as_raw(), image.dimensions())
+        .map_err(|e| VenkyError::Other(format!("OCR input error: {}", e)))?;
+    let input = engine
+        .prepare_input(source)
+        .map_err(|e| VenkyError::Other(format!("OCR input error: {}", e)))?;
+    let word_rects = engine
+        .detect_words(&input)
+        .map_err(|e| VenkyError::Other(format!("OCR detection error: {}", e)))?;
+    let line_rects = engine.find_text_lines(&input, &word_rects);
+    let lines = engine
+        .recognize_text(&input, &line_rects)
+        .map_err(|e| VenkyError::Other(format!("OCR recognition error: {}", e)))?;
+
+    Ok(lines
+        .into_iter()
+        .flatten()
+        .map(|line| OcrLine {
+            text: line.to_string(),
+            words: line
+                .words()
+                .map(|word| {
+                    let rect = word.rotated_rect().bounding_rect();
+                    OcrWord {
+                        text: word.to_string(),
+                        x: rect.left() as i32,
+                        y: rect.top() as i32,
+                        width: rect.width().max(0.0) as u32,
+                        height: rect.height().max(0.0) as u32,
+                    }
+                })
+                .collect(),
+        })
+        .collect())
+}
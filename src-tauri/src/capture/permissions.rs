@@ -0,0 +1,214 @@
+use cpal::traits::HostTrait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CommandResult, VenkyError};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum PermissionKind {
+    Microphone,
+    ScreenRecording,
+    SystemAudio,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum PermissionStatus {
+    Granted,
+    Denied,
+    /// No input hardware to ask about
+    Unavailable,
+    /// Not asked yet, or the OS gives no way to tell; capture reports it if it's refused
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionState {
+    pub kind: PermissionKind,
+    pub status: PermissionStatus,
+    /// What the user needs to do when the permission isn't granted
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionsReport {
+    pub microphone: PermissionState,
+    pub screen_recording: PermissionState,
+    pub system_audio: PermissionState,
+}
+
+fn check(kind: PermissionKind) -> PermissionState {
+    let status = match kind {
+        PermissionKind::Microphone => microphone_status(),
+        // Both go through ScreenCaptureKit on macOS and need no grant elsewhere
+        PermissionKind::ScreenRecording | PermissionKind::SystemAudio => {
+            if scap::has_permission() {
+                PermissionStatus::Granted
+            } else {
+                PermissionStatus::Denied
+            }
+        }
+    };
+
+    let hint = match status {
+        PermissionStatus::Granted | PermissionStatus::Unknown => None,
+        PermissionStatus::Unavailable => Some("No microphone found".to_string()),
+        PermissionStatus::Denied => Some(grant_hint(kind)),
+    };
+
+    PermissionState { kind, status, hint }
+}
+
+/// Actionable instructions for granting `kind`
+pub fn grant_hint(kind: PermissionKind) -> String {
+    format!(
+        "Allow VenkyAI under {} and restart the app",
+        settings_pane_name(kind)
+    )
+}
+
+fn microphone_status() -> PermissionStatus {
+    if cpal::default_host().default_input_device().is_none() {
+        return PermissionStatus::Unavailable;
+    }
+    #[cfg(target_os = "macos")]
+    let status = av_capture::microphone_status();
+    // An input config is handed out whether or not access was granted
+    #[cfg(not(target_os = "macos"))]
+    let status = PermissionStatus::Unknown;
+    status
+}
+
+/// Microphone access through AVFoundation, which owns the macOS prompt
+#[cfg(target_os = "macos")]
+mod av_capture {
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_av_foundation::{AVAuthorizationStatus, AVCaptureDevice, AVMediaTypeAudio};
+
+    use super::PermissionStatus;
+
+    pub fn microphone_status() -> PermissionStatus {
+        let Some(audio) = (unsafe { AVMediaTypeAudio }) else {
+            return PermissionStatus::Unknown;
+        };
+        match unsafe { AVCaptureDevice::authorizationStatusForMediaType(audio) } {
+            AVAuthorizationStatus::Authorized => PermissionStatus::Granted,
+            AVAuthorizationStatus::Denied | AVAuthorizationStatus::Restricted => {
+                PermissionStatus::Denied
+            }
+            _ => PermissionStatus::Unknown,
+        }
+    }
+
+    /// Show the OS prompt and wait for the answer. Only prompts while the status is undetermined.
+    pub async fn request_microphone() {
+        let Some(audio) = (unsafe { AVMediaTypeAudio }) else {
+            return;
+        };
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = parking_lot::Mutex::new(Some(tx));
+        // AVFoundation copies the block, so ours can go before waiting (it isn't Send)
+        {
+            let handler = RcBlock::new(move |granted: Bool| {
+                if let Some(tx) = tx.lock().take() {
+                    let _ = tx.send(granted.as_bool());
+                }
+            });
+            unsafe {
+                AVCaptureDevice::requestAccessForMediaType_completionHandler(audio, &handler)
+            };
+        }
+        match rx.await {
+            Ok(granted) => log::info!("Microphone access granted: {}", granted),
+            Err(_) => log::warn!("Microphone prompt closed without an answer"),
+        }
+    }
+}
+
+fn settings_pane_name(kind: PermissionKind) -> &'static str {
+    match kind {
+        PermissionKind::Microphone => {
+            if cfg!(target_os = "macos") {
+                "System Settings → Privacy & Security → Microphone"
+            } else {
+                "Settings → Privacy → Microphone"
+            }
+        }
+        PermissionKind::ScreenRecording | PermissionKind::SystemAudio => {
+            if cfg!(target_os = "macos") {
+                "System Settings → Privacy & Security → Screen & System Audio Recording"
+            } else {
+                "Settings → Privacy → Screen capture"
+            }
+        }
+    }
+}
+
+/// Deep link to the Settings pane controlling `kind`, if the platform has one
+fn settings_url(kind: PermissionKind) -> Option<&'static str> {
+    if cfg!(target_os = "macos") {
+        Some(match kind {
+            PermissionKind::Microphone => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone"
+            }
+            PermissionKind::ScreenRecording | PermissionKind::SystemAudio => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture"
+            }
+        })
+    } else if cfg!(target_os = "windows") {
+        match kind {
+            PermissionKind::Microphone => Some("ms-settings:privacy-microphone"),
+            PermissionKind::ScreenRecording | PermissionKind::SystemAudio => None,
+        }
+    } else {
+        None
+    }
+}
+
+fn open_settings(url: &str) -> Result<(), VenkyError> {
+    let result = if cfg!(target_os = "windows") {
+        std::process::Command::new("cmd")
+            .args(["/C", "start", "", url])
+            .spawn()
+    } else {
+        std::process::Command::new("open").arg(url).spawn()
+    };
+    result
+        .map(|_| ())
+        .map_err(|e| VenkyError::Other(format!("Failed to open Settings: {}", e)))
+}
+
+#[tauri::command]
+pub fn check_permissions() -> PermissionsReport {
+    PermissionsReport {
+        microphone: check(PermissionKind::Microphone),
+        screen_recording: check(PermissionKind::ScreenRecording),
+        system_audio: check(PermissionKind::SystemAudio),
+    }
+}
+
+/// Trigger the OS prompt for `kind`, or open its Settings pane when the OS won't prompt again
+#[tauri::command]
+pub async fn request_permission(kind: PermissionKind) -> CommandResult<PermissionState> {
+    let state = check(kind);
+    #[cfg(target_os = "macos")]
+    if kind == PermissionKind::Microphone && state.status == PermissionStatus::Unknown {
+        av_capture::request_microphone().await;
+        return Ok(check(kind));
+    }
+    if state.status != PermissionStatus::Denied {
+        return Ok(state);
+    }
+
+    if matches!(
+        kind,
+        PermissionKind::ScreenRecording | PermissionKind::SystemAudio
+    ) && scap::request_permission()
+    {
+        return Ok(check(kind));
+    }
+
+    if let Some(url) = settings_url(kind) {
+        open_settings(url)?;
+    }
+    Ok(check(kind))
+}
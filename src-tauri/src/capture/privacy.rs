@@ -0,0 +1,164 @@
+use image::RgbaImage;
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::CaptureState;
+use crate::config::AppConfig;
+
+type CaptureStateHandle = Arc<Mutex<CaptureState>>;
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacyGuardConfig {
+    pub enabled: bool,
+    /// Regexes; a match on screen (needs the OCR models) or in the transcript pauses live AI
+    pub markers: Vec<String>,
+}
+
+impl Default for PrivacyGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            markers: vec![
+                r"(?i)\boff the record\b".to_string(),
+                // Login forms and password managers
+                r"(?i)\b(password|passcode|passphrase)\s*:?\s*$".to_string(),
+                // Patient data
+                r"(?i)\b(patient (name|id)|medical record (number|no)|MRN|date of birth)\b"
+                    .to_string(),
+                // US social security numbers
+                r"\b\d{3}-\d{2}-\d{4}\b".to_string(),
+            ],
+        }
+    }
+}
+
+/// Why live AI is paused; emitted as "privacy-pause" (with `paused: false` on resume)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyPause {
+    pub paused: bool,
+    /// "screen" or "transcript"
+    pub source: Option<String>,
+    /// The marker pattern that matched
+    pub marker: Option<String>,
+    pub since: Option<String>,
+}
+
+fn compile(config: &PrivacyGuardConfig) -> Vec<Regex> {
+    config
+        .markers
+        .iter()
+        .filter_map(|p| match Regex::new(p) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                log::warn!("Invalid privacy marker {}: {}", p, e);
+                None
+            }
+        })
+        .collect()
+}
+
+pub fn is_paused(app: &AppHandle) -> bool {
+    app.state::<CaptureStateHandle>()
+        .lock()
+        .privacy_pause
+        .is_some()
+}
+
+/// Suspend screen uploads and suggestions; the latest screen context is dropped too
+pub fn pause(app: &AppHandle, source: &str, marker: &str) {
+    let status = PrivacyPause {
+        paused: true,
+        source: Some(source.to_string()),
+        marker: Some(marker.to_string()),
+        since: Some(chrono::Utc::now().to_rfc3339()),
+    };
+    {
+        let capture_state = app.state::<CaptureStateHandle>();
+        let mut capture = capture_state.lock();
+        if capture.privacy_pause.is_some() {
+            return;
+        }
+        capture.privacy_pause = Some(status.clone());
+        capture.last_screen_capture = None;
+    }
+    log::info!("Live AI paused: {} matched {}", source, marker);
+    let _ = app.emit("privacy-pause", status);
+}
+
+/// Pause live AI if `text` contains a sensitive marker; returns whether it matched
+pub fn check_text(app: &AppHandle, source: &str, text: &str) -> bool {
+    let config = app.state::<ConfigState>().lock().privacy_guard.clone();
+    if !config.enabled {
+        return false;
+    }
+    let matched = compile(&config)
+        .into_iter()
+        .find(|re| text.lines().any(|line| re.is_match(line.trim())));
+    match matched {
+        Some(re) => {
+            pause(app, source, re.as_str());
+            true
+        }
+        None => false,
+    }
+}
+
+/// OCR the screenshot and pause live AI if sensitive text is showing. Fails open when OCR
+/// isn't available, since the redaction step still applies to the upload.
+pub fn check_screen(app: &AppHandle, image: &RgbaImage) -> bool {
+    let config = app.state::<ConfigState>().lock().privacy_guard.clone();
+    if !config.enabled || config.markers.is_empty() {
+        return false;
+    }
+    match super::ocr::recognize(image) {
+        Ok(lines) => {
+            let text = lines
+                .iter()
+                .map(|l| l.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            check_text(app, "screen", &text)
+        }
+        Err(e) => {
+            log::debug!("Privacy check skipped, OCR unavailable: {}", e);
+            false
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_privacy_status(capture: tauri::State<'_, CaptureStateHandle>) -> PrivacyPause {
+    capture
+        .lock()
+        .privacy_pause
+        .clone()
+        .unwrap_or(PrivacyPause {
+            paused: false,
+            source: None,
+            marker: None,
+            since: None,
+        })
+}
+
+/// Lift a privacy pause so screen context and suggestions start flowing again
+#[tauri::command]
+pub fn resume_live_ai(app: AppHandle, capture: tauri::State<'_, CaptureStateHandle>) {
+    if capture.lock().privacy_pause.take().is_none() {
+        return;
+    }
+    log::info!("Live AI resumed");
+    let _ = app.emit(
+        "privacy-pause",
+        PrivacyPause {
+            paused: false,
+            source: None,
+            marker: None,
+            since: None,
+        },
+    );
+}
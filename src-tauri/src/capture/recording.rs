@@ -0,0 +1,330 @@
+use chrono::{DateTime, Duration, Utc};
+use flacenc::component::BitRepr;
+use flacenc::error::Verify;
+use flacenc::source::Fill;
+use parking_lot::Mutex;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tauri::Manager;
+
+use super::audio::AudioEngineState;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::manager::Session;
+use crate::session::storage::SessionAudio;
+use crate::session::storage_actor::StorageActor;
+
+/// Directory under app data holding per-session recordings
+pub fn recordings_dir(app_data: &Path) -> PathBuf {
+    app_data.join("recordings")
+}
+
+/// Stop any running mic/system capture and return the spilled recordings by source
+pub fn finish_recordings(engine: &AudioEngineState) -> Vec<(String, PathBuf)> {
+    let mut recordings = Vec::new();
+    let mut engine = engine.lock();
+
+    if engine.is_mic_active() {
+        match engine.stop_mic() {
+            Ok(Some(path)) => recordings.push(("mic".to_string(), PathBuf::from(path))),
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to stop mic capture: {}", e),
+        }
+    }
+
+    if engine.is_system_active() {
+        match engine.stop_system() {
+            Ok(Some(path)) => recordings.push(("system".to_string(), PathBuf::from(path))),
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to stop system audio capture: {}", e),
+        }
+    }
+
+    recordings
+}
+
+/// The stretch of the shared recording that belongs to one session
+#[derive(Debug, Clone)]
+pub struct SessionSpan {
+    pub session_id: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl SessionSpan {
+    /// From the session's start to its end, or to now while it is still running
+    pub fn of(session: &Session) -> Self {
+        let parse = |t: &str| DateTime::parse_from_rfc3339(t).map(|t| t.with_timezone(&Utc));
+        let now = Utc::now();
+        Self {
+            session_id: session.id.clone(),
+            start: parse(&session.start_time).unwrap_or(now),
+            end: session
+                .end_time
+                .as_deref()
+                .and_then(|t| parse(t).ok())
+                .unwrap_or(now),
+        }
+    }
+}
+
+/// Sessions that ended while capture kept running for others. Their part of the recording
+/// is cut out once capture stops.
+pub type EndedSpansState = Arc<Mutex<Vec<SessionSpan>>>;
+
+/// Keep the span of a session that ended while other sessions still use the capture
+pub fn hold_until_capture_stops(app: &tauri::AppHandle, session: &Session) {
+    app.state::<EndedSpansState>()
+        .lock()
+        .push(SessionSpan::of(session));
+}
+
+/// Cut each session's span out of the spilled WAV recordings, compress it into a FLAC file
+/// under app data and record it on that session. Runs on a background thread since encoding
+/// an hour of audio takes a while; returns it.
+pub fn persist_session_recordings(
+    app_data: PathBuf,
+    storage: StorageActor,
+    spans: Vec<SessionSpan>,
+    recordings: Vec<(String, PathBuf)>,
+    stopped_at: DateTime<Utc>,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let dir = recordings_dir(&app_data);
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            log::error!("Failed to create recordings dir: {}", e);
+            return;
+        }
+
+        for (source, wav_path) in recordings {
+            let (rate, frames) = match hound::WavReader::open(&wav_path) {
+                Ok(reader) => (
+                    reader.spec().sample_rate.max(1) as i64,
+                    reader.duration() as i64,
+                ),
+                Err(e) => {
+                    log::error!("Failed to open {} recording: {}", source, e);
+                    continue;
+                }
+            };
+            // The recording ran until capture stopped, so it began its own length earlier
+            let started_at = stopped_at - Duration::milliseconds(frames * 1000 / rate);
+            let frame_at = |t: DateTime<Utc>| {
+                ((t - started_at).num_milliseconds() * rate / 1000).clamp(0, frames) as usize
+            };
+
+            let mut kept = true;
+            for span in &spans {
+                let (from, to) = (span.start.max(started_at), span.end.min(stopped_at));
+                let (first, last) = (frame_at(from), frame_at(to));
+                if first >= last {
+                    continue;
+                }
+                let flac_path = dir.join(format!("{}-{}.flac", span.session_id, source));
+                if let Err(e) = encode_flac(&wav_path, first as u32, last as u32, &flac_path) {
+                    log::error!("Failed to encode {} recording: {}", source, e);
+                    std::fs::remove_file(&flac_path).ok();
+                    kept = false;
+                    continue;
+                }
+                let session_id = span.session_id.clone();
+                let path = flac_path.to_string_lossy().to_string();
+                let source = source.clone();
+                let from = from.to_rfc3339();
+                storage.send(move |s| {
+                    if let Err(e) = s.add_session_audio(&session_id, &source, &path, Some(&from)) {
+                        log::error!("Failed to record session audio: {}", e);
+                    }
+                });
+            }
+            if kept {
+                std::fs::remove_file(&wav_path).ok();
+            }
+        }
+    })
+}
+
+/// Encode frames `first..last` of a 16-bit PCM WAV file as FLAC, a block at a time, so a
+/// long recording is never held in memory whole
+fn encode_flac(wav_path: &Path, first: u32, last: u32, flac_path: &Path) -> Result<(), VenkyError> {
+    let read_err =
+        |e: hound::Error| VenkyError::Storage(format!("Failed to read recording: {}", e));
+    let mut reader = hound::WavReader::open(wav_path).map_err(read_err)?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+    reader
+        .seek(first)
+        .map_err(|e| VenkyError::Storage(format!("Failed to read recording: {}", e)))?;
+
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|e| VenkyError::Audio(format!("FLAC config error: {:?}", e)))?;
+    let block_size = config.block_size;
+    let mut stream = flacenc::component::Stream::new(
+        spec.sample_rate as usize,
+        channels,
+        spec.bits_per_sample as usize,
+    )
+    .map_err(|e| VenkyError::Audio(format!("FLAC config error: {:?}", e)))?;
+    let mut framebuf = flacenc::source::FrameBuf::with_size(channels, block_size)
+        .map_err(|e| VenkyError::Audio(format!("FLAC config error: {:?}", e)))?;
+
+    let write_err =
+        |e: std::io::Error| VenkyError::Storage(format!("Failed to write FLAC file: {}", e));
+    let mut out = BufWriter::new(File::create(flac_path).map_err(write_err)?);
+    // Written again at the end, once the stream info has the frame sizes and sample count
+    write_bits(&stream, &mut out)?;
+
+    let mut samples = reader
+        .samples::<i16>()
+        .take((last - first) as usize * channels);
+    let mut block = Vec::with_capacity(block_size * channels);
+    let mut frame_number = 0;
+    loop {
+        block.clear();
+        for sample in samples.by_ref().take(block_size * channels) {
+            block.push(i32::from(sample.map_err(read_err)?));
+        }
+        if block.is_empty() {
+            break;
+        }
+        framebuf
+            .fill_interleaved(&block)
+            .map_err(|e| VenkyError::Audio(format!("FLAC encode error: {:?}", e)))?;
+        let frame = flacenc::encode_fixed_size_frame(
+            &config,
+            &framebuf,
+            frame_number,
+            stream.stream_info(),
+        )
+        .map_err(|e| VenkyError::Audio(format!("FLAC encode error: {:?}", e)))?;
+        stream.stream_info_mut().update_frame_info(&frame);
+        write_bits(&frame, &mut out)?;
+        frame_number += 1;
+    }
+
+    let mut file = out.into_inner().map_err(|e| write_err(e.into_error()))?;
+    file.seek(SeekFrom::Start(0)).map_err(write_err)?;
+    write_bits(&stream, &mut file)?;
+    file.sync_all().map_err(write_err)
+}
+
+fn write_bits(value: &impl BitRepr, out: &mut impl Write) -> Result<(), VenkyError> {
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    value
+        .write(&mut sink)
+        .map_err(|e| VenkyError::Audio(format!("FLAC write error: {:?}", e)))?;
+    out.write_all(sink.as_slice())
+        .map_err(|e| VenkyError::Storage(format!("Failed to write FLAC file: {}", e)))
+}
+
+/// Decode a persisted recording (FLAC or WAV) into mono f32 samples and its sample rate
+pub fn read_recording(path: &Path) -> Result<(Vec<f32>, u32), VenkyError> {
+    let is_flac = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("flac"))
+        .unwrap_or(false);
+
+    let (samples, channels, sample_rate) = if is_flac {
+        let mut reader = claxon::FlacReader::open(path)
+            .map_err(|e| VenkyError::Audio(format!("Failed to open FLAC: {}", e)))?;
+        let info = reader.streaminfo();
+        let scale = (1i64 << (info.bits_per_sample - 1)) as f32;
+        let samples: Vec<f32> = reader
+            .samples()
+            .map(|s| s.map(|v| v as f32 / scale))
+            .collect::<Result<_, _>>()
+            .map_err(|e| VenkyError::Audio(format!("Failed to decode FLAC: {}", e)))?;
+        (samples, info.channels as u16, info.sample_rate)
+    } else {
+        let mut reader = hound::WavReader::open(path)
+            .map_err(|e| VenkyError::Storage(format!("Failed to open recording: {}", e)))?;
+        let spec = reader.spec();
+        let samples: Vec<f32> = match spec.sample_format {
+            hound::SampleFormat::Float => reader
+                .samples::<f32>()
+                .collect::<Result<_, _>>()
+                .map_err(|e| VenkyError::Storage(format!("Failed to read recording: {}", e)))?,
+            hound::SampleFormat::Int => {
+                let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .samples::<i32>()
+                    .map(|s| s.map(|v| v as f32 / scale))
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| VenkyError::Storage(format!("Failed to read recording: {}", e)))?
+            }
+        };
+        (samples, spec.channels, spec.sample_rate)
+    };
+
+    Ok((
+        super::audio::downmix_to_mono(&samples, channels),
+        sample_rate,
+    ))
+}
+
+#[tauri::command]
+pub async fn get_session_audio_path(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<SessionAudio>> {
+    Ok(storage
+        .call(move |s| s.get_session_audio(&session_id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn delete_session_audio(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<()> {
+    Ok(storage
+        .call(move |s| {
+            for audio in s.get_session_audio(&session_id)? {
+                if let Err(e) = std::fs::remove_file(&audio.path) {
+                    log::warn!("Failed to delete {}: {}", audio.path, e);
+                }
+            }
+            s.delete_session_audio(&session_id)
+        })
+        .await?)
+}
+
+/// Stop capture and persist its recordings if the user opted in, cut between `running`
+/// (the sessions it stops for) and the sessions that ended while it ran. Returns the thread
+/// encoding them, if any.
+pub fn save_if_enabled(app: &tauri::AppHandle, running: &[Session]) -> Option<JoinHandle<()>> {
+    let save = app
+        .state::<Arc<Mutex<crate::config::AppConfig>>>()
+        .lock()
+        .save_session_audio;
+    let recordings = finish_recordings(&app.state::<AudioEngineState>());
+    let stopped_at = Utc::now();
+    let mut spans = std::mem::take(&mut *app.state::<EndedSpansState>().lock());
+    spans.extend(running.iter().map(SessionSpan::of));
+
+    if !save || spans.is_empty() {
+        for (_, path) in recordings {
+            std::fs::remove_file(path).ok();
+        }
+        return None;
+    }
+
+    let app_data = match app.path().app_data_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::error!("Failed to resolve app data dir: {}", e);
+            return None;
+        }
+    };
+
+    Some(persist_session_recordings(
+        app_data,
+        app.state::<StorageActor>().inner().clone(),
+        spans,
+        recordings,
+        stopped_at,
+    ))
+}
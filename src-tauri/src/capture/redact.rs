@@ -0,0 +1,138 @@
+use image::RgbaImage;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::VenkyError;
+
+/// Region of the screen to blur, in fractions of the image size so it survives resizing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactRegion {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    pub regions: Vec<RedactRegion>,
+    /// Regexes; on-screen text matching any of them is blacked out (needs the OCR models)
+    pub text_patterns: Vec<String>,
+    pub blur_sigma: f32,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            regions: Vec::new(),
+            text_patterns: vec![
+                // Email addresses
+                r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}".to_string(),
+                // OpenAI-style secret keys
+                r"sk-[A-Za-z0-9_-]{16,}".to_string(),
+                // AWS access key ids
+                r"AKIA[0-9A-Z]{16}".to_string(),
+            ],
+            blur_sigma: 12.0,
+        }
+    }
+}
+
+/// Blur configured regions and mask matching text in place. Fails closed: if text
+/// patterns are configured but OCR can't run, the capture is rejected rather than sent raw.
+pub fn apply(image: &mut RgbaImage, config: &RedactionConfig) -> Result<(), VenkyError> {
+    if !config.enabled {
+        return Ok(());
+    }
+
+    for region in &config.regions {
+        blur_region(image, region, config.blur_sigma);
+    }
+
+    if config.text_patterns.is_empty() {
+        return Ok(());
+    }
+
+    let patterns = config
+        .text_patterns
+        .iter()
+        .map(|p| {
+            Regex::new(p)
+                .map_err(|e| VenkyError::Other(format!("Invalid redaction pattern {}: {}", p, e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let lines = super::ocr::recognize(image)
+        .map_err(|e| VenkyError::Other(format!("Redaction needs OCR but it failed: {}", e)))?;
+
+    for line in &lines {
+        if !patterns.iter().any(|re| re.is_match(&line.text)) {
+            continue;
+        }
+
+        let mut masked_word = false;
+        for word in &line.words {
+            if patterns.iter().any(|re| re.is_match(&word.text)) {
+                fill_rect(image, word.x, word.y, word.width, word.height);
+                masked_word = true;
+            }
+        }
+
+        // The match spans several words, so hide the whole line
+        if !masked_word {
+            if let Some((x, y, width, height)) = line.bounds() {
+                fill_rect(image, x, y, width, height);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace text matching the configured patterns, for OCR output that leaves the machine
+pub fn redact_text(text: &str, config: &RedactionConfig) -> String {
+    if !config.enabled {
+        return text.to_string();
+    }
+
+    let mut text = text.to_string();
+    for pattern in &config.text_patterns {
+        match Regex::new(pattern) {
+            Ok(re) => text = re.replace_all(&text, "[REDACTED]").into_owned(),
+            Err(e) => log::warn!("Invalid redaction pattern {}: {}", pattern, e),
+        }
+    }
+    text
+}
+
+fn blur_region(image: &mut RgbaImage, region: &RedactRegion, sigma: f32) {
+    let (img_w, img_h) = image.dimensions();
+    let x = (region.x.clamp(0.0, 1.0) * img_w as f32) as u32;
+    let y = (region.y.clamp(0.0, 1.0) * img_h as f32) as u32;
+    let width = ((region.width.max(0.0) * img_w as f32) as u32).min(img_w - x);
+    let height = ((region.height.max(0.0) * img_h as f32) as u32).min(img_h - y);
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let patch = image::imageops::crop_imm(image, x, y, width, height).to_image();
+    let blurred = image::imageops::blur(&patch, sigma);
+    image::imageops::replace(image, &blurred, x as i64, y as i64);
+}
+
+fn fill_rect(image: &mut RgbaImage, x: i32, y: i32, width: u32, height: u32) {
+    let (img_w, img_h) = image.dimensions();
+    let left = x.max(0) as u32;
+    let top = y.max(0) as u32;
+    let right = ((x + width as i32).max(0) as u32).min(img_w);
+    let bottom = ((y + height as i32).max(0) as u32).min(img_h);
+
+    for py in top..bottom {
+        for px in left..right {
+            image.put_pixel(px, py, image::Rgba([0, 0, 0, 255]));
+        }
+    }
+}
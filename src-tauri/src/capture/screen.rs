@@ -1,6 +1,48 @@
 use base64::{engine::general_purpose::STANDARD, Engine};
-use serde::Serialize;
+use image::RgbaImage;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::{history, CaptureState};
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type CaptureStateHandle = Arc<Mutex<CaptureState>>;
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+/// Width of the previews returned by `list_capture_targets`
+const THUMBNAIL_WIDTH: u32 = 320;
+/// Perceptual hashes differing in more than this many of 64 bits count as a new screen
+const SCREEN_CHANGE_THRESHOLD: u32 = 6;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ScreenshotFormat {
+    Png,
+    Jpeg,
+    WebP,
+}
+
+impl ScreenshotFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ScreenshotFormat::Png => "image/png",
+            ScreenshotFormat::Jpeg => "image/jpeg",
+            ScreenshotFormat::WebP => "image/webp",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ScreenshotFormat::Png => "png",
+            ScreenshotFormat::Jpeg => "jpg",
+            ScreenshotFormat::WebP => "webp",
+        }
+    }
+}
 
 #[derive(Debug, Serialize)]
 pub struct ScreenCapture {
@@ -8,49 +50,484 @@ pub struct ScreenCapture {
     pub width: u32,
     pub height: u32,
     pub timestamp: String,
+    /// MIME type of the encoded image
+    pub format: String,
+    /// Encoded size before base64, so the UI can show what a request will cost
+    pub byte_size: usize,
+}
+
+/// A capture written to disk instead of returned inline
+#[derive(Debug, Serialize)]
+pub struct ScreenCaptureFile {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub timestamp: String,
+    pub format: String,
+    pub byte_size: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MonitorInfo {
+    pub index: usize,
+    pub id: u32,
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub is_primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum CaptureTargetKind {
+    Monitor,
+    Window,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CaptureTarget {
+    pub kind: CaptureTargetKind,
+    pub id: u32,
+    pub title: String,
+    pub app_name: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub thumbnail_base64: Option<String>,
 }
 
 #[tauri::command]
-pub fn capture_screen() -> Result<ScreenCapture, String> {
-    let monitors = xcap::Monitor::all().map_err(|e| format!("Failed to list monitors: {}", e))?;
+pub fn capture_screen(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+) -> CommandResult<ScreenCapture> {
+    let cfg = config.lock().clone();
+    to_screen_capture(&app, grab_monitor(0)?, &cfg)
+}
 
-    let monitor = monitors
-        .first()
-        .ok_or_else(|| "No monitors found".to_string())?;
+/// Like `capture_screen` (or `capture_window`/`capture_monitor` when an id/index is given),
+/// but writes the image under app data and returns its path, keeping large images off the IPC bridge
+#[tauri::command]
+pub fn capture_screen_to_file(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+    monitor_index: Option<usize>,
+    window_id: Option<u32>,
+) -> CommandResult<ScreenCaptureFile> {
+    let cfg = config.lock().clone();
+    let image = match window_id {
+        Some(id) => grab_window(id)?,
+        None => grab_monitor(monitor_index.unwrap_or(0))?,
+    };
+    let (width, height) = image.dimensions();
+    let bytes = encode_capture(image, &cfg)?;
+    let path = history::write_capture_file(&app, &bytes, &cfg)?;
+
+    if let Some(session_id) = history::active_session_id(&app) {
+        history::record_capture(&app, &session_id, &path, &bytes, &cfg);
+    }
+
+    Ok(ScreenCaptureFile {
+        path: path.to_string_lossy().to_string(),
+        width,
+        height,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        format: cfg.screenshot_format.mime_type().to_string(),
+        byte_size: bytes.len(),
+    })
+}
+
+#[tauri::command]
+pub fn list_monitors() -> CommandResult<Vec<MonitorInfo>> {
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| VenkyError::Other(format!("Failed to list monitors: {}", e)))?;
+
+    Ok(monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| MonitorInfo {
+            index,
+            id: monitor.id().unwrap_or(0),
+            name: monitor.name().unwrap_or_default(),
+            width: monitor.width().unwrap_or(0),
+            height: monitor.height().unwrap_or(0),
+            x: monitor.x().unwrap_or(0),
+            y: monitor.y().unwrap_or(0),
+            is_primary: monitor.is_primary().unwrap_or(false),
+        })
+        .collect())
+}
+
+/// Capture the monitor at `index` in `list_monitors` order
+#[tauri::command]
+pub fn capture_monitor(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+    index: usize,
+) -> CommandResult<ScreenCapture> {
+    let cfg = config.lock().clone();
+    to_screen_capture(&app, grab_monitor(index)?, &cfg)
+}
+
+/// Capture every monitor, either one capture per monitor or stitched
+/// left-to-right (by screen position) into a single image
+#[tauri::command]
+pub fn capture_all_monitors(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+    stitch: Option<bool>,
+) -> CommandResult<Vec<ScreenCapture>> {
+    let cfg = config.lock().clone();
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| VenkyError::Other(format!("Failed to list monitors: {}", e)))?;
+    if monitors.is_empty() {
+        return Err(VenkyError::Other("No monitors found".to_string()));
+    }
+
+    let mut images = Vec::with_capacity(monitors.len());
+    for monitor in &monitors {
+        let image = monitor
+            .capture_image()
+            .map_err(|e| VenkyError::Other(format!("Failed to capture screen: {}", e)))?;
+        images.push((monitor.x().unwrap_or(0), image));
+    }
+
+    if !stitch.unwrap_or(false) {
+        let captures: Result<Vec<_>, VenkyError> = images
+            .into_iter()
+            .map(|(_, image)| to_screen_capture(&app, image, &cfg))
+            .collect();
+        return captures;
+    }
+
+    images.sort_by_key(|(x, _)| *x);
+    let width = images.iter().map(|(_, image)| image.width()).sum();
+    let height = images
+        .iter()
+        .map(|(_, image)| image.height())
+        .max()
+        .unwrap_or(0);
+
+    let mut canvas = RgbaImage::new(width, height);
+    let mut offset = 0i64;
+    for (_, image) in &images {
+        image::imageops::overlay(&mut canvas, image, offset, 0);
+        offset += image.width() as i64;
+    }
+
+    Ok(vec![to_screen_capture(&app, canvas, &cfg)?])
+}
+
+/// Capture a single window by id, e.g. just the slide deck or the coding pane
+#[tauri::command]
+pub fn capture_window(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+    id: u32,
+) -> CommandResult<ScreenCapture> {
+    let cfg = config.lock().clone();
+    to_screen_capture(&app, grab_window(id)?, &cfg)
+}
+
+/// The overlay and other VenkyAI windows must never be sent to the vision model
+fn is_own_window(window: &xcap::Window) -> bool {
+    window
+        .pid()
+        .map(|pid| pid == std::process::id())
+        .unwrap_or(false)
+}
 
-    let image = monitor
+/// Raw image of the monitor at `index`
+fn grab_monitor(index: usize) -> Result<RgbaImage, VenkyError> {
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| VenkyError::Other(format!("Failed to list monitors: {}", e)))?;
+
+    let monitor = monitors.get(index).ok_or_else(|| {
+        VenkyError::Other(format!(
+            "Monitor {} not found ({} available)",
+            index,
+            monitors.len()
+        ))
+    })?;
+
+    monitor
         .capture_image()
-        .map_err(|e| format!("Failed to capture screen: {}", e))?;
+        .map_err(|e| VenkyError::Other(format!("Failed to capture screen: {}", e)))
+}
 
-    let width = image.width();
-    let height = image.height();
+pub fn grab_window(id: u32) -> Result<RgbaImage, VenkyError> {
+    let windows = xcap::Window::all()
+        .map_err(|e| VenkyError::Other(format!("Failed to list windows: {}", e)))?;
 
-    // Resize for efficiency (max 1280px wide)
-    let image = if width > 1280 {
-        let scale = 1280.0 / width as f64;
-        let new_height = (height as f64 * scale) as u32;
-        image::imageops::resize(
-            &image,
-            1280,
-            new_height,
-            image::imageops::FilterType::Triangle,
-        )
-    } else {
-        image
-    };
+    let window = windows
+        .into_iter()
+        .find(|w| w.id().ok() == Some(id))
+        .ok_or_else(|| VenkyError::Other(format!("Window {} not found", id)))?;
 
-    // Encode to PNG then base64
-    let mut buffer = Cursor::new(Vec::new());
-    image::DynamicImage::ImageRgba8(image)
-        .write_to(&mut buffer, image::ImageFormat::Png)
-        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    if is_own_window(&window) {
+        return Err(VenkyError::Other(
+            "Can't capture VenkyAI's own windows".to_string(),
+        ));
+    }
+    if window.is_minimized().unwrap_or(false) {
+        return Err(VenkyError::Other("Window is minimized".to_string()));
+    }
+
+    window
+        .capture_image()
+        .map_err(|e| VenkyError::Other(format!("Failed to capture window: {}", e)))
+}
 
-    let base64_image = STANDARD.encode(buffer.into_inner());
+/// Monitors and visible windows that can be captured, optionally with small previews
+#[tauri::command]
+pub fn list_capture_targets(thumbnails: Option<bool>) -> CommandResult<Vec<CaptureTarget>> {
+    let thumbnails = thumbnails.unwrap_or(true);
+    let mut targets = Vec::new();
+
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| VenkyError::Other(format!("Failed to list monitors: {}", e)))?;
+    for monitor in monitors {
+        let Ok(id) = monitor.id() else { continue };
+        targets.push(CaptureTarget {
+            kind: CaptureTargetKind::Monitor,
+            id,
+            title: monitor.name().unwrap_or_default(),
+            app_name: None,
+            width: monitor.width().unwrap_or(0),
+            height: monitor.height().unwrap_or(0),
+            thumbnail_base64: thumbnails
+                .then(|| monitor.capture_image().ok().and_then(thumbnail))
+                .flatten(),
+        });
+    }
+
+    let windows = xcap::Window::all()
+        .map_err(|e| VenkyError::Other(format!("Failed to list windows: {}", e)))?;
+    for window in windows {
+        if window.is_minimized().unwrap_or(true) || is_own_window(&window) {
+            continue;
+        }
+        let (Ok(id), Ok(title)) = (window.id(), window.title()) else {
+            continue;
+        };
+        // Skip untitled helper/overlay windows
+        if title.is_empty() {
+            continue;
+        }
+        targets.push(CaptureTarget {
+            kind: CaptureTargetKind::Window,
+            id,
+            title,
+            app_name: window.app_name().ok(),
+            width: window.width().unwrap_or(0),
+            height: window.height().unwrap_or(0),
+            thumbnail_base64: thumbnails
+                .then(|| window.capture_image().ok().and_then(thumbnail))
+                .flatten(),
+        });
+    }
+
+    Ok(targets)
+}
+
+/// Captures the screen every `capture_interval_secs` and, when the content changed
+/// meaningfully, stores it as the latest screen context and emits "screen-context-updated".
+pub async fn screen_context_loop(app: AppHandle) {
+    let mut last_hash: Option<u64> = None;
+
+    loop {
+        let interval_secs = app.state::<ConfigState>().lock().capture_interval_secs;
+        // 0 disables the loop; keep polling so it picks up a config change
+        if interval_secs == 0 {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            continue;
+        }
+        tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+        if super::privacy::is_paused(&app) {
+            continue;
+        }
+
+        let image = match capture_primary_monitor() {
+            Ok(image) => image,
+            Err(e) => {
+                log::debug!("Screen context capture skipped: {}", e);
+                continue;
+            }
+        };
+
+        let hash = perceptual_hash(&image);
+        let changed = match last_hash {
+            Some(prev) => (prev ^ hash).count_ones() > SCREEN_CHANGE_THRESHOLD,
+            None => true,
+        };
+        if !changed {
+            continue;
+        }
+        last_hash = Some(hash);
+        // OCR is slow, keep it off the async workers
+        let sensitive = {
+            let (app, image) = (app.clone(), image.clone());
+            tokio::task::spawn_blocking(move || super::privacy::check_screen(&app, &image))
+                .await
+                .unwrap_or(true)
+        };
+        if sensitive {
+            continue;
+        }
+
+        let cfg = app.state::<ConfigState>().lock().clone();
+        match to_screen_capture(&app, image, &cfg) {
+            Ok(capture) => {
+                app.state::<CaptureStateHandle>().lock().last_screen_capture =
+                    Some(capture.base64_image.clone());
+                let _ = app.emit("screen-context-updated", &capture);
+            }
+            Err(e) => log::error!("Failed to encode screen context: {}", e),
+        }
+    }
+}
+
+pub fn capture_primary_monitor() -> Result<RgbaImage, VenkyError> {
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| VenkyError::Other(format!("Failed to list monitors: {}", e)))?;
+    let monitor = monitors
+        .iter()
+        .find(|m| m.is_primary().unwrap_or(false))
+        .or_else(|| monitors.first())
+        .ok_or_else(|| VenkyError::Other("No monitors found".to_string()))?;
+
+    monitor
+        .capture_image()
+        .map_err(|e| VenkyError::Other(format!("Failed to capture screen: {}", e)))
+}
+
+/// 64-bit difference hash: each bit says whether a pixel of a 9x8 grayscale
+/// thumbnail is brighter than its right neighbour
+pub fn perceptual_hash(image: &RgbaImage) -> u64 {
+    let gray = image::imageops::grayscale(image);
+    let small = image::imageops::resize(&gray, 9, 8, image::imageops::FilterType::Triangle);
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+    hash
+}
+
+/// Downscale to the configured max dimension, apply redaction and encode in the configured format.
+/// Captures taken during a session are also kept on disk for later review.
+pub fn to_screen_capture(
+    app: &AppHandle,
+    image: RgbaImage,
+    cfg: &AppConfig,
+) -> Result<ScreenCapture, VenkyError> {
+    let width = image.width();
+    let height = image.height();
+    let bytes = encode_capture(image, cfg)?;
+    history::keep_for_session(app, &bytes, cfg);
 
     Ok(ScreenCapture {
-        base64_image,
+        byte_size: bytes.len(),
+        base64_image: STANDARD.encode(bytes),
         width,
         height,
         timestamp: chrono::Utc::now().to_rfc3339(),
+        format: cfg.screenshot_format.mime_type().to_string(),
     })
 }
+
+fn encode_capture(image: RgbaImage, cfg: &AppConfig) -> Result<Vec<u8>, VenkyError> {
+    // Resize for efficiency
+    let mut image = resize_to_fit(image, cfg.screenshot_max_dimension);
+    super::redact::apply(&mut image, &cfg.redaction)?;
+    encode_image(image, cfg.screenshot_format, cfg.screenshot_quality)
+}
+
+/// Encode as PNG, or lossy JPEG/WebP at `quality` (1-100)
+fn encode_image(
+    image: RgbaImage,
+    format: ScreenshotFormat,
+    quality: u8,
+) -> Result<Vec<u8>, VenkyError> {
+    let quality = quality.clamp(1, 100);
+    match format {
+        ScreenshotFormat::Png => {
+            let mut buffer = Cursor::new(Vec::new());
+            image::DynamicImage::ImageRgba8(image)
+                .write_to(&mut buffer, image::ImageFormat::Png)
+                .map_err(|e| VenkyError::Other(format!("Failed to encode image: {}", e)))?;
+            Ok(buffer.into_inner())
+        }
+        ScreenshotFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let rgb = image::DynamicImage::ImageRgba8(image).to_rgb8();
+            let mut buffer = Vec::new();
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, quality)
+                .encode_image(&rgb)
+                .map_err(|e| VenkyError::Other(format!("Failed to encode image: {}", e)))?;
+            Ok(buffer)
+        }
+        ScreenshotFormat::WebP => {
+            let encoded = webp::Encoder::from_rgba(image.as_raw(), image.width(), image.height())
+                .encode(quality as f32);
+            Ok(encoded.to_vec())
+        }
+    }
+}
+
+/// MIME type of a base64-encoded image, sniffed from its magic bytes
+pub fn mime_from_base64(base64_image: &str) -> &'static str {
+    if base64_image.starts_with("/9j/") {
+        ScreenshotFormat::Jpeg.mime_type()
+    } else if base64_image.starts_with("UklGR") {
+        ScreenshotFormat::WebP.mime_type()
+    } else {
+        ScreenshotFormat::Png.mime_type()
+    }
+}
+
+fn thumbnail(image: RgbaImage) -> Option<String> {
+    let thumb = resize_to_width(image, THUMBNAIL_WIDTH);
+    encode_image(thumb, ScreenshotFormat::Png, 100)
+        .ok()
+        .map(|bytes| STANDARD.encode(bytes))
+}
+
+/// Downscale so neither side exceeds `max_dimension` (0 leaves the image alone)
+fn resize_to_fit(image: RgbaImage, max_dimension: u32) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    if max_dimension == 0 || (width <= max_dimension && height <= max_dimension) {
+        return image;
+    }
+
+    let scale = max_dimension as f64 / width.max(height) as f64;
+    let new_width = ((width as f64 * scale) as u32).max(1);
+    let new_height = ((height as f64 * scale) as u32).max(1);
+    image::imageops::resize(
+        &image,
+        new_width,
+        new_height,
+        image::imageops::FilterType::Triangle,
+    )
+}
+
+fn resize_to_width(image: RgbaImage, max_width: u32) -> RgbaImage {
+    let width = image.width();
+    let height = image.height();
+    if width <= max_width {
+        return image;
+    }
+
+    let scale = max_width as f64 / width as f64;
+    let new_height = ((height as f64 * scale) as u32).max(1);
+    image::imageops::resize(
+        &image,
+        max_width,
+        new_height,
+        image::imageops::FilterType::Triangle,
+    )
+}
@@ -0,0 +1,270 @@
+use parking_lot::Mutex;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tauri::{AppHandle, Manager};
+
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::storage_actor::StorageActor;
+
+pub type ScreenRecorderState = Arc<Mutex<ScreenRecorder>>;
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+/// A running recording: scap frames piped into an ffmpeg process writing the mp4
+struct ActiveRecording {
+    session_id: String,
+    path: PathBuf,
+    /// RFC 3339 time capture began
+    started_at: String,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Result<(), VenkyError>>,
+}
+
+#[derive(Default)]
+pub struct ScreenRecorder {
+    active: Option<ActiveRecording>,
+}
+
+impl ScreenRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&mut self, session_id: &str, path: PathBuf, fps: u32) -> Result<(), VenkyError> {
+        if self.active.is_some() {
+            return Err(VenkyError::Other(
+                "Screen recording already active".to_string(),
+            ));
+        }
+        if !scap::has_permission() {
+            return Err(VenkyError::Permission(
+                "Screen recording permission not granted".to_string(),
+            ));
+        }
+
+        let options = scap::capturer::Options {
+            fps: fps.max(1),
+            show_cursor: true,
+            captures_audio: false,
+            output_type: scap::frame::FrameType::BGRAFrame,
+            ..Default::default()
+        };
+        let mut capturer = scap::capturer::Capturer::build(options)
+            .map_err(|e| VenkyError::Other(format!("Failed to build scap capturer: {:?}", e)))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_signal = stop.clone();
+        let output = path.clone();
+
+        let thread = std::thread::spawn(move || {
+            capturer.start_capture();
+            let result = record_frames(&mut capturer, &output, fps.max(1), &stop_signal);
+            capturer.stop_capture();
+            result
+        });
+
+        self.active = Some(ActiveRecording {
+            session_id: session_id.to_string(),
+            path,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            stop,
+            thread,
+        });
+        Ok(())
+    }
+
+    /// Signal the recording of `session_id` to stop; finishing it is up to the caller
+    fn stop(&mut self, session_id: &str) -> Option<ActiveRecording> {
+        if self.active.as_ref()?.session_id != session_id {
+            return None;
+        }
+        let recording = self.active.take()?;
+        recording.stop.store(true, Ordering::SeqCst);
+        Some(recording)
+    }
+}
+
+impl ActiveRecording {
+    /// Wait for ffmpeg to finish the file; returns the session id, path and start time
+    fn finish(self) -> Result<(String, PathBuf, String), VenkyError> {
+        self.thread
+            .join()
+            .map_err(|_| VenkyError::Other("Screen recording thread panicked".to_string()))??;
+        Ok((self.session_id, self.path, self.started_at))
+    }
+}
+
+/// ffmpeg is started on the first frame, once the frame size is known
+struct Encoder {
+    child: Child,
+    stdin: ChildStdin,
+    width: i32,
+    height: i32,
+}
+
+fn record_frames(
+    capturer: &mut scap::capturer::Capturer,
+    output: &Path,
+    fps: u32,
+    stop: &AtomicBool,
+) -> Result<(), VenkyError> {
+    let mut encoder: Option<Encoder> = None;
+
+    while !stop.load(Ordering::SeqCst) {
+        let frame = match capturer.get_next_frame() {
+            Ok(scap::frame::Frame::Video(scap::frame::VideoFrame::BGRA(frame))) => frame,
+            Ok(_) => continue,
+            Err(e) => {
+                log::error!("Error getting next screen frame: {:?}", e);
+                break;
+            }
+        };
+        if frame.width <= 0 || frame.height <= 0 {
+            continue;
+        }
+
+        if encoder.is_none() {
+            encoder = Some(spawn_ffmpeg(output, frame.width, frame.height, fps)?);
+        }
+        let Some(ref mut enc) = encoder else {
+            continue;
+        };
+
+        // Raw video needs a fixed frame size; drop frames from a resized display
+        if frame.width != enc.width || frame.height != enc.height {
+            continue;
+        }
+        if let Err(e) = enc.stdin.write_all(&frame.data) {
+            log::error!("Failed to feed screen recording encoder: {}", e);
+            break;
+        }
+    }
+
+    match encoder {
+        Some(Encoder {
+            mut child, stdin, ..
+        }) => {
+            // Closing stdin lets ffmpeg flush and write the mp4 index
+            drop(stdin);
+            let status = child
+                .wait()
+                .map_err(|e| VenkyError::Other(format!("ffmpeg did not exit cleanly: {}", e)))?;
+            if status.success() {
+                Ok(())
+            } else {
+                Err(VenkyError::Other(format!("ffmpeg exited with {}", status)))
+            }
+        }
+        None => Err(VenkyError::Other(
+            "No screen frames were captured".to_string(),
+        )),
+    }
+}
+
+fn spawn_ffmpeg(output: &Path, width: i32, height: i32, fps: u32) -> Result<Encoder, VenkyError> {
+    let mut child = Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-loglevel",
+            "error",
+            "-f",
+            "rawvideo",
+            "-pix_fmt",
+            "bgra",
+        ])
+        .args(["-s", &format!("{}x{}", width, height)])
+        .args(["-r", &fps.to_string(), "-i", "-"])
+        // yuv420p needs even dimensions
+        .args(["-vf", "scale=trunc(iw/2)*2:trunc(ih/2)*2"])
+        .args(["-c:v", "libx264", "-preset", "veryfast", "-crf", "28"])
+        .args(["-pix_fmt", "yuv420p", "-movflags", "+faststart"])
+        .arg(output)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            VenkyError::Other(format!("Failed to start ffmpeg (is it installed?): {}", e))
+        })?;
+
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| VenkyError::Other("Failed to open ffmpeg stdin".to_string()))?;
+
+    Ok(Encoder {
+        child,
+        stdin,
+        width,
+        height,
+    })
+}
+
+/// Start recording the screen for a new session if the user opted in
+pub fn start_if_enabled(app: &AppHandle, session_id: &str) {
+    let (enabled, fps) = {
+        let cfg = app.state::<ConfigState>();
+        let cfg = cfg.lock();
+        (cfg.record_screen, cfg.screen_recording_fps)
+    };
+    if !enabled {
+        return;
+    }
+
+    let dir = match app.path().app_data_dir() {
+        Ok(dir) => super::recording::recordings_dir(&dir),
+        Err(e) => {
+            log::error!("Failed to resolve app data dir: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::error!("Failed to create recordings dir: {}", e);
+        return;
+    }
+
+    let path = dir.join(format!("{}-screen.mp4", session_id));
+    if let Err(e) = app
+        .state::<ScreenRecorderState>()
+        .lock()
+        .start(session_id, path, fps)
+    {
+        log::error!("Failed to start screen recording: {}", e);
+    }
+}
+
+/// Finish the session's screen recording, if it has one, on a background thread and link it.
+/// Returns the thread finishing it.
+pub fn stop_and_save(app: &AppHandle, session_id: &str) -> Option<JoinHandle<()>> {
+    let recorder = app.state::<ScreenRecorderState>();
+    let storage = app.state::<StorageActor>().inner().clone();
+
+    // Stop under the lock but finish outside it so a new session can start recording meanwhile
+    let recording = recorder.lock().stop(session_id)?;
+
+    let finishing = std::thread::spawn(move || match recording.finish() {
+        Ok((session_id, path, started_at)) => {
+            let path = path.to_string_lossy().to_string();
+            storage.send(move |s| {
+                if let Err(e) = s.set_session_recording(&session_id, &path, Some(&started_at)) {
+                    log::error!("Failed to record session screen recording: {}", e);
+                }
+            });
+        }
+        Err(e) => log::error!("Screen recording failed: {}", e),
+    });
+    Some(finishing)
+}
+
+#[tauri::command]
+pub async fn get_session_recording_path(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Option<String>> {
+    Ok(storage
+        .call(move |s| s.get_session_recording(&session_id))
+        .await?)
+}
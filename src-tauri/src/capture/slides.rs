@@ -0,0 +1,152 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::screen::{self, ScreenCapture};
+use super::CaptureState;
+use crate::config::AppConfig;
+use crate::error::VenkyError;
+use crate::session::manager::SessionManager;
+
+type CaptureStateHandle = Arc<Mutex<CaptureState>>;
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// How often the presented window is sampled while watching for slide changes
+const SLIDE_CHECK_INTERVAL_SECS: u64 = 2;
+/// Hash distance (of 64 bits) that counts as a different slide rather than a cursor or video tile
+const SLIDE_CHANGE_THRESHOLD: u32 = 10;
+/// Two samples this close are the same frame, i.e. the slide transition has finished
+const SLIDE_STABLE_THRESHOLD: u32 = 3;
+
+#[derive(Debug, Serialize)]
+pub struct SlideChange {
+    pub index: u32,
+    pub text: String,
+    pub capture: ScreenCapture,
+}
+
+/// During sessions with slide detection on, samples the meeting window (falling back to the
+/// primary monitor), and once a new slide has settled snapshots it, OCRs it, stores its text
+/// as the current slide and emits "slide-changed".
+pub async fn slide_watch_loop(app: AppHandle) {
+    let mut interval = tokio::time::interval(Duration::from_secs(SLIDE_CHECK_INTERVAL_SECS));
+    let mut session_id: Option<String> = None;
+    let mut slide_hash: Option<u64> = None;
+    let mut previous_hash: Option<u64> = None;
+    let mut index = 0u32;
+
+    loop {
+        interval.tick().await;
+
+        let cfg = app.state::<ConfigState>().lock().clone();
+        let session = app
+            .state::<SessionState>()
+            .lock()
+            .focused()
+            .map(|s| (s.id.clone(), s.meeting_window.clone()));
+
+        let Some((id, meeting_window)) = session.filter(|_| cfg.detect_slides) else {
+            if session_id.take().is_some() {
+                app.state::<CaptureStateHandle>().lock().current_slide_text = None;
+            }
+            continue;
+        };
+
+        // Start counting again for every session
+        if session_id.as_deref() != Some(id.as_str()) {
+            session_id = Some(id);
+            slide_hash = None;
+            previous_hash = None;
+            index = 0;
+        }
+
+        let image = match meeting_window_image(meeting_window.as_deref()) {
+            Ok(image) => image,
+            Err(e) => {
+                log::debug!("Slide check skipped: {}", e);
+                continue;
+            }
+        };
+
+        let hash = screen::perceptual_hash(&image);
+        let settled = previous_hash
+            .map(|prev| (prev ^ hash).count_ones() <= SLIDE_STABLE_THRESHOLD)
+            .unwrap_or(false);
+        previous_hash = Some(hash);
+
+        let is_new = slide_hash
+            .map(|slide| (slide ^ hash).count_ones() > SLIDE_CHANGE_THRESHOLD)
+            .unwrap_or(true);
+        if !settled || !is_new {
+            continue;
+        }
+        slide_hash = Some(hash);
+        index += 1;
+
+        let ocr_image = image.clone();
+        let text =
+            match tokio::task::spawn_blocking(move || super::ocr::recognize(&ocr_image)).await {
+                Ok(Ok(lines)) => lines
+                    .into_iter()
+                    .map(|line| line.text)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Ok(Err(e)) => {
+                    log::warn!("Slide OCR failed: {}", e);
+                    String::new()
+                }
+                Err(e) => {
+                    log::error!("Slide OCR task failed: {}", e);
+                    String::new()
+                }
+            };
+        let text = super::redact::redact_text(&text, &cfg.redaction);
+
+        let capture = match screen::to_screen_capture(&app, image, &cfg) {
+            Ok(capture) => capture,
+            Err(e) => {
+                log::error!("Failed to snapshot slide: {}", e);
+                continue;
+            }
+        };
+
+        log::info!("Slide {} detected ({} chars of text)", index, text.len());
+        app.state::<CaptureStateHandle>().lock().current_slide_text =
+            (!text.is_empty()).then(|| text.clone());
+        let _ = app.emit(
+            "slide-changed",
+            SlideChange {
+                index,
+                text,
+                capture,
+            },
+        );
+    }
+}
+
+/// The window the session was auto-started for, or the primary monitor for sessions started
+/// by hand and meetings whose window can't be found any more
+fn meeting_window_image(meeting_window: Option<&str>) -> Result<image::RgbaImage, VenkyError> {
+    let Some(meeting_window) = meeting_window else {
+        return screen::capture_primary_monitor();
+    };
+    let window_id = xcap::Window::all()
+        .unwrap_or_default()
+        .into_iter()
+        .find(|w| w.title().map(|t| t == meeting_window).unwrap_or(false))
+        .and_then(|w| w.id().ok());
+
+    match window_id {
+        Some(id) => screen::grab_window(id),
+        None => {
+            log::debug!(
+                "Meeting window \"{}\" not found; watching the primary monitor for slides",
+                meeting_window
+            );
+            screen::capture_primary_monitor()
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+use crate::error::VenkyError;
+use crate::session::export::{self, ExportFormat};
+use crate::session::storage::Storage;
+
+/// Same place Tauri keeps app data for the "com.venkyai.app" identifier
+const APP_IDENTIFIER: &str = "com.venkyai.app";
+
+const USAGE: &str = "Usage:
+  venkyai transcribe <audio file> [--out <file>]
+  venkyai summarize --session <id> [--template <id>]
+  venkyai export --session <id> [--format md|pdf|json] [--out <file>]
+  venkyai export --all --out <dir> [--format md|pdf|json]
+
+Without a command the app starts as usual.";
+
+/// Run a command given on the command line without starting the GUI. Returns the exit code,
+/// or None when there is no command and the app should start normally.
+pub fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = args.first()?.as_str();
+    let rest = &args[1..];
+    let result = match command {
+        "transcribe" => tauri::async_runtime::block_on(transcribe(rest)),
+        "summarize" => tauri::async_runtime::block_on(summarize(rest)),
+        "export" => export(rest),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        // Flags such as the login item's --hidden are for the GUI
+        _ if command.starts_with('-') => return None,
+        _ => Err(VenkyError::Other(format!(
+            "Unknown command \"{}\"\n\n{}",
+            command, USAGE
+        ))),
+    };
+    match result {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("venkyai: {}", e);
+            Some(1)
+        }
+    }
+}
+
+/// Value following `flag`, if given
+fn option<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
+
+fn app_data_dir() -> Result<PathBuf, VenkyError> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| VenkyError::Storage("Failed to resolve app data dir".to_string()))
+}
+
+fn load_config() -> Result<AppConfig, VenkyError> {
+    let app_data = app_data_dir()?;
+    let (config, diagnostics) = AppConfig::load(&app_data);
+    for diagnostic in diagnostics {
+        eprintln!("venkyai: config: {}", diagnostic.message);
+    }
+    crate::http::configure(&config);
+    Ok(config)
+}
+
+fn open_storage() -> Result<Storage, VenkyError> {
+    let db_path = app_data_dir()?.join("venkyai.db");
+    if !db_path.exists() {
+        return Err(VenkyError::Storage(format!(
+            "No VenkyAI database at {}",
+            db_path.display()
+        )));
+    }
+    crate::session::encryption::open_database(&db_path)
+}
+
+fn audio_mime(path: &Path) -> Result<&'static str, VenkyError> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "wav" => Ok("audio/wav"),
+        "mp3" => Ok("audio/mpeg"),
+        "m4a" => Ok("audio/mp4"),
+        "mp4" => Ok("video/mp4"),
+        "flac" => Ok("audio/flac"),
+        "ogg" => Ok("audio/ogg"),
+        "webm" => Ok("audio/webm"),
+        _ => Err(VenkyError::Other(format!(
+            "Unsupported audio format: {}",
+            path.display()
+        ))),
+    }
+}
+
+fn timestamp(secs: f64) -> String {
+    let secs = secs.max(0.0) as u64;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Transcribe an audio file with the configured OpenAI key, one timed line per segment
+async fn transcribe(args: &[String]) -> Result<(), VenkyError> {
+    let file = args
+        .first()
+        .filter(|a| !a.starts_with("--"))
+        .ok_or_else(|| VenkyError::Other(format!("Missing audio file\n\n{}", USAGE)))?;
+    let path = Path::new(file);
+    let mime = audio_mime(path)?;
+    let audio = std::fs::read(path)
+        .map_err(|e| VenkyError::Storage(format!("Failed to read {}: {}", file, e)))?;
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("audio");
+
+    let cfg = load_config()?;
+    let segments = crate::ai::stt::transcribe_file_segments(&cfg, audio, file_name, mime).await?;
+    let transcript: String = segments
+        .iter()
+        .map(|s| format!("[{}] {}\n", timestamp(s.start), s.text.trim()))
+        .collect();
+
+    match option(args, "--out") {
+        Some(out) => std::fs::write(out, transcript)
+            .map_err(|e| VenkyError::Storage(format!("Failed to write {}: {}", out, e))),
+        None => {
+            print!("{}", transcript);
+            Ok(())
+        }
+    }
+}
+
+/// Summarize a saved session, store the summary and print it
+async fn summarize(args: &[String]) -> Result<(), VenkyError> {
+    let session_id = option(args, "--session")
+        .ok_or_else(|| VenkyError::Other(format!("Missing --session\n\n{}", USAGE)))?;
+    let cfg = load_config()?;
+    let storage = open_storage()?;
+
+    let session = storage.get_session(session_id)?;
+    let highlights = storage.get_session_highlights(session_id)?;
+    let chapters = storage.get_session_chapters(session_id)?;
+    let template = crate::session::summary::template_for(
+        &storage,
+        session.purpose,
+        option(args, "--template"),
+    )?;
+
+    let summary = crate::redaction::for_session(
+        session_id,
+        crate::session::summary::summarize(
+            &cfg,
+            &session,
+            &highlights,
+            &chapters,
+            &template,
+            |completed, total| eprintln!("Summarizing: {}/{}", completed, total),
+        ),
+    )
+    .await?;
+    storage.set_session_summary(session_id, &summary)?;
+    println!("{}", summary);
+    Ok(())
+}
+
+/// Export sessions as Markdown, PDF or JSON: one session to `--out` (a file) or stdout, or
+/// every session into the `--out` directory
+fn export(args: &[String]) -> Result<(), VenkyError> {
+    let format = match option(args, "--format") {
+        Some(f) => ExportFormat::parse(f)
+            .ok_or_else(|| VenkyError::Other(format!("Unknown format \"{}\"", f)))?,
+        None => ExportFormat::Json,
+    };
+    let storage = open_storage()?;
+
+    if let Some(session_id) = option(args, "--session") {
+        let document = export::render(&export::gather(&storage, session_id)?, format)?;
+        return match option(args, "--out") {
+            Some(out) => std::fs::write(out, document)
+                .map_err(|e| VenkyError::Storage(format!("Failed to write {}: {}", out, e))),
+            None => std::io::stdout()
+                .write_all(&document)
+                .map_err(|e| VenkyError::Storage(format!("Failed to write output: {}", e))),
+        };
+    }
+    if !args.iter().any(|a| a == "--all") {
+        return Err(VenkyError::Other(format!(
+            "Pass --all or --session <id>\n\n{}",
+            USAGE
+        )));
+    }
+    let dir = option(args, "--out")
+        .ok_or_else(|| VenkyError::Other("--all needs an --out directory".to_string()))?;
+    let count = export::export_all(&storage, format, Path::new(dir))?;
+    eprintln!("Exported {} session(s) to {}", count, dir);
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// Error returned by commands, serialized as `{ "code": "...", "message": "..." }` so the
+/// frontend can pick a fix to offer from the code and show the message as is
+#[derive(Debug, Clone, PartialEq)]
+pub enum VenkyError {
+    /// An AI, speech or integration provider refused the request, e.g. a missing or bad API key
+    Provider(String),
+    /// Capturing or playing audio failed, e.g. no microphone
+    Audio(String),
+    /// Reading or writing the database or app data failed
+    Storage(String),
+    /// The OS hasn't granted access the feature needs, e.g. the microphone or screen recording
+    Permission(String),
+    /// A request didn't get through: offline, proxy, timeout
+    Network(String),
+    /// Anything else, e.g. invalid input
+    Other(String),
+}
+
+pub type CommandResult<T> = Result<T, VenkyError>;
+
+impl VenkyError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            VenkyError::Provider(_) => "provider",
+            VenkyError::Audio(_) => "audio",
+            VenkyError::Storage(_) => "storage",
+            VenkyError::Permission(_) => "permission",
+            VenkyError::Network(_) => "network",
+            VenkyError::Other(_) => "other",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            VenkyError::Provider(m)
+            | VenkyError::Audio(m)
+            | VenkyError::Storage(m)
+            | VenkyError::Permission(m)
+            | VenkyError::Network(m)
+            | VenkyError::Other(m) => m,
+        }
+    }
+}
+
+impl fmt::Display for VenkyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for VenkyError {}
+
+impl Serialize for VenkyError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("VenkyError", 2)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", self.message())?;
+        error.end()
+    }
+}
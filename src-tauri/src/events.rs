@@ -0,0 +1,179 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::ai::streaming::StreamEvent;
+use crate::config::AppConfig;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+/// Events sent often enough to be worth coalescing, and their default intervals
+pub const DEFAULT_INTERVALS_MS: &[(&str, u64)] = &[
+    ("llm-token", 30),
+    ("live-suggestion", 30),
+    ("quick-ask", 30),
+    ("clipboard-answer", 30),
+    ("transcription-chunk", 250),
+];
+
+/// How much coalescing one event is doing, i.e. how hard it would otherwise be pushing
+/// the IPC bridge
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EventStats {
+    pub event: String,
+    /// Updates handed to the emitter
+    pub received: u64,
+    /// Events that crossed to the webview
+    pub emitted: u64,
+    /// Most updates folded into one event
+    pub largest_batch: u64,
+}
+
+static STATS: Lazy<Mutex<HashMap<&'static str, EventStats>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Text waiting for its event's interval to pass
+#[derive(Default)]
+struct PendingText {
+    text: String,
+    count: u64,
+    last_sent: Option<Instant>,
+    flush_scheduled: bool,
+}
+
+impl PendingText {
+    fn take(&mut self) -> (String, u64) {
+        self.last_sent = Some(Instant::now());
+        (
+            std::mem::take(&mut self.text),
+            std::mem::take(&mut self.count),
+        )
+    }
+}
+
+static PENDING: Lazy<Mutex<HashMap<&'static str, PendingText>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Count one emitted event carrying `batch` updates
+fn record(event: &'static str, batch: u64) {
+    let mut stats = STATS.lock();
+    let entry = stats.entry(event).or_insert_with(|| EventStats {
+        event: event.to_string(),
+        ..Default::default()
+    });
+    entry.received += batch;
+    entry.emitted += 1;
+    entry.largest_batch = entry.largest_batch.max(batch);
+}
+
+/// The configured interval for `event`; zero sends every update as it comes
+fn interval(app: &AppHandle, event: &str) -> Duration {
+    let ms = app
+        .state::<ConfigState>()
+        .lock()
+        .event_throttle_ms
+        .get(event)
+        .copied()
+        .unwrap_or(0);
+    Duration::from_millis(ms)
+}
+
+/// Wrap a stream's `on_event` so tokens reach it at most once per `event`'s interval,
+/// joined. The first token goes straight through and anything held back goes out before
+/// the end event.
+pub fn batch_tokens<F>(
+    app: &AppHandle,
+    event: &'static str,
+    mut on_event: F,
+) -> impl FnMut(StreamEvent<'_>)
+where
+    F: FnMut(StreamEvent<'_>),
+{
+    let interval = interval(app, event);
+    let mut pending = String::new();
+    let mut count = 0;
+    let mut last_sent: Option<Instant> = None;
+
+    move |stream_event: StreamEvent<'_>| match stream_event {
+        StreamEvent::Token(token) => {
+            pending.push_str(token);
+            count += 1;
+            if !last_sent.is_some_and(|t| t.elapsed() < interval) {
+                on_event(StreamEvent::Token(&pending));
+                record(event, count);
+                pending.clear();
+                count = 0;
+                last_sent = Some(Instant::now());
+            }
+        }
+        StreamEvent::End(full) => {
+            if count > 0 {
+                on_event(StreamEvent::Token(&pending));
+                record(event, count);
+                pending.clear();
+                count = 0;
+            }
+            on_event(StreamEvent::End(full));
+        }
+        StreamEvent::Start => on_event(StreamEvent::Start),
+    }
+}
+
+fn send_text(app: &AppHandle, event: &'static str, (text, count): (String, u64)) {
+    if count > 0 {
+        let _ = app.emit(event, text);
+        record(event, count);
+    }
+}
+
+/// Emit `text` as `event`, joining pieces that arrive within the event's interval of the
+/// last one sent. A piece after a quiet spell goes out right away.
+pub fn emit_text(app: &AppHandle, event: &'static str, text: String) {
+    let interval = interval(app, event);
+    let mut pending = PENDING.lock();
+    let entry = pending.entry(event).or_default();
+    if !entry.text.is_empty() {
+        entry.text.push(' ');
+    }
+    entry.text.push_str(text.trim());
+    entry.count += 1;
+    if entry.flush_scheduled {
+        return;
+    }
+
+    let wait = entry
+        .last_sent
+        .map_or(Duration::ZERO, |t| interval.saturating_sub(t.elapsed()));
+    if wait.is_zero() {
+        let batch = entry.take();
+        drop(pending);
+        send_text(app, event, batch);
+        return;
+    }
+    entry.flush_scheduled = true;
+    drop(pending);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(wait).await;
+        let batch = {
+            let mut pending = PENDING.lock();
+            let entry = pending.entry(event).or_default();
+            entry.flush_scheduled = false;
+            entry.take()
+        };
+        send_text(&app, event, batch);
+    });
+}
+
+/// Delivery counts per coalesced event since launch
+#[tauri::command]
+pub fn get_event_stats() -> Vec<EventStats> {
+    let mut stats: Vec<EventStats> = STATS.lock().values().cloned().collect();
+    stats.sort_by(|a, b| a.event.cmp(&b.event));
+    stats
+}
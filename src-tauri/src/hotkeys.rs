@@ -0,0 +1,283 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::manager::{self, SessionManager, SessionPurpose};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Something a global shortcut can be bound to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    ToggleOverlay,
+    /// Start a session, or end the focused one
+    ToggleSession,
+    Highlight,
+    Mute,
+    /// Ask the AI what to say about the last moments of the session; the UI also gets
+    /// "push-to-ask" on press and release
+    PushToAsk,
+    /// Turn stealth mode on or off
+    Stealth,
+    /// Stop an answer being typed out
+    StopTyping,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 7] = [
+        HotkeyAction::ToggleOverlay,
+        HotkeyAction::ToggleSession,
+        HotkeyAction::Highlight,
+        HotkeyAction::Mute,
+        HotkeyAction::PushToAsk,
+        HotkeyAction::Stealth,
+        HotkeyAction::StopTyping,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleOverlay => "show/hide overlay",
+            HotkeyAction::ToggleSession => "start/stop session",
+            HotkeyAction::Highlight => "mark highlight",
+            HotkeyAction::Mute => "mute",
+            HotkeyAction::PushToAsk => "push-to-ask",
+            HotkeyAction::Stealth => "toggle stealth mode",
+            HotkeyAction::StopTyping => "stop typing",
+        }
+    }
+
+    /// The combo bound in `cfg`; empty when unbound
+    pub fn combo(self, cfg: &AppConfig) -> &str {
+        match self {
+            HotkeyAction::ToggleOverlay => &cfg.hotkey,
+            HotkeyAction::ToggleSession => &cfg.session_hotkey,
+            HotkeyAction::Highlight => &cfg.highlight_hotkey,
+            HotkeyAction::Mute => &cfg.mute_hotkey,
+            HotkeyAction::PushToAsk => &cfg.ask_hotkey,
+            HotkeyAction::Stealth => &cfg.stealth_hotkey,
+            HotkeyAction::StopTyping => &cfg.stop_typing_hotkey,
+        }
+    }
+
+    fn combo_mut(self, cfg: &mut AppConfig) -> &mut String {
+        match self {
+            HotkeyAction::ToggleOverlay => &mut cfg.hotkey,
+            HotkeyAction::ToggleSession => &mut cfg.session_hotkey,
+            HotkeyAction::Highlight => &mut cfg.highlight_hotkey,
+            HotkeyAction::Mute => &mut cfg.mute_hotkey,
+            HotkeyAction::PushToAsk => &mut cfg.ask_hotkey,
+            HotkeyAction::Stealth => &mut cfg.stealth_hotkey,
+            HotkeyAction::StopTyping => &mut cfg.stop_typing_hotkey,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HotkeyBinding {
+    pub action: HotkeyAction,
+    pub combo: String,
+}
+
+fn parse(combo: &str) -> Result<Shortcut, VenkyError> {
+    combo
+        .parse::<Shortcut>()
+        .map_err(|e| VenkyError::Other(format!("Invalid shortcut \"{}\": {}", combo, e)))
+}
+
+/// Every bound combo must parse and belong to one action only; "Ctrl+Shift+X" and
+/// "Shift+Control+X" count as the same
+pub fn check_bindings(cfg: &AppConfig) -> Result<(), VenkyError> {
+    let mut seen: Vec<(Shortcut, HotkeyAction)> = Vec::new();
+    for action in HotkeyAction::ALL {
+        let combo = action.combo(cfg);
+        if combo.is_empty() {
+            continue;
+        }
+        let shortcut = parse(combo)?;
+        if let Some((_, other)) = seen.iter().find(|(s, _)| *s == shortcut) {
+            return Err(VenkyError::Other(format!(
+                "{} is already used to {}",
+                combo,
+                other.label()
+            )));
+        }
+        seen.push((shortcut, action));
+    }
+    Ok(())
+}
+
+/// Start a quick session with the configured auto-capture, or end the focused session
+pub fn toggle_session(app: &AppHandle) -> Result<(), VenkyError> {
+    let focused = app.state::<SessionState>().lock().focused_id.clone();
+    if focused.is_some() {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = manager::end_session(app, None).await {
+                log::error!("Failed to end session: {}", e);
+            }
+        });
+    } else {
+        let title = format!("Session {}", chrono::Local::now().format("%b %-d, %H:%M"));
+        manager::create_session(
+            app.clone(),
+            app.state(),
+            title,
+            SessionPurpose::Meeting,
+            None,
+            None,
+        )?;
+        crate::ai::live_engine::start_auto_capture(app, None);
+    }
+    Ok(())
+}
+
+fn trigger(app: &AppHandle, action: HotkeyAction, state: ShortcutState) {
+    if action == HotkeyAction::PushToAsk {
+        let _ = app.emit("push-to-ask", state == ShortcutState::Pressed);
+        if state == ShortcutState::Pressed {
+            crate::ai::quick_ask::ask_in_background(app);
+        }
+        return;
+    }
+    if state != ShortcutState::Pressed {
+        return;
+    }
+    let result = match action {
+        HotkeyAction::ToggleOverlay => crate::toggle_overlay(app.clone()).map(|_| ()),
+        HotkeyAction::ToggleSession => toggle_session(app),
+        HotkeyAction::Highlight => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = manager::add_highlight(&app, None).await {
+                    log::warn!("Shortcut to {} failed: {}", action.label(), e);
+                }
+            });
+            Ok(())
+        }
+        HotkeyAction::Mute => {
+            crate::capture::audio::toggle_capture_muted(app);
+            Ok(())
+        }
+        HotkeyAction::Stealth => crate::stealth::toggle(app).map(|_| ()),
+        HotkeyAction::StopTyping => {
+            crate::session::type_out::stop();
+            Ok(())
+        }
+        HotkeyAction::PushToAsk => Ok(()),
+    };
+    if let Err(e) = result {
+        log::warn!("Shortcut to {} failed: {}", action.label(), e);
+    }
+}
+
+fn register(app: &AppHandle, action: HotkeyAction, combo: &str) -> Result<(), VenkyError> {
+    if combo.is_empty() {
+        return Ok(());
+    }
+    let shortcut = parse(combo)?;
+    let handle = app.clone();
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            trigger(&handle, action, event.state());
+        })
+        .map_err(|e| {
+            VenkyError::Other(format!(
+                "Failed to register {} for {} (it may be taken by another app): {}",
+                combo,
+                action.label(),
+                e
+            ))
+        })
+}
+
+fn unregister(app: &AppHandle, combo: &str) {
+    if combo.is_empty() {
+        return;
+    }
+    if let Err(e) = parse(combo).and_then(|shortcut| {
+        app.global_shortcut()
+            .unregister(shortcut)
+            .map_err(|e| VenkyError::Other(e.to_string()))
+    }) {
+        log::warn!("Failed to unregister shortcut {}: {}", combo, e);
+    }
+}
+
+/// Bind every configured shortcut at startup
+pub fn register_all(app: &AppHandle, cfg: &AppConfig) {
+    for action in HotkeyAction::ALL {
+        if let Err(e) = register(app, action, action.combo(cfg)) {
+            log::error!("{}", e);
+        }
+    }
+}
+
+/// Move shortcuts whose combo changed between `old` and `new`. All old combos are released
+/// first so two actions can swap keys.
+pub fn rebind(app: &AppHandle, old: &AppConfig, new: &AppConfig) {
+    let changed: Vec<HotkeyAction> = HotkeyAction::ALL
+        .into_iter()
+        .filter(|action| action.combo(old) != action.combo(new))
+        .collect();
+    for action in &changed {
+        unregister(app, action.combo(old));
+    }
+    for action in changed {
+        if let Err(e) = register(app, action, action.combo(new)) {
+            log::error!("{}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_hotkeys(config: tauri::State<'_, ConfigState>) -> Vec<HotkeyBinding> {
+    let cfg = config.lock();
+    HotkeyAction::ALL
+        .into_iter()
+        .map(|action| HotkeyBinding {
+            action,
+            combo: action.combo(&cfg).to_string(),
+        })
+        .collect()
+}
+
+/// Bind `action` to `combo` (empty unbinds it) right away and save it. Fails without
+/// changing anything when another action or app already uses the combo.
+#[tauri::command]
+pub fn set_hotkey(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+    action: HotkeyAction,
+    combo: String,
+) -> CommandResult<HotkeyBinding> {
+    let combo = combo.trim().to_string();
+    let old = config.lock().clone();
+    let mut new = old.clone();
+    *action.combo_mut(&mut new) = combo.clone();
+    check_bindings(&new)?;
+    if action.combo(&old) == combo {
+        return Ok(HotkeyBinding { action, combo });
+    }
+
+    unregister(&app, action.combo(&old));
+    if let Err(e) = register(&app, action, &combo) {
+        if let Err(restore) = register(&app, action, action.combo(&old)) {
+            log::error!("{}", restore);
+        }
+        return Err(e);
+    }
+
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))?;
+    *action.combo_mut(&mut config.lock()) = combo.clone();
+    config.lock().save(&app_data)?;
+    Ok(HotkeyBinding { action, combo })
+}
@@ -0,0 +1,83 @@
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use reqwest::{Certificate, Client, Proxy};
+use std::time::Duration;
+
+use crate::config::AppConfig;
+use crate::error::VenkyError;
+
+/// How long an idle pooled connection is kept for reuse
+const POOL_IDLE_SECS: u64 = 90;
+
+/// One client for every outbound request, so connections to the same host are reused.
+/// Rebuilt by `configure` when the network settings change.
+static CLIENT: Lazy<RwLock<Client>> = Lazy::new(|| {
+    RwLock::new(build(&AppConfig::default()).unwrap_or_else(|e| {
+        log::error!("{}", e);
+        Client::new()
+    }))
+});
+
+/// The shared client; cheap to clone, and clones share the connection pool
+pub fn client() -> Client {
+    CLIENT.read().clone()
+}
+
+fn proxy(url: &str) -> Result<Proxy, VenkyError> {
+    Proxy::all(url).map_err(|e| VenkyError::Other(format!("Invalid proxy URL \"{}\": {}", url, e)))
+}
+
+/// Every certificate in a PEM file of extra trusted CAs
+fn ca_certificates(path: &str) -> Result<Vec<Certificate>, VenkyError> {
+    let pem = std::fs::read(path)
+        .map_err(|e| VenkyError::Storage(format!("Failed to read {}: {}", path, e)))?;
+    Certificate::from_pem_bundle(&pem)
+        .map_err(|e| VenkyError::Other(format!("Invalid CA certificate file {}: {}", path, e)))
+}
+
+fn build(cfg: &AppConfig) -> Result<Client, VenkyError> {
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_secs(cfg.http_connect_timeout_secs as u64))
+        .pool_idle_timeout(Duration::from_secs(POOL_IDLE_SECS));
+    // Per read rather than per request, so long streams and uploads aren't cut off
+    if cfg.http_read_timeout_secs > 0 {
+        builder = builder.read_timeout(Duration::from_secs(cfg.http_read_timeout_secs as u64));
+    }
+    let proxy_url = cfg.http_proxy.trim();
+    if !proxy_url.is_empty() {
+        builder = builder.proxy(proxy(proxy_url)?);
+    }
+    let ca_path = cfg.http_ca_cert_path.trim();
+    if !ca_path.is_empty() {
+        for cert in ca_certificates(ca_path)? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    builder
+        .build()
+        .map_err(|e| VenkyError::Network(format!("Failed to build HTTP client: {}", e)))
+}
+
+/// Check the proxy URL and CA file before they're saved
+pub fn check(cfg: &AppConfig) -> Result<(), VenkyError> {
+    let proxy_url = cfg.http_proxy.trim();
+    if !proxy_url.is_empty() {
+        proxy(proxy_url)?;
+    }
+    let ca_path = cfg.http_ca_cert_path.trim();
+    if !ca_path.is_empty() && ca_certificates(ca_path)?.is_empty() {
+        return Err(VenkyError::Other(format!(
+            "No certificates found in {}",
+            ca_path
+        )));
+    }
+    Ok(())
+}
+
+/// Rebuild the shared client from the network settings; on failure the previous one stays
+pub fn configure(cfg: &AppConfig) {
+    match build(cfg) {
+        Ok(client) => *CLIENT.write() = client,
+        Err(e) => log::error!("{}", e),
+    }
+}
@@ -0,0 +1,1038 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::oauth::{self, Connection, OAuthClient, TokenResponse};
+use crate::error::{CommandResult, VenkyError};
+use crate::session::manager::{SessionManager, SessionPurpose};
+use crate::session::storage::{Participant, Storage};
+use crate::session::storage_actor::StorageActor;
+
+type CalendarState = Arc<Mutex<CalendarConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Settings key the calendar connection is stored under
+const SETTINGS_KEY: &str = "calendar";
+/// How often the calendar is checked for meetings that are starting
+const CALENDAR_CHECK_INTERVAL_SECS: u64 = 60;
+/// A meeting counts as starting from this long before its start time
+const START_LEAD_SECS: i64 = 60;
+/// Meetings joined late still get a session if they started within this window
+const LATE_START_SECS: i64 = 10 * 60;
+
+const GOOGLE_AUTHORIZE_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const GOOGLE_SCOPES: &str = "https://www.googleapis.com/auth/calendar.events";
+const OUTLOOK_AUTHORIZE_URL: &str =
+    "https://login.microsoftonline.com/common/oauth2/v2.0/authorize";
+const OUTLOOK_TOKEN_URL: &str = "https://login.microsoftonline.com/common/oauth2/v2.0/token";
+const OUTLOOK_SCOPES: &str = "offline_access User.Read Calendars.ReadWrite";
+/// Held while the sign-in is refreshed, so refreshes take turns
+static REFRESHING: Lazy<tokio::sync::Mutex<()>> = Lazy::new(Default::default);
+
+/// Join links of the common video meeting services
+static VIDEO_LINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"https://([a-z0-9-]+\.)?(zoom\.us/j|meet\.google\.com|teams\.microsoft\.com/l/meetup-join|teams\.live\.com/meet|[a-z0-9-]+\.webex\.com/[a-z0-9-]+/j\.php|global\.gotomeeting\.com/join)[^\s<>\x22]*",
+    )
+    .unwrap()
+});
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum CalendarProvider {
+    Google,
+    Outlook,
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    pub provider: CalendarProvider,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<String>,
+    pub account_email: Option<String>,
+    /// Start a session when a calendar meeting with a video link begins
+    pub auto_sessions: bool,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            provider: CalendarProvider::None,
+            client_id: String::new(),
+            client_secret: None,
+            access_token: String::new(),
+            refresh_token: None,
+            expires_at: None,
+            account_email: None,
+            auto_sessions: true,
+        }
+    }
+}
+
+impl CalendarConfig {
+    pub fn load(storage: &Storage) -> Self {
+        storage
+            .get_setting(SETTINGS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &Storage) -> Result<(), VenkyError> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| VenkyError::Storage(format!("Failed to save calendar settings: {}", e)))?;
+        storage.set_setting(SETTINGS_KEY, Some(&json))
+    }
+}
+
+impl Connection for CalendarConfig {
+    const NOT_CONNECTED: &'static str = "No calendar connected";
+    const EXPIRED: &'static str = "Calendar session expired; reconnect the calendar";
+
+    fn is_connected(&self) -> bool {
+        self.provider != CalendarProvider::None && !self.access_token.is_empty()
+    }
+
+    fn oauth_client(&self) -> OAuthClient<'_> {
+        match self.provider {
+            CalendarProvider::Google => OAuthClient {
+                authorize_url: GOOGLE_AUTHORIZE_URL,
+                token_url: GOOGLE_TOKEN_URL,
+                client_id: &self.client_id,
+                client_secret: self.client_secret.as_deref(),
+                scopes: GOOGLE_SCOPES,
+                extra_params: &[("access_type", "offline"), ("prompt", "consent")],
+                redirect_uri: None,
+            },
+            _ => OAuthClient {
+                authorize_url: OUTLOOK_AUTHORIZE_URL,
+                token_url: OUTLOOK_TOKEN_URL,
+                client_id: &self.client_id,
+                client_secret: self.client_secret.as_deref(),
+                scopes: OUTLOOK_SCOPES,
+                extra_params: &[],
+                redirect_uri: None,
+            },
+        }
+    }
+
+    fn expires_at(&self) -> Option<&str> {
+        self.expires_at.as_deref()
+    }
+
+    fn refresh_token(&self) -> Option<&str> {
+        self.refresh_token.as_deref()
+    }
+
+    fn set_tokens(&mut self, tokens: TokenResponse) {
+        self.expires_at = tokens.expires_at();
+        self.access_token = tokens.access_token;
+        if tokens.refresh_token.is_some() {
+            self.refresh_token = tokens.refresh_token;
+        }
+    }
+}
+
+/// Connection details safe to show in the UI (no tokens)
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarStatus {
+    pub provider: CalendarProvider,
+    pub connected: bool,
+    pub account_email: Option<String>,
+    pub auto_sessions: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarAttendee {
+    pub name: Option<String>,
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub id: String,
+    pub title: String,
+    /// RFC 3339, UTC
+    pub start: String,
+    pub end: String,
+    pub attendees: Vec<CalendarAttendee>,
+    pub video_link: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Emitted as "calendar-session-started"
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarSessionStarted {
+    pub session_id: String,
+    pub event: CalendarEvent,
+}
+
+/// A valid access token, refreshing (and persisting) it first when it has expired
+pub async fn access_token(app: &AppHandle) -> Result<CalendarConfig, VenkyError> {
+    oauth::fresh_connection(
+        &REFRESHING,
+        &app.state::<CalendarState>(),
+        &app.state::<StorageActor>(),
+        CalendarConfig::save,
+    )
+    .await
+}
+
+fn find_video_link(texts: &[Option<&str>]) -> Option<String> {
+    texts
+        .iter()
+        .flatten()
+        .find_map(|t| VIDEO_LINK.find(t))
+        .map(|m| m.as_str().to_string())
+}
+
+// ─── Google ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct GoogleEvents {
+    #[serde(default)]
+    items: Vec<GoogleEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleEvent {
+    id: String,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    location: Option<String>,
+    #[serde(default)]
+    hangout_link: Option<String>,
+    start: GoogleTime,
+    end: GoogleTime,
+    #[serde(default)]
+    attendees: Vec<GoogleAttendee>,
+    #[serde(default)]
+    conference_data: Option<GoogleConference>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleTime {
+    /// Absent for all-day events
+    #[serde(default)]
+    date_time: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleAttendee {
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    resource: bool,
+    #[serde(default)]
+    response_status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleConference {
+    #[serde(default)]
+    entry_points: Vec<GoogleEntryPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleEntryPoint {
+    entry_point_type: String,
+    uri: String,
+}
+
+fn to_utc(t: &str) -> Option<String> {
+    chrono::DateTime::parse_from_rfc3339(t)
+        .ok()
+        .map(|t| t.with_timezone(&chrono::Utc).to_rfc3339())
+}
+
+async fn google_events(
+    config: &CalendarConfig,
+    from: &str,
+    to: &str,
+) -> Result<Vec<CalendarEvent>, VenkyError> {
+    let response = crate::http::client()
+        .get("https://www.googleapis.com/calendar/v3/calendars/primary/events")
+        .bearer_auth(&config.access_token)
+        .query(&[
+            ("timeMin", from),
+            ("timeMax", to),
+            ("singleEvents", "true"),
+            ("orderBy", "startTime"),
+        ])
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("Google Calendar request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "Google Calendar error ({}): {}",
+            status, body
+        )));
+    }
+    let events: GoogleEvents = response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+
+    Ok(events
+        .items
+        .into_iter()
+        .filter_map(|e| {
+            let start = to_utc(e.start.date_time.as_deref()?)?;
+            let end = to_utc(e.end.date_time.as_deref()?)?;
+            let conference = e.conference_data.as_ref().and_then(|c| {
+                c.entry_points
+                    .iter()
+                    .find(|p| p.entry_point_type == "video")
+                    .map(|p| p.uri.clone())
+            });
+            let video_link =
+                e.hangout_link.clone().or(conference).or_else(|| {
+                    find_video_link(&[e.location.as_deref(), e.description.as_deref()])
+                });
+            Some(CalendarEvent {
+                id: e.id,
+                title: e.summary.unwrap_or_else(|| "Untitled meeting".to_string()),
+                start,
+                end,
+                attendees: e
+                    .attendees
+                    .into_iter()
+                    // Rooms and people who declined aren't in the meeting
+                    .filter(|a| !a.resource && a.response_status.as_deref() != Some("declined"))
+                    .filter_map(|a| {
+                        Some(CalendarAttendee {
+                            email: a.email?,
+                            name: a.display_name,
+                        })
+                    })
+                    .collect(),
+                video_link,
+                description: e.description,
+            })
+        })
+        .collect())
+}
+
+async fn google_account_email(config: &CalendarConfig) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Calendar {
+        id: String,
+    }
+    let response = crate::http::client()
+        .get("https://www.googleapis.com/calendar/v3/calendars/primary")
+        .bearer_auth(&config.access_token)
+        .send()
+        .await
+        .ok()?;
+    response.json::<Calendar>().await.ok().map(|c| c.id)
+}
+
+// ─── Outlook ─────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct OutlookEvents {
+    #[serde(default)]
+    value: Vec<OutlookEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OutlookEvent {
+    id: String,
+    #[serde(default)]
+    subject: Option<String>,
+    #[serde(default)]
+    body_preview: Option<String>,
+    #[serde(default)]
+    is_all_day: bool,
+    start: OutlookTime,
+    end: OutlookTime,
+    #[serde(default)]
+    location: Option<OutlookLocation>,
+    #[serde(default)]
+    attendees: Vec<OutlookAttendee>,
+    #[serde(default)]
+    online_meeting: Option<OutlookOnlineMeeting>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OutlookTime {
+    /// Local to the `Prefer: outlook.timezone` header, without an offset
+    date_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OutlookLocation {
+    #[serde(default)]
+    display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OutlookAttendee {
+    email_address: OutlookEmail,
+    /// "required", "optional" or "resource"
+    #[serde(rename = "type", default)]
+    kind: Option<String>,
+    #[serde(default)]
+    status: Option<OutlookResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutlookResponse {
+    #[serde(default)]
+    response: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutlookEmail {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    address: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OutlookOnlineMeeting {
+    #[serde(default)]
+    join_url: Option<String>,
+}
+
+/// Graph returns UTC times (as requested) with fractional seconds and no offset
+fn outlook_to_utc(t: &str) -> Option<String> {
+    chrono::NaiveDateTime::parse_from_str(t, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|t| t.and_utc().to_rfc3339())
+}
+
+async fn outlook_events(
+    config: &CalendarConfig,
+    from: &str,
+    to: &str,
+) -> Result<Vec<CalendarEvent>, VenkyError> {
+    let response = crate::http::client()
+        .get("https://graph.microsoft.com/v1.0/me/calendarView")
+        .bearer_auth(&config.access_token)
+        .header("Prefer", "outlook.timezone=\"UTC\"")
+        .query(&[
+            ("startDateTime", from),
+            ("endDateTime", to),
+            ("$orderby", "start/dateTime"),
+            ("$top", "50"),
+        ])
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("Outlook request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "Outlook error ({}): {}",
+            status, body
+        )));
+    }
+    let events: OutlookEvents = response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+
+    Ok(events
+        .value
+        .into_iter()
+        .filter(|e| !e.is_all_day)
+        .filter_map(|e| {
+            let location = e.location.and_then(|l| l.display_name);
+            let video_link = e
+                .online_meeting
+                .and_then(|m| m.join_url)
+                .or_else(|| find_video_link(&[location.as_deref(), e.body_preview.as_deref()]));
+            Some(CalendarEvent {
+                id: e.id,
+                title: e.subject.unwrap_or_else(|| "Untitled meeting".to_string()),
+                start: outlook_to_utc(&e.start.date_time)?,
+                end: outlook_to_utc(&e.end.date_time)?,
+                attendees: e
+                    .attendees
+                    .into_iter()
+                    .filter(|a| {
+                        a.kind.as_deref() != Some("resource")
+                            && a.status.as_ref().and_then(|s| s.response.as_deref())
+                                != Some("declined")
+                    })
+                    .filter_map(|a| {
+                        Some(CalendarAttendee {
+                            email: a.email_address.address?,
+                            name: a.email_address.name,
+                        })
+                    })
+                    .collect(),
+                video_link,
+                description: e.body_preview,
+            })
+        })
+        .collect())
+}
+
+async fn outlook_account_email(config: &CalendarConfig) -> Option<String> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Me {
+        #[serde(default)]
+        mail: Option<String>,
+        #[serde(default)]
+        user_principal_name: Option<String>,
+    }
+    let response = crate::http::client()
+        .get("https://graph.microsoft.com/v1.0/me")
+        .bearer_auth(&config.access_token)
+        .send()
+        .await
+        .ok()?;
+    let me = response.json::<Me>().await.ok()?;
+    me.mail.or(me.user_principal_name)
+}
+
+/// Events overlapping the given RFC 3339 range, soonest first
+pub async fn events_between(
+    app: &AppHandle,
+    from: &str,
+    to: &str,
+) -> Result<Vec<CalendarEvent>, VenkyError> {
+    let config = access_token(app).await?;
+    match config.provider {
+        CalendarProvider::Google => google_events(&config, from, to).await,
+        CalendarProvider::Outlook => outlook_events(&config, from, to).await,
+        CalendarProvider::None => Err(VenkyError::Other("No calendar connected".to_string())),
+    }
+}
+
+// ─── Auto sessions ───────────────────────────────────────────────────────────
+
+/// Guess what kind of session a meeting is from its title
+fn infer_purpose(title: &str) -> SessionPurpose {
+    let title = title.to_lowercase();
+    if title.contains("interview") {
+        SessionPurpose::Interview
+    } else if [
+        "demo",
+        "discovery",
+        "pricing",
+        "proposal",
+        "sales",
+        "prospect",
+    ]
+    .iter()
+    .any(|w| title.contains(w))
+    {
+        SessionPurpose::Sales
+    } else {
+        SessionPurpose::Meeting
+    }
+}
+
+fn start_event_session(app: &AppHandle, event: &CalendarEvent) -> Result<(), VenkyError> {
+    let context = event
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| format!("Calendar invite:\n{}", d));
+    let session = crate::session::manager::create_session(
+        app.clone(),
+        app.state(),
+        event.title.clone(),
+        infer_purpose(&event.title),
+        context,
+        None,
+    )?;
+
+    let own_email = app.state::<CalendarState>().lock().account_email.clone();
+    let participants = event
+        .attendees
+        .iter()
+        .filter(|attendee| own_email.as_deref() != Some(attendee.email.as_str()))
+        .map(|attendee| Participant {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session.id.clone(),
+            name: attendee
+                .name
+                .clone()
+                .unwrap_or_else(|| attendee.email.clone()),
+            email: Some(attendee.email.clone()),
+            role: None,
+            crm_contact_id: None,
+            source: "calendar".to_string(),
+        })
+        .collect::<Vec<_>>();
+    // Queued ahead of the enrichment below, which reads them back
+    app.state::<StorageActor>().send(move |s| {
+        for participant in &participants {
+            if let Err(e) = s.add_participant(participant) {
+                log::warn!("Failed to add calendar participant: {}", e);
+            }
+        }
+    });
+
+    crate::integrations::crm::attendees::enrich_if_enabled(app, &session.id);
+
+    crate::ai::live_engine::start_auto_capture(app, None);
+    let _ = app.emit(
+        "calendar-session-started",
+        CalendarSessionStarted {
+            session_id: session.id,
+            event: event.clone(),
+        },
+    );
+    Ok(())
+}
+
+/// Start a session, with the invite's title, attendees and purpose, when a calendar
+/// meeting with a video link begins and nothing is being recorded yet
+pub async fn calendar_loop(app: AppHandle) {
+    let mut interval = tokio::time::interval(Duration::from_secs(CALENDAR_CHECK_INTERVAL_SECS));
+    // Events already started (or skipped), so each gets at most one session
+    let mut handled: HashSet<String> = HashSet::new();
+
+    loop {
+        interval.tick().await;
+
+        let (connected, enabled) = {
+            let calendar = app.state::<CalendarState>();
+            let calendar = calendar.lock();
+            (calendar.is_connected(), calendar.auto_sessions)
+        };
+        let auto_start = app
+            .state::<Arc<Mutex<crate::config::AppConfig>>>()
+            .lock()
+            .auto_start_session;
+        if !connected || !enabled || !auto_start {
+            continue;
+        }
+
+        let now = chrono::Utc::now();
+        let from = now - chrono::Duration::seconds(LATE_START_SECS);
+        let to = now + chrono::Duration::seconds(START_LEAD_SECS);
+        let events = match events_between(&app, &from.to_rfc3339(), &to.to_rfc3339()).await {
+            Ok(events) => events,
+            Err(e) => {
+                log::warn!("Calendar check failed: {}", e);
+                continue;
+            }
+        };
+
+        for event in events {
+            if event.video_link.is_none() || handled.contains(&event.id) {
+                continue;
+            }
+            let parse = |t: &str| chrono::DateTime::parse_from_rfc3339(t).ok();
+            let (Some(start), Some(end)) = (parse(&event.start), parse(&event.end)) else {
+                continue;
+            };
+            // Starting soon or already under way, and not over
+            if start < from || start > to || end <= now {
+                continue;
+            }
+            handled.insert(event.id.clone());
+
+            let busy = !app.state::<SessionState>().lock().sessions.is_empty();
+            if busy {
+                log::info!(
+                    "Calendar meeting {} started during another session",
+                    event.title
+                );
+                continue;
+            }
+            match start_event_session(&app, &event) {
+                Ok(()) => log::info!("Started session for calendar meeting {}", event.title),
+                Err(e) => log::warn!("Failed to start calendar session: {}", e),
+            }
+        }
+    }
+}
+
+// ─── Follow-ups ──────────────────────────────────────────────────────────────
+
+/// A follow-up meeting proposed from a session
+#[derive(Debug, Clone, Serialize)]
+pub struct FollowupEvent {
+    pub title: String,
+    pub start: String,
+    pub end: String,
+    pub attendees: Vec<CalendarAttendee>,
+    pub description: String,
+    /// The invite as an iCalendar file, for any calendar app
+    pub ics_path: String,
+    /// Id of the event created in the connected calendar, if it was pushed there
+    pub calendar_event_id: Option<String>,
+}
+
+/// Escape text for an iCalendar property value
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets, as RFC 5545 requires
+fn ics_fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+fn ics_time(t: &chrono::DateTime<chrono::Utc>) -> String {
+    t.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn build_ics(
+    event: &FollowupEvent,
+    start: &chrono::DateTime<chrono::Utc>,
+    end: &chrono::DateTime<chrono::Utc>,
+    organizer: Option<&str>,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//VenkyAI//Follow-up//EN".to_string(),
+        "METHOD:REQUEST".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}@venkyai", uuid::Uuid::new_v4()),
+        format!("DTSTAMP:{}", ics_time(&chrono::Utc::now())),
+        format!("DTSTART:{}", ics_time(start)),
+        format!("DTEND:{}", ics_time(end)),
+        format!("SUMMARY:{}", ics_escape(&event.title)),
+        format!("DESCRIPTION:{}", ics_escape(&event.description)),
+    ];
+    if let Some(organizer) = organizer {
+        lines.push(format!("ORGANIZER:mailto:{}", organizer));
+    }
+    for attendee in &event.attendees {
+        let name = attendee.name.as_deref().unwrap_or(&attendee.email);
+        lines.push(format!(
+            "ATTENDEE;CN=\"{}\";ROLE=REQ-PARTICIPANT;RSVP=TRUE:mailto:{}",
+            name.replace('"', ""),
+            attendee.email
+        ));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = lines
+        .iter()
+        .map(|l| ics_fold(l))
+        .collect::<Vec<_>>()
+        .join("\r\n");
+    ics.push_str("\r\n");
+    ics
+}
+
+async fn google_create_event(
+    config: &CalendarConfig,
+    event: &FollowupEvent,
+) -> Result<String, VenkyError> {
+    #[derive(Deserialize)]
+    struct Created {
+        id: String,
+    }
+    let body = serde_json::json!({
+        "summary": event.title,
+        "description": event.description,
+        "start": { "dateTime": event.start },
+        "end": { "dateTime": event.end },
+        "attendees": event.attendees.iter().map(|a| serde_json::json!({
+            "email": a.email,
+            "displayName": a.name,
+        })).collect::<Vec<_>>(),
+    });
+
+    let response = crate::http::client()
+        .post("https://www.googleapis.com/calendar/v3/calendars/primary/events")
+        .bearer_auth(&config.access_token)
+        .query(&[("sendUpdates", "all")])
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("Google Calendar request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "Google Calendar error ({}): {}",
+            status, body
+        )));
+    }
+    let created: Created = response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+    Ok(created.id)
+}
+
+async fn outlook_create_event(
+    config: &CalendarConfig,
+    event: &FollowupEvent,
+    start: &chrono::DateTime<chrono::Utc>,
+    end: &chrono::DateTime<chrono::Utc>,
+) -> Result<String, VenkyError> {
+    #[derive(Deserialize)]
+    struct Created {
+        id: String,
+    }
+    let graph_time = |t: &chrono::DateTime<chrono::Utc>| t.format("%Y-%m-%dT%H:%M:%S").to_string();
+    let body = serde_json::json!({
+        "subject": event.title,
+        "body": { "contentType": "text", "content": event.description },
+        "start": { "dateTime": graph_time(start), "timeZone": "UTC" },
+        "end": { "dateTime": graph_time(end), "timeZone": "UTC" },
+        "attendees": event.attendees.iter().map(|a| serde_json::json!({
+            "emailAddress": { "address": a.email, "name": a.name },
+            "type": "required",
+        })).collect::<Vec<_>>(),
+    });
+
+    let response = crate::http::client()
+        .post("https://graph.microsoft.com/v1.0/me/events")
+        .bearer_auth(&config.access_token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("Outlook request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "Outlook error ({}): {}",
+            status, body
+        )));
+    }
+    let created: Created = response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+    Ok(created.id)
+}
+
+// ─── Tauri Commands ──────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_calendar_status(calendar: tauri::State<'_, CalendarState>) -> CalendarStatus {
+    let config = calendar.lock();
+    CalendarStatus {
+        provider: config.provider.clone(),
+        connected: config.is_connected(),
+        account_email: config.account_email.clone(),
+        auto_sessions: config.auto_sessions,
+    }
+}
+
+/// Sign in to Google Calendar or Outlook in the browser and keep the tokens
+#[tauri::command]
+pub async fn connect_calendar(
+    app: AppHandle,
+    provider: CalendarProvider,
+    client_id: String,
+    client_secret: Option<String>,
+) -> CommandResult<CalendarStatus> {
+    if provider == CalendarProvider::None {
+        return Err(VenkyError::Other("Choose Google or Outlook".to_string()));
+    }
+    if client_id.trim().is_empty() {
+        return Err(VenkyError::Other(
+            "An OAuth client ID is required".to_string(),
+        ));
+    }
+
+    let mut config = CalendarConfig {
+        provider,
+        client_id: client_id.trim().to_string(),
+        client_secret: client_secret.filter(|s| !s.trim().is_empty()),
+        auto_sessions: app.state::<CalendarState>().lock().auto_sessions,
+        ..CalendarConfig::default()
+    };
+    let tokens = oauth::authorize(&app, &config.oauth_client()).await?;
+    config.access_token = tokens.access_token.clone();
+    config.refresh_token = tokens.refresh_token.clone();
+    config.expires_at = tokens.expires_at();
+    config.account_email = match config.provider {
+        CalendarProvider::Google => google_account_email(&config).await,
+        _ => outlook_account_email(&config).await,
+    };
+
+    let row = config.clone();
+    app.state::<StorageActor>()
+        .call(move |s| row.save(s))
+        .await?;
+    *app.state::<CalendarState>().lock() = config;
+    let status = get_calendar_status(app.state());
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn disconnect_calendar(
+    calendar: tauri::State<'_, CalendarState>,
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<()> {
+    let config = {
+        let mut config = calendar.lock();
+        *config = CalendarConfig {
+            auto_sessions: config.auto_sessions,
+            ..CalendarConfig::default()
+        };
+        config.clone()
+    };
+    Ok(storage.call(move |s| config.save(s)).await?)
+}
+
+#[tauri::command]
+pub async fn set_calendar_auto_sessions(
+    calendar: tauri::State<'_, CalendarState>,
+    storage: tauri::State<'_, StorageActor>,
+    enabled: bool,
+) -> CommandResult<()> {
+    let config = {
+        let mut config = calendar.lock();
+        config.auto_sessions = enabled;
+        config.clone()
+    };
+    Ok(storage.call(move |s| config.save(s)).await?)
+}
+
+/// Meetings in the next `hours` (default 24), soonest first
+#[tauri::command]
+pub async fn list_upcoming_events(
+    app: AppHandle,
+    hours: Option<u32>,
+) -> CommandResult<Vec<CalendarEvent>> {
+    let now = chrono::Utc::now();
+    let until = now + chrono::Duration::hours(hours.unwrap_or(24).clamp(1, 24 * 14) as i64);
+    events_between(&app, &now.to_rfc3339(), &until.to_rfc3339()).await
+}
+
+/// Turn a session's participants and open action items into a follow-up invite: always
+/// written as an .ics file, and also created in the connected calendar unless `push` is false
+#[tauri::command]
+pub async fn create_followup_event(
+    app: AppHandle,
+    session_id: String,
+    proposed_time: String,
+    duration_mins: Option<u32>,
+    push: Option<bool>,
+) -> CommandResult<FollowupEvent> {
+    let start = chrono::DateTime::parse_from_rfc3339(&proposed_time)
+        .map_err(|e| VenkyError::Other(format!("Invalid proposed time: {}", e)))?
+        .with_timezone(&chrono::Utc);
+    let end =
+        start + chrono::Duration::minutes(duration_mins.unwrap_or(30).clamp(5, 8 * 60) as i64);
+
+    let id = session_id.clone();
+    let (session, participants, items) = app
+        .state::<StorageActor>()
+        .call(move |s| {
+            Ok((
+                s.get_session(&id)?,
+                s.get_session_participants(&id)?,
+                s.list_action_items(Some(&id), Some("open"))?,
+            ))
+        })
+        .await?;
+    let calendar = app.state::<CalendarState>().lock().clone();
+
+    let mut description = format!("Follow-up to \"{}\".", session.title);
+    if !items.is_empty() {
+        description.push_str("\n\nOpen action items:");
+        for item in &items {
+            description.push_str(&format!("\n- {}", item.description));
+            if let Some(ref owner) = item.owner {
+                description.push_str(&format!(" ({})", owner));
+            }
+            if let Some(ref due) = item.due_date {
+                description.push_str(&format!(", due {}", due));
+            }
+        }
+    }
+
+    let mut event = FollowupEvent {
+        title: format!("Follow-up: {}", session.title),
+        start: start.to_rfc3339(),
+        end: end.to_rfc3339(),
+        attendees: participants
+            .into_iter()
+            .filter(|p| calendar.account_email.as_deref() != p.email.as_deref())
+            .filter_map(|p| {
+                Some(CalendarAttendee {
+                    email: p.email?,
+                    name: Some(p.name),
+                })
+            })
+            .collect(),
+        description,
+        ics_path: String::new(),
+        calendar_event_id: None,
+    };
+
+    let ics = build_ics(&event, &start, &end, calendar.account_email.as_deref());
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))?
+        .join("followups");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| VenkyError::Storage(format!("Failed to create followups dir: {}", e)))?;
+    let path = dir.join(format!(
+        "{}-{}.ics",
+        session_id,
+        start.format("%Y%m%dT%H%M")
+    ));
+    std::fs::write(&path, ics)
+        .map_err(|e| VenkyError::Storage(format!("Failed to write invite: {}", e)))?;
+    event.ics_path = path.to_string_lossy().to_string();
+
+    if push.unwrap_or(true) && calendar.is_connected() {
+        let config = access_token(&app).await?;
+        event.calendar_event_id = Some(match config.provider {
+            CalendarProvider::Google => google_create_event(&config, &event).await?,
+            _ => outlook_create_event(&config, &event, &start, &end).await?,
+        });
+    }
+    Ok(event)
+}
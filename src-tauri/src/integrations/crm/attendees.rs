@@ -0,0 +1,200 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::{backend, keep_refreshed_token, CRMState};
+use crate::config::AppConfig;
+use crate::error::VenkyError;
+use crate::session::manager::SessionManager;
+use crate::session::storage::{PastMeeting, Storage};
+use crate::session::storage_actor::StorageActor;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Past meetings looked up per attendee, and kept for the session overall
+const MEETINGS_PER_ATTENDEE: usize = 3;
+const MAX_PAST_MEETINGS: usize = 5;
+/// Each summary is cut to this many characters so the context stays small
+const MAX_SUMMARY_CHARS: usize = 600;
+
+/// Emitted as "attendees-enriched"
+#[derive(Debug, Clone, Serialize)]
+pub struct AttendeesEnriched {
+    pub session_id: String,
+    /// Attendees linked to a CRM contact
+    pub matched: usize,
+    pub past_meetings: Vec<PastMeeting>,
+}
+
+/// Link attendees with an email to their existing CRM contact; nothing is created in the
+/// CRM. Returns how many attendees are linked.
+async fn match_contacts(app: &AppHandle, session_id: &str) -> Result<usize, VenkyError> {
+    let storage = app.state::<StorageActor>();
+    let crm = app.state::<CRMState>();
+
+    let config = crm.lock().clone();
+    let token = config.api_key.clone();
+    let Some(mut backend) = backend(config) else {
+        return Ok(0);
+    };
+
+    let id = session_id.to_string();
+    let participants = storage
+        .call(move |s| s.get_session_participants(&id))
+        .await?;
+    let mut matched = 0;
+    for participant in participants {
+        if participant.crm_contact_id.is_some() {
+            matched += 1;
+            continue;
+        }
+        let Some(email) = participant
+            .email
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+        else {
+            continue;
+        };
+        match backend.find_contact(email).await {
+            Ok(Some(contact_id)) => {
+                storage
+                    .call(move |s| s.set_participant_crm_contact(&participant.id, &contact_id))
+                    .await?;
+                matched += 1;
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("CRM lookup of {} failed: {}", email, e),
+        }
+    }
+
+    keep_refreshed_token(&crm, &storage, &token, backend.config());
+    Ok(matched)
+}
+
+/// The most recent summarized sessions any attendee took part in, by email or CRM contact
+fn past_meetings(storage: &Storage, session_id: &str) -> Result<Vec<PastMeeting>, VenkyError> {
+    let mut meetings: Vec<PastMeeting> = Vec::new();
+    for participant in storage.get_session_participants(session_id)? {
+        if participant.email.is_none() && participant.crm_contact_id.is_none() {
+            continue;
+        }
+        for meeting in storage.past_meetings_with(
+            participant.email.as_deref(),
+            participant.crm_contact_id.as_deref(),
+            session_id,
+            MEETINGS_PER_ATTENDEE,
+        )? {
+            if !meetings.iter().any(|m| m.session_id == meeting.session_id) {
+                meetings.push(meeting);
+            }
+        }
+    }
+    meetings.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    meetings.truncate(MAX_PAST_MEETINGS);
+    Ok(meetings)
+}
+
+fn history_context(meetings: &[PastMeeting]) -> String {
+    let mut text = "Past meetings with these attendees:".to_string();
+    for meeting in meetings {
+        let date = meeting.start_time.get(..10).unwrap_or(&meeting.start_time);
+        let summary: String = meeting.summary.chars().take(MAX_SUMMARY_CHARS).collect();
+        text.push_str(&format!(
+            "\n\n{} - {}\n{}",
+            date,
+            meeting.title,
+            summary.trim()
+        ));
+    }
+    text
+}
+
+/// Append the attendees' history to the session's context, live and stored
+async fn add_to_context(
+    app: &AppHandle,
+    session_id: &str,
+    history: String,
+) -> Result<(), VenkyError> {
+    let join = move |existing: Option<String>| match existing.filter(|c| !c.trim().is_empty()) {
+        Some(existing) => format!("{}\n\n{}", existing, history),
+        None => history.clone(),
+    };
+
+    let live_context = {
+        let state = app.state::<SessionState>();
+        let mut mgr = state.lock();
+        mgr.sessions.get_mut(session_id).map(|live| {
+            live.context = Some(join(live.context.take()));
+            live.context.clone()
+        })
+    };
+    let id = session_id.to_string();
+    app.state::<StorageActor>()
+        .call(move |s| {
+            let context = match live_context {
+                Some(context) => context,
+                None => Some(join(s.get_session(&id)?.context)),
+            };
+            s.set_session_context(&id, context.as_deref())
+        })
+        .await?;
+    Ok(())
+}
+
+async fn enrich(app: &AppHandle, session_id: &str) -> Result<AttendeesEnriched, VenkyError> {
+    // History is still found by email when the CRM can't be reached
+    let matched = match match_contacts(app, session_id).await {
+        Ok(matched) => matched,
+        Err(e) => {
+            log::warn!(
+                "Matching attendees of {} to the CRM failed: {}",
+                session_id,
+                e
+            );
+            0
+        }
+    };
+
+    let id = session_id.to_string();
+    let past_meetings = app
+        .state::<StorageActor>()
+        .call(move |s| past_meetings(s, &id))
+        .await?;
+    if !past_meetings.is_empty() {
+        add_to_context(app, session_id, history_context(&past_meetings)).await?;
+    }
+
+    Ok(AttendeesEnriched {
+        session_id: session_id.to_string(),
+        matched,
+        past_meetings,
+    })
+}
+
+/// In the background, match a calendar session's attendees to CRM contacts and add their
+/// past meeting summaries to its context, then emit "attendees-enriched"
+pub fn enrich_if_enabled(app: &AppHandle, session_id: &str) {
+    if !app.state::<ConfigState>().lock().attendee_history {
+        return;
+    }
+
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        match enrich(&app, &session_id).await {
+            Ok(enriched) => {
+                log::info!(
+                    "Session {}: {} attendee(s) matched to the CRM, {} past meeting(s) found",
+                    session_id,
+                    enriched.matched,
+                    enriched.past_meetings.len()
+                );
+                let _ = app.emit("attendees-enriched", enriched);
+            }
+            Err(e) => log::warn!("Attendee enrichment for {} failed: {}", session_id, e),
+        }
+    });
+}
@@ -0,0 +1,240 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::{backend, keep_refreshed_token, CRMState};
+use crate::config::AppConfig;
+use crate::error::VenkyError;
+use crate::integrations::{CRMContact, CRMNote, CRMProvider};
+use crate::session::storage::Participant;
+use crate::session::storage_actor::StorageActor;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+/// A contact to log the meeting on, with the participant it came from if any
+struct SyncContact {
+    participant_id: Option<String>,
+    contact: CRMContact,
+}
+
+/// One step of the end-of-session CRM sync, emitted as "crm-sync-step"
+#[derive(Debug, Clone, Serialize)]
+pub struct CRMSyncStep {
+    pub session_id: String,
+    /// "summary", "contacts", "match", "note" or "done"
+    pub step: String,
+    pub success: bool,
+    pub message: String,
+}
+
+fn report(app: &AppHandle, session_id: &str, step: &str, success: bool, message: String) {
+    if !success {
+        log::warn!("CRM sync of {} ({}): {}", session_id, step, message);
+    }
+    let _ = app.emit(
+        "crm-sync-step",
+        CRMSyncStep {
+            session_id: session_id.to_string(),
+            step: step.to_string(),
+            success,
+            message,
+        },
+    );
+}
+
+/// A participant with an email as a CRM contact; the last word of the name is the surname
+fn participant_contact(participant: &Participant) -> Option<CRMContact> {
+    let email = participant.email.as_deref()?.trim();
+    if email.is_empty() {
+        return None;
+    }
+    let name = participant.name.trim();
+    let (first_name, last_name) = name.rsplit_once(' ').unwrap_or(("", name));
+    Some(CRMContact {
+        id: participant.crm_contact_id.clone(),
+        email: email.to_string(),
+        first_name: first_name.trim().to_string(),
+        last_name: last_name.trim().to_string(),
+        company: None,
+        phone: None,
+        title: participant.role.clone(),
+    })
+}
+
+/// After a session ends, summarize it, match or create its participants and the people
+/// mentioned in it in the CRM, and log the summary as a note on each of them
+pub fn sync_if_enabled(app: &AppHandle, session_id: &str) {
+    let enabled = app.state::<ConfigState>().lock().crm_auto_sync;
+    if !enabled || app.state::<CRMState>().lock().provider == CRMProvider::None {
+        return;
+    }
+
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let (success, message) = match run(&app, &session_id).await {
+            Ok(logged) => (true, format!("Logged to {} contact(s)", logged)),
+            Err(e) => (false, e.to_string()),
+        };
+        let provider = app.state::<CRMState>().lock().provider.clone();
+        let kind = if success {
+            crate::metrics::CRM_SYNC
+        } else {
+            crate::metrics::CRM_SYNC_FAILED
+        };
+        crate::metrics::record(kind, &format!("{:?}", provider).to_lowercase(), 1);
+        if !success {
+            crate::notifications::crm_sync_failed(&app, &message);
+        }
+        report(&app, &session_id, "done", success, message);
+    });
+}
+
+/// Returns how many contacts the note was logged on
+async fn run(app: &AppHandle, session_id: &str) -> Result<usize, VenkyError> {
+    let storage = app.state::<StorageActor>();
+    let crm = app.state::<CRMState>();
+
+    let id = session_id.to_string();
+    let existing = storage.call(move |s| s.get_session(&id)).await?.summary;
+    let summary = match existing.filter(|s| !s.trim().is_empty()) {
+        Some(summary) => summary,
+        None => {
+            let result = crate::session::summary::summarize_session(app, session_id, None).await;
+            match &result {
+                Ok(_) => report(
+                    app,
+                    session_id,
+                    "summary",
+                    true,
+                    "Summary generated".to_string(),
+                ),
+                Err(e) => report(app, session_id, "summary", false, e.to_string()),
+            }
+            result?
+        }
+    };
+
+    let id = session_id.to_string();
+    let participants = storage
+        .call(move |s| s.get_session_participants(&id))
+        .await?;
+    let mut contacts: Vec<SyncContact> = participants
+        .iter()
+        .filter_map(|p| {
+            participant_contact(p).map(|contact| SyncContact {
+                participant_id: Some(p.id.clone()),
+                contact,
+            })
+        })
+        .collect();
+
+    // People mentioned with an email who aren't participants yet
+    let cfg = app.state::<ConfigState>().lock().clone();
+    match crate::session::contacts::extract(&cfg, &storage, session_id).await {
+        Ok(extracted) => {
+            for contact in extracted {
+                let known = contacts
+                    .iter()
+                    .any(|c| c.contact.email.eq_ignore_ascii_case(&contact.email));
+                if !contact.email.is_empty() && !known {
+                    contacts.push(SyncContact {
+                        participant_id: None,
+                        contact,
+                    });
+                }
+            }
+        }
+        Err(e) => report(app, session_id, "contacts", false, e.to_string()),
+    }
+    if contacts.is_empty() {
+        return Err(VenkyError::Other(
+            "No contacts with an email address to log the meeting on".to_string(),
+        ));
+    }
+    report(
+        app,
+        session_id,
+        "contacts",
+        true,
+        format!("{} contact(s) with an email address", contacts.len()),
+    );
+
+    let config = crm.lock().clone();
+    let token = config.api_key.clone();
+    let mut backend = backend(config)
+        .ok_or_else(|| VenkyError::Other("No CRM provider configured".to_string()))?;
+
+    let mut contact_ids = Vec::new();
+    for SyncContact {
+        participant_id,
+        contact,
+    } in &contacts
+    {
+        let name = format!("{} {}", contact.first_name, contact.last_name);
+        let name = name.trim();
+        if let Some(ref id) = contact.id {
+            contact_ids.push(id.clone());
+            continue;
+        }
+        let matched = match backend.find_contact(&contact.email).await {
+            Ok(Some(id)) => Ok((id, "Matched")),
+            Ok(None) => match backend.create_contact(contact).await {
+                Ok(result) if result.success => result
+                    .record_id
+                    .map(|id| (id, "Created"))
+                    .ok_or_else(|| "No record id returned".to_string()),
+                Ok(result) => Err(result.message),
+                Err(e) => Err(e.to_string()),
+            },
+            Err(e) => Err(e.to_string()),
+        };
+        match matched {
+            Ok((id, action)) => {
+                if let Some(participant_id) = participant_id {
+                    let (participant_id, contact_id, name) =
+                        (participant_id.clone(), id.clone(), name.to_string());
+                    storage.send(move |s| {
+                        if let Err(e) = s.set_participant_crm_contact(&participant_id, &contact_id)
+                        {
+                            log::warn!("Failed to link {} to CRM contact: {}", name, e);
+                        }
+                    });
+                }
+                report(
+                    app,
+                    session_id,
+                    "match",
+                    true,
+                    format!("{} {}", action, name),
+                );
+                contact_ids.push(id);
+            }
+            Err(e) => report(app, session_id, "match", false, format!("{}: {}", name, e)),
+        }
+    }
+
+    let mut redactor = crate::redaction::Redactor::for_session_id(&cfg, session_id).await;
+    let summary = redactor.redact(&summary);
+    redactor.report();
+
+    let mut logged = 0;
+    for contact_id in &contact_ids {
+        let note = CRMNote {
+            contact_id: contact_id.clone(),
+            content: summary.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        match backend.add_note(&note).await {
+            Ok(result) => {
+                logged += result.success as usize;
+                report(app, session_id, "note", result.success, result.message);
+            }
+            Err(e) => report(app, session_id, "note", false, e.to_string()),
+        }
+    }
+
+    keep_refreshed_token(&crm, &storage, &token, backend.config());
+    Ok(logged)
+}
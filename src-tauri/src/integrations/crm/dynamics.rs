@@ -0,0 +1,220 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::future::BoxFuture;
+use reqwest::{Client, Method, Response};
+
+use super::{error_result, send, AttachmentFile, CrmBackend, OAuthEndpoints};
+use crate::error::VenkyError;
+use crate::integrations::{CRMConfig, CRMContact, CRMNote, CRMSyncResult};
+
+/// Work or school accounts of any tenant; a tenant id or domain narrows it down
+const DYNAMICS_LOGIN_URL: &str = "https://login.microsoftonline.com/organizations";
+
+/// Dataverse caps note attachments at 5 MB by default, and the upload is base64
+const DYNAMICS_MAX_ATTACHMENT_BYTES: usize = 3 * 1024 * 1024;
+
+/// OData string literal; quotes are doubled
+fn odata_escape(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+pub struct Dynamics {
+    pub config: CRMConfig,
+}
+
+impl Dynamics {
+    /// Call the Dataverse Web API; writes ask for the created record back
+    async fn request(
+        &mut self,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<Response, VenkyError> {
+        send(self, |client: &Client, config: &CRMConfig| {
+            let url = format!(
+                "{}/api/data/v9.2/{}",
+                config.instance_url.trim_end_matches('/'),
+                path
+            );
+            let request = client
+                .request(method.clone(), url)
+                .header("Authorization", format!("Bearer {}", config.api_key))
+                .header("OData-MaxVersion", "4.0")
+                .header("OData-Version", "4.0")
+                .header("Accept", "application/json");
+            match body {
+                Some(body) => request.header("Prefer", "return=representation").json(body),
+                None => request,
+            }
+        })
+        .await
+    }
+
+    /// Id of the contact with this email, if there is one
+    async fn contact_by_email(&mut self, email: &str) -> Result<Option<String>, VenkyError> {
+        let path = format!(
+            "contacts?$select=contactid&$filter=emailaddress1 eq '{}'&$top=1",
+            odata_escape(email)
+        );
+        let response = self.request(Method::GET, &path, None).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(VenkyError::Provider(format!(
+                "Dynamics error ({}): {}",
+                status, body
+            )));
+        }
+        let result: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+        Ok(result["value"][0]["contactid"].as_str().map(str::to_string))
+    }
+
+    /// Create a note (annotation) on a contact, reporting `message` when it succeeds
+    async fn create_annotation(
+        &mut self,
+        contact_id: &str,
+        mut body: serde_json::Value,
+        message: String,
+    ) -> Result<CRMSyncResult, VenkyError> {
+        body["objectid_contact@odata.bind"] = format!("/contacts({})", contact_id).into();
+        let response = self
+            .request(Method::POST, "annotations", Some(&body))
+            .await?;
+        if !response.status().is_success() {
+            return Ok(error_result(self.name(), response).await);
+        }
+
+        let created: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+        Ok(CRMSyncResult {
+            success: true,
+            message,
+            record_id: created["annotationid"].as_str().map(str::to_string),
+        })
+    }
+}
+
+impl CrmBackend for Dynamics {
+    fn name(&self) -> &'static str {
+        "Dynamics"
+    }
+
+    fn config(&self) -> &CRMConfig {
+        &self.config
+    }
+
+    fn config_mut(&mut self) -> &mut CRMConfig {
+        &mut self.config
+    }
+
+    /// Entra ID sign-in; the token is scoped to the org in `instance_url`
+    fn oauth(&self) -> Option<OAuthEndpoints> {
+        let login = match self.config.login_url.trim() {
+            "" => DYNAMICS_LOGIN_URL,
+            url => url.trim_end_matches('/'),
+        };
+        Some(OAuthEndpoints {
+            authorize_url: format!("{}/oauth2/v2.0/authorize", login),
+            token_url: format!("{}/oauth2/v2.0/token", login),
+            scopes: format!(
+                "{}/user_impersonation offline_access",
+                self.config.instance_url.trim_end_matches('/')
+            ),
+            extra_params: Vec::new(),
+        })
+    }
+
+    fn max_attachment_bytes(&self) -> usize {
+        DYNAMICS_MAX_ATTACHMENT_BYTES
+    }
+
+    fn find_contact<'a>(
+        &'a mut self,
+        email: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>, VenkyError>> {
+        Box::pin(self.contact_by_email(email))
+    }
+
+    /// Update the contact with the same email, or create one
+    fn create_contact<'a>(
+        &'a mut self,
+        contact: &'a CRMContact,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            // The company is a lookup to an account record, so it isn't sent
+            let body = serde_json::json!({
+                "firstname": contact.first_name,
+                "lastname": contact.last_name,
+                "emailaddress1": contact.email,
+                "telephone1": contact.phone,
+                "jobtitle": contact.title,
+            });
+
+            let existing = match contact.id.clone() {
+                Some(id) => Some(id),
+                None => self.contact_by_email(&contact.email).await?,
+            };
+            let (response, message) = match existing.as_deref() {
+                Some(id) => (
+                    self.request(Method::PATCH, &format!("contacts({})", id), Some(&body))
+                        .await?,
+                    "Contact updated in Dynamics",
+                ),
+                None => (
+                    self.request(Method::POST, "contacts", Some(&body)).await?,
+                    "Contact created in Dynamics",
+                ),
+            };
+            if !response.status().is_success() {
+                return Ok(error_result(self.name(), response).await);
+            }
+
+            let saved: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+            Ok(CRMSyncResult {
+                success: true,
+                message: message.to_string(),
+                record_id: saved["contactid"].as_str().map(str::to_string).or(existing),
+            })
+        })
+    }
+
+    fn add_note<'a>(
+        &'a mut self,
+        note: &'a CRMNote,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "subject": format!("Meeting Notes - {}", note.timestamp),
+                "notetext": note.content,
+            });
+            self.create_annotation(&note.contact_id, body, "Note added to Dynamics".to_string())
+                .await
+        })
+    }
+
+    fn attach_file<'a>(
+        &'a mut self,
+        record_id: &'a str,
+        file: &'a AttachmentFile,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "subject": file.title,
+                "filename": file.file_name,
+                "mimetype": file.mime,
+                "documentbody": STANDARD.encode(file.content.as_bytes()),
+                "isdocument": true,
+            });
+            let message = format!("{} attached in Dynamics", file.title);
+            self.create_annotation(record_id, body, message).await
+        })
+    }
+}
@@ -0,0 +1,326 @@
+use futures::future::BoxFuture;
+use reqwest::{Client, Response};
+use serde::Deserialize;
+
+use super::{error_result, send, AttachmentFile, CrmBackend};
+use crate::error::VenkyError;
+use crate::integrations::{CRMConfig, CRMContact, CRMDeal, CRMNote, CRMSyncResult};
+use crate::session::manager::Session;
+
+const HUBSPOT_API_URL: &str = "https://api.hubapi.com";
+
+/// HubSpot file manager folder for uploaded transcripts
+const HUBSPOT_FOLDER: &str = "/VenkyAI";
+
+/// HubSpot-defined association types
+const HUBSPOT_NOTE_TO_CONTACT: u32 = 202;
+const HUBSPOT_MEETING_TO_CONTACT: u32 = 200;
+const HUBSPOT_MEETING_TO_DEAL: u32 = 212;
+
+#[derive(Debug, Deserialize)]
+struct HubSpotCreateResponse {
+    id: Option<String>,
+}
+
+fn association(id: &str, type_id: u32) -> serde_json::Value {
+    serde_json::json!({
+        "to": { "id": id },
+        "types": [{
+            "associationCategory": "HUBSPOT_DEFINED",
+            "associationTypeId": type_id
+        }]
+    })
+}
+
+pub struct HubSpot {
+    pub config: CRMConfig,
+}
+
+impl HubSpot {
+    async fn post(&mut self, path: &str, body: &serde_json::Value) -> Result<Response, VenkyError> {
+        send(self, |client: &Client, config: &CRMConfig| {
+            client
+                .post(format!("{}{}", HUBSPOT_API_URL, path))
+                .header("Authorization", format!("Bearer {}", config.api_key))
+                .json(body)
+        })
+        .await
+    }
+
+    /// Create one CRM object, reporting `message` when it succeeds
+    async fn create(
+        &mut self,
+        path: &str,
+        body: &serde_json::Value,
+        message: String,
+    ) -> Result<CRMSyncResult, VenkyError> {
+        let response = self.post(path, body).await?;
+        if !response.status().is_success() {
+            return Ok(error_result(self.name(), response).await);
+        }
+
+        let result: HubSpotCreateResponse = response
+            .json()
+            .await
+            .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+
+        Ok(CRMSyncResult {
+            success: true,
+            message,
+            record_id: result.id,
+        })
+    }
+
+    /// Log the session as a Meeting engagement on the contact, and on the deal if one is given
+    pub async fn log_meeting(
+        &mut self,
+        session: &Session,
+        contact_id: &str,
+        deal_id: Option<&str>,
+        outcome: &str,
+    ) -> Result<CRMSyncResult, VenkyError> {
+        let mut associations = vec![association(contact_id, HUBSPOT_MEETING_TO_CONTACT)];
+        if let Some(deal_id) = deal_id {
+            associations.push(association(deal_id, HUBSPOT_MEETING_TO_DEAL));
+        }
+
+        let end_time = session.end_time.as_deref().unwrap_or(&session.start_time);
+        let body = serde_json::json!({
+            "properties": {
+                "hs_timestamp": session.start_time,
+                "hs_meeting_title": session.title,
+                "hs_meeting_body": session.summary.as_deref().unwrap_or_default(),
+                "hs_meeting_start_time": session.start_time,
+                "hs_meeting_end_time": end_time,
+                "hs_meeting_outcome": outcome,
+            },
+            "associations": associations,
+        });
+
+        self.create(
+            "/crm/v3/objects/meetings",
+            &body,
+            "Meeting logged in HubSpot".to_string(),
+        )
+        .await
+    }
+}
+
+impl CrmBackend for HubSpot {
+    fn name(&self) -> &'static str {
+        "HubSpot"
+    }
+
+    fn config(&self) -> &CRMConfig {
+        &self.config
+    }
+
+    fn config_mut(&mut self) -> &mut CRMConfig {
+        &mut self.config
+    }
+
+    fn find_contact<'a>(
+        &'a mut self,
+        email: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>, VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "filterGroups": [{
+                    "filters": [{ "propertyName": "email", "operator": "EQ", "value": email }]
+                }],
+                "limit": 1,
+            });
+            let response = self.post("/crm/v3/objects/contacts/search", &body).await?;
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(VenkyError::Provider(format!(
+                    "HubSpot error ({}): {}",
+                    status, body
+                )));
+            }
+            let found: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+            Ok(found["results"][0]["id"].as_str().map(str::to_string))
+        })
+    }
+
+    fn create_contact<'a>(
+        &'a mut self,
+        contact: &'a CRMContact,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "properties": {
+                    "firstname": contact.first_name,
+                    "lastname": contact.last_name,
+                    "email": contact.email,
+                    "phone": contact.phone.as_deref().unwrap_or(""),
+                    "company": contact.company.as_deref().unwrap_or(""),
+                    "jobtitle": contact.title.as_deref().unwrap_or(""),
+                }
+            });
+            self.create(
+                "/crm/v3/objects/contacts",
+                &body,
+                "Contact created in HubSpot".to_string(),
+            )
+            .await
+        })
+    }
+
+    fn add_note<'a>(
+        &'a mut self,
+        note: &'a CRMNote,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "properties": {
+                    "hs_timestamp": note.timestamp,
+                    "hs_note_body": note.content,
+                },
+                "associations": [association(&note.contact_id, HUBSPOT_NOTE_TO_CONTACT)]
+            });
+            self.create(
+                "/crm/v3/objects/notes",
+                &body,
+                "Note added to HubSpot".to_string(),
+            )
+            .await
+        })
+    }
+
+    /// Upload a file to HubSpot's file manager, then log a note on the contact with it attached
+    fn attach_file<'a>(
+        &'a mut self,
+        record_id: &'a str,
+        file: &'a AttachmentFile,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let response = send(self, |client: &Client, config: &CRMConfig| {
+                let part = reqwest::multipart::Part::bytes(file.content.clone().into_bytes())
+                    .file_name(file.file_name.clone())
+                    .mime_str(file.mime)
+                    .expect("attachment MIME types are valid");
+                let form = reqwest::multipart::Form::new()
+                    .part("file", part)
+                    .text("folderPath", HUBSPOT_FOLDER)
+                    .text("options", r#"{"access":"PRIVATE"}"#);
+                client
+                    .post(format!("{}/files/v3/files", HUBSPOT_API_URL))
+                    .header("Authorization", format!("Bearer {}", config.api_key))
+                    .multipart(form)
+            })
+            .await?;
+            if !response.status().is_success() {
+                return Ok(error_result(self.name(), response).await);
+            }
+
+            let uploaded: HubSpotCreateResponse = response
+                .json()
+                .await
+                .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+            let file_id = uploaded.id.ok_or_else(|| {
+                VenkyError::Provider("HubSpot did not return a file id".to_string())
+            })?;
+
+            let body = serde_json::json!({
+                "properties": {
+                    "hs_timestamp": chrono::Utc::now().to_rfc3339(),
+                    "hs_note_body": file.title,
+                    "hs_attachment_ids": file_id,
+                },
+                "associations": [association(record_id, HUBSPOT_NOTE_TO_CONTACT)]
+            });
+            let message = format!("{} attached in HubSpot", file.title);
+            let mut result = self.create("/crm/v3/objects/notes", &body, message).await?;
+            if result.success {
+                result.record_id = Some(file_id);
+            }
+            Ok(result)
+        })
+    }
+
+    /// Deals associated with the contact
+    fn list_deals<'a>(
+        &'a mut self,
+        contact_id: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<CRMDeal>, VenkyError>> {
+        Box::pin(async move {
+            let response = send(self, |client: &Client, config: &CRMConfig| {
+                client
+                    .get(format!(
+                        "{}/crm/v4/objects/contacts/{}/associations/deals",
+                        HUBSPOT_API_URL, contact_id
+                    ))
+                    .header("Authorization", format!("Bearer {}", config.api_key))
+            })
+            .await?;
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(VenkyError::Provider(format!(
+                    "HubSpot error ({}): {}",
+                    status, body
+                )));
+            }
+            let associated: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+            let inputs: Vec<serde_json::Value> = associated["results"]
+                .as_array()
+                .map(|results| {
+                    results
+                        .iter()
+                        .filter_map(|r| r["toObjectId"].as_u64())
+                        .map(|id| serde_json::json!({ "id": id.to_string() }))
+                        .collect()
+                })
+                .unwrap_or_default();
+            if inputs.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let body = serde_json::json!({
+                "properties": ["dealname", "dealstage", "amount", "closedate"],
+                "inputs": inputs,
+            });
+            let response = self.post("/crm/v3/objects/deals/batch/read", &body).await?;
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(VenkyError::Provider(format!(
+                    "HubSpot error ({}): {}",
+                    status, body
+                )));
+            }
+            let deals: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+
+            Ok(deals["results"]
+                .as_array()
+                .map(|results| {
+                    results
+                        .iter()
+                        .filter_map(|d| {
+                            let props = &d["properties"];
+                            Some(CRMDeal {
+                                id: d["id"].as_str()?.to_string(),
+                                name: props["dealname"].as_str().unwrap_or_default().to_string(),
+                                stage: props["dealstage"].as_str().map(str::to_string),
+                                // HubSpot returns every property as a string
+                                amount: props["amount"].as_str().and_then(|a| a.parse().ok()),
+                                close_date: props["closedate"].as_str().map(str::to_string),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default())
+        })
+    }
+}
@@ -0,0 +1,692 @@
+pub mod attendees;
+pub mod auto_sync;
+mod dynamics;
+mod hubspot;
+mod salesforce;
+mod webhook;
+mod zoho;
+
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::sync::Arc;
+use tauri::AppHandle;
+
+use super::oauth::{self, OAuthClient};
+use super::{CRMConfig, CRMContact, CRMDeal, CRMNote, CRMProvider, CRMSyncResult, ConnectionTest};
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+use crate::redaction::Redactor;
+use crate::session::manager::{Session, TIMELINE_SPEAKER};
+use crate::session::storage_actor::StorageActor;
+
+use dynamics::Dynamics;
+use hubspot::HubSpot;
+use salesforce::Salesforce;
+use webhook::Webhook;
+use zoho::Zoho;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type CRMState = Arc<Mutex<CRMConfig>>;
+
+/// Largest file uploaded in one piece unless a provider needs less; longer transcripts
+/// are split into parts
+const MAX_ATTACHMENT_BYTES: usize = 20 * 1024 * 1024;
+
+/// Looked up to test a connection; the reserved TLD means it never matches a real contact
+const PROBE_EMAIL: &str = "connection-test@venkyai.invalid";
+
+// ─── Providers ───────────────────────────────────────────────────────────────
+
+/// Where a provider signs users in and refreshes their tokens
+struct OAuthEndpoints {
+    authorize_url: String,
+    token_url: String,
+    scopes: String,
+    extra_params: Vec<(&'static str, &'static str)>,
+}
+
+/// One CRM backend. Each provider owns a copy of the config so a token refreshed during a
+/// call can be written back afterwards.
+trait CrmBackend: Send {
+    fn name(&self) -> &'static str;
+
+    fn config(&self) -> &CRMConfig;
+
+    fn config_mut(&mut self) -> &mut CRMConfig;
+
+    /// Browser sign-in endpoints; None for providers that only take an API key
+    fn oauth(&self) -> Option<OAuthEndpoints> {
+        None
+    }
+
+    fn max_attachment_bytes(&self) -> usize {
+        MAX_ATTACHMENT_BYTES
+    }
+
+    /// Id of the contact with this email; providers that can't search find nothing
+    fn find_contact<'a>(
+        &'a mut self,
+        _email: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>, VenkyError>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    fn create_contact<'a>(
+        &'a mut self,
+        contact: &'a CRMContact,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>>;
+
+    fn add_note<'a>(
+        &'a mut self,
+        note: &'a CRMNote,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>>;
+
+    fn attach_file<'a>(
+        &'a mut self,
+        record_id: &'a str,
+        file: &'a AttachmentFile,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>>;
+
+    /// Make a cheap authenticated call to check the credentials, by default a contact search
+    fn check<'a>(&'a mut self) -> BoxFuture<'a, Result<(), VenkyError>> {
+        Box::pin(async move { self.find_contact(PROBE_EMAIL).await.map(|_| ()) })
+    }
+
+    fn list_deals<'a>(
+        &'a mut self,
+        _contact_id: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<CRMDeal>, VenkyError>> {
+        let name = self.name();
+        Box::pin(async move { Err(VenkyError::Other(format!("{} has no deals to list", name))) })
+    }
+}
+
+fn backend(config: CRMConfig) -> Option<Box<dyn CrmBackend>> {
+    match config.provider {
+        CRMProvider::Salesforce => Some(Box::new(Salesforce { config })),
+        CRMProvider::HubSpot => Some(Box::new(HubSpot { config })),
+        CRMProvider::Zoho => Some(Box::new(Zoho { config })),
+        CRMProvider::Dynamics => Some(Box::new(Dynamics { config })),
+        CRMProvider::Webhook => Some(Box::new(Webhook { config })),
+        CRMProvider::None => None,
+    }
+}
+
+fn not_configured() -> CRMSyncResult {
+    CRMSyncResult {
+        success: false,
+        message: "No CRM provider configured".to_string(),
+        record_id: None,
+    }
+}
+
+/// The provider's error response as a failed sync result
+async fn error_result(provider: &str, response: Response) -> CRMSyncResult {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    CRMSyncResult {
+        success: false,
+        message: format!("{} error ({}): {}", provider, status, body),
+        record_id: None,
+    }
+}
+
+/// Get a new access token with the stored refresh token
+async fn refresh<B: CrmBackend + ?Sized>(backend: &mut B) -> Result<(), VenkyError> {
+    let name = backend.name();
+    let endpoints = backend.oauth().ok_or_else(|| {
+        VenkyError::Provider(format!("{} token expired; update the API key", name))
+    })?;
+    let config = backend.config_mut();
+    let refresh_token = config.refresh_token.clone().ok_or_else(|| {
+        VenkyError::Provider(format!("{} token expired; reconnect {}", name, name))
+    })?;
+    let client = OAuthClient {
+        authorize_url: &endpoints.authorize_url,
+        token_url: &endpoints.token_url,
+        client_id: &config.client_id,
+        client_secret: None,
+        scopes: &endpoints.scopes,
+        extra_params: &endpoints.extra_params,
+        redirect_uri: None,
+    };
+    let tokens = oauth::refresh(&client, &refresh_token).await?;
+
+    config.api_key = tokens.access_token;
+    if let Some(instance_url) = tokens.instance_url.or(tokens.api_domain) {
+        config.instance_url = instance_url;
+    }
+    if tokens.refresh_token.is_some() {
+        config.refresh_token = tokens.refresh_token;
+    }
+    Ok(())
+}
+
+/// Send a request built from the current config, refreshing the access token once if the
+/// provider rejects it
+async fn send<B: CrmBackend + ?Sized>(
+    backend: &mut B,
+    build: impl Fn(&Client, &CRMConfig) -> RequestBuilder,
+) -> Result<Response, VenkyError> {
+    let client = crate::http::client();
+    let mut refreshed = false;
+    loop {
+        let response = build(&client, backend.config()).send().await.map_err(|e| {
+            VenkyError::Network(format!("{} request failed: {}", backend.name(), e))
+        })?;
+
+        if response.status() == StatusCode::UNAUTHORIZED
+            && !refreshed
+            && backend.config().refresh_token.is_some()
+        {
+            log::info!("{} access token expired, refreshing", backend.name());
+            refresh(&mut *backend).await?;
+            refreshed = true;
+            continue;
+        }
+        return Ok(response);
+    }
+}
+
+// ─── Attachments ─────────────────────────────────────────────────────────────
+
+struct AttachmentFile {
+    title: String,
+    file_name: String,
+    mime: &'static str,
+    content: String,
+}
+
+/// Split `text` on line boundaries into pieces of at most `max_bytes`
+fn chunk_text(text: &str, max_bytes: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in text.split_inclusive('\n') {
+        let mut line = line;
+        // A single line over the limit is cut at a char boundary
+        while line.len() > max_bytes {
+            let mut cut = max_bytes;
+            while !line.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            chunks.push(line[..cut].to_string());
+            line = &line[cut..];
+        }
+        if current.len() + line.len() > max_bytes {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Title safe to use as a file name on any OS
+fn file_stem(title: &str) -> String {
+    let stem: String = title
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match stem.trim() {
+        "" => "Session".to_string(),
+        stem => stem.to_string(),
+    }
+}
+
+/// The session's transcript (in parts if needed) and summary as uploadable files
+fn session_files(session: &Session, max_bytes: usize) -> Vec<AttachmentFile> {
+    let stem = file_stem(&session.title);
+    let mut files = Vec::new();
+
+    let entries = session
+        .transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER)
+        .collect::<Vec<_>>();
+    if !entries.is_empty() {
+        let mut text = format!("{}\n{}\n\n", session.title, session.start_time);
+        for e in entries {
+            text.push_str(&format!("[{}] {}: {}\n", e.timestamp, e.speaker, e.text));
+        }
+        let parts = chunk_text(&text, max_bytes);
+        let total = parts.len();
+        for (i, content) in parts.into_iter().enumerate() {
+            let suffix = if total > 1 {
+                format!(" (part {} of {})", i + 1, total)
+            } else {
+                String::new()
+            };
+            files.push(AttachmentFile {
+                title: format!("{} - Transcript{}", session.title, suffix),
+                file_name: format!("{} - Transcript{}.txt", stem, suffix),
+                mime: "text/plain",
+                content,
+            });
+        }
+    }
+
+    if let Some(summary) = session.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        files.push(AttachmentFile {
+            title: format!("{} - Summary", session.title),
+            file_name: format!("{} - Summary.md", stem),
+            mime: "text/markdown",
+            content: format!("# {}\n\n{}\n", session.title, summary),
+        });
+    }
+    files
+}
+
+// ─── Tauri Commands ──────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_crm_config(crm: tauri::State<'_, CRMState>) -> CRMConfig {
+    crm.lock().clone()
+}
+
+/// Apply and persist the CRM settings; the API key goes to the OS keychain
+#[tauri::command]
+pub async fn update_crm_config(
+    crm: tauri::State<'_, CRMState>,
+    storage: tauri::State<'_, StorageActor>,
+    mut config: CRMConfig,
+) -> CommandResult<()> {
+    if config.provider == CRMProvider::Webhook {
+        webhook::validate(&config.webhook)?;
+    }
+    // The UI never sees the refresh token, so keep the one from the OAuth sign-in
+    {
+        let current = crm.lock();
+        if config.refresh_token.is_none() && config.provider == current.provider {
+            config.refresh_token = current.refresh_token.clone();
+        }
+    }
+    let row = config.clone();
+    storage.call(move |s| row.save(s)).await?;
+    *crm.lock() = config;
+    Ok(())
+}
+
+/// Sign in to an OAuth provider (Salesforce, Zoho or Dynamics 365) in the browser and keep
+/// the tokens. `login_url` overrides the sign-in host (sandbox, data center or tenant);
+/// Dynamics also needs the org URL as `instance_url`. `redirect_uri` is the loopback callback
+/// registered on the client, for providers that match it exactly (Salesforce).
+#[tauri::command]
+pub async fn connect_crm(
+    app: AppHandle,
+    crm: tauri::State<'_, CRMState>,
+    storage: tauri::State<'_, StorageActor>,
+    provider: CRMProvider,
+    client_id: String,
+    login_url: Option<String>,
+    instance_url: Option<String>,
+    redirect_uri: Option<String>,
+) -> CommandResult<CRMConfig> {
+    if client_id.trim().is_empty() {
+        return Err(VenkyError::Other(
+            "An OAuth client id is required".to_string(),
+        ));
+    }
+    let config = CRMConfig {
+        provider,
+        client_id: client_id.trim().to_string(),
+        login_url: login_url.unwrap_or_default().trim().to_string(),
+        instance_url: instance_url
+            .unwrap_or_default()
+            .trim()
+            .trim_end_matches('/')
+            .to_string(),
+        ..CRMConfig::default()
+    };
+    let mut backend =
+        backend(config).ok_or_else(|| VenkyError::Other("Choose a CRM provider".to_string()))?;
+    let endpoints = backend
+        .oauth()
+        .ok_or_else(|| VenkyError::Other(format!("{} connects with an API key", backend.name())))?;
+
+    let config = backend.config_mut();
+    let client = OAuthClient {
+        authorize_url: &endpoints.authorize_url,
+        token_url: &endpoints.token_url,
+        client_id: &config.client_id,
+        client_secret: None,
+        scopes: &endpoints.scopes,
+        extra_params: &endpoints.extra_params,
+        redirect_uri: redirect_uri
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty()),
+    };
+    let tokens = oauth::authorize(&app, &client).await?;
+
+    config.api_key = tokens.access_token;
+    config.refresh_token = tokens.refresh_token;
+    if let Some(instance_url) = tokens.instance_url.or(tokens.api_domain) {
+        config.instance_url = instance_url;
+    }
+    if config.instance_url.is_empty() {
+        return Err(VenkyError::Provider(
+            "The provider did not return an instance URL".to_string(),
+        ));
+    }
+
+    let config = config.clone();
+    let row = config.clone();
+    storage.call(move |s| row.save(s)).await?;
+    *crm.lock() = config.clone();
+    Ok(config)
+}
+
+/// Write back an access token refreshed during a request, so the next call and launch use it
+fn keep_refreshed_token(crm: &CRMState, storage: &StorageActor, before: &str, config: &CRMConfig) {
+    if config.api_key == before {
+        return;
+    }
+    {
+        let mut current = crm.lock();
+        current.api_key = config.api_key.clone();
+        current.instance_url = config.instance_url.clone();
+        current.refresh_token = config.refresh_token.clone();
+    }
+    let config = config.clone();
+    storage.send(move |s| {
+        if let Err(e) = config.save(s) {
+            log::error!("Failed to save refreshed CRM token: {}", e);
+        }
+    });
+}
+
+/// Check that the saved CRM credentials work, refreshing the token if it has expired
+#[tauri::command]
+pub async fn test_crm_connection(
+    crm: tauri::State<'_, CRMState>,
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<ConnectionTest> {
+    let config = crm.lock().clone();
+    let token = config.api_key.clone();
+    let Some(mut backend) = backend(config) else {
+        return Ok(ConnectionTest {
+            success: false,
+            latency_ms: 0,
+            message: "No CRM provider configured".to_string(),
+        });
+    };
+
+    let name = backend.name();
+    let test = ConnectionTest::run(async {
+        backend.check().await?;
+        Ok(format!("Connected to {}", name))
+    })
+    .await;
+    keep_refreshed_token(&crm, &storage, &token, backend.config());
+    Ok(test)
+}
+
+/// Create the contact, or update it where the provider matches existing contacts by email
+#[tauri::command]
+pub async fn crm_sync_contact(
+    crm: tauri::State<'_, CRMState>,
+    storage: tauri::State<'_, StorageActor>,
+    contact: CRMContact,
+) -> CommandResult<CRMSyncResult> {
+    let config = crm.lock().clone();
+    let token = config.api_key.clone();
+    let Some(mut backend) = backend(config) else {
+        return Ok(not_configured());
+    };
+
+    let result = backend.create_contact(&contact).await;
+    keep_refreshed_token(&crm, &storage, &token, backend.config());
+    Ok(result?)
+}
+
+/// Add a note to a contact, redacted as text from `session_id`, the session it was written for
+#[tauri::command]
+pub async fn crm_sync_notes(
+    config: tauri::State<'_, ConfigState>,
+    crm: tauri::State<'_, CRMState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+    mut note: CRMNote,
+) -> CommandResult<CRMSyncResult> {
+    let cfg = config.lock().clone();
+    let mut redactor = Redactor::for_session_id(&cfg, &session_id).await;
+    note.content = redactor.redact(&note.content);
+    redactor.report();
+
+    let config = crm.lock().clone();
+    let token = config.api_key.clone();
+    let Some(mut backend) = backend(config) else {
+        return Ok(not_configured());
+    };
+
+    let result = backend.add_note(&note).await;
+    keep_refreshed_token(&crm, &storage, &token, backend.config());
+    Ok(result?)
+}
+
+/// Attach a session's full transcript and summary as files on a CRM record, one result
+/// per uploaded file; stops at the first upload that fails
+#[tauri::command]
+pub async fn crm_attach_transcript(
+    config: tauri::State<'_, ConfigState>,
+    crm: tauri::State<'_, CRMState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+    record_id: String,
+) -> CommandResult<Vec<CRMSyncResult>> {
+    let cfg = config.lock().clone();
+    let config = crm.lock().clone();
+    let token = config.api_key.clone();
+    let Some(mut backend) = backend(config) else {
+        return Ok(vec![not_configured()]);
+    };
+
+    let id = session_id.clone();
+    let mut session = storage.call(move |s| s.get_session(&id)).await?;
+    let mut redactor = Redactor::for_session_id(&cfg, &session_id).await;
+    redactor.redact_session(&mut session);
+    redactor.report();
+    let files = session_files(&session, backend.max_attachment_bytes());
+    if files.is_empty() {
+        return Err(VenkyError::Other(
+            "Session has no transcript or summary to attach".to_string(),
+        ));
+    }
+
+    let mut results = Vec::new();
+    let mut outcome = Ok(());
+    for file in &files {
+        match backend.attach_file(&record_id, file).await {
+            Ok(result) => {
+                let failed = !result.success;
+                results.push(result);
+                if failed {
+                    break;
+                }
+            }
+            Err(e) => {
+                outcome = Err(e);
+                break;
+            }
+        }
+    }
+
+    keep_refreshed_token(&crm, &storage, &token, backend.config());
+    outcome?;
+    Ok(results)
+}
+
+/// Push a session's action items to Salesforce as Tasks and log the meeting as an Event.
+/// The contact defaults to the first participant linked to a CRM contact.
+#[tauri::command]
+pub async fn crm_sync_action_items(
+    config: tauri::State<'_, ConfigState>,
+    crm: tauri::State<'_, CRMState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+    contact_id: Option<String>,
+    opportunity_id: Option<String>,
+) -> CommandResult<Vec<CRMSyncResult>> {
+    let cfg = config.lock().clone();
+    let config = crm.lock().clone();
+    if config.provider != CRMProvider::Salesforce {
+        return Err(VenkyError::Other(
+            "Action items can only be synced to Salesforce".to_string(),
+        ));
+    }
+
+    let id = session_id.clone();
+    let (mut session, mut items, participants) = storage
+        .call(move |s| {
+            Ok((
+                s.get_session(&id)?,
+                s.list_action_items(Some(&id), None)?,
+                s.get_session_participants(&id)?,
+            ))
+        })
+        .await?;
+    let mut redactor = Redactor::for_session_id(&cfg, &session_id).await;
+    redactor.redact_session(&mut session);
+    for item in &mut items {
+        item.description = redactor.redact(&item.description);
+        item.owner = item.owner.as_deref().map(|o| redactor.redact(o));
+    }
+    redactor.report();
+    let who_id = contact_id.or_else(|| participants.into_iter().find_map(|p| p.crm_contact_id));
+    let what_id = opportunity_id.filter(|id| !id.trim().is_empty());
+
+    let token = config.api_key.clone();
+    let mut salesforce = Salesforce { config };
+    let mut results = Vec::new();
+    let mut outcome = Ok(());
+    for item in &items {
+        match salesforce
+            .create_task(item, who_id.as_deref(), what_id.as_deref())
+            .await
+        {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                outcome = Err(e);
+                break;
+            }
+        }
+    }
+    if outcome.is_ok() {
+        match salesforce
+            .log_event(&session, who_id.as_deref(), what_id.as_deref())
+            .await
+        {
+            Ok(result) => results.push(result),
+            Err(e) => outcome = Err(e),
+        }
+    }
+
+    keep_refreshed_token(&crm, &storage, &token, &salesforce.config);
+    outcome?;
+    Ok(results)
+}
+
+/// Deals (HubSpot) or open opportunities (Salesforce) of a contact, to pick which one a
+/// call belongs to
+#[tauri::command]
+pub async fn crm_list_deals(
+    crm: tauri::State<'_, CRMState>,
+    storage: tauri::State<'_, StorageActor>,
+    contact_id: String,
+) -> CommandResult<Vec<CRMDeal>> {
+    let config = crm.lock().clone();
+    let token = config.api_key.clone();
+    let mut backend = backend(config)
+        .ok_or_else(|| VenkyError::Other("No CRM provider configured".to_string()))?;
+
+    let result = backend.list_deals(&contact_id).await;
+    keep_refreshed_token(&crm, &storage, &token, backend.config());
+    Ok(result?)
+}
+
+/// Log a session as a HubSpot Meeting engagement on the contact and, optionally, a deal.
+/// `outcome` is a HubSpot meeting outcome such as "COMPLETED" (the default) or "NO_SHOW".
+#[tauri::command]
+pub async fn crm_log_meeting(
+    config: tauri::State<'_, ConfigState>,
+    crm: tauri::State<'_, CRMState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+    contact_id: String,
+    deal_id: Option<String>,
+    outcome: Option<String>,
+) -> CommandResult<CRMSyncResult> {
+    let cfg = config.lock().clone();
+    let config = crm.lock().clone();
+    if config.provider != CRMProvider::HubSpot {
+        return Err(VenkyError::Other(
+            "Meetings can only be logged to HubSpot".to_string(),
+        ));
+    }
+    let id = session_id.clone();
+    let mut session = storage.call(move |s| s.get_session(&id)).await?;
+    let mut redactor = Redactor::for_session_id(&cfg, &session_id).await;
+    redactor.redact_session(&mut session);
+    redactor.report();
+    let outcome = outcome
+        .filter(|o| !o.trim().is_empty())
+        .map(|o| o.trim().to_uppercase())
+        .unwrap_or_else(|| "COMPLETED".to_string());
+
+    Ok(HubSpot { config }
+        .log_meeting(
+            &session,
+            &contact_id,
+            deal_id.as_deref().filter(|id| !id.trim().is_empty()),
+            &outcome,
+        )
+        .await?)
+}
+
+#[tauri::command]
+pub fn get_crm_providers() -> Vec<serde_json::Value> {
+    vec![
+        serde_json::json!({
+            "name": "Salesforce",
+            "id": "Salesforce",
+            "description": "Sync contacts and meeting notes to Salesforce CRM",
+            "requiresInstanceUrl": true
+        }),
+        serde_json::json!({
+            "name": "HubSpot",
+            "id": "HubSpot",
+            "description": "Sync contacts and meeting notes to HubSpot CRM",
+            "requiresInstanceUrl": false
+        }),
+        serde_json::json!({
+            "name": "Zoho CRM",
+            "id": "Zoho",
+            "description": "Sync contacts, notes and transcripts to Zoho CRM",
+            "requiresInstanceUrl": false
+        }),
+        serde_json::json!({
+            "name": "Dynamics 365",
+            "id": "Dynamics",
+            "description": "Sync contacts, notes and transcripts to Microsoft Dynamics 365",
+            "requiresInstanceUrl": true
+        }),
+        serde_json::json!({
+            "name": "Custom Webhook",
+            "id": "Webhook",
+            "description": "POST contacts and notes as JSON to your own endpoint (internal CRMs, Attio, Zapier)",
+            "requiresInstanceUrl": true
+        }),
+    ]
+}
@@ -0,0 +1,316 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::future::BoxFuture;
+use reqwest::{Client, Method, Response};
+use serde::Deserialize;
+
+use super::{error_result, send, AttachmentFile, CrmBackend, OAuthEndpoints};
+use crate::error::VenkyError;
+use crate::integrations::{CRMConfig, CRMContact, CRMDeal, CRMNote, CRMSyncResult};
+use crate::session::manager::Session;
+use crate::session::storage::ActionItem;
+
+const SALESFORCE_LOGIN_URL: &str = "https://login.salesforce.com";
+
+/// Scopes requested from the Salesforce connected app
+const SALESFORCE_SCOPES: &str = "api refresh_token";
+
+#[derive(Debug, Deserialize)]
+struct SalesforceCreateResponse {
+    id: Option<String>,
+    success: Option<bool>,
+}
+
+fn soql_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+pub struct Salesforce {
+    pub config: CRMConfig,
+}
+
+impl Salesforce {
+    /// Call the REST API under /services/data
+    async fn request(
+        &mut self,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<Response, VenkyError> {
+        send(self, |client: &Client, config: &CRMConfig| {
+            let url = format!("{}/services/data/v59.0/{}", config.instance_url, path);
+            let request = client
+                .request(method.clone(), url)
+                .header("Authorization", format!("Bearer {}", config.api_key));
+            match body {
+                Some(body) => request.json(body),
+                None => request,
+            }
+        })
+        .await
+    }
+
+    /// Insert one record, reporting `message` when it succeeds
+    async fn insert(
+        &mut self,
+        sobject: &str,
+        body: &serde_json::Value,
+        message: String,
+    ) -> Result<CRMSyncResult, VenkyError> {
+        let response = self
+            .request(Method::POST, &format!("sobjects/{}/", sobject), Some(body))
+            .await?;
+        if !response.status().is_success() {
+            return Ok(error_result(self.name(), response).await);
+        }
+
+        let result: SalesforceCreateResponse = response
+            .json()
+            .await
+            .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+
+        Ok(CRMSyncResult {
+            success: result.success.unwrap_or(false),
+            message,
+            record_id: result.id,
+        })
+    }
+
+    /// Records returned by a SOQL query
+    async fn query(&mut self, soql: &str) -> Result<Vec<serde_json::Value>, VenkyError> {
+        let query = reqwest::Url::parse_with_params("http://localhost/", &[("q", soql)])
+            .map_err(|e| VenkyError::Other(format!("Invalid query: {}", e)))?;
+        let path = format!("query/?{}", query.query().unwrap_or_default());
+        let response = self.request(Method::GET, &path, None).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(VenkyError::Provider(format!(
+                "Salesforce error ({}): {}",
+                status, body
+            )));
+        }
+        let mut result: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+        match result["records"].take() {
+            serde_json::Value::Array(records) => Ok(records),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Active Salesforce user whose name matches an action item owner
+    async fn find_user(&mut self, name: &str) -> Result<Option<String>, VenkyError> {
+        let soql = format!(
+            "SELECT Id FROM User WHERE Name = '{}' AND IsActive = true LIMIT 1",
+            soql_escape(name)
+        );
+        let records = self.query(&soql).await?;
+        Ok(records
+            .first()
+            .and_then(|r| r["Id"].as_str())
+            .map(str::to_string))
+    }
+
+    /// Create a Task for an action item, assigned to the matching user when the owner is one.
+    /// `who_id` is the contact and `what_id` the opportunity the task relates to.
+    pub async fn create_task(
+        &mut self,
+        item: &ActionItem,
+        who_id: Option<&str>,
+        what_id: Option<&str>,
+    ) -> Result<CRMSyncResult, VenkyError> {
+        let owner_id = match item.owner.as_deref() {
+            Some(owner) => self.find_user(owner).await?,
+            None => None,
+        };
+        // ActivityDate only takes a date; anything vaguer stays in the description
+        let due_date = item
+            .due_date
+            .as_deref()
+            .filter(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok());
+
+        let mut description = item.description.clone();
+        if let (Some(owner), None) = (item.owner.as_deref(), owner_id.as_deref()) {
+            description.push_str(&format!("\nOwner: {}", owner));
+        }
+        if let (Some(due), None) = (item.due_date.as_deref(), due_date) {
+            description.push_str(&format!("\nDue: {}", due));
+        }
+
+        let mut body = serde_json::json!({
+            "Subject": item.description.chars().take(255).collect::<String>(),
+            "Description": description,
+            "Status": if item.status == "done" { "Completed" } else { "Not Started" },
+            "Priority": "Normal",
+        });
+        if let Some(owner_id) = owner_id {
+            body["OwnerId"] = owner_id.into();
+        }
+        if let Some(due_date) = due_date {
+            body["ActivityDate"] = due_date.into();
+        }
+        if let Some(who_id) = who_id {
+            body["WhoId"] = who_id.into();
+        }
+        if let Some(what_id) = what_id {
+            body["WhatId"] = what_id.into();
+        }
+
+        self.insert("Task", &body, "Task created in Salesforce".to_string())
+            .await
+    }
+
+    /// Log the session as a completed Event covering the time it ran
+    pub async fn log_event(
+        &mut self,
+        session: &Session,
+        who_id: Option<&str>,
+        what_id: Option<&str>,
+    ) -> Result<CRMSyncResult, VenkyError> {
+        let mut body = serde_json::json!({
+            "Subject": session.title.chars().take(255).collect::<String>(),
+            "StartDateTime": session.start_time,
+            "EndDateTime": session.end_time.as_deref().unwrap_or(&session.start_time),
+            "Description": session.summary.as_deref().unwrap_or_default(),
+        });
+        if let Some(who_id) = who_id {
+            body["WhoId"] = who_id.into();
+        }
+        if let Some(what_id) = what_id {
+            body["WhatId"] = what_id.into();
+        }
+
+        self.insert("Event", &body, "Event created in Salesforce".to_string())
+            .await
+    }
+}
+
+impl CrmBackend for Salesforce {
+    fn name(&self) -> &'static str {
+        "Salesforce"
+    }
+
+    fn config(&self) -> &CRMConfig {
+        &self.config
+    }
+
+    fn config_mut(&mut self) -> &mut CRMConfig {
+        &mut self.config
+    }
+
+    fn oauth(&self) -> Option<OAuthEndpoints> {
+        let login = match self.config.login_url.trim() {
+            "" => SALESFORCE_LOGIN_URL,
+            url => url.trim_end_matches('/'),
+        };
+        Some(OAuthEndpoints {
+            authorize_url: format!("{}/services/oauth2/authorize", login),
+            token_url: format!("{}/services/oauth2/token", login),
+            scopes: SALESFORCE_SCOPES.to_string(),
+            extra_params: Vec::new(),
+        })
+    }
+
+    fn find_contact<'a>(
+        &'a mut self,
+        email: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>, VenkyError>> {
+        Box::pin(async move {
+            let soql = format!(
+                "SELECT Id FROM Contact WHERE Email = '{}' LIMIT 1",
+                soql_escape(email)
+            );
+            let records = self.query(&soql).await?;
+            Ok(records
+                .first()
+                .and_then(|r| r["Id"].as_str())
+                .map(str::to_string))
+        })
+    }
+
+    fn create_contact<'a>(
+        &'a mut self,
+        contact: &'a CRMContact,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "FirstName": contact.first_name,
+                "LastName": contact.last_name,
+                "Email": contact.email,
+                "Phone": contact.phone,
+                "Company": contact.company,
+                "Title": contact.title,
+            });
+            self.insert(
+                "Contact",
+                &body,
+                "Contact created in Salesforce".to_string(),
+            )
+            .await
+        })
+    }
+
+    fn add_note<'a>(
+        &'a mut self,
+        note: &'a CRMNote,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "ParentId": note.contact_id,
+                "Title": format!("Meeting Notes - {}", note.timestamp),
+                "Body": note.content,
+            });
+            self.insert("Note", &body, "Note added to Salesforce".to_string())
+                .await
+        })
+    }
+
+    /// Upload a file as a ContentVersion; publishing it to the record links it there
+    fn attach_file<'a>(
+        &'a mut self,
+        record_id: &'a str,
+        file: &'a AttachmentFile,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "Title": file.title,
+                "PathOnClient": file.file_name,
+                "VersionData": STANDARD.encode(file.content.as_bytes()),
+                "FirstPublishLocationId": record_id,
+            });
+            let message = format!("{} attached in Salesforce", file.title);
+            self.insert("ContentVersion", &body, message).await
+        })
+    }
+
+    /// Open opportunities the contact has a role on
+    fn list_deals<'a>(
+        &'a mut self,
+        contact_id: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<CRMDeal>, VenkyError>> {
+        Box::pin(async move {
+            let soql = format!(
+                "SELECT Opportunity.Id, Opportunity.Name, Opportunity.StageName, Opportunity.Amount, \
+                 Opportunity.CloseDate FROM OpportunityContactRole \
+                 WHERE ContactId = '{}' AND Opportunity.IsClosed = false",
+                soql_escape(contact_id)
+            );
+            let records = self.query(&soql).await?;
+            Ok(records
+                .iter()
+                .filter_map(|r| {
+                    let opp = &r["Opportunity"];
+                    Some(CRMDeal {
+                        id: opp["Id"].as_str()?.to_string(),
+                        name: opp["Name"].as_str().unwrap_or_default().to_string(),
+                        stage: opp["StageName"].as_str().map(str::to_string),
+                        amount: opp["Amount"].as_f64(),
+                        close_date: opp["CloseDate"].as_str().map(str::to_string),
+                    })
+                })
+                .collect())
+        })
+    }
+}
@@ -0,0 +1,148 @@
+use futures::future::BoxFuture;
+use reqwest::Client;
+
+use super::{error_result, send, AttachmentFile, CrmBackend};
+use crate::error::VenkyError;
+use crate::integrations::template::{fill, parse};
+use crate::integrations::{CRMConfig, CRMContact, CRMNote, CRMSyncResult, CRMWebhookConfig};
+
+/// The request body for one event: the filled template, or the event and record as-is
+fn payload(
+    template: &str,
+    event: &str,
+    record: serde_json::Value,
+) -> Result<serde_json::Value, VenkyError> {
+    if template.trim().is_empty() {
+        let mut body = serde_json::json!({ "event": event });
+        body[event] = record;
+        return Ok(body);
+    }
+    let template = parse(template, event)?;
+    let mut fields = record;
+    fields["event"] = event.into();
+    Ok(fill(&template, &fields))
+}
+
+/// Check that both templates are valid JSON before they are saved
+pub fn validate(config: &CRMWebhookConfig) -> Result<(), VenkyError> {
+    for (event, template) in [
+        ("contact", &config.contact_template),
+        ("note", &config.note_template),
+    ] {
+        if !template.trim().is_empty() {
+            parse(template, event)?;
+        }
+    }
+    Ok(())
+}
+
+/// POSTs contacts, notes and attachments as JSON to a user-configured endpoint
+pub struct Webhook {
+    pub config: CRMConfig,
+}
+
+impl Webhook {
+    async fn post(
+        &mut self,
+        body: serde_json::Value,
+        message: String,
+    ) -> Result<CRMSyncResult, VenkyError> {
+        if self.config.instance_url.trim().is_empty() {
+            return Err(VenkyError::Other("No webhook URL configured".to_string()));
+        }
+        let response = send(self, |client: &Client, config: &CRMConfig| {
+            let request = client.post(config.instance_url.trim()).json(&body);
+            match (config.webhook.auth_header.trim(), config.api_key.as_str()) {
+                ("", _) | (_, "") => request,
+                (header, value) => request.header(header, value),
+            }
+        })
+        .await?;
+        if !response.status().is_success() {
+            return Ok(error_result(self.name(), response).await);
+        }
+
+        // Endpoints that answer with an id get it recorded; anything else is just a success
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        let record_id = match &body["id"] {
+            serde_json::Value::String(id) => Some(id.clone()),
+            serde_json::Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        };
+        Ok(CRMSyncResult {
+            success: true,
+            message,
+            record_id,
+        })
+    }
+}
+
+impl CrmBackend for Webhook {
+    fn name(&self) -> &'static str {
+        "Webhook"
+    }
+
+    fn config(&self) -> &CRMConfig {
+        &self.config
+    }
+
+    fn config_mut(&mut self) -> &mut CRMConfig {
+        &mut self.config
+    }
+
+    fn create_contact<'a>(
+        &'a mut self,
+        contact: &'a CRMContact,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let record =
+                serde_json::to_value(contact).map_err(|e| VenkyError::Other(e.to_string()))?;
+            let body = payload(&self.config.webhook.contact_template, "contact", record)?;
+            self.post(body, "Contact sent to webhook".to_string()).await
+        })
+    }
+
+    fn add_note<'a>(
+        &'a mut self,
+        note: &'a CRMNote,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let record =
+                serde_json::to_value(note).map_err(|e| VenkyError::Other(e.to_string()))?;
+            let body = payload(&self.config.webhook.note_template, "note", record)?;
+            self.post(body, "Note sent to webhook".to_string()).await
+        })
+    }
+
+    /// Webhooks can't be searched, so send a test event the endpoint can ignore
+    fn check<'a>(&'a mut self) -> BoxFuture<'a, Result<(), VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({ "event": "test" });
+            let result = self.post(body, "Test event sent".to_string()).await?;
+            if result.success {
+                Ok(())
+            } else {
+                Err(VenkyError::Provider(result.message))
+            }
+        })
+    }
+
+    fn attach_file<'a>(
+        &'a mut self,
+        record_id: &'a str,
+        file: &'a AttachmentFile,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "event": "attachment",
+                "record_id": record_id,
+                "title": file.title,
+                "file_name": file.file_name,
+                "mime_type": file.mime,
+                "content": file.content,
+            });
+            self.post(body, format!("{} sent to webhook", file.title))
+                .await
+        })
+    }
+}
@@ -0,0 +1,202 @@
+use futures::future::BoxFuture;
+use reqwest::{Client, Response, StatusCode};
+
+use super::{error_result, send, AttachmentFile, CrmBackend, OAuthEndpoints};
+use crate::error::VenkyError;
+use crate::integrations::{CRMConfig, CRMContact, CRMNote, CRMSyncResult};
+
+/// US data center; EU, India, Australia etc. accounts sign in at their own host
+const ZOHO_ACCOUNTS_URL: &str = "https://accounts.zoho.com";
+const ZOHO_API_URL: &str = "https://www.zohoapis.com";
+
+const ZOHO_SCOPES: &str = "ZohoCRM.modules.ALL,ZohoCRM.Files.CREATE";
+
+pub struct Zoho {
+    pub config: CRMConfig,
+}
+
+impl Zoho {
+    fn api_url(config: &CRMConfig, path: &str) -> String {
+        let domain = match config.instance_url.trim() {
+            "" => ZOHO_API_URL,
+            url => url.trim_end_matches('/'),
+        };
+        format!("{}/crm/v2/{}", domain, path)
+    }
+
+    async fn post(&mut self, path: &str, body: &serde_json::Value) -> Result<Response, VenkyError> {
+        send(self, |client: &Client, config: &CRMConfig| {
+            client
+                .post(Self::api_url(config, path))
+                .header(
+                    "Authorization",
+                    format!("Zoho-oauthtoken {}", config.api_key),
+                )
+                .json(body)
+        })
+        .await
+    }
+
+    /// Zoho answers 2xx with per-record results; the first record says whether it worked
+    async fn record_result(
+        &self,
+        response: Response,
+        message: String,
+    ) -> Result<CRMSyncResult, VenkyError> {
+        if !response.status().is_success() {
+            return Ok(error_result(self.name(), response).await);
+        }
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+        let record = &body["data"][0];
+
+        if record["status"].as_str() != Some("success") {
+            return Ok(CRMSyncResult {
+                success: false,
+                message: format!(
+                    "Zoho error ({}): {}",
+                    record["code"].as_str().unwrap_or("unknown"),
+                    record["message"].as_str().unwrap_or_default()
+                ),
+                record_id: None,
+            });
+        }
+        Ok(CRMSyncResult {
+            success: true,
+            message,
+            record_id: record["details"]["id"].as_str().map(str::to_string),
+        })
+    }
+}
+
+impl CrmBackend for Zoho {
+    fn name(&self) -> &'static str {
+        "Zoho"
+    }
+
+    fn config(&self) -> &CRMConfig {
+        &self.config
+    }
+
+    fn config_mut(&mut self) -> &mut CRMConfig {
+        &mut self.config
+    }
+
+    fn oauth(&self) -> Option<OAuthEndpoints> {
+        let accounts = match self.config.login_url.trim() {
+            "" => ZOHO_ACCOUNTS_URL,
+            url => url.trim_end_matches('/'),
+        };
+        Some(OAuthEndpoints {
+            authorize_url: format!("{}/oauth/v2/auth", accounts),
+            token_url: format!("{}/oauth/v2/token", accounts),
+            scopes: ZOHO_SCOPES.to_string(),
+            extra_params: vec![("access_type", "offline"), ("prompt", "consent")],
+        })
+    }
+
+    fn find_contact<'a>(
+        &'a mut self,
+        email: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>, VenkyError>> {
+        Box::pin(async move {
+            let response = send(self, |client: &Client, config: &CRMConfig| {
+                client
+                    .get(Self::api_url(config, "Contacts/search"))
+                    .query(&[("email", email)])
+                    .header(
+                        "Authorization",
+                        format!("Zoho-oauthtoken {}", config.api_key),
+                    )
+            })
+            .await?;
+            // 204 when nothing matches
+            if response.status() == StatusCode::NO_CONTENT {
+                return Ok(None);
+            }
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(VenkyError::Provider(format!(
+                    "Zoho error ({}): {}",
+                    status, body
+                )));
+            }
+            let found: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+            Ok(found["data"][0]["id"].as_str().map(str::to_string))
+        })
+    }
+
+    /// Upsert on email, so syncing a known contact updates it instead of duplicating it
+    fn create_contact<'a>(
+        &'a mut self,
+        contact: &'a CRMContact,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            // Account_Name is a lookup to an Account record, so the company isn't sent
+            let body = serde_json::json!({
+                "data": [{
+                    "First_Name": contact.first_name,
+                    "Last_Name": contact.last_name,
+                    "Email": contact.email,
+                    "Phone": contact.phone,
+                    "Title": contact.title,
+                }],
+                "duplicate_check_fields": ["Email"],
+            });
+            let response = self.post("Contacts/upsert", &body).await?;
+            self.record_result(response, "Contact synced to Zoho".to_string())
+                .await
+        })
+    }
+
+    fn add_note<'a>(
+        &'a mut self,
+        note: &'a CRMNote,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "data": [{
+                    "Note_Title": format!("Meeting Notes - {}", note.timestamp),
+                    "Note_Content": note.content,
+                    "Parent_Id": note.contact_id,
+                    "se_module": "Contacts",
+                }]
+            });
+            let response = self.post("Notes", &body).await?;
+            self.record_result(response, "Note added to Zoho".to_string())
+                .await
+        })
+    }
+
+    fn attach_file<'a>(
+        &'a mut self,
+        record_id: &'a str,
+        file: &'a AttachmentFile,
+    ) -> BoxFuture<'a, Result<CRMSyncResult, VenkyError>> {
+        Box::pin(async move {
+            let path = format!("Contacts/{}/Attachments", record_id);
+            let response = send(self, |client: &Client, config: &CRMConfig| {
+                let part = reqwest::multipart::Part::bytes(file.content.clone().into_bytes())
+                    .file_name(file.file_name.clone())
+                    .mime_str(file.mime)
+                    .expect("attachment MIME types are valid");
+                client
+                    .post(Self::api_url(config, &path))
+                    .header(
+                        "Authorization",
+                        format!("Zoho-oauthtoken {}", config.api_key),
+                    )
+                    .multipart(reqwest::multipart::Form::new().part("file", part))
+            })
+            .await?;
+            self.record_result(response, format!("{} attached in Zoho", file.title))
+                .await
+        })
+    }
+}
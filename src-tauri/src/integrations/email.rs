@@ -0,0 +1,492 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use super::keychain;
+use super::oauth::{self, Connection, OAuthClient, TokenResponse};
+use crate::error::{CommandResult, VenkyError};
+use crate::session::storage::Storage;
+use crate::session::storage_actor::StorageActor;
+
+type EmailState = Arc<Mutex<EmailConfig>>;
+
+/// Settings key the email account is stored under; the SMTP password lives in the keychain
+const SETTINGS_KEY: &str = "email";
+const SMTP_KEYCHAIN_ACCOUNT: &str = "smtp-password";
+
+const GOOGLE_AUTHORIZE_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const GMAIL_SCOPES: &str = "https://www.googleapis.com/auth/gmail.send email";
+const OUTLOOK_AUTHORIZE_URL: &str =
+    "https://login.microsoftonline.com/common/oauth2/v2.0/authorize";
+const OUTLOOK_TOKEN_URL: &str = "https://login.microsoftonline.com/common/oauth2/v2.0/token";
+const OUTLOOK_SCOPES: &str = "offline_access User.Read Mail.Send";
+/// Held while the sign-in is refreshed, so refreshes take turns
+static REFRESHING: Lazy<tokio::sync::Mutex<()>> = Lazy::new(Default::default);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum EmailProvider {
+    Gmail,
+    Outlook,
+    Smtp,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS, usually port 587
+    StartTls,
+    /// TLS from the start, usually port 465
+    Tls,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    pub provider: EmailProvider,
+    /// Sender address; the signed-in account for Gmail and Outlook
+    pub from_address: String,
+    pub from_name: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    #[serde(skip_serializing)]
+    pub access_token: String,
+    #[serde(skip_serializing)]
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<String>,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_security: SmtpSecurity,
+    pub smtp_username: String,
+    /// Kept in the keychain; empty when saving keeps the stored one
+    #[serde(skip_serializing)]
+    pub smtp_password: String,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            provider: EmailProvider::None,
+            from_address: String::new(),
+            from_name: String::new(),
+            client_id: String::new(),
+            client_secret: None,
+            access_token: String::new(),
+            refresh_token: None,
+            expires_at: None,
+            smtp_host: String::new(),
+            smtp_port: 587,
+            smtp_security: SmtpSecurity::StartTls,
+            smtp_username: String::new(),
+            smtp_password: String::new(),
+        }
+    }
+}
+
+/// `EmailConfig` as saved in the settings table, tokens included
+#[derive(Serialize, Deserialize)]
+struct StoredEmailConfig {
+    #[serde(flatten)]
+    config: EmailConfig,
+    #[serde(default)]
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+impl EmailConfig {
+    pub fn load(storage: &Storage) -> Self {
+        let stored = storage
+            .get_setting(SETTINGS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str::<StoredEmailConfig>(&json).ok());
+        let Some(stored) = stored else {
+            return Self::default();
+        };
+        let smtp_password = keychain::get_secret(SMTP_KEYCHAIN_ACCOUNT)
+            .unwrap_or_else(|e| {
+                log::error!("{}", e);
+                None
+            })
+            .unwrap_or_default();
+        Self {
+            access_token: stored.access_token,
+            refresh_token: stored.refresh_token,
+            smtp_password,
+            ..stored.config
+        }
+    }
+
+    pub fn save(&self, storage: &Storage) -> Result<(), VenkyError> {
+        keychain::set_secret(SMTP_KEYCHAIN_ACCOUNT, Some(&self.smtp_password))?;
+        let json = serde_json::to_string(&StoredEmailConfig {
+            config: self.clone(),
+            access_token: self.access_token.clone(),
+            refresh_token: self.refresh_token.clone(),
+        })
+        .map_err(|e| VenkyError::Storage(format!("Failed to save email settings: {}", e)))?;
+        storage.set_setting(SETTINGS_KEY, Some(&json))
+    }
+}
+
+impl Connection for EmailConfig {
+    const NOT_CONNECTED: &'static str = "No email account connected";
+    const EXPIRED: &'static str = "Email session expired; reconnect the account";
+
+    fn is_connected(&self) -> bool {
+        !self.access_token.is_empty()
+    }
+
+    fn oauth_client(&self) -> OAuthClient<'_> {
+        match self.provider {
+            EmailProvider::Gmail => OAuthClient {
+                authorize_url: GOOGLE_AUTHORIZE_URL,
+                token_url: GOOGLE_TOKEN_URL,
+                client_id: &self.client_id,
+                client_secret: self.client_secret.as_deref(),
+                scopes: GMAIL_SCOPES,
+                extra_params: &[("access_type", "offline"), ("prompt", "consent")],
+                redirect_uri: None,
+            },
+            _ => OAuthClient {
+                authorize_url: OUTLOOK_AUTHORIZE_URL,
+                token_url: OUTLOOK_TOKEN_URL,
+                client_id: &self.client_id,
+                client_secret: self.client_secret.as_deref(),
+                scopes: OUTLOOK_SCOPES,
+                extra_params: &[],
+                redirect_uri: None,
+            },
+        }
+    }
+
+    fn expires_at(&self) -> Option<&str> {
+        self.expires_at.as_deref()
+    }
+
+    fn refresh_token(&self) -> Option<&str> {
+        self.refresh_token.as_deref()
+    }
+
+    fn set_tokens(&mut self, tokens: TokenResponse) {
+        self.expires_at = tokens.expires_at();
+        self.access_token = tokens.access_token;
+        if tokens.refresh_token.is_some() {
+            self.refresh_token = tokens.refresh_token;
+        }
+    }
+}
+
+/// The message to send; usually the generated recap, edited by the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailDraft {
+    pub subject: String,
+    /// Plain text
+    pub body: String,
+}
+
+/// A valid access token, refreshing (and persisting) it first when it has expired
+async fn access_token(app: &AppHandle) -> Result<EmailConfig, VenkyError> {
+    oauth::fresh_connection(
+        &REFRESHING,
+        &app.state::<EmailState>(),
+        &app.state::<StorageActor>(),
+        EmailConfig::save,
+    )
+    .await
+}
+
+fn mailbox(address: &str, name: Option<&str>) -> Result<Mailbox, VenkyError> {
+    let email = address
+        .trim()
+        .parse()
+        .map_err(|e| VenkyError::Other(format!("Invalid email address {}: {}", address, e)))?;
+    Ok(Mailbox::new(
+        name.map(str::to_string).filter(|n| !n.trim().is_empty()),
+        email,
+    ))
+}
+
+/// The RFC 5322 message, as sent over SMTP and uploaded to Gmail
+fn build_message(
+    config: &EmailConfig,
+    recipients: &[String],
+    draft: &EmailDraft,
+) -> Result<Message, VenkyError> {
+    let mut builder = Message::builder()
+        .from(mailbox(&config.from_address, Some(&config.from_name))?)
+        .subject(&draft.subject);
+    for recipient in recipients {
+        builder = builder.to(mailbox(recipient, None)?);
+    }
+    builder
+        .header(ContentType::TEXT_PLAIN)
+        .body(draft.body.clone())
+        .map_err(|e| VenkyError::Other(format!("Failed to build email: {}", e)))
+}
+
+async fn send_gmail(
+    config: &EmailConfig,
+    recipients: &[String],
+    draft: &EmailDraft,
+) -> Result<(), VenkyError> {
+    let raw = URL_SAFE_NO_PAD.encode(build_message(config, recipients, draft)?.formatted());
+    let response = crate::http::client()
+        .post("https://gmail.googleapis.com/gmail/v1/users/me/messages/send")
+        .bearer_auth(&config.access_token)
+        .json(&serde_json::json!({ "raw": raw }))
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("Gmail request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "Gmail error ({}): {}",
+            status, body
+        )));
+    }
+    Ok(())
+}
+
+async fn send_outlook(
+    config: &EmailConfig,
+    recipients: &[String],
+    draft: &EmailDraft,
+) -> Result<(), VenkyError> {
+    let to: Vec<serde_json::Value> = recipients
+        .iter()
+        .map(|r| serde_json::json!({ "emailAddress": { "address": r.trim() } }))
+        .collect();
+    let body = serde_json::json!({
+        "message": {
+            "subject": draft.subject,
+            "body": { "contentType": "Text", "content": draft.body },
+            "toRecipients": to,
+        },
+        "saveToSentItems": true,
+    });
+    let response = crate::http::client()
+        .post("https://graph.microsoft.com/v1.0/me/sendMail")
+        .bearer_auth(&config.access_token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("Outlook request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "Outlook error ({}): {}",
+            status, body
+        )));
+    }
+    Ok(())
+}
+
+async fn send_smtp(
+    config: &EmailConfig,
+    recipients: &[String],
+    draft: &EmailDraft,
+) -> Result<(), VenkyError> {
+    if config.smtp_host.trim().is_empty() {
+        return Err(VenkyError::Other("No SMTP server configured".to_string()));
+    }
+    let host = config.smtp_host.trim();
+    let builder = match config.smtp_security {
+        SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+    }
+    .map_err(|e| VenkyError::Other(format!("Invalid SMTP server {}: {}", host, e)))?;
+
+    let mut builder = builder.port(config.smtp_port);
+    if !config.smtp_username.is_empty() {
+        builder = builder.credentials(Credentials::new(
+            config.smtp_username.clone(),
+            config.smtp_password.clone(),
+        ));
+    }
+    builder
+        .build()
+        .send(build_message(config, recipients, draft)?)
+        .await
+        .map_err(|e| VenkyError::Network(format!("SMTP send failed: {}", e)))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_email_config(email: tauri::State<'_, EmailState>) -> EmailConfig {
+    email.lock().clone()
+}
+
+/// Save SMTP settings or the sender name; a signed-in Gmail/Outlook account keeps its tokens
+#[tauri::command]
+pub async fn update_email_config(
+    email: tauri::State<'_, EmailState>,
+    storage: tauri::State<'_, StorageActor>,
+    mut config: EmailConfig,
+) -> CommandResult<()> {
+    {
+        let current = email.lock();
+        if config.provider == current.provider {
+            config.access_token = current.access_token.clone();
+            config.refresh_token = current.refresh_token.clone();
+            config.expires_at = current.expires_at.clone();
+        }
+        if config.smtp_password.is_empty() {
+            config.smtp_password = current.smtp_password.clone();
+        }
+    }
+    let row = config.clone();
+    storage.call(move |s| row.save(s)).await?;
+    *email.lock() = config;
+    Ok(())
+}
+
+/// Sign in to Gmail or Outlook in the browser so mail can be sent as that account
+#[tauri::command]
+pub async fn connect_email(
+    app: AppHandle,
+    provider: EmailProvider,
+    client_id: String,
+    client_secret: Option<String>,
+) -> CommandResult<EmailConfig> {
+    if !matches!(provider, EmailProvider::Gmail | EmailProvider::Outlook) {
+        return Err(VenkyError::Other("Choose Gmail or Outlook".to_string()));
+    }
+    if client_id.trim().is_empty() {
+        return Err(VenkyError::Other(
+            "An OAuth client ID is required".to_string(),
+        ));
+    }
+
+    let current = app.state::<EmailState>().lock().clone();
+    let mut config = EmailConfig {
+        provider,
+        client_id: client_id.trim().to_string(),
+        client_secret: client_secret.filter(|s| !s.trim().is_empty()),
+        from_name: current.from_name,
+        ..EmailConfig::default()
+    };
+    let tokens = oauth::authorize(&app, &config.oauth_client()).await?;
+    config.access_token = tokens.access_token.clone();
+    config.refresh_token = tokens.refresh_token.clone();
+    config.expires_at = tokens.expires_at();
+    config.from_address = account_email(&config).await.unwrap_or_default();
+
+    let row = config.clone();
+    app.state::<StorageActor>()
+        .call(move |s| row.save(s))
+        .await?;
+    *app.state::<EmailState>().lock() = config.clone();
+    Ok(config)
+}
+
+/// Address of the signed-in account
+async fn account_email(config: &EmailConfig) -> Option<String> {
+    let url = match config.provider {
+        EmailProvider::Gmail => "https://gmail.googleapis.com/gmail/v1/users/me/profile",
+        _ => "https://graph.microsoft.com/v1.0/me",
+    };
+    let response = crate::http::client()
+        .get(url)
+        .bearer_auth(&config.access_token)
+        .send()
+        .await
+        .ok()?;
+    let me: serde_json::Value = response.json().await.ok()?;
+    ["emailAddress", "mail", "userPrincipalName"]
+        .iter()
+        .find_map(|field| me[field].as_str())
+        .map(str::to_string)
+}
+
+#[tauri::command]
+pub async fn disconnect_email(
+    email: tauri::State<'_, EmailState>,
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<()> {
+    let config = {
+        let mut config = email.lock();
+        *config = EmailConfig {
+            from_name: config.from_name.clone(),
+            ..EmailConfig::default()
+        };
+        config.clone()
+    };
+    Ok(storage.call(move |s| config.save(s)).await?)
+}
+
+/// Send a session's follow-up email; with no recipients it goes to the participants with an
+/// email address. Returns who it was sent to.
+#[tauri::command]
+pub async fn send_followup_email(
+    app: AppHandle,
+    session_id: String,
+    recipients: Vec<String>,
+    draft: EmailDraft,
+) -> CommandResult<Vec<String>> {
+    let storage = app.state::<StorageActor>();
+    let recipients: Vec<String> = recipients
+        .into_iter()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .collect();
+    let id = session_id.clone();
+    let (recipients, draft) = storage
+        .call(move |s| {
+            let mut recipients = recipients;
+            let mut draft = draft;
+            if recipients.is_empty() {
+                recipients = s
+                    .get_session_participants(&id)?
+                    .into_iter()
+                    .filter_map(|p| p.email)
+                    .filter(|e| !e.trim().is_empty())
+                    .collect();
+            }
+            if draft.subject.trim().is_empty() {
+                draft.subject = format!("Follow-up: {}", s.get_session(&id)?.title);
+            }
+            Ok((recipients, draft))
+        })
+        .await?;
+    if recipients.is_empty() {
+        return Err(VenkyError::Other(
+            "No recipients with an email address".to_string(),
+        ));
+    }
+    if draft.body.trim().is_empty() {
+        return Err(VenkyError::Other("The email is empty".to_string()));
+    }
+
+    let provider = app.state::<EmailState>().lock().provider.clone();
+    match provider {
+        EmailProvider::Gmail => send_gmail(&access_token(&app).await?, &recipients, &draft).await?,
+        EmailProvider::Outlook => {
+            send_outlook(&access_token(&app).await?, &recipients, &draft).await?
+        }
+        EmailProvider::Smtp => {
+            let config = app.state::<EmailState>().lock().clone();
+            send_smtp(&config, &recipients, &draft).await?
+        }
+        EmailProvider::None => {
+            return Err(VenkyError::Other("No email account configured".to_string()))
+        }
+    }
+    log::info!(
+        "Sent follow-up for session {} to {} recipient(s)",
+        session_id,
+        recipients.len()
+    );
+    Ok(recipients)
+}
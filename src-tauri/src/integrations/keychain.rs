@@ -0,0 +1,38 @@
+use crate::error::VenkyError;
+
+/// Service name secrets are filed under in the OS keychain
+const SERVICE: &str = "VenkyAI";
+
+fn entry(account: &str) -> Result<keyring::Entry, VenkyError> {
+    keyring::Entry::new(SERVICE, account)
+        .map_err(|e| VenkyError::Storage(format!("Keychain unavailable: {}", e)))
+}
+
+/// Read a secret; None when nothing is stored for `account`
+pub fn get_secret(account: &str) -> Result<Option<String>, VenkyError> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(VenkyError::Storage(format!(
+            "Failed to read {} from the keychain: {}",
+            account, e
+        ))),
+    }
+}
+
+/// Store a secret, or remove it when `secret` is None or empty
+pub fn set_secret(account: &str, secret: Option<&str>) -> Result<(), VenkyError> {
+    let entry = entry(account)?;
+    match secret.filter(|s| !s.is_empty()) {
+        Some(secret) => entry.set_password(secret).map_err(|e| {
+            VenkyError::Storage(format!("Failed to save {} to the keychain: {}", account, e))
+        }),
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(VenkyError::Storage(format!(
+                "Failed to remove {} from the keychain: {}",
+                account, e
+            ))),
+        },
+    }
+}
@@ -1,18 +1,77 @@
+pub mod calendar;
 pub mod crm;
+pub mod email;
+pub mod keychain;
+pub mod notify;
+pub mod oauth;
+pub mod sync;
+pub mod template;
+pub mod tickets;
+pub mod webhooks;
+pub mod zoom;
 
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Instant;
+
+use crate::error::VenkyError;
+use crate::session::storage::Storage;
+
+/// Settings key for the CRM provider and instance URL; the API key lives in the keychain
+const CRM_SETTINGS_KEY: &str = "crm";
+/// Keychain account holding the CRM API key
+const CRM_KEYCHAIN_ACCOUNT: &str = "crm-api-key";
+/// Keychain account holding the CRM OAuth refresh token
+const CRM_REFRESH_KEYCHAIN_ACCOUNT: &str = "crm-refresh-token";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CRMConfig {
     pub provider: CRMProvider,
+    /// API key, the OAuth access token, or the webhook's auth header value
     pub api_key: String,
+    /// Salesforce/Dynamics org URL, Zoho API domain or webhook URL
     pub instance_url: String,
+    /// OAuth client (Salesforce connected app, Zoho client, Entra app registration)
+    #[serde(default)]
+    pub client_id: String,
+    /// OAuth sign-in host: a Salesforce sandbox or My Domain, Zoho data center or Entra tenant
+    #[serde(default)]
+    pub login_url: String,
+    /// Never sent to the UI; kept in the keychain
+    #[serde(default, skip_serializing)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub webhook: CRMWebhookConfig,
+}
+
+/// Request shape for the custom webhook provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CRMWebhookConfig {
+    /// Header the API key is sent in, e.g. "Authorization" or "X-Api-Key"
+    pub auth_header: String,
+    /// JSON bodies with `{{field}}` placeholders; empty sends the record as-is
+    pub contact_template: String,
+    pub note_template: String,
+}
+
+impl Default for CRMWebhookConfig {
+    fn default() -> Self {
+        Self {
+            auth_header: "Authorization".to_string(),
+            contact_template: String::new(),
+            note_template: String::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CRMProvider {
     Salesforce,
     HubSpot,
+    Zoho,
+    Dynamics,
+    Webhook,
     None,
 }
 
@@ -22,10 +81,74 @@ impl Default for CRMConfig {
             provider: CRMProvider::None,
             api_key: String::new(),
             instance_url: String::new(),
+            client_id: String::new(),
+            login_url: String::new(),
+            refresh_token: None,
+            webhook: CRMWebhookConfig::default(),
         }
     }
 }
 
+/// The part of `CRMConfig` kept in the settings table
+#[derive(Serialize, Deserialize)]
+struct StoredCRMConfig {
+    provider: CRMProvider,
+    instance_url: String,
+    #[serde(default)]
+    client_id: String,
+    #[serde(default)]
+    login_url: String,
+    #[serde(default)]
+    webhook: CRMWebhookConfig,
+}
+
+impl CRMConfig {
+    /// Load the saved provider and instance URL, with the API key from the keychain
+    pub fn load(storage: &Storage) -> Self {
+        let stored = match storage.get_setting(CRM_SETTINGS_KEY) {
+            Ok(Some(json)) => serde_json::from_str::<StoredCRMConfig>(&json).ok(),
+            Ok(None) => None,
+            Err(e) => {
+                log::error!("Failed to load CRM settings: {}", e);
+                None
+            }
+        };
+        let Some(stored) = stored else {
+            return Self::default();
+        };
+
+        let secret = |account| {
+            keychain::get_secret(account).unwrap_or_else(|e| {
+                log::error!("{}", e);
+                None
+            })
+        };
+        Self {
+            provider: stored.provider,
+            api_key: secret(CRM_KEYCHAIN_ACCOUNT).unwrap_or_default(),
+            instance_url: stored.instance_url,
+            client_id: stored.client_id,
+            login_url: stored.login_url,
+            refresh_token: secret(CRM_REFRESH_KEYCHAIN_ACCOUNT),
+            webhook: stored.webhook,
+        }
+    }
+
+    pub fn save(&self, storage: &Storage) -> Result<(), VenkyError> {
+        keychain::set_secret(CRM_KEYCHAIN_ACCOUNT, Some(&self.api_key))?;
+        keychain::set_secret(CRM_REFRESH_KEYCHAIN_ACCOUNT, self.refresh_token.as_deref())?;
+        let json = serde_json::to_string(&StoredCRMConfig {
+            provider: self.provider.clone(),
+            instance_url: self.instance_url.clone(),
+            client_id: self.client_id.clone(),
+            login_url: self.login_url.clone(),
+            webhook: self.webhook.clone(),
+        })
+        .map_err(|e| VenkyError::Storage(format!("Failed to save CRM settings: {}", e)))?;
+        storage.set_setting(CRM_SETTINGS_KEY, Some(&json))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CRMContact {
     pub id: Option<String>,
@@ -34,6 +157,9 @@ pub struct CRMContact {
     pub last_name: String,
     pub company: Option<String>,
     pub phone: Option<String>,
+    /// Job title or role
+    #[serde(default)]
+    pub title: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,9 +169,53 @@ pub struct CRMNote {
     pub timestamp: String,
 }
 
+/// A deal (HubSpot) or opportunity (Salesforce) a call can be filed under
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CRMDeal {
+    pub id: String,
+    pub name: String,
+    pub stage: Option<String>,
+    pub amount: Option<f64>,
+    pub close_date: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CRMSyncResult {
     pub success: bool,
     pub message: String,
     pub record_id: Option<String>,
 }
+
+/// Outcome of a settings "Test connection" check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionTest {
+    pub success: bool,
+    /// How long the check took, including any token refresh
+    pub latency_ms: u64,
+    /// What worked, or why it didn't
+    pub message: String,
+}
+
+impl ConnectionTest {
+    /// Time a check that resolves to a success message or an error
+    pub async fn run<F>(check: F) -> Self
+    where
+        F: Future<Output = Result<String, VenkyError>>,
+    {
+        let started = Instant::now();
+        let result = check.await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        match result {
+            Ok(message) => Self {
+                success: true,
+                latency_ms,
+                message,
+            },
+            Err(e) => Self {
+                success: false,
+                latency_ms,
+                message: e.to_string(),
+            },
+        }
+    }
+}
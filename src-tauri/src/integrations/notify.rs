@@ -0,0 +1,403 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::template::{fill, parse};
+use super::ConnectionTest;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::manager::Session;
+use crate::session::storage::{ActionItem, Storage};
+use crate::session::storage_actor::StorageActor;
+
+/// Settings key the notifiers are stored under
+const SETTINGS_KEY: &str = "notifiers";
+/// Discord rejects embed descriptions longer than this
+const DISCORD_MAX_DESCRIPTION: usize = 4096;
+/// Teams cards over ~28 KB are rejected, so long summaries are cut short
+const TEAMS_MAX_TEXT: usize = 20_000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum NotifierKind {
+    /// Teams incoming webhook or Workflows "post to a channel" URL
+    Teams,
+    Discord,
+    /// Any URL, sent a JSON body built from the notifier's templates
+    Webhook,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    SessionEnded,
+    ActionItemsCreated,
+}
+
+impl NotifyEvent {
+    fn as_str(&self) -> &'static str {
+        match self {
+            NotifyEvent::SessionEnded => "session_ended",
+            NotifyEvent::ActionItemsCreated => "action_items_created",
+        }
+    }
+}
+
+/// One place session summaries and action items are pushed to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Notifier {
+    pub id: String,
+    pub name: String,
+    pub kind: NotifierKind,
+    pub url: String,
+    pub enabled: bool,
+    /// Events this notifier is sent
+    pub events: Vec<NotifyEvent>,
+    /// Webhook bodies with `{{field}}` placeholders; empty sends the event as-is
+    pub session_ended_template: String,
+    pub action_items_template: String,
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            kind: NotifierKind::Webhook,
+            url: String::new(),
+            enabled: true,
+            events: vec![NotifyEvent::SessionEnded, NotifyEvent::ActionItemsCreated],
+            session_ended_template: String::new(),
+            action_items_template: String::new(),
+        }
+    }
+}
+
+impl Notifier {
+    fn template(&self, event: NotifyEvent) -> &str {
+        match event {
+            NotifyEvent::SessionEnded => &self.session_ended_template,
+            NotifyEvent::ActionItemsCreated => &self.action_items_template,
+        }
+    }
+}
+
+/// A message to send, both as text for chat apps and as fields for webhook templates
+struct Notification {
+    event: NotifyEvent,
+    title: String,
+    /// Markdown
+    text: String,
+    fields: serde_json::Value,
+}
+
+fn load(storage: &Storage) -> Vec<Notifier> {
+    storage
+        .get_setting(SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save(storage: &Storage, notifiers: &[Notifier]) -> Result<(), VenkyError> {
+    let json = serde_json::to_string(notifiers)
+        .map_err(|e| VenkyError::Storage(format!("Failed to save notifiers: {}", e)))?;
+    storage.set_setting(SETTINGS_KEY, Some(&json))
+}
+
+/// Cut `text` to at most `max` characters, marking the cut
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+fn action_item_line(item: &ActionItem) -> String {
+    let mut line = format!("- {}", item.description);
+    if let Some(ref owner) = item.owner {
+        line.push_str(&format!(" ({})", owner));
+    }
+    if let Some(ref due) = item.due_date {
+        line.push_str(&format!(", due {}", due));
+    }
+    line
+}
+
+fn session_fields(session: &Session) -> serde_json::Value {
+    serde_json::json!({
+        "id": session.id,
+        "title": session.title,
+        "purpose": session.purpose,
+        "start_time": session.start_time,
+        "end_time": session.end_time,
+    })
+}
+
+fn session_ended(session: &Session, summary: &str, items: &[ActionItem]) -> Notification {
+    let mut text = summary.trim().to_string();
+    if !items.is_empty() {
+        let lines = items.iter().map(action_item_line).collect::<Vec<_>>();
+        text.push_str(&format!("\n\n**Action items**\n{}", lines.join("\n")));
+    }
+    Notification {
+        event: NotifyEvent::SessionEnded,
+        title: format!("Meeting ended: {}", session.title),
+        text,
+        fields: serde_json::json!({
+            "session": session_fields(session),
+            "summary": summary,
+            "action_items": items,
+        }),
+    }
+}
+
+fn action_items_created(session: &Session, items: &[ActionItem]) -> Notification {
+    let lines = items.iter().map(action_item_line).collect::<Vec<_>>();
+    Notification {
+        event: NotifyEvent::ActionItemsCreated,
+        title: format!("{} action item(s) from {}", items.len(), session.title),
+        text: lines.join("\n"),
+        fields: serde_json::json!({
+            "session": session_fields(session),
+            "action_items": items,
+            "count": items.len(),
+        }),
+    }
+}
+
+/// The request body `notifier` expects for `notification`
+fn payload(
+    notifier: &Notifier,
+    notification: &Notification,
+) -> Result<serde_json::Value, VenkyError> {
+    let event = notification.event.as_str();
+    match notifier.kind {
+        NotifierKind::Teams => Ok(serde_json::json!({
+            "type": "message",
+            "attachments": [{
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "content": {
+                    "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                    "type": "AdaptiveCard",
+                    "version": "1.4",
+                    "body": [
+                        {
+                            "type": "TextBlock",
+                            "text": notification.title,
+                            "weight": "Bolder",
+                            "size": "Medium",
+                            "wrap": true,
+                        },
+                        {
+                            "type": "TextBlock",
+                            "text": truncate(&notification.text, TEAMS_MAX_TEXT),
+                            "wrap": true,
+                        },
+                    ],
+                },
+            }],
+        })),
+        NotifierKind::Discord => Ok(serde_json::json!({
+            "username": "VenkyAI",
+            "embeds": [{
+                "title": truncate(&notification.title, 256),
+                "description": truncate(&notification.text, DISCORD_MAX_DESCRIPTION),
+            }],
+        })),
+        NotifierKind::Webhook => {
+            let mut fields = notification.fields.clone();
+            fields["event"] = event.into();
+            fields["title"] = notification.title.clone().into();
+            fields["text"] = notification.text.clone().into();
+            let template = notifier.template(notification.event);
+            if template.trim().is_empty() {
+                return Ok(fields);
+            }
+            Ok(fill(&parse(template, event)?, &fields))
+        }
+    }
+}
+
+async fn send(notifier: &Notifier, notification: &Notification) -> Result<(), VenkyError> {
+    let body = payload(notifier, notification)?;
+    let response = crate::http::client()
+        .post(notifier.url.trim())
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("{} request failed: {}", notifier.name, e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "{} error ({}): {}",
+            notifier.name, status, body
+        )));
+    }
+    Ok(())
+}
+
+/// Send to every enabled notifier subscribed to the event; failures are only logged
+async fn dispatch(storage: &StorageActor, notification: Notification) {
+    let notifiers = storage.call(|s| Ok(load(s))).await.unwrap_or_default();
+    for notifier in notifiers
+        .iter()
+        .filter(|n| n.enabled && n.events.contains(&notification.event))
+    {
+        if let Err(e) = send(notifier, &notification).await {
+            log::warn!("Notification to {} failed: {}", notifier.name, e);
+        }
+    }
+}
+
+async fn has_subscribers(storage: &StorageActor, event: NotifyEvent) -> bool {
+    storage
+        .call(|s| Ok(load(s)))
+        .await
+        .unwrap_or_default()
+        .iter()
+        .any(|n| n.enabled && n.events.contains(&event))
+}
+
+/// After a session ends, send its summary (generated if missing) and action items
+pub fn notify_session_ended(app: &AppHandle, session_id: &str) {
+    let storage = app.state::<StorageActor>().inner().clone();
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        if !has_subscribers(&storage, NotifyEvent::SessionEnded).await {
+            return;
+        }
+        let id = session_id.clone();
+        let session = match storage.call(move |s| s.get_session(&id)).await {
+            Ok(session) => session,
+            Err(e) => {
+                log::warn!("Not notifying about session {}: {}", session_id, e);
+                return;
+            }
+        };
+        let summary = match session.summary.clone().filter(|s| !s.trim().is_empty()) {
+            Some(summary) => summary,
+            None => {
+                match crate::session::summary::summarize_session(&app, &session_id, None).await {
+                    Ok(summary) => summary,
+                    Err(e) => {
+                        log::warn!("Not notifying about session {}: {}", session_id, e);
+                        return;
+                    }
+                }
+            }
+        };
+        let id = session_id.clone();
+        let items = storage
+            .call(move |s| s.list_action_items(Some(&id), None))
+            .await
+            .unwrap_or_default();
+        dispatch(&storage, session_ended(&session, &summary, &items)).await;
+    });
+}
+
+/// Send newly extracted action items
+pub fn notify_action_items(app: &AppHandle, session_id: &str, items: &[ActionItem]) {
+    if items.is_empty() {
+        return;
+    }
+
+    let storage = app.state::<StorageActor>().inner().clone();
+    let session_id = session_id.to_string();
+    let items = items.to_vec();
+    tauri::async_runtime::spawn(async move {
+        if !has_subscribers(&storage, NotifyEvent::ActionItemsCreated).await {
+            return;
+        }
+        let id = session_id.clone();
+        let session = match storage.call(move |s| s.get_session(&id)).await {
+            Ok(session) => session,
+            Err(e) => {
+                log::warn!("Not notifying about session {}: {}", session_id, e);
+                return;
+            }
+        };
+        dispatch(&storage, action_items_created(&session, &items)).await;
+    });
+}
+
+#[tauri::command]
+pub async fn list_notifiers(
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<Vec<Notifier>> {
+    Ok(storage.call(|s| Ok(load(s))).await?)
+}
+
+/// Add a notifier, or replace the one with the same id
+#[tauri::command]
+pub async fn save_notifier(
+    storage: tauri::State<'_, StorageActor>,
+    mut notifier: Notifier,
+) -> CommandResult<Notifier> {
+    if notifier.url.trim().is_empty() {
+        return Err(VenkyError::Other("A webhook URL is required".to_string()));
+    }
+    for event in [NotifyEvent::SessionEnded, NotifyEvent::ActionItemsCreated] {
+        let template = notifier.template(event);
+        if !template.trim().is_empty() {
+            parse(template, event.as_str())?;
+        }
+    }
+    if notifier.id.is_empty() {
+        notifier.id = uuid::Uuid::new_v4().to_string();
+    }
+    if notifier.name.trim().is_empty() {
+        notifier.name = format!("{:?}", notifier.kind);
+    }
+
+    let saved = notifier.clone();
+    storage
+        .call(move |s| {
+            let mut notifiers = load(s);
+            match notifiers.iter_mut().find(|n| n.id == saved.id) {
+                Some(existing) => *existing = saved,
+                None => notifiers.push(saved),
+            }
+            save(s, &notifiers)
+        })
+        .await?;
+    Ok(notifier)
+}
+
+#[tauri::command]
+pub async fn delete_notifier(
+    storage: tauri::State<'_, StorageActor>,
+    id: String,
+) -> CommandResult<()> {
+    Ok(storage
+        .call(move |s| {
+            let mut notifiers = load(s);
+            notifiers.retain(|n| n.id != id);
+            save(s, &notifiers)
+        })
+        .await?)
+}
+
+/// Post a sample message so the user can see where and how notifications land
+#[tauri::command]
+pub async fn test_notifier(notifier: Notifier) -> CommandResult<ConnectionTest> {
+    let notification = Notification {
+        event: NotifyEvent::SessionEnded,
+        title: "VenkyAI test notification".to_string(),
+        text: "Session summaries and action items will be posted here.".to_string(),
+        fields: serde_json::json!({
+            "session": { "id": "test", "title": "Test meeting" },
+            "summary": "Session summaries will be posted here.",
+            "action_items": [],
+        }),
+    };
+    Ok(ConnectionTest::run(async {
+        send(&notifier, &notification).await?;
+        Ok(format!("Test message sent to {}", notifier.name))
+    })
+    .await)
+}
@@ -0,0 +1,327 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_shell::ShellExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::error::VenkyError;
+use crate::session::storage::Storage;
+use crate::session::storage_actor::StorageActor;
+
+/// How long the browser sign-in may take before the flow is abandoned
+const AUTHORIZE_TIMEOUT_SECS: u64 = 5 * 60;
+
+const CALLBACK_PAGE: &str = "<html><body style=\"font-family: sans-serif\">\
+    <h3>VenkyAI is connected.</h3><p>You can close this tab.</p></body></html>";
+
+/// Endpoints and client of one OAuth 2.0 provider
+pub struct OAuthClient<'a> {
+    pub authorize_url: &'a str,
+    pub token_url: &'a str,
+    pub client_id: &'a str,
+    /// Desktop clients of some providers (Google) still get a non-confidential secret
+    pub client_secret: Option<&'a str>,
+    pub scopes: &'a str,
+    /// Provider-specific authorize parameters, e.g. Google's `access_type=offline`
+    pub extra_params: &'a [(&'a str, &'a str)],
+    /// Loopback callback registered with the provider, e.g. `http://localhost:8765/callback`,
+    /// for providers that match it exactly (Salesforce, Zoom). A random port when unset.
+    pub redirect_uri: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub expires_in: Option<i64>,
+    /// Salesforce returns the org URL with its tokens
+    #[serde(default)]
+    pub instance_url: Option<String>,
+    /// Zoho returns the API host of the account's data center
+    #[serde(default)]
+    pub api_domain: Option<String>,
+}
+
+impl TokenResponse {
+    /// RFC 3339 expiry, a minute early so a token isn't used right as it lapses
+    pub fn expires_at(&self) -> Option<String> {
+        self.expires_in
+            .map(|secs| (chrono::Utc::now() + chrono::Duration::seconds(secs - 60)).to_rfc3339())
+    }
+}
+
+/// True when an RFC 3339 expiry is missing or already past
+pub fn is_expired(expires_at: Option<&str>) -> bool {
+    expires_at
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t <= chrono::Utc::now())
+        .unwrap_or(true)
+}
+
+fn random_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn query_params(request_line: &str) -> HashMap<String, String> {
+    let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let query = target.split_once('?').map(|(_, q)| q).unwrap_or_default();
+    reqwest::Url::parse(&format!("http://localhost/?{}", query))
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default()
+}
+
+/// Wait for the provider to redirect the browser back to the loopback listener
+async fn await_callback(listener: TcpListener) -> Result<HashMap<String, String>, VenkyError> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| VenkyError::Network(format!("OAuth callback failed: {}", e)))?;
+
+        let mut buf = vec![0u8; 8192];
+        let n = stream
+            .read(&mut buf)
+            .await
+            .map_err(|e| VenkyError::Network(format!("OAuth callback failed: {}", e)))?;
+        let request = String::from_utf8_lossy(&buf[..n]);
+        let params = query_params(request.lines().next().unwrap_or_default());
+
+        // Browsers also ask for /favicon.ico; only the redirect carries a code or error
+        if !params.contains_key("code") && !params.contains_key("error") {
+            let _ = stream
+                .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+                .await;
+            continue;
+        }
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            CALLBACK_PAGE.len(),
+            CALLBACK_PAGE
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return Ok(params);
+    }
+}
+
+/// Listen on `redirect_uri`'s port, or a free one when there is none, returning the URI to
+/// send to the provider
+async fn callback_listener(
+    redirect_uri: Option<&str>,
+) -> Result<(TcpListener, String), VenkyError> {
+    let Some(redirect_uri) = redirect_uri else {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| VenkyError::Other(format!("Failed to open OAuth callback port: {}", e)))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| VenkyError::Other(format!("Failed to open OAuth callback port: {}", e)))?
+            .port();
+        return Ok((listener, format!("http://127.0.0.1:{}/callback", port)));
+    };
+
+    let url = reqwest::Url::parse(redirect_uri)
+        .map_err(|e| VenkyError::Other(format!("Invalid redirect URI {}: {}", redirect_uri, e)))?;
+    if url.scheme() != "http" || !matches!(url.host_str(), Some("localhost" | "127.0.0.1")) {
+        return Err(VenkyError::Other(format!(
+            "Redirect URI {} must be http://localhost or http://127.0.0.1",
+            redirect_uri
+        )));
+    }
+    let port = url
+        .port()
+        .ok_or_else(|| VenkyError::Other(format!("Redirect URI {} needs a port", redirect_uri)))?;
+    let listener = TcpListener::bind(("127.0.0.1", port)).await.map_err(|e| {
+        VenkyError::Other(format!(
+            "Failed to open OAuth callback port {}: {}",
+            port, e
+        ))
+    })?;
+    Ok((listener, redirect_uri.to_string()))
+}
+
+/// Run the authorization-code flow with PKCE: open the provider's sign-in page in the
+/// browser, catch the redirect on a loopback port and exchange the code for tokens
+pub async fn authorize(
+    app: &AppHandle,
+    client: &OAuthClient<'_>,
+) -> Result<TokenResponse, VenkyError> {
+    let (listener, redirect_uri) = callback_listener(client.redirect_uri).await?;
+
+    let verifier = random_token();
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let state = random_token();
+
+    let mut url = reqwest::Url::parse(client.authorize_url)
+        .map_err(|e| VenkyError::Other(format!("Invalid authorize URL: {}", e)))?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", client.client_id)
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("scope", client.scopes)
+        .append_pair("state", &state)
+        .append_pair("code_challenge", &challenge)
+        .append_pair("code_challenge_method", "S256")
+        .extend_pairs(client.extra_params.iter());
+
+    #[allow(deprecated)]
+    app.shell()
+        .open(url.as_str(), None)
+        .map_err(|e| VenkyError::Other(format!("Failed to open browser: {}", e)))?;
+
+    let params = tokio::time::timeout(
+        Duration::from_secs(AUTHORIZE_TIMEOUT_SECS),
+        await_callback(listener),
+    )
+    .await
+    .map_err(|_| VenkyError::Provider("Sign-in timed out".to_string()))??;
+
+    if let Some(error) = params.get("error") {
+        let detail = params.get("error_description").unwrap_or(error);
+        return Err(VenkyError::Provider(format!(
+            "Sign-in was not completed: {}",
+            detail
+        )));
+    }
+    if params.get("state") != Some(&state) {
+        return Err(VenkyError::Provider(
+            "OAuth state mismatch; sign-in aborted".to_string(),
+        ));
+    }
+    let code = params
+        .get("code")
+        .ok_or_else(|| VenkyError::Provider("No authorization code returned".to_string()))?;
+
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("client_id", client.client_id),
+        ("code_verifier", verifier.as_str()),
+    ];
+    if let Some(secret) = client.client_secret {
+        form.push(("client_secret", secret));
+    }
+    request_token(client.token_url, &form).await
+}
+
+/// Trade a refresh token for a new access token
+pub async fn refresh(
+    client: &OAuthClient<'_>,
+    refresh_token: &str,
+) -> Result<TokenResponse, VenkyError> {
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("client_id", client.client_id),
+    ];
+    if let Some(secret) = client.client_secret {
+        form.push(("client_secret", secret));
+    }
+    request_token(client.token_url, &form).await
+}
+
+/// A saved sign-in with an access token that `fresh_connection` keeps valid
+pub trait Connection: Clone + Send + 'static {
+    /// Error for when nothing is signed in
+    const NOT_CONNECTED: &'static str;
+    /// Error for when the sign-in has lapsed and can't be refreshed
+    const EXPIRED: &'static str;
+
+    fn is_connected(&self) -> bool;
+    fn oauth_client(&self) -> OAuthClient<'_>;
+    fn expires_at(&self) -> Option<&str>;
+    fn refresh_token(&self) -> Option<&str>;
+    /// Take the tokens from a refresh, keeping the refresh token unless a new one was sent
+    fn set_tokens(&mut self, tokens: TokenResponse);
+}
+
+/// The connection in `state` with a valid access token, refreshing and saving it first
+/// when it has expired. Refreshes of one integration take turns on `turn`, so two callers
+/// never spend the same refresh token (providers that rotate them reject the second), and
+/// tokens refreshed for an account that was disconnected or replaced meanwhile are dropped.
+pub async fn fresh_connection<C: Connection>(
+    turn: &tokio::sync::Mutex<()>,
+    state: &Mutex<C>,
+    storage: &StorageActor,
+    save: fn(&C, &Storage) -> Result<(), VenkyError>,
+) -> Result<C, VenkyError> {
+    let connected = |config: &C| {
+        if config.is_connected() {
+            Ok(())
+        } else {
+            Err(VenkyError::Other(C::NOT_CONNECTED.to_string()))
+        }
+    };
+    let config = state.lock().clone();
+    connected(&config)?;
+    if !is_expired(config.expires_at()) {
+        return Ok(config);
+    }
+
+    let _turn = turn.lock().await;
+    // Whoever had the turn before may have refreshed it already
+    let mut config = state.lock().clone();
+    connected(&config)?;
+    if !is_expired(config.expires_at()) {
+        return Ok(config);
+    }
+    let refresh_token = config
+        .refresh_token()
+        .map(str::to_string)
+        .ok_or_else(|| VenkyError::Provider(C::EXPIRED.to_string()))?;
+    let tokens = refresh(&config.oauth_client(), &refresh_token).await?;
+    config.set_tokens(tokens);
+
+    let mut current = state.lock();
+    connected(&current)?;
+    if current.refresh_token() != Some(refresh_token.as_str()) {
+        // Signed in again while this refresh ran; the new sign-in wins
+        return Ok(current.clone());
+    }
+    *current = config.clone();
+    // Queued under the state lock, so the save of a later disconnect lands after this one
+    let row = config.clone();
+    storage.send(move |s| {
+        if let Err(e) = save(&row, s) {
+            log::error!("Failed to save refreshed sign-in: {}", e);
+        }
+    });
+    Ok(config)
+}
+
+async fn request_token(
+    token_url: &str,
+    form: &[(&str, &str)],
+) -> Result<TokenResponse, VenkyError> {
+    let response = crate::http::client()
+        .post(token_url)
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("Token request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "Token request failed ({}): {}",
+            status, body
+        )));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))
+}
@@ -0,0 +1,880 @@
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::keychain;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::storage::{SessionRecord, Storage};
+use crate::session::storage_actor::StorageActor;
+use crate::settings_bundle::{check_kdf_rounds, derive_key, KDF_ROUNDS, MIN_PASSPHRASE_CHARS};
+
+/// Settings key for the sync target; the credentials and passphrase live in the keychain
+const SETTINGS_KEY: &str = "cloud_sync";
+const S3_KEYCHAIN_ACCOUNT: &str = "sync-s3-secret-key";
+const WEBDAV_KEYCHAIN_ACCOUNT: &str = "sync-webdav-password";
+const PASSPHRASE_KEYCHAIN_ACCOUNT: &str = "sync-passphrase";
+/// Sent to the settings page in place of a saved secret; sending it back keeps the secret
+const MASKED_SECRET: &str = "********";
+
+const FORMAT: &str = "venkyai-sync";
+const SYNC_VERSION: u32 = 1;
+/// Unencrypted; holds the salt every device derives the key from
+const META_FILE: &str = "sync.json";
+/// Encrypted map of session id to the version stored remotely
+const INDEX_FILE: &str = "index.json";
+/// How often the loop checks whether a sync is due
+const SYNC_CHECK_INTERVAL_SECS: u64 = 60;
+
+/// Set while a sync runs, so the loop and "Sync now" don't overlap
+static SYNCING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum SyncBackend {
+    /// AWS S3 or any S3-compatible store (R2, B2, MinIO, ...)
+    #[default]
+    S3,
+    WebDav,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub enabled: bool,
+    pub backend: SyncBackend,
+    /// e.g. https://<account>.r2.cloudflarestorage.com; empty for AWS
+    pub s3_endpoint: String,
+    pub s3_region: String,
+    pub s3_bucket: String,
+    pub s3_access_key: String,
+    pub s3_secret_key: String,
+    /// Folder URL, e.g. https://cloud.example.com/remote.php/dav/files/me
+    pub webdav_url: String,
+    pub webdav_username: String,
+    pub webdav_password: String,
+    /// Sessions are encrypted with a key derived from this before they leave the machine;
+    /// every device must use the same one
+    pub passphrase: String,
+    /// Folder (WebDAV) or key prefix (S3) the sync files go under
+    pub prefix: String,
+    /// Minutes between automatic syncs; 0 syncs only on request
+    pub interval_mins: u32,
+    pub last_synced_at: Option<String>,
+    pub last_attempt_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: SyncBackend::S3,
+            s3_endpoint: String::new(),
+            s3_region: "us-east-1".to_string(),
+            s3_bucket: String::new(),
+            s3_access_key: String::new(),
+            s3_secret_key: String::new(),
+            webdav_url: String::new(),
+            webdav_username: String::new(),
+            webdav_password: String::new(),
+            passphrase: String::new(),
+            prefix: "venkyai".to_string(),
+            interval_mins: 15,
+            last_synced_at: None,
+            last_attempt_at: None,
+            last_error: None,
+        }
+    }
+}
+
+/// The part of `SyncConfig` kept in the settings table
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct StoredSyncConfig {
+    enabled: bool,
+    backend: SyncBackend,
+    s3_endpoint: String,
+    s3_region: String,
+    s3_bucket: String,
+    s3_access_key: String,
+    webdav_url: String,
+    webdav_username: String,
+    prefix: String,
+    interval_mins: u32,
+    last_synced_at: Option<String>,
+    last_attempt_at: Option<String>,
+    last_error: Option<String>,
+}
+
+impl Default for StoredSyncConfig {
+    fn default() -> Self {
+        let defaults = SyncConfig::default();
+        Self {
+            enabled: defaults.enabled,
+            backend: defaults.backend,
+            s3_endpoint: defaults.s3_endpoint,
+            s3_region: defaults.s3_region,
+            s3_bucket: defaults.s3_bucket,
+            s3_access_key: defaults.s3_access_key,
+            webdav_url: defaults.webdav_url,
+            webdav_username: defaults.webdav_username,
+            prefix: defaults.prefix,
+            interval_mins: defaults.interval_mins,
+            last_synced_at: None,
+            last_attempt_at: None,
+            last_error: None,
+        }
+    }
+}
+
+fn load_stored(storage: &Storage) -> StoredSyncConfig {
+    storage
+        .get_setting(SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_stored(storage: &Storage, stored: &StoredSyncConfig) -> Result<(), VenkyError> {
+    let json = serde_json::to_string(stored)
+        .map_err(|e| VenkyError::Storage(format!("Failed to save sync settings: {}", e)))?;
+    storage.set_setting(SETTINGS_KEY, Some(&json))
+}
+
+impl SyncConfig {
+    /// The target from the settings table and the secrets from the keychain. The keychain
+    /// is read outside the storage thread, so a slow one doesn't hold up the database.
+    pub async fn load(storage: &StorageActor) -> Result<Self, VenkyError> {
+        let stored = storage.call(|s| Ok(load_stored(s))).await?;
+        let secret = |account| {
+            keychain::get_secret(account)
+                .unwrap_or_else(|e| {
+                    log::error!("{}", e);
+                    None
+                })
+                .unwrap_or_default()
+        };
+        Ok(Self {
+            enabled: stored.enabled,
+            backend: stored.backend,
+            s3_endpoint: stored.s3_endpoint,
+            s3_region: stored.s3_region,
+            s3_bucket: stored.s3_bucket,
+            s3_access_key: stored.s3_access_key,
+            s3_secret_key: secret(S3_KEYCHAIN_ACCOUNT),
+            webdav_url: stored.webdav_url,
+            webdav_username: stored.webdav_username,
+            webdav_password: secret(WEBDAV_KEYCHAIN_ACCOUNT),
+            passphrase: secret(PASSPHRASE_KEYCHAIN_ACCOUNT),
+            prefix: stored.prefix,
+            interval_mins: stored.interval_mins,
+            last_synced_at: stored.last_synced_at,
+            last_attempt_at: stored.last_attempt_at,
+            last_error: stored.last_error,
+        })
+    }
+
+    /// Save the target and credentials; the sync status is left as recorded
+    pub async fn save(&self, storage: &StorageActor) -> Result<(), VenkyError> {
+        keychain::set_secret(S3_KEYCHAIN_ACCOUNT, Some(&self.s3_secret_key))?;
+        keychain::set_secret(WEBDAV_KEYCHAIN_ACCOUNT, Some(&self.webdav_password))?;
+        keychain::set_secret(PASSPHRASE_KEYCHAIN_ACCOUNT, Some(&self.passphrase))?;
+        let target = StoredSyncConfig {
+            enabled: self.enabled,
+            backend: self.backend,
+            s3_endpoint: self.s3_endpoint.trim().trim_end_matches('/').to_string(),
+            s3_region: self.s3_region.trim().to_string(),
+            s3_bucket: self.s3_bucket.trim().to_string(),
+            s3_access_key: self.s3_access_key.trim().to_string(),
+            webdav_url: self.webdav_url.trim().trim_end_matches('/').to_string(),
+            webdav_username: self.webdav_username.trim().to_string(),
+            prefix: self.prefix.trim().trim_matches('/').to_string(),
+            interval_mins: self.interval_mins,
+            last_synced_at: None,
+            last_attempt_at: None,
+            last_error: None,
+        };
+        storage
+            .call(move |s| {
+                let status = load_stored(s);
+                save_stored(
+                    s,
+                    &StoredSyncConfig {
+                        last_synced_at: status.last_synced_at,
+                        last_attempt_at: status.last_attempt_at,
+                        last_error: status.last_error,
+                        ..target
+                    },
+                )
+            })
+            .await
+    }
+
+    /// This config with its secrets masked, for the settings page
+    fn masked(mut self) -> Self {
+        for secret in [
+            &mut self.s3_secret_key,
+            &mut self.webdav_password,
+            &mut self.passphrase,
+        ] {
+            if !secret.is_empty() {
+                *secret = MASKED_SECRET.to_string();
+            }
+        }
+        self
+    }
+
+    /// Put the saved secrets back where the settings page sent the mask unchanged
+    fn unmask(&mut self, saved: &SyncConfig) {
+        for (secret, saved) in [
+            (&mut self.s3_secret_key, &saved.s3_secret_key),
+            (&mut self.webdav_password, &saved.webdav_password),
+            (&mut self.passphrase, &saved.passphrase),
+        ] {
+            if *secret == MASKED_SECRET {
+                secret.clone_from(saved);
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), VenkyError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        match self.backend {
+            SyncBackend::S3 => {
+                if self.s3_bucket.trim().is_empty()
+                    || self.s3_access_key.trim().is_empty()
+                    || self.s3_secret_key.is_empty()
+                {
+                    return Err(VenkyError::Other(
+                        "S3 sync needs a bucket, access key and secret key".to_string(),
+                    ));
+                }
+                if self.s3_endpoint.trim().is_empty() && self.s3_region.trim().is_empty() {
+                    return Err(VenkyError::Other(
+                        "S3 sync needs an endpoint or a region".to_string(),
+                    ));
+                }
+            }
+            SyncBackend::WebDav => {
+                if !self.webdav_url.trim().starts_with("http") {
+                    return Err(VenkyError::Other(
+                        "WebDAV sync needs the folder's http(s) URL".to_string(),
+                    ));
+                }
+            }
+        }
+        if self.passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+            return Err(VenkyError::Other(format!(
+                "The sync passphrase must be at least {} characters",
+                MIN_PASSPHRASE_CHARS
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of one sync
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncResult {
+    pub pushed: usize,
+    pub pulled: usize,
+    /// Sessions removed here because they were deleted on another device
+    pub deleted: usize,
+    /// Sessions edited on both sides since the last sync; the later edit was kept
+    pub conflicts: usize,
+}
+
+/// `sync.json`: how the key is derived, readable before the key is known
+#[derive(Debug, Serialize, Deserialize)]
+struct Meta {
+    format: String,
+    version: u32,
+    kdf_rounds: u32,
+    salt: String,
+}
+
+/// What the target holds for one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    updated_at: String,
+    /// Deleted on some device; the session file is gone
+    #[serde(default)]
+    deleted: bool,
+}
+
+type Index = BTreeMap<String, IndexEntry>;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// The time in the format `sessions.updated_at` uses
+fn now() -> String {
+    chrono::Utc::now()
+        .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+        .to_string()
+}
+
+/// A nonce followed by the ciphertext
+fn seal(key: &Key, plaintext: &[u8]) -> Result<Vec<u8>, VenkyError> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = ChaCha20Poly1305::new(key)
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| VenkyError::Other("Failed to encrypt sync data".to_string()))?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(sealed)
+}
+
+fn open(key: &Key, sealed: &[u8]) -> Result<Vec<u8>, VenkyError> {
+    if sealed.len() < 12 {
+        return Err(VenkyError::Other("Corrupt sync data".to_string()));
+    }
+    let (nonce, ciphertext) = sealed.split_at(12);
+    ChaCha20Poly1305::new(key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| {
+            VenkyError::Other("Wrong sync passphrase, or the sync data was modified".to_string())
+        })
+}
+
+fn session_file(session_id: &str) -> String {
+    format!("sessions/{}", session_id)
+}
+
+/// The S3 bucket or WebDAV folder sync files are read from and written to
+struct Remote {
+    client: Client,
+    config: SyncConfig,
+}
+
+impl Remote {
+    fn new(config: &SyncConfig) -> Self {
+        Self {
+            client: crate::http::client(),
+            config: config.clone(),
+        }
+    }
+
+    fn url(&self, name: &str) -> String {
+        let prefix = self.config.prefix.trim_matches('/');
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        match self.config.backend {
+            SyncBackend::S3 => {
+                let endpoint = match self.config.s3_endpoint.trim_end_matches('/') {
+                    "" => format!("https://s3.{}.amazonaws.com", self.config.s3_region),
+                    endpoint => endpoint.to_string(),
+                };
+                format!("{}/{}/{}", endpoint, self.config.s3_bucket, path)
+            }
+            SyncBackend::WebDav => {
+                format!("{}/{}", self.config.webdav_url.trim_end_matches('/'), path)
+            }
+        }
+    }
+
+    /// A request with S3 Signature V4 or WebDAV basic auth applied
+    fn request(
+        &self,
+        method: Method,
+        name: &str,
+        body: Vec<u8>,
+    ) -> Result<RequestBuilder, VenkyError> {
+        let url = self.url(name);
+        if self.config.backend == SyncBackend::WebDav {
+            return Ok(self
+                .client
+                .request(method, &url)
+                .basic_auth(
+                    &self.config.webdav_username,
+                    Some(&self.config.webdav_password),
+                )
+                .body(body));
+        }
+
+        let parsed = reqwest::Url::parse(&url)
+            .map_err(|e| VenkyError::Other(format!("Invalid S3 endpoint: {}", e)))?;
+        let host = match (parsed.host_str(), parsed.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(VenkyError::Other("Invalid S3 endpoint".to_string())),
+        };
+        let region = match self.config.s3_region.trim() {
+            "" => "us-east-1",
+            region => region,
+        };
+        let time = chrono::Utc::now();
+        let amz_date = time.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = time.format("%Y%m%d").to_string();
+        let payload_hash = hex(&Sha256::digest(&body));
+
+        let canonical = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method,
+            parsed.path(),
+            host,
+            payload_hash,
+            amz_date,
+            payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex(&Sha256::digest(canonical.as_bytes()))
+        );
+        let signing_key = [region, "s3", "aws4_request"].iter().fold(
+            hmac_sha256(
+                format!("AWS4{}", self.config.s3_secret_key).as_bytes(),
+                date.as_bytes(),
+            ),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+        let signature = hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        Ok(self
+            .client
+            .request(method, parsed)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header(
+                "Authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+                    self.config.s3_access_key, scope, signature
+                ),
+            )
+            .body(body))
+    }
+
+    /// Create the WebDAV folders; S3 keys need none
+    async fn prepare(&self) -> Result<(), VenkyError> {
+        if self.config.backend != SyncBackend::WebDav {
+            return Ok(());
+        }
+        let mkcol = Method::from_bytes(b"MKCOL").expect("MKCOL is a valid method");
+        for folder in ["", "sessions/"] {
+            let resp = self
+                .request(mkcol.clone(), folder, Vec::new())?
+                .send()
+                .await
+                .map_err(|e| VenkyError::Network(format!("WebDAV request failed: {}", e)))?;
+            // 405 means the folder is already there
+            if !resp.status().is_success() && resp.status() != StatusCode::METHOD_NOT_ALLOWED {
+                return Err(VenkyError::Provider(format!(
+                    "Failed to create the WebDAV folder: {}",
+                    resp.status()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Vec<u8>>, VenkyError> {
+        let resp = self
+            .request(Method::GET, name, Vec::new())?
+            .send()
+            .await
+            .map_err(|e| VenkyError::Network(format!("Sync request failed: {}", e)))?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(VenkyError::Provider(format!(
+                "Failed to read {}: {}",
+                name,
+                resp.status()
+            )));
+        }
+        let bytes = resp
+            .bytes()
+            .await
+            .map_err(|e| VenkyError::Network(format!("Failed to read {}: {}", name, e)))?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    async fn put(&self, name: &str, body: Vec<u8>) -> Result<(), VenkyError> {
+        let resp = self
+            .request(Method::PUT, name, body)?
+            .send()
+            .await
+            .map_err(|e| VenkyError::Network(format!("Sync request failed: {}", e)))?;
+        if !resp.status().is_success() {
+            return Err(VenkyError::Provider(format!(
+                "Failed to write {}: {}",
+                name,
+                resp.status()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), VenkyError> {
+        let resp = self
+            .request(Method::DELETE, name, Vec::new())?
+            .send()
+            .await
+            .map_err(|e| VenkyError::Network(format!("Sync request failed: {}", e)))?;
+        if !resp.status().is_success() && resp.status() != StatusCode::NOT_FOUND {
+            return Err(VenkyError::Provider(format!(
+                "Failed to delete {}: {}",
+                name,
+                resp.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The key sync data is encrypted with, setting the target up on first use
+async fn remote_key(remote: &Remote, passphrase: &str) -> Result<Key, VenkyError> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let meta = match remote.get(META_FILE).await? {
+        Some(bytes) => {
+            let meta: Meta = serde_json::from_slice(&bytes).map_err(|_| {
+                VenkyError::Other(
+                    "The sync folder holds something other than VenkyAI data".to_string(),
+                )
+            })?;
+            if meta.format != FORMAT {
+                return Err(VenkyError::Other(
+                    "The sync folder holds something other than VenkyAI data".to_string(),
+                ));
+            }
+            if meta.version > SYNC_VERSION {
+                return Err(VenkyError::Other(
+                    "The sync data is from a newer version of VenkyAI".to_string(),
+                ));
+            }
+            check_kdf_rounds(meta.kdf_rounds)?;
+            meta
+        }
+        None => {
+            let mut salt = [0u8; 16];
+            OsRng.fill_bytes(&mut salt);
+            let meta = Meta {
+                format: FORMAT.to_string(),
+                version: SYNC_VERSION,
+                kdf_rounds: KDF_ROUNDS,
+                salt: b64.encode(salt),
+            };
+            let json = serde_json::to_vec_pretty(&meta)
+                .map_err(|e| VenkyError::Other(format!("Serialize error: {}", e)))?;
+            remote.put(META_FILE, json).await?;
+            meta
+        }
+    };
+    let salt = b64
+        .decode(&meta.salt)
+        .map_err(|e| VenkyError::Other(format!("Corrupt sync data: {}", e)))?;
+
+    let passphrase = passphrase.to_string();
+    tokio::task::spawn_blocking(move || derive_key(&passphrase, &salt, meta.kdf_rounds))
+        .await
+        .map_err(|e| VenkyError::Other(format!("Key derivation failed: {}", e)))
+}
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    Nothing,
+    Push,
+    Pull,
+    /// Deleted on another device
+    DeleteLocal,
+    /// Deleted here; remove it from the target
+    PushDeletion,
+    /// Gone on both sides; drop the last-synced version
+    Forget,
+}
+
+/// What to do with one session given its version here, as of the last sync, and on the
+/// target. Where both sides changed, the later change wins.
+fn plan(local: Option<&str>, base: Option<&str>, remote: Option<&IndexEntry>) -> (Action, bool) {
+    let remote_changed = remote.map(|r| r.updated_at.as_str()) != base;
+    match (local, remote) {
+        (Some(_), None) => (Action::Push, false),
+        (Some(local), Some(remote)) => {
+            let take_remote = if remote.deleted {
+                Action::DeleteLocal
+            } else {
+                Action::Pull
+            };
+            match (Some(local) != base, remote_changed) {
+                (false, false) => (Action::Nothing, false),
+                (true, false) => (Action::Push, false),
+                (false, true) => (take_remote, false),
+                (true, true) if local > remote.updated_at.as_str() => (Action::Push, true),
+                (true, true) => (take_remote, true),
+            }
+        }
+        (None, Some(remote)) if remote.deleted => match base {
+            Some(_) => (Action::Forget, false),
+            None => (Action::Nothing, false),
+        },
+        // An edit made elsewhere since the last sync outlives a deletion here
+        (None, Some(_)) if base.is_some() && !remote_changed => (Action::PushDeletion, false),
+        (None, Some(_)) => (Action::Pull, false),
+        (None, None) => match base {
+            Some(_) => (Action::Forget, false),
+            None => (Action::Nothing, false),
+        },
+    }
+}
+
+/// Push local changes to the target and pull other devices' changes
+async fn sync(app: &AppHandle, config: &SyncConfig) -> Result<SyncResult, VenkyError> {
+    let remote = Remote::new(config);
+    remote.prepare().await?;
+    let key = remote_key(&remote, &config.passphrase).await?;
+    let mut index: Index = match remote.get(INDEX_FILE).await? {
+        Some(sealed) => serde_json::from_slice(&open(&key, &sealed)?)
+            .map_err(|e| VenkyError::Other(format!("Corrupt sync index: {}", e)))?,
+        None => Index::new(),
+    };
+
+    let storage = app.state::<StorageActor>();
+    let (versions, bases) = storage
+        .call(|s| Ok((s.session_versions()?, s.get_sync_state()?)))
+        .await?;
+    let ids: BTreeSet<String> = versions
+        .keys()
+        .chain(bases.keys())
+        .chain(index.keys())
+        .cloned()
+        .collect();
+
+    let mut result = SyncResult::default();
+    // Versions pushed or deleted here only count as synced once the index says so
+    let mut pending = Vec::new();
+    let mut index_changed = false;
+    for id in &ids {
+        let local = match versions.get(id) {
+            // Still running; synced once it ends
+            Some(None) => continue,
+            Some(Some(version)) => Some(version.as_str()),
+            None => None,
+        };
+        let base = bases.get(id).map(String::as_str);
+        let (action, conflict) = plan(local, base, index.get(id));
+        if conflict {
+            result.conflicts += 1;
+        }
+
+        match action {
+            Action::Nothing => {}
+            Action::Push => {
+                let session_id = id.clone();
+                let record = storage
+                    .call(move |s| s.get_session_record(&session_id))
+                    .await?;
+                let json = serde_json::to_vec(&record)
+                    .map_err(|e| VenkyError::Other(format!("Serialize error: {}", e)))?;
+                remote.put(&session_file(id), seal(&key, &json)?).await?;
+                index.insert(
+                    id.clone(),
+                    IndexEntry {
+                        updated_at: record.updated_at.clone(),
+                        deleted: false,
+                    },
+                );
+                pending.push((id.clone(), Some(record.updated_at)));
+                index_changed = true;
+                result.pushed += 1;
+            }
+            Action::Pull => {
+                let Some(entry) = index.get(id) else {
+                    continue;
+                };
+                let Some(sealed) = remote.get(&session_file(id)).await? else {
+                    log::warn!("Sync: session {} is listed but missing", id);
+                    continue;
+                };
+                let mut record: SessionRecord = serde_json::from_slice(&open(&key, &sealed)?)
+                    .map_err(|e| VenkyError::Other(format!("Corrupt synced session: {}", e)))?;
+                record.updated_at = entry.updated_at.clone();
+                let session_id = id.clone();
+                storage
+                    .call(move |s| {
+                        s.put_session_record(&record)?;
+                        s.set_sync_state(&session_id, Some(&record.updated_at))
+                    })
+                    .await?;
+                result.pulled += 1;
+            }
+            Action::DeleteLocal => {
+                let session_id = id.clone();
+                let paths = storage
+                    .call(move |s| {
+                        let paths = s.delete_session(&session_id)?;
+                        s.set_sync_state(&session_id, None)?;
+                        Ok(paths)
+                    })
+                    .await?;
+                crate::session::retention::remove_files(&paths);
+                result.deleted += 1;
+            }
+            Action::PushDeletion => {
+                remote.delete(&session_file(id)).await?;
+                index.insert(
+                    id.clone(),
+                    IndexEntry {
+                        updated_at: now(),
+                        deleted: true,
+                    },
+                );
+                pending.push((id.clone(), None));
+                index_changed = true;
+            }
+            Action::Forget => {
+                let session_id = id.clone();
+                storage
+                    .call(move |s| s.set_sync_state(&session_id, None))
+                    .await?
+            }
+        }
+    }
+
+    if index_changed {
+        let json = serde_json::to_vec(&index)
+            .map_err(|e| VenkyError::Other(format!("Serialize error: {}", e)))?;
+        remote.put(INDEX_FILE, seal(&key, &json)?).await?;
+        storage
+            .call(move |s| {
+                for (id, version) in &pending {
+                    s.set_sync_state(id, version.as_deref())?;
+                }
+                Ok(())
+            })
+            .await?;
+    }
+
+    Ok(result)
+}
+
+/// Sync once and record the outcome for the settings page
+async fn run(app: &AppHandle) -> Result<SyncResult, VenkyError> {
+    if SYNCING.swap(true, Ordering::SeqCst) {
+        return Err(VenkyError::Other("A sync is already running".to_string()));
+    }
+    let storage = app.state::<StorageActor>();
+    let result = match SyncConfig::load(&storage).await {
+        Ok(config) => match config.validate() {
+            Ok(()) if config.enabled => sync(app, &config).await,
+            Ok(()) => Err(VenkyError::Other("Sync is turned off".to_string())),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    };
+    SYNCING.store(false, Ordering::SeqCst);
+
+    let outcome = result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+    storage.send(move |s| {
+        let mut stored = load_stored(s);
+        stored.last_attempt_at = Some(chrono::Utc::now().to_rfc3339());
+        match outcome {
+            Ok(()) => {
+                stored.last_synced_at = stored.last_attempt_at.clone();
+                stored.last_error = None;
+            }
+            Err(e) => stored.last_error = Some(e),
+        }
+        if let Err(e) = save_stored(s, &stored) {
+            log::error!("{}", e);
+        }
+    });
+
+    match &result {
+        Ok(summary) if summary.pulled + summary.deleted > 0 => {
+            log::info!(
+                "Sync: pulled {}, deleted {}, pushed {}",
+                summary.pulled,
+                summary.deleted,
+                summary.pushed
+            );
+            let _ = app.emit("sync-completed", summary);
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Sync failed: {}", e),
+    }
+    result
+}
+
+/// Sync on the configured interval while sync is on
+pub async fn sync_loop(app: AppHandle) {
+    let mut interval = tokio::time::interval(Duration::from_secs(SYNC_CHECK_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+
+        // Only the schedule is needed here, so the keychain isn't touched every minute
+        let loaded = app
+            .state::<StorageActor>()
+            .call(|s| Ok(load_stored(s)))
+            .await;
+        let Ok(config) = loaded else {
+            continue;
+        };
+        if !config.enabled || config.interval_mins == 0 {
+            continue;
+        }
+        let last = config
+            .last_attempt_at
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok());
+        let due = match last {
+            Some(last) => {
+                chrono::Utc::now() - last.with_timezone(&chrono::Utc)
+                    >= chrono::Duration::minutes(config.interval_mins as i64)
+            }
+            None => true,
+        };
+        if due {
+            let _ = run(&app).await;
+        }
+    }
+}
+
+/// The sync settings with the secret key, password and passphrase masked
+#[tauri::command]
+pub async fn get_sync_config(storage: tauri::State<'_, StorageActor>) -> CommandResult<SyncConfig> {
+    Ok(SyncConfig::load(&storage).await?.masked())
+}
+
+/// Save the sync settings; a secret left as the mask from `get_sync_config` keeps its value
+#[tauri::command]
+pub async fn update_sync_config(
+    storage: tauri::State<'_, StorageActor>,
+    mut config: SyncConfig,
+) -> CommandResult<()> {
+    config.unmask(&SyncConfig::load(&storage).await?);
+    config.validate()?;
+    Ok(config.save(&storage).await?)
+}
+
+/// Sync now instead of waiting for the interval
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> CommandResult<SyncResult> {
+    run(&app).await
+}
@@ -0,0 +1,43 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::error::VenkyError;
+
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*([\w.]+)\s*\}\}").unwrap());
+
+/// Fill `{{field}}` placeholders in every string of `template`. A string that is nothing but
+/// a placeholder takes the field's JSON value, so numbers and nulls keep their type.
+pub fn fill(template: &serde_json::Value, fields: &serde_json::Value) -> serde_json::Value {
+    let lookup = |name: &str| fields.pointer(&format!("/{}", name.replace('.', "/")));
+    match template {
+        serde_json::Value::String(s) => {
+            if let Some(caps) = PLACEHOLDER.captures(s) {
+                if caps[0].len() == s.trim().len() {
+                    return lookup(&caps[1]).cloned().unwrap_or(serde_json::Value::Null);
+                }
+            }
+            let filled =
+                PLACEHOLDER.replace_all(s, |caps: &regex::Captures| match lookup(&caps[1]) {
+                    Some(serde_json::Value::String(v)) => v.clone(),
+                    Some(serde_json::Value::Null) | None => String::new(),
+                    Some(v) => v.to_string(),
+                });
+            serde_json::Value::String(filled.into_owned())
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(|v| fill(v, fields)).collect())
+        }
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), fill(v, fields)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Parse a JSON template; `what` names it in the error
+pub fn parse(template: &str, what: &str) -> Result<serde_json::Value, VenkyError> {
+    serde_json::from_str(template)
+        .map_err(|e| VenkyError::Other(format!("Invalid {} template: {}", what, e)))
+}
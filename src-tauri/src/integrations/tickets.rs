@@ -0,0 +1,561 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::keychain;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::storage::{ActionItem, Participant, Storage};
+use crate::session::storage_actor::StorageActor;
+
+/// Settings key for the Jira site and account; the tokens live in the keychain
+const SETTINGS_KEY: &str = "ticket_trackers";
+const JIRA_KEYCHAIN_ACCOUNT: &str = "jira-api-token";
+const LINEAR_KEYCHAIN_ACCOUNT: &str = "linear-api-key";
+
+const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
+/// Jira rejects summaries longer than this
+const JIRA_MAX_SUMMARY: usize = 255;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum TicketProvider {
+    Jira,
+    Linear,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TicketConfig {
+    /// Jira Cloud site, e.g. https://acme.atlassian.net
+    pub jira_url: String,
+    /// Atlassian account the API token belongs to
+    pub jira_email: String,
+    pub jira_api_token: String,
+    /// Linear personal API key
+    pub linear_api_key: String,
+}
+
+/// The part of `TicketConfig` kept in the settings table
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct StoredTicketConfig {
+    jira_url: String,
+    jira_email: String,
+}
+
+impl TicketConfig {
+    pub fn load(storage: &Storage) -> Self {
+        let stored: StoredTicketConfig = storage
+            .get_setting(SETTINGS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let secret = |account| {
+            keychain::get_secret(account)
+                .unwrap_or_else(|e| {
+                    log::error!("{}", e);
+                    None
+                })
+                .unwrap_or_default()
+        };
+        Self {
+            jira_url: stored.jira_url,
+            jira_email: stored.jira_email,
+            jira_api_token: secret(JIRA_KEYCHAIN_ACCOUNT),
+            linear_api_key: secret(LINEAR_KEYCHAIN_ACCOUNT),
+        }
+    }
+
+    pub fn save(&self, storage: &Storage) -> Result<(), VenkyError> {
+        keychain::set_secret(JIRA_KEYCHAIN_ACCOUNT, Some(&self.jira_api_token))?;
+        keychain::set_secret(LINEAR_KEYCHAIN_ACCOUNT, Some(&self.linear_api_key))?;
+        let json = serde_json::to_string(&StoredTicketConfig {
+            jira_url: self.jira_url.trim().trim_end_matches('/').to_string(),
+            jira_email: self.jira_email.trim().to_string(),
+        })
+        .map_err(|e| VenkyError::Storage(format!("Failed to save ticket settings: {}", e)))?;
+        storage.set_setting(SETTINGS_KEY, Some(&json))
+    }
+}
+
+/// A Jira project or Linear team tickets can be filed in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketProject {
+    pub id: String,
+    pub key: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketResult {
+    pub action_item_id: String,
+    pub success: bool,
+    pub message: String,
+    pub issue_key: Option<String>,
+    pub issue_url: Option<String>,
+}
+
+/// Jira's rich-text format for a plain-text description
+fn jira_document(text: &str) -> serde_json::Value {
+    let paragraphs = text
+        .split("\n\n")
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| {
+            serde_json::json!({
+                "type": "paragraph",
+                "content": [{ "type": "text", "text": p.trim() }],
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({ "type": "doc", "version": 1, "content": paragraphs })
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// The due date if it is a real YYYY-MM-DD date; trackers reject anything else
+fn due_date(item: &ActionItem) -> Option<&str> {
+    item.due_date
+        .as_deref()
+        .filter(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok())
+}
+
+/// Email of an action item's owner: an explicit choice, the owner if it is an email, or the
+/// session participant with that name
+fn owner_email(
+    item: &ActionItem,
+    assignees: &HashMap<String, String>,
+    participants: &[Participant],
+) -> Option<String> {
+    if let Some(email) = assignees.get(&item.id) {
+        return Some(email.trim().to_string()).filter(|e| !e.is_empty());
+    }
+    let owner = item.owner.as_deref()?.trim();
+    if owner.contains('@') {
+        return Some(owner.to_string());
+    }
+    participants
+        .iter()
+        .find(|p| {
+            let name = p.name.trim();
+            name.eq_ignore_ascii_case(owner)
+                || name
+                    .split_whitespace()
+                    .next()
+                    .is_some_and(|first| first.eq_ignore_ascii_case(owner))
+        })
+        .and_then(|p| p.email.clone())
+}
+
+struct Jira<'a> {
+    client: Client,
+    config: &'a TicketConfig,
+}
+
+impl<'a> Jira<'a> {
+    fn new(config: &'a TicketConfig) -> Result<Self, VenkyError> {
+        if config.jira_url.trim().is_empty()
+            || config.jira_email.trim().is_empty()
+            || config.jira_api_token.is_empty()
+        {
+            return Err(VenkyError::Provider(
+                "Jira site, email and API token are required".to_string(),
+            ));
+        }
+        Ok(Self {
+            client: crate::http::client(),
+            config,
+        })
+    }
+
+    async fn get(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<serde_json::Value, VenkyError> {
+        let response = self
+            .client
+            .get(format!("{}/rest/api/3/{}", self.config.jira_url, path))
+            .query(query)
+            .basic_auth(&self.config.jira_email, Some(&self.config.jira_api_token))
+            .send()
+            .await
+            .map_err(|e| VenkyError::Network(format!("Jira request failed: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(VenkyError::Provider(format!(
+                "Jira error ({}): {}",
+                status, body
+            )));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))
+    }
+
+    async fn projects(&self) -> Result<Vec<TicketProject>, VenkyError> {
+        let found = self
+            .get(
+                "project/search",
+                &[("maxResults", "100"), ("orderBy", "name")],
+            )
+            .await?;
+        Ok(found["values"]
+            .as_array()
+            .map(|projects| {
+                projects
+                    .iter()
+                    .map(|p| TicketProject {
+                        id: p["id"].as_str().unwrap_or_default().to_string(),
+                        key: p["key"].as_str().unwrap_or_default().to_string(),
+                        name: p["name"].as_str().unwrap_or_default().to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn account_id(&self, email: &str) -> Result<Option<String>, VenkyError> {
+        let users = self.get("user/search", &[("query", email)]).await?;
+        Ok(users[0]["accountId"].as_str().map(str::to_string))
+    }
+
+    async fn create(
+        &self,
+        project_id: &str,
+        item: &ActionItem,
+        description: &str,
+        assignee: Option<&str>,
+    ) -> Result<(String, String), VenkyError> {
+        let account_id = match assignee {
+            Some(email) => self.account_id(email).await?,
+            None => None,
+        };
+        let mut fields = serde_json::json!({
+            "project": { "id": project_id },
+            "issuetype": { "name": "Task" },
+            "summary": truncate(&item.description, JIRA_MAX_SUMMARY),
+            "description": jira_document(description),
+        });
+        if let Some(due) = due_date(item) {
+            fields["duedate"] = due.into();
+        }
+        if let Some(id) = account_id {
+            fields["assignee"] = serde_json::json!({ "accountId": id });
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/rest/api/3/issue", self.config.jira_url))
+            .basic_auth(&self.config.jira_email, Some(&self.config.jira_api_token))
+            .json(&serde_json::json!({ "fields": fields }))
+            .send()
+            .await
+            .map_err(|e| VenkyError::Network(format!("Jira request failed: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(VenkyError::Provider(format!(
+                "Jira error ({}): {}",
+                status, body
+            )));
+        }
+        let created: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+        let key = created["key"]
+            .as_str()
+            .ok_or_else(|| VenkyError::Provider("Jira returned no issue key".to_string()))?;
+        Ok((
+            key.to_string(),
+            format!("{}/browse/{}", self.config.jira_url, key),
+        ))
+    }
+}
+
+struct Linear<'a> {
+    client: Client,
+    api_key: &'a str,
+}
+
+impl<'a> Linear<'a> {
+    fn new(config: &'a TicketConfig) -> Result<Self, VenkyError> {
+        if config.linear_api_key.is_empty() {
+            return Err(VenkyError::Provider(
+                "A Linear API key is required".to_string(),
+            ));
+        }
+        Ok(Self {
+            client: crate::http::client(),
+            api_key: &config.linear_api_key,
+        })
+    }
+
+    async fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value, VenkyError> {
+        let response = self
+            .client
+            .post(LINEAR_API_URL)
+            .header("Authorization", self.api_key)
+            .json(&serde_json::json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .map_err(|e| VenkyError::Network(format!("Linear request failed: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(VenkyError::Provider(format!(
+                "Linear error ({}): {}",
+                status, body
+            )));
+        }
+        let mut body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))?;
+        // GraphQL errors come back with a 200
+        if let Some(error) = body["errors"][0]["message"].as_str() {
+            return Err(VenkyError::Provider(format!("Linear error: {}", error)));
+        }
+        Ok(body["data"].take())
+    }
+
+    async fn teams(&self) -> Result<Vec<TicketProject>, VenkyError> {
+        let data = self
+            .graphql(
+                "query { teams(first: 100) { nodes { id key name } } }",
+                serde_json::json!({}),
+            )
+            .await?;
+        Ok(data["teams"]["nodes"]
+            .as_array()
+            .map(|teams| {
+                teams
+                    .iter()
+                    .map(|t| TicketProject {
+                        id: t["id"].as_str().unwrap_or_default().to_string(),
+                        key: t["key"].as_str().unwrap_or_default().to_string(),
+                        name: t["name"].as_str().unwrap_or_default().to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn user_id(&self, email: &str) -> Result<Option<String>, VenkyError> {
+        let data = self
+            .graphql(
+                "query($email: String!) { users(filter: { email: { eq: $email } }) { nodes { id } } }",
+                serde_json::json!({ "email": email }),
+            )
+            .await?;
+        Ok(data["users"]["nodes"][0]["id"].as_str().map(str::to_string))
+    }
+
+    async fn create(
+        &self,
+        team_id: &str,
+        item: &ActionItem,
+        description: &str,
+        assignee: Option<&str>,
+    ) -> Result<(String, String), VenkyError> {
+        let assignee_id = match assignee {
+            Some(email) => self.user_id(email).await?,
+            None => None,
+        };
+        let data = self
+            .graphql(
+                "mutation($input: IssueCreateInput!) { issueCreate(input: $input) { success issue { identifier url } } }",
+                serde_json::json!({
+                    "input": {
+                        "teamId": team_id,
+                        "title": item.description,
+                        "description": description,
+                        "dueDate": due_date(item),
+                        "assigneeId": assignee_id,
+                    }
+                }),
+            )
+            .await?;
+        let issue = &data["issueCreate"]["issue"];
+        match (issue["identifier"].as_str(), issue["url"].as_str()) {
+            (Some(key), Some(url)) => Ok((key.to_string(), url.to_string())),
+            _ => Err(VenkyError::Provider(
+                "Linear did not create the issue".to_string(),
+            )),
+        }
+    }
+}
+
+enum Tracker<'a> {
+    Jira(Jira<'a>),
+    Linear(Linear<'a>),
+}
+
+impl<'a> Tracker<'a> {
+    fn new(provider: TicketProvider, config: &'a TicketConfig) -> Result<Self, VenkyError> {
+        match provider {
+            TicketProvider::Jira => Jira::new(config).map(Tracker::Jira),
+            TicketProvider::Linear => Linear::new(config).map(Tracker::Linear),
+        }
+    }
+
+    async fn projects(&self) -> Result<Vec<TicketProject>, VenkyError> {
+        match self {
+            Tracker::Jira(jira) => jira.projects().await,
+            Tracker::Linear(linear) => linear.teams().await,
+        }
+    }
+
+    /// File `item` and return the new ticket's key and URL
+    async fn create(
+        &self,
+        project_id: &str,
+        item: &ActionItem,
+        description: &str,
+        assignee: Option<&str>,
+    ) -> Result<(String, String), VenkyError> {
+        match self {
+            Tracker::Jira(jira) => jira.create(project_id, item, description, assignee).await,
+            Tracker::Linear(linear) => linear.create(project_id, item, description, assignee).await,
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_ticket_config(
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<TicketConfig> {
+    Ok(storage.call(|s| Ok(TicketConfig::load(s))).await?)
+}
+
+#[tauri::command]
+pub async fn update_ticket_config(
+    storage: tauri::State<'_, StorageActor>,
+    config: TicketConfig,
+) -> CommandResult<()> {
+    Ok(storage.call(move |s| config.save(s)).await?)
+}
+
+/// Jira projects or Linear teams the saved credentials can file tickets in
+#[tauri::command]
+pub async fn list_ticket_projects(
+    storage: tauri::State<'_, StorageActor>,
+    provider: TicketProvider,
+) -> CommandResult<Vec<TicketProject>> {
+    let config = storage.call(|s| Ok(TicketConfig::load(s))).await?;
+    Tracker::new(provider, &config)?.projects().await
+}
+
+/// File the chosen action items as Jira issues or Linear tickets and keep each ticket's key on
+/// its action item. Owners are assigned by email: `assignees` maps action item ids to emails,
+/// otherwise an owner that is an email or a participant's name is used. Items that already
+/// have a ticket are skipped.
+#[tauri::command]
+pub async fn create_tickets(
+    storage: tauri::State<'_, StorageActor>,
+    provider: TicketProvider,
+    project_id: String,
+    action_item_ids: Vec<String>,
+    assignees: Option<HashMap<String, String>>,
+) -> CommandResult<Vec<TicketResult>> {
+    let assignees = assignees.unwrap_or_default();
+    let (config, items, sessions) = storage
+        .call(move |s| {
+            let items: Vec<ActionItem> = s
+                .list_action_items(None, None)?
+                .into_iter()
+                .filter(|item| action_item_ids.contains(&item.id))
+                .collect();
+            let mut sessions = HashMap::new();
+            for item in &items {
+                if !sessions.contains_key(&item.session_id) {
+                    let session = s.get_session(&item.session_id)?;
+                    let participants = s.get_session_participants(&item.session_id)?;
+                    sessions.insert(item.session_id.clone(), (session, participants));
+                }
+            }
+            Ok((TicketConfig::load(s), items, sessions))
+        })
+        .await?;
+    if items.is_empty() {
+        return Err(VenkyError::Other(
+            "None of the action items were found".to_string(),
+        ));
+    }
+    let tracker = Tracker::new(provider, &config)?;
+
+    let mut results = Vec::new();
+    for item in &items {
+        if let Some(ref key) = item.issue_key {
+            results.push(TicketResult {
+                action_item_id: item.id.clone(),
+                success: true,
+                message: format!("Already filed as {}", key),
+                issue_key: Some(key.clone()),
+                issue_url: item.issue_url.clone(),
+            });
+            continue;
+        }
+
+        let (session, participants) = &sessions[&item.session_id];
+        let mut description = format!(
+            "Action item from \"{}\" ({}).",
+            session.title, session.start_time
+        );
+        if let Some(ref owner) = item.owner {
+            description.push_str(&format!("\n\nOwner: {}", owner));
+        }
+        let assignee = owner_email(item, &assignees, participants);
+
+        let created = tracker
+            .create(&project_id, item, &description, assignee.as_deref())
+            .await;
+        let (key, url) = match created {
+            Ok(created) => created,
+            Err(e) => {
+                results.push(TicketResult {
+                    action_item_id: item.id.clone(),
+                    success: false,
+                    message: e.to_string(),
+                    issue_key: None,
+                    issue_url: None,
+                });
+                continue;
+            }
+        };
+        let (id, issue_key, issue_url) = (item.id.clone(), key.clone(), url.clone());
+        let saved = storage
+            .call(move |s| s.set_action_item_issue(&id, &issue_key, &issue_url))
+            .await;
+        results.push(match saved {
+            Ok(()) => TicketResult {
+                action_item_id: item.id.clone(),
+                success: true,
+                message: format!("Created {}", key),
+                issue_key: Some(key),
+                issue_url: Some(url),
+            },
+            Err(e) => TicketResult {
+                action_item_id: item.id.clone(),
+                success: false,
+                message: format!("Created {} but couldn't save it: {}", key, e),
+                issue_key: Some(key),
+                issue_url: Some(url),
+            },
+        });
+    }
+    Ok(results)
+}
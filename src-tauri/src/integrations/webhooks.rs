@@ -0,0 +1,226 @@
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use sha2::Sha256;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::ConnectionTest;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::storage::WebhookSubscription;
+use crate::session::storage_actor::StorageActor;
+
+pub const SESSION_STARTED: &str = "session-started";
+pub const TRANSCRIPTION_CHUNK: &str = "transcription-chunk";
+pub const SUMMARY_READY: &str = "summary-ready";
+pub const ACTION_ITEM_CREATED: &str = "action-item-created";
+/// Events a webhook can subscribe to
+pub const EVENTS: [&str; 4] = [
+    SESSION_STARTED,
+    TRANSCRIPTION_CHUNK,
+    SUMMARY_READY,
+    ACTION_ITEM_CREATED,
+];
+
+/// Deliveries that fail with a network error, 429 or 5xx are retried, waiting 2, 4, 8... seconds
+const MAX_ATTEMPTS: u32 = 5;
+const DELIVERY_TIMEOUT_SECS: u64 = 15;
+
+/// Hex HMAC-SHA256 of `timestamp.body`, sent as `X-VenkyAI-Signature: sha256=<hex>`
+fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// A signed POST of `body`
+fn signed_request(
+    client: &Client,
+    webhook: &WebhookSubscription,
+    event: &str,
+    body: &[u8],
+) -> reqwest::RequestBuilder {
+    let timestamp = chrono::Utc::now().timestamp().to_string();
+    client
+        .post(&webhook.url)
+        .timeout(Duration::from_secs(DELIVERY_TIMEOUT_SECS))
+        .header("Content-Type", "application/json")
+        .header("X-VenkyAI-Event", event)
+        .header(
+            "X-VenkyAI-Signature",
+            format!("sha256={}", sign(&webhook.secret, &timestamp, body)),
+        )
+        .header("X-VenkyAI-Timestamp", timestamp)
+        .body(body.to_vec())
+}
+
+/// POST one payload, retrying transient failures; returns the final status
+async fn deliver(
+    client: &Client,
+    webhook: &WebhookSubscription,
+    event: &str,
+    body: &[u8],
+) -> Result<String, VenkyError> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        // Signed again on every attempt so the timestamp stays fresh
+        let result = signed_request(client, webhook, event, body).send().await;
+
+        let (outcome, retry) = match result {
+            Ok(response) if response.status().is_success() => {
+                return Ok(response.status().to_string())
+            }
+            Ok(response) => {
+                let status = response.status();
+                let retry = status.is_server_error() || status.as_u16() == 429;
+                (VenkyError::Provider(status.to_string()), retry)
+            }
+            Err(e) => (VenkyError::Network(format!("Request failed: {}", e)), true),
+        };
+        if !retry || attempt >= MAX_ATTEMPTS {
+            return Err(outcome);
+        }
+        tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+    }
+}
+
+/// POST `data` to every enabled webhook subscribed to `event`, in the background
+pub fn dispatch(app: &AppHandle, event: &'static str, data: serde_json::Value) {
+    let storage = app.state::<StorageActor>().inner().clone();
+    tauri::async_runtime::spawn(async move {
+        let webhooks: Vec<WebhookSubscription> = match storage.call(|s| s.get_webhooks()).await {
+            Ok(webhooks) => webhooks
+                .into_iter()
+                .filter(|w| w.enabled && w.events.iter().any(|e| e == event))
+                .collect(),
+            Err(e) => {
+                log::error!("Failed to load webhooks: {}", e);
+                return;
+            }
+        };
+        if webhooks.is_empty() {
+            return;
+        }
+
+        let payload = serde_json::json!({
+            "id": uuid::Uuid::new_v4().to_string(),
+            "event": event,
+            "created_at": chrono::Utc::now().to_rfc3339(),
+            "data": data,
+        });
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                log::error!("Failed to encode {} webhook payload: {}", event, e);
+                return;
+            }
+        };
+        let client = crate::http::client();
+        for webhook in webhooks {
+            let storage = storage.clone();
+            let client = client.clone();
+            let body = body.clone();
+            tauri::async_runtime::spawn(async move {
+                let status = match deliver(&client, &webhook, event, &body).await {
+                    Ok(status) => status,
+                    Err(e) => {
+                        log::warn!("Webhook {} failed for {}: {}", webhook.url, event, e);
+                        e.to_string()
+                    }
+                };
+                storage.send(move |s| {
+                    if let Err(e) = s.record_webhook_delivery(&webhook.id, &status) {
+                        log::error!("{}", e);
+                    }
+                });
+            });
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn list_webhooks(
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<Vec<WebhookSubscription>> {
+    Ok(storage.call(|s| s.get_webhooks()).await?)
+}
+
+/// Add or update a webhook; a new one gets an id and a signing secret
+#[tauri::command]
+pub async fn save_webhook(
+    storage: tauri::State<'_, StorageActor>,
+    mut webhook: WebhookSubscription,
+) -> CommandResult<WebhookSubscription> {
+    let url = reqwest::Url::parse(webhook.url.trim())
+        .map_err(|e| VenkyError::Other(format!("Invalid webhook URL: {}", e)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(VenkyError::Other(
+            "Webhook URLs must be http or https".to_string(),
+        ));
+    }
+    if let Some(unknown) = webhook
+        .events
+        .iter()
+        .find(|e| !EVENTS.contains(&e.as_str()))
+    {
+        return Err(VenkyError::Other(format!(
+            "Unknown webhook event: {}",
+            unknown
+        )));
+    }
+    webhook.url = url.to_string();
+    if webhook.id.is_empty() {
+        webhook.id = uuid::Uuid::new_v4().to_string();
+        webhook.created_at = chrono::Utc::now().to_rfc3339();
+    }
+    if webhook.secret.is_empty() {
+        webhook.secret = format!(
+            "whsec_{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+    }
+    let row = webhook.clone();
+    storage.call(move |s| s.save_webhook(&row)).await?;
+    Ok(webhook)
+}
+
+#[tauri::command]
+pub async fn delete_webhook(
+    storage: tauri::State<'_, StorageActor>,
+    id: String,
+) -> CommandResult<()> {
+    Ok(storage.call(move |s| s.delete_webhook(&id)).await?)
+}
+
+/// Send a signed "ping" event once, without retries
+#[tauri::command]
+pub async fn test_webhook(webhook: WebhookSubscription) -> CommandResult<ConnectionTest> {
+    let body = serde_json::to_vec(&serde_json::json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "event": "ping",
+        "created_at": chrono::Utc::now().to_rfc3339(),
+        "data": {},
+    }))
+    .map_err(|e| VenkyError::Other(e.to_string()))?;
+    Ok(ConnectionTest::run(async {
+        let response = signed_request(&crate::http::client(), &webhook, "ping", &body)
+            .send()
+            .await
+            .map_err(|e| VenkyError::Network(format!("Request failed: {}", e)))?;
+        let message = format!("Endpoint answered {}", response.status());
+        if response.status().is_success() {
+            Ok(message)
+        } else {
+            Err(VenkyError::Provider(message))
+        }
+    })
+    .await)
+}
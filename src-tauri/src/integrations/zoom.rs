@@ -0,0 +1,816 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::oauth::{self, Connection, OAuthClient, TokenResponse};
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+use crate::session::manager::{
+    Session, SessionManager, SessionPurpose, SessionStatus, TranscriptEntry,
+};
+use crate::session::storage::{Participant, Storage};
+use crate::session::storage_actor::StorageActor;
+use crate::session::titles::SessionTitle;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+type ZoomState = Arc<Mutex<ZoomConfig>>;
+
+/// Settings key the Zoom connection is stored under
+const SETTINGS_KEY: &str = "zoom";
+const AUTHORIZE_URL: &str = "https://zoom.us/oauth/authorize";
+const TOKEN_URL: &str = "https://zoom.us/oauth/token";
+const API_URL: &str = "https://api.zoom.us/v2";
+const SCOPES: &str = "user:read meeting:read recording:read";
+/// A scheduled meeting counts as the current one from this long before its start time
+const START_LEAD_MINS: i64 = 15;
+/// Zoom only lists recordings for up to a month per request
+const MAX_RECORDING_DAYS: u32 = 30;
+/// Held while the sign-in is refreshed, so refreshes take turns
+static REFRESHING: Lazy<tokio::sync::Mutex<()>> = Lazy::new(Default::default);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZoomConfig {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<String>,
+    pub account_email: Option<String>,
+    /// Retitle auto-started Zoom sessions and add their invitees
+    pub enrich_sessions: bool,
+}
+
+impl Default for ZoomConfig {
+    fn default() -> Self {
+        Self {
+            client_id: String::new(),
+            client_secret: None,
+            access_token: String::new(),
+            refresh_token: None,
+            expires_at: None,
+            account_email: None,
+            enrich_sessions: true,
+        }
+    }
+}
+
+impl ZoomConfig {
+    pub fn load(storage: &Storage) -> Self {
+        storage
+            .get_setting(SETTINGS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &Storage) -> Result<(), VenkyError> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| VenkyError::Storage(format!("Failed to save Zoom settings: {}", e)))?;
+        storage.set_setting(SETTINGS_KEY, Some(&json))
+    }
+}
+
+impl Connection for ZoomConfig {
+    const NOT_CONNECTED: &'static str = "Zoom is not connected";
+    const EXPIRED: &'static str = "Zoom session expired; reconnect Zoom";
+
+    fn is_connected(&self) -> bool {
+        !self.access_token.is_empty()
+    }
+
+    fn oauth_client(&self) -> OAuthClient<'_> {
+        OAuthClient {
+            authorize_url: AUTHORIZE_URL,
+            token_url: TOKEN_URL,
+            client_id: &self.client_id,
+            client_secret: self.client_secret.as_deref(),
+            scopes: SCOPES,
+            extra_params: &[],
+            redirect_uri: None,
+        }
+    }
+
+    fn expires_at(&self) -> Option<&str> {
+        self.expires_at.as_deref()
+    }
+
+    fn refresh_token(&self) -> Option<&str> {
+        self.refresh_token.as_deref()
+    }
+
+    fn set_tokens(&mut self, tokens: TokenResponse) {
+        self.expires_at = tokens.expires_at();
+        self.access_token = tokens.access_token;
+        // Zoom rotates refresh tokens; the old one stops working
+        if tokens.refresh_token.is_some() {
+            self.refresh_token = tokens.refresh_token;
+        }
+    }
+}
+
+/// Connection details safe to show in the UI (no tokens)
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoomStatus {
+    pub connected: bool,
+    pub account_email: Option<String>,
+    pub enrich_sessions: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoomParticipant {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoomMeeting {
+    /// The meeting number
+    pub id: String,
+    pub topic: String,
+    /// RFC 3339, UTC
+    pub start_time: Option<String>,
+    pub duration_mins: Option<u32>,
+    pub agenda: Option<String>,
+    pub join_url: Option<String>,
+    pub participants: Vec<ZoomParticipant>,
+}
+
+/// A meeting with cloud recordings
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoomRecording {
+    /// Identifies this occurrence of the meeting; pass it to `import_zoom_recording`
+    pub uuid: String,
+    pub meeting_id: String,
+    pub topic: String,
+    pub start_time: String,
+    pub duration_mins: u32,
+    /// Zoom's own transcript exists, so importing needs no speech-to-text
+    pub has_transcript: bool,
+    /// Size of the audio-only file, if there is one
+    pub audio_bytes: Option<u64>,
+}
+
+/// Emitted as "zoom-session-enriched"
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoomSessionEnriched {
+    pub session_id: String,
+    pub meeting: ZoomMeeting,
+}
+
+/// A valid access token, refreshing (and persisting) it first when it has expired
+pub async fn access_token(app: &AppHandle) -> Result<ZoomConfig, VenkyError> {
+    oauth::fresh_connection(
+        &REFRESHING,
+        &app.state::<ZoomState>(),
+        &app.state::<StorageActor>(),
+        ZoomConfig::save,
+    )
+    .await
+}
+
+async fn api_get<T: DeserializeOwned>(
+    config: &ZoomConfig,
+    path: &str,
+    query: &[(&str, &str)],
+) -> Result<T, VenkyError> {
+    let response = crate::http::client()
+        .get(format!("{}{}", API_URL, path))
+        .bearer_auth(&config.access_token)
+        .query(query)
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("Zoom request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(VenkyError::Provider(format!(
+            "Zoom API error ({}): {}",
+            status, body
+        )));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| VenkyError::Provider(format!("Parse error: {}", e)))
+}
+
+/// Meeting UUIDs that start with `/` or contain `//` must be encoded twice in a path
+fn encode_uuid(uuid: &str) -> String {
+    let once = percent_encode(uuid);
+    if uuid.starts_with('/') || uuid.contains("//") {
+        percent_encode(&once)
+    } else {
+        once
+    }
+}
+
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// ─── Meetings ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct MeetingList {
+    #[serde(default)]
+    meetings: Vec<ListedMeeting>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedMeeting {
+    id: u64,
+    #[serde(default)]
+    topic: String,
+    #[serde(default)]
+    start_time: Option<String>,
+    #[serde(default)]
+    duration: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeetingDetails {
+    #[serde(default)]
+    agenda: Option<String>,
+    #[serde(default)]
+    join_url: Option<String>,
+    #[serde(default)]
+    settings: Option<MeetingSettings>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeetingSettings {
+    #[serde(default)]
+    meeting_invitees: Vec<Invitee>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Invitee {
+    email: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PastParticipants {
+    #[serde(default)]
+    participants: Vec<PastParticipant>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PastParticipant {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    user_email: Option<String>,
+}
+
+async fn meeting_details(config: &ZoomConfig, listed: ListedMeeting) -> ZoomMeeting {
+    let path = format!("/meetings/{}", listed.id);
+    let details = match api_get::<MeetingDetails>(config, &path, &[]).await {
+        Ok(details) => Some(details),
+        Err(e) => {
+            log::warn!("Failed to load Zoom meeting {}: {}", listed.id, e);
+            None
+        }
+    };
+    let (agenda, join_url, invitees) = match details {
+        Some(d) => (
+            d.agenda.filter(|a| !a.trim().is_empty()),
+            d.join_url,
+            d.settings.map(|s| s.meeting_invitees).unwrap_or_default(),
+        ),
+        None => (None, None, Vec::new()),
+    };
+    ZoomMeeting {
+        id: listed.id.to_string(),
+        topic: listed.topic,
+        start_time: listed.start_time,
+        duration_mins: listed.duration,
+        agenda,
+        join_url,
+        participants: invitees
+            .into_iter()
+            .map(|i| ZoomParticipant {
+                name: None,
+                email: Some(i.email),
+            })
+            .collect(),
+    }
+}
+
+/// The meeting in progress, or else the scheduled one starting soonest around now
+pub async fn current_meeting(app: &AppHandle) -> Result<Option<ZoomMeeting>, VenkyError> {
+    let config = access_token(app).await?;
+
+    let live: MeetingList = api_get(&config, "/users/me/meetings", &[("type", "live")]).await?;
+    if let Some(meeting) = live.meetings.into_iter().next() {
+        return Ok(Some(meeting_details(&config, meeting).await));
+    }
+
+    let upcoming: MeetingList = api_get(
+        &config,
+        "/users/me/meetings",
+        &[("type", "upcoming"), ("page_size", "30")],
+    )
+    .await?;
+    let now = chrono::Utc::now();
+    let current = upcoming
+        .meetings
+        .into_iter()
+        .filter_map(|m| {
+            let start = chrono::DateTime::parse_from_rfc3339(m.start_time.as_deref()?).ok()?;
+            let end = start + chrono::Duration::minutes(m.duration.unwrap_or(60) as i64);
+            let starts_soon = start <= now + chrono::Duration::minutes(START_LEAD_MINS);
+            (starts_soon && end > now).then(|| ((start - now).num_seconds().abs(), m))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, m)| m);
+    match current {
+        Some(meeting) => Ok(Some(meeting_details(&config, meeting).await)),
+        None => Ok(None),
+    }
+}
+
+fn add_participants(
+    app: &AppHandle,
+    session_id: &str,
+    participants: &[ZoomParticipant],
+    own_email: Option<&str>,
+) {
+    // People who dropped and rejoined are listed once per join
+    let mut seen = HashSet::new();
+    let mut added = Vec::new();
+    for p in participants {
+        if p.email.is_some() && p.email.as_deref() == own_email {
+            continue;
+        }
+        let Some(name) = p.name.clone().or_else(|| p.email.clone()) else {
+            continue;
+        };
+        if !seen.insert((name.to_lowercase(), p.email.clone())) {
+            continue;
+        }
+        added.push(Participant {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            name,
+            email: p.email.clone(),
+            role: None,
+            crm_contact_id: None,
+            source: "zoom".to_string(),
+        });
+    }
+    app.state::<StorageActor>().send(move |s| {
+        for participant in &added {
+            if let Err(e) = s.add_participant(participant) {
+                log::warn!("Failed to add Zoom participant: {}", e);
+            }
+        }
+    });
+}
+
+/// Give a session the current Zoom meeting's topic and invitees
+async fn enrich_session(app: &AppHandle, session_id: &str) -> Result<(), VenkyError> {
+    let Some(meeting) = current_meeting(app).await? else {
+        return Ok(());
+    };
+
+    let topic = meeting.topic.trim().to_string();
+    if !topic.is_empty() {
+        let (id, title) = (session_id.to_string(), topic.clone());
+        app.state::<StorageActor>()
+            .call(move |s| s.set_session_title(&id, &title, None))
+            .await?;
+        if let Some(live) = app
+            .state::<SessionState>()
+            .lock()
+            .sessions
+            .get_mut(session_id)
+        {
+            live.title = topic.clone();
+        }
+        let _ = app.emit(
+            "session-title-updated",
+            SessionTitle {
+                session_id: session_id.to_string(),
+                title: topic,
+                description: None,
+            },
+        );
+    }
+
+    let own_email = app.state::<ZoomState>().lock().account_email.clone();
+    add_participants(app, session_id, &meeting.participants, own_email.as_deref());
+
+    let _ = app.emit(
+        "zoom-session-enriched",
+        ZoomSessionEnriched {
+            session_id: session_id.to_string(),
+            meeting,
+        },
+    );
+    Ok(())
+}
+
+/// In the background, enrich a session auto-started for a Zoom window, if Zoom is connected
+pub fn enrich_if_connected(app: &AppHandle, session_id: &str) {
+    let enabled = {
+        let zoom = app.state::<ZoomState>();
+        let zoom = zoom.lock();
+        zoom.is_connected() && zoom.enrich_sessions
+    };
+    if !enabled {
+        return;
+    }
+
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = enrich_session(&app, &session_id).await {
+            log::warn!("Zoom enrichment for session {} failed: {}", session_id, e);
+        }
+    });
+}
+
+// ─── Cloud recordings ────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct RecordingList {
+    #[serde(default)]
+    meetings: Vec<RecordedMeeting>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordedMeeting {
+    uuid: String,
+    id: u64,
+    #[serde(default)]
+    topic: String,
+    start_time: String,
+    #[serde(default)]
+    duration: u32,
+    #[serde(default)]
+    recording_files: Vec<RecordingFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordingFile {
+    #[serde(default)]
+    file_type: String,
+    #[serde(default)]
+    file_size: Option<u64>,
+    #[serde(default)]
+    download_url: Option<String>,
+}
+
+impl RecordedMeeting {
+    fn file(&self, file_type: &str) -> Option<&RecordingFile> {
+        self.recording_files
+            .iter()
+            .find(|f| f.file_type == file_type && f.download_url.is_some())
+    }
+
+    fn summary(&self) -> ZoomRecording {
+        ZoomRecording {
+            uuid: self.uuid.clone(),
+            meeting_id: self.id.to_string(),
+            topic: self.topic.clone(),
+            start_time: self.start_time.clone(),
+            duration_mins: self.duration,
+            has_transcript: self.file("TRANSCRIPT").is_some(),
+            audio_bytes: self.file("M4A").and_then(|f| f.file_size),
+        }
+    }
+}
+
+async fn download(config: &ZoomConfig, file: &RecordingFile) -> Result<Vec<u8>, VenkyError> {
+    let url = file
+        .download_url
+        .as_deref()
+        .ok_or_else(|| VenkyError::Provider("Recording file has no download link".to_string()))?;
+    let response = crate::http::client()
+        .get(url)
+        .bearer_auth(&config.access_token)
+        .send()
+        .await
+        .map_err(|e| VenkyError::Network(format!("Recording download failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(VenkyError::Provider(format!(
+            "Recording download failed ({})",
+            status
+        )));
+    }
+    response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| VenkyError::Network(format!("Recording download failed: {}", e)))
+}
+
+/// Seconds from the start of a `HH:MM:SS.mmm` WebVTT (or `HH:MM:SS,mmm` SRT) cue time
+fn vtt_seconds(time: &str) -> Option<f64> {
+    let mut secs = 0.0;
+    for part in time.trim().replace(',', ".").split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(secs)
+}
+
+/// Cues of a WebVTT or SRT transcript. Zoom writes the speaker as "Speaker Name: text",
+/// Teams as a `<v Speaker Name>` voice tag.
+pub fn parse_vtt(vtt: &str) -> Vec<(f64, Option<String>, String)> {
+    let mut cues = Vec::new();
+    for block in vtt.replace("\r\n", "\n").split("\n\n") {
+        let mut lines = block.lines().skip_while(|l| !l.contains("-->"));
+        let Some(start) = lines
+            .next()
+            .and_then(|l| l.split("-->").next())
+            .and_then(vtt_seconds)
+        else {
+            continue;
+        };
+        let text = lines.collect::<Vec<_>>().join(" ");
+        if let Some((speaker, rest)) = text
+            .strip_prefix("<v ")
+            .and_then(|voiced| voiced.split_once('>'))
+        {
+            let text = rest.replace("</v>", "").trim().to_string();
+            if !text.is_empty() {
+                cues.push((start, Some(speaker.trim().to_string()), text));
+            }
+            continue;
+        }
+        let (speaker, text) = match text.split_once(": ") {
+            Some((speaker, rest)) if speaker.len() <= 60 => {
+                (Some(speaker.trim().to_string()), rest.trim().to_string())
+            }
+            _ => (None, text.trim().to_string()),
+        };
+        if !text.is_empty() {
+            cues.push((start, speaker, text));
+        }
+    }
+    cues
+}
+
+/// Zoom's transcript when the meeting has one, else the audio-only file run through Whisper
+async fn transcribe_recording(
+    cfg: &AppConfig,
+    config: &ZoomConfig,
+    meeting: &RecordedMeeting,
+) -> Result<Vec<TranscriptEntry>, VenkyError> {
+    let start = chrono::DateTime::parse_from_rfc3339(&meeting.start_time)
+        .map_err(|e| VenkyError::Other(format!("Invalid recording start time: {}", e)))?;
+    let entry = |offset_secs: f64, speaker: String, text: String| TranscriptEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: (start + chrono::Duration::milliseconds((offset_secs * 1000.0) as i64))
+            .to_rfc3339(),
+        speaker,
+        text,
+        source: Some("zoom".to_string()),
+    };
+
+    if let Some(file) = meeting.file("TRANSCRIPT") {
+        let vtt = String::from_utf8_lossy(&download(config, file).await?).into_owned();
+        return Ok(parse_vtt(&vtt)
+            .into_iter()
+            .map(|(offset, speaker, text)| {
+                entry(
+                    offset,
+                    speaker.unwrap_or_else(|| "Others".to_string()),
+                    text,
+                )
+            })
+            .collect());
+    }
+
+    let file = meeting.file("M4A").ok_or_else(|| {
+        VenkyError::Other("This recording has no transcript or audio-only file".to_string())
+    })?;
+    if file.file_size.unwrap_or(0) as usize > crate::ai::stt::MAX_UPLOAD_BYTES {
+        return Err(VenkyError::Other(
+            "The recording's audio is too large to transcribe; enable Zoom's cloud transcripts"
+                .to_string(),
+        ));
+    }
+    let audio = download(config, file).await?;
+    let segments =
+        crate::ai::stt::transcribe_file_segments(cfg, audio, "audio.m4a", "audio/mp4").await?;
+    Ok(segments
+        .into_iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .map(|s| entry(s.start, "Others".to_string(), s.text.trim().to_string()))
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_zoom_status(zoom: tauri::State<'_, ZoomState>) -> ZoomStatus {
+    let config = zoom.lock();
+    ZoomStatus {
+        connected: config.is_connected(),
+        account_email: config.account_email.clone(),
+        enrich_sessions: config.enrich_sessions,
+    }
+}
+
+/// Sign in to Zoom in the browser and keep the tokens. `redirect_uri` is the loopback
+/// redirect URL set on the Zoom app, which Zoom matches exactly.
+#[tauri::command]
+pub async fn connect_zoom(
+    app: AppHandle,
+    client_id: String,
+    client_secret: Option<String>,
+    redirect_uri: Option<String>,
+) -> CommandResult<ZoomStatus> {
+    if client_id.trim().is_empty() {
+        return Err(VenkyError::Other(
+            "A Zoom OAuth client ID is required".to_string(),
+        ));
+    }
+
+    #[derive(Deserialize)]
+    struct Me {
+        email: Option<String>,
+    }
+
+    let mut config = ZoomConfig {
+        client_id: client_id.trim().to_string(),
+        client_secret: client_secret.filter(|s| !s.trim().is_empty()),
+        enrich_sessions: app.state::<ZoomState>().lock().enrich_sessions,
+        ..ZoomConfig::default()
+    };
+    let client = OAuthClient {
+        redirect_uri: redirect_uri
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty()),
+        ..config.oauth_client()
+    };
+    let tokens = oauth::authorize(&app, &client).await?;
+    config.access_token = tokens.access_token.clone();
+    config.refresh_token = tokens.refresh_token.clone();
+    config.expires_at = tokens.expires_at();
+    config.account_email = api_get::<Me>(&config, "/users/me", &[])
+        .await
+        .ok()
+        .and_then(|me| me.email);
+
+    let row = config.clone();
+    app.state::<StorageActor>()
+        .call(move |s| row.save(s))
+        .await?;
+    *app.state::<ZoomState>().lock() = config;
+    Ok(get_zoom_status(app.state()))
+}
+
+#[tauri::command]
+pub async fn disconnect_zoom(
+    zoom: tauri::State<'_, ZoomState>,
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<()> {
+    let config = {
+        let mut config = zoom.lock();
+        *config = ZoomConfig {
+            enrich_sessions: config.enrich_sessions,
+            ..ZoomConfig::default()
+        };
+        config.clone()
+    };
+    Ok(storage.call(move |s| config.save(s)).await?)
+}
+
+#[tauri::command]
+pub async fn set_zoom_enrich_sessions(
+    zoom: tauri::State<'_, ZoomState>,
+    storage: tauri::State<'_, StorageActor>,
+    enabled: bool,
+) -> CommandResult<()> {
+    let config = {
+        let mut config = zoom.lock();
+        config.enrich_sessions = enabled;
+        config.clone()
+    };
+    Ok(storage.call(move |s| config.save(s)).await?)
+}
+
+/// The Zoom meeting in progress or about to start, with its invitees
+#[tauri::command]
+pub async fn get_current_zoom_meeting(app: AppHandle) -> CommandResult<Option<ZoomMeeting>> {
+    current_meeting(&app).await
+}
+
+/// Meetings with cloud recordings from the last `days` (default 7, at most 30), newest first
+#[tauri::command]
+pub async fn list_zoom_recordings(
+    app: AppHandle,
+    days: Option<u32>,
+) -> CommandResult<Vec<ZoomRecording>> {
+    let config = access_token(&app).await?;
+    let to = chrono::Utc::now().date_naive();
+    let from = to - chrono::Duration::days(days.unwrap_or(7).clamp(1, MAX_RECORDING_DAYS) as i64);
+    let (from, to) = (from.to_string(), to.to_string());
+    let list: RecordingList = api_get(
+        &config,
+        "/users/me/recordings",
+        &[("from", &from), ("to", &to), ("page_size", "100")],
+    )
+    .await?;
+
+    let mut recordings: Vec<ZoomRecording> =
+        list.meetings.iter().map(RecordedMeeting::summary).collect();
+    recordings.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    Ok(recordings)
+}
+
+/// Transcribe a cloud recording into `session_id`'s transcript (replacing it), or into a
+/// new saved session with the meeting's topic, time and participants
+#[tauri::command]
+pub async fn import_zoom_recording(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+    uuid: String,
+    session_id: Option<String>,
+) -> CommandResult<Session> {
+    let cfg = config.lock().clone();
+    let zoom = access_token(&app).await?;
+    let path = format!("/meetings/{}/recordings", encode_uuid(&uuid));
+    let meeting: RecordedMeeting = api_get(&zoom, &path, &[]).await?;
+    let transcript = transcribe_recording(&cfg, &zoom, &meeting).await?;
+    if transcript.is_empty() {
+        return Err(VenkyError::Other("The recording has no speech".to_string()));
+    }
+
+    let storage = app.state::<StorageActor>().inner().clone();
+    if let Some(session_id) = session_id {
+        return Ok(storage
+            .call(move |s| {
+                s.update_transcript(&session_id, &transcript)?;
+                s.get_session(&session_id)
+            })
+            .await?);
+    }
+
+    let start = chrono::DateTime::parse_from_rfc3339(&meeting.start_time)
+        .map_err(|e| VenkyError::Other(format!("Invalid recording start time: {}", e)))?;
+    let session = Session {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: meeting.topic.clone(),
+        description: None,
+        meeting_window: None,
+        purpose: SessionPurpose::Meeting,
+        context: None,
+        prompt_template_id: None,
+        status: SessionStatus::Ended,
+        start_time: start.to_rfc3339(),
+        end_time: Some((start + chrono::Duration::minutes(meeting.duration as i64)).to_rfc3339()),
+        transcript: Vec::new(),
+        suggestions: Vec::new(),
+        summary: None,
+    };
+    let row = session.clone();
+    storage
+        .call(move |s| {
+            s.save_session(&row)?;
+            s.update_transcript(&row.id, &transcript)
+        })
+        .await?;
+
+    // Past participants need a paid plan on some accounts; the transcript stands without them
+    let path = format!("/past_meetings/{}/participants", encode_uuid(&uuid));
+    match api_get::<PastParticipants>(&zoom, &path, &[("page_size", "300")]).await {
+        Ok(past) => {
+            let participants: Vec<ZoomParticipant> = past
+                .participants
+                .into_iter()
+                .map(|p| ZoomParticipant {
+                    name: p.name,
+                    email: p.user_email.filter(|e| !e.is_empty()),
+                })
+                .collect();
+            add_participants(
+                &app,
+                &session.id,
+                &participants,
+                zoom.account_email.as_deref(),
+            );
+        }
+        Err(e) => log::warn!("Zoom participants for {} unavailable: {}", uuid, e),
+    }
+
+    Ok(storage.call(move |s| s.get_session(&session.id)).await?)
+}
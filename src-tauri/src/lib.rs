@@ -1,24 +1,43 @@
 mod ai;
+mod api_server;
+mod autostart;
+mod backup;
 mod capture;
+pub mod cli;
 mod config;
+mod error;
+mod events;
+mod hotkeys;
+mod http;
 mod integrations;
+mod logging;
+mod metrics;
+mod notifications;
+mod overlay;
+mod profiles;
+mod redaction;
 mod session;
+mod settings_bundle;
+mod shutdown;
+mod stealth;
+mod tray;
+mod windows;
 
 use parking_lot::Mutex;
 use std::sync::Arc;
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
-    Emitter, Manager,
-};
+use tauri::{Emitter, Manager};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    env_logger::init();
-
     tauri::Builder::default()
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![autostart::HIDDEN_FLAG]),
+        ))
         .setup(|app| {
             // ─── State Management ────────────────────────────────────────
             let app_data = app
@@ -26,109 +45,157 @@ pub fn run() {
                 .app_data_dir()
                 .expect("Failed to get app data dir");
             std::fs::create_dir_all(&app_data).ok();
+            logging::init(&app_data);
+            capture::ocr::init(&app_data);
+            backup::remove_leftovers(&app_data);
 
             // App config
-            let app_config = config::AppConfig::load(&app_data);
+            let (app_config, config_diagnostics) = config::AppConfig::load(&app_data);
+            let startup_config = app_config.clone();
+            http::configure(&startup_config);
             let config_state = Arc::new(Mutex::new(app_config));
             app.manage(config_state);
+            app.manage(Arc::new(Mutex::new(config_diagnostics)));
 
             // Capture state
             let capture_state = Arc::new(Mutex::new(capture::CaptureState::default()));
             app.manage(capture_state);
 
-            // Session state
-            let session_state = Arc::new(Mutex::new(
-                session::manager::SessionManager::new(),
-            ));
-            app.manage(session_state);
+            // Audio engine state
+            let audio_engine = Arc::new(Mutex::new(capture::audio::AudioEngine::new()));
+            app.manage(audio_engine);
+            app.manage(capture::recording::EndedSpansState::default());
+
+            // Screen recorder state
+            let screen_recorder =
+                Arc::new(Mutex::new(capture::screen_recording::ScreenRecorder::new()));
+            app.manage(screen_recorder);
 
             // Storage state
             let db_path = app_data.join("venkyai.db");
-            let storage = session::storage::Storage::new(&db_path)
-                .expect("Failed to initialize storage");
-            let storage_state = Arc::new(Mutex::new(storage));
-            app.manage(storage_state);
+            // A database that won't open leaves the app running locked until it is unlocked
+            let (storage, lock_error) = match session::encryption::open_database(&db_path) {
+                Ok(storage) => (storage, None),
+                Err(e) => {
+                    log::error!("Database is locked: {}", e);
+                    let placeholder = session::encryption::locked_placeholder()
+                        .expect("Failed to initialize storage");
+                    (placeholder, Some(e.to_string()))
+                }
+            };
+            app.manage(Arc::new(Mutex::new(
+                session::encryption::DatabaseLock::new(&db_path, lock_error),
+            )));
+            let calendar_config = integrations::calendar::CalendarConfig::load(&storage);
+            let crm_config = integrations::CRMConfig::load(&storage);
+            let email_config = integrations::email::EmailConfig::load(&storage);
+            let zoom_config = integrations::zoom::ZoomConfig::load(&storage);
+            // Everything that touches SQLite from here on hands its work to this thread
+            let storage_actor = session::storage_actor::StorageActor::spawn(storage)
+                .expect("Failed to start storage thread");
+            app.manage(storage_actor.clone());
+
+            // Session state, resuming a session cut off by a crash
+            let session_state = Arc::new(Mutex::new(session::manager::SessionManager::new(
+                storage_actor,
+                app.handle().clone(),
+            )));
+            app.manage(session_state);
+            redaction::init(app.handle());
+            metrics::init(app.handle());
+            app.manage(ai::live_engine::AutoSessionState::default());
+            app.manage(session::consent::ConsentState::default());
 
             // CRM state
-            let crm_state = Arc::new(Mutex::new(integrations::CRMConfig::default()));
+            let crm_state = Arc::new(Mutex::new(crm_config));
             app.manage(crm_state);
 
-            // ─── System Tray ─────────────────────────────────────────────
-            let show_item = MenuItem::with_id(app, "show", "Show VenkyAI", true, None::<&str>)
-                .expect("failed to create menu item");
-            let hide_item = MenuItem::with_id(app, "hide", "Hide VenkyAI", true, None::<&str>)
-                .expect("failed to create menu item");
-            let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)
-                .expect("failed to create menu item");
-
-            let menu = Menu::with_items(app, &[&show_item, &hide_item, &quit_item])
-                .expect("failed to create menu");
-
-            // Load icon from embedded bytes — decode the PNG to raw RGBA
-            let icon_png_bytes = include_bytes!("../icons/32x32.png");
-            let icon_image = image::load_from_memory(icon_png_bytes)
-                .expect("Failed to decode icon PNG");
-            let rgba = icon_image.to_rgba8();
-            let (w, h) = rgba.dimensions();
-            let tray_icon = tauri::image::Image::new_owned(rgba.into_raw(), w, h);
-
-            let _tray = TrayIconBuilder::new()
-                .icon(tray_icon)
-                .tooltip("VenkyAI — AI Meeting Assistant")
-                .menu(&menu)
-                .on_menu_event(move |app, event| match event.id.as_ref() {
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("overlay") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    "hide" => {
-                        if let Some(window) = app.get_webview_window("overlay") {
-                            let _ = window.hide();
-                        }
-                    }
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    _ => {}
-                })
-                .build(app)
-                .expect("Failed to create tray icon");
-
-            // ─── Global Hotkey (Cmd+Shift+C / Ctrl+Shift+C) ─────────────
-            use tauri_plugin_global_shortcut::GlobalShortcutExt;
-
-            let app_handle = app.handle().clone();
-            app.global_shortcut().on_shortcut("CmdOrCtrl+Shift+C", move |_app, _shortcut, _event| {
-                if let Some(window) = app_handle.get_webview_window("overlay") {
-                    if window.is_visible().unwrap_or(false) {
-                        let _ = window.hide();
-                        let _ = app_handle.emit("overlay-visibility", false);
-                    } else {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                        let _ = app_handle.emit("overlay-visibility", true);
-                    }
+            // Calendar connection, kept in the settings table
+            app.manage(Arc::new(Mutex::new(calendar_config)));
+
+            // Account follow-up emails are sent from
+            app.manage(Arc::new(Mutex::new(email_config)));
+
+            // Zoom connection, kept in the settings table
+            app.manage(Arc::new(Mutex::new(zoom_config)));
+
+            // Keep the suggestion panel (every window in stealth mode) out of captures and
+            // screen shares
+            apply_content_protection(app.handle(), &startup_config);
+            // Where the overlay was left, and how it was set up
+            overlay::init(app.handle(), &startup_config);
+            // The overlay starts hidden so a launch at login stays in the tray
+            if !autostart::start_hidden(&startup_config) {
+                if let Some(window) = app.get_webview_window("overlay") {
+                    let _ = window.show();
                 }
-            }).expect("Failed to register global shortcut");
+            }
+            autostart::sync(app.handle(), startup_config.launch_at_login);
 
-            log::info!("VenkyAI initialized. Hotkey: Cmd/Ctrl+Shift+C");
+            // ─── System Tray ─────────────────────────────────────────────
+            tray::init(app.handle()).expect("Failed to create tray icon");
+
+            // ─── Global Hotkeys (rebindable in settings) ─────────────────
+            hotkeys::register_all(app.handle(), &startup_config);
+
+            // ─── Action item reminders ───────────────────────────────────
+            notifications::start_reminders(app.handle());
+
+            log::info!("VenkyAI initialized. Hotkey: {}", startup_config.hotkey);
+
+            // Localhost API for external tools, when enabled
+            let mut api_server = api_server::ApiServer::new();
+            api_server.start(app.handle());
+            app.manage(Arc::new(Mutex::new(api_server)));
+
+            // Meeting detection, transcription and suggestions; restarted when settings change
+            let mut live_engine = ai::live_engine::LiveEngine::new();
+            live_engine.start(app.handle());
+            app.manage(Arc::new(Mutex::new(live_engine)));
+
+            let handle_devices = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                capture::audio::device_watch_loop(handle_devices).await;
+            });
 
-            // Spawn background monitoring tasks
-            let handle = app.handle().clone();
+            let handle_screen = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                ai::live_engine::LiveEngine::start_monitoring(handle).await;
+                capture::screen::screen_context_loop(handle_screen).await;
             });
 
-            let handle_transcribe = app.handle().clone();
+            let handle_calendar = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                ai::live_engine::transcription_loop(handle_transcribe).await;
+                integrations::calendar::calendar_loop(handle_calendar).await;
             });
 
-            let handle_suggestions = app.handle().clone();
+            let handle_slides = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                ai::live_engine::suggestion_loop(handle_suggestions).await;
+                capture::slides::slide_watch_loop(handle_slides).await;
+            });
+
+            let handle_titles = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                session::titles::title_loop(handle_titles).await;
+            });
+
+            let handle_share = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                stealth::share_watch_loop(handle_share).await;
+            });
+
+            let handle_retention = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                session::retention::retention_loop(handle_retention).await;
+            });
+
+            let handle_clipboard = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                ai::clipboard::clipboard_watch_loop(handle_clipboard).await;
+            });
+
+            let handle_sync = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                integrations::sync::sync_loop(handle_sync).await;
             });
 
             Ok(())
@@ -137,54 +204,254 @@ pub fn run() {
             // Config
             config::get_config,
             config::update_config,
+            config::get_config_diagnostics,
+            // Global hotkeys
+            hotkeys::get_hotkeys,
+            hotkeys::set_hotkey,
+            // Settings profiles
+            profiles::list_profiles,
+            profiles::switch_profile,
+            profiles::duplicate_profile,
+            // Settings export/import
+            settings_bundle::export_settings,
+            settings_bundle::import_settings,
+            backup::create_backup,
+            backup::restore_backup,
             // Screen capture
             capture::screen::capture_screen,
+            capture::screen::capture_window,
+            capture::screen::capture_screen_to_file,
+            capture::privacy::get_privacy_status,
+            capture::privacy::resume_live_ai,
+            session::storage::get_session_captures,
+            capture::screen_recording::get_session_recording_path,
+            capture::screen::list_monitors,
+            capture::screen::capture_monitor,
+            capture::screen::capture_all_monitors,
+            capture::screen::list_capture_targets,
             // Audio capture
             capture::audio::start_audio_capture,
             capture::audio::stop_audio_capture,
             capture::audio::get_audio_status,
             capture::audio::start_system_audio_capture,
             capture::audio::stop_system_audio_capture,
+            capture::audio::list_audio_targets,
+            capture::audio::pause_audio_capture,
+            capture::audio::resume_audio_capture,
+            capture::audio::pause_system_audio_capture,
+            capture::audio::resume_system_audio_capture,
+            capture::audio::set_capture_muted,
+            capture::recording::get_session_audio_path,
+            // Permissions
+            capture::permissions::check_permissions,
+            capture::permissions::request_permission,
+            capture::recording::delete_session_audio,
             // AI / LLM
             ai::llm::ask_ai,
             ai::llm::ask_ai_with_context,
             ai::llm::get_available_providers,
+            ai::llm::test_openai_key,
+            ai::llm::test_ollama_connection,
             // Speech-to-text
             ai::stt::transcribe_audio,
+            ai::stt::retranscribe_session,
+            ai::stt::test_stt_provider,
+            // Text-to-speech
+            ai::tts::speak_text,
+            ai::tts::stop_speaking,
+            ai::tts::list_output_devices,
             // Streaming
             ai::streaming::stream_chat,
+            ai::quick_ask::ask_about_recent,
+            ai::clipboard::copy_suggestion_to_clipboard,
             // Session management
             session::manager::create_session,
             session::manager::end_session,
             session::manager::add_transcript_entry,
+            session::manager::update_transcript_entry,
+            session::manager::mark_highlight,
+            session::manager::delete_transcript_entry,
             session::manager::get_current_session,
+            session::manager::get_active_sessions,
+            session::manager::get_interrupted_sessions,
+            session::manager::resume_session,
+            session::manager::focus_session,
+            ai::live_engine::confirm_auto_session,
+            ai::live_engine::enable_live_suggestions,
+            ai::live_engine::disable_live_suggestions,
+            ai::live_engine::get_live_engine_status,
+            ai::live_engine::restart_live_engine,
             session::manager::get_session_transcript,
             session::manager::generate_summary,
+            // Session history
+            session::storage::get_all_sessions,
+            session::storage::get_session_by_id,
+            session::retention::delete_session,
+            session::retention::purge_all_data,
+            session::storage::search_sessions,
+            session::storage::get_transcript_edits,
+            session::storage::get_session_highlights,
+            session::storage::accept_suggestion,
+            session::teleprompter::stop_teleprompter,
+            session::type_out::type_out_answer,
+            session::type_out::stop_typing,
+            session::stats::get_session_stats,
+            session::action_items::extract_action_items,
+            session::summary::get_summary_templates,
+            session::summary::save_summary_template,
+            session::summary::delete_summary_template,
+            session::summary::set_purpose_summary_template,
+            session::summary::generate_summary_for_session,
+            session::summary::generate_summary_by_id,
+            session::action_items::list_action_items,
+            session::action_items::complete_action_item,
+            session::chapters::generate_chapters,
+            session::chapters::get_session_chapters,
+            session::interview::extract_interview_qa,
+            session::interview::get_interview_qa,
+            session::interview::export_interview_qa,
+            session::export::export_session,
+            session::export::export_all_sessions,
+            session::subtitles::export_subtitles,
+            session::vault::export_to_vault,
+            session::import::import_transcript,
+            session::titles::refresh_session_title,
+            session::keywords::get_keyword_watches,
+            session::keywords::save_keyword_watch,
+            session::keywords::delete_keyword_watch,
+            session::storage::add_participant,
+            session::storage::remove_participant,
+            session::storage::get_session_participants,
+            session::storage::populate_participants_from_speakers,
+            session::storage::get_prompt_templates,
+            session::storage::save_prompt_template,
+            session::storage::delete_prompt_template,
+            // Database encryption
+            session::encryption::get_database_encryption,
+            session::encryption::enable_database_encryption,
+            session::encryption::disable_database_encryption,
+            session::encryption::rotate_database_key,
+            session::encryption::unlock_database,
+            // PII redaction
+            redaction::get_redaction_report,
+            redaction::preview_redaction,
+            // Recording consent
+            session::consent::confirm_consent,
+            session::consent::get_consent_record,
             // CRM integration
             integrations::crm::get_crm_config,
             integrations::crm::update_crm_config,
+            integrations::crm::connect_crm,
             integrations::crm::crm_sync_contact,
             integrations::crm::crm_sync_notes,
+            session::contacts::extract_contacts,
+            integrations::crm::crm_attach_transcript,
+            integrations::crm::crm_sync_action_items,
+            integrations::crm::crm_list_deals,
+            integrations::crm::crm_log_meeting,
             integrations::crm::get_crm_providers,
+            integrations::crm::test_crm_connection,
+            // Notifiers
+            integrations::notify::list_notifiers,
+            integrations::notify::save_notifier,
+            integrations::notify::delete_notifier,
+            integrations::notify::test_notifier,
+            // Local API server
+            api_server::get_api_server_status,
+            api_server::regenerate_api_token,
+            // Outbound webhooks
+            integrations::webhooks::list_webhooks,
+            integrations::webhooks::save_webhook,
+            integrations::webhooks::delete_webhook,
+            integrations::webhooks::test_webhook,
+            // Email
+            integrations::email::get_email_config,
+            integrations::email::update_email_config,
+            integrations::email::connect_email,
+            integrations::email::disconnect_email,
+            integrations::email::send_followup_email,
+            // Issue trackers
+            integrations::tickets::get_ticket_config,
+            integrations::tickets::update_ticket_config,
+            integrations::tickets::list_ticket_projects,
+            integrations::tickets::create_tickets,
+            // Cloud sync
+            integrations::sync::get_sync_config,
+            integrations::sync::update_sync_config,
+            integrations::sync::sync_now,
+            // Calendar integration
+            integrations::calendar::get_calendar_status,
+            integrations::calendar::connect_calendar,
+            integrations::calendar::disconnect_calendar,
+            integrations::calendar::set_calendar_auto_sessions,
+            integrations::calendar::list_upcoming_events,
+            integrations::calendar::create_followup_event,
+            // Zoom
+            integrations::zoom::get_zoom_status,
+            integrations::zoom::connect_zoom,
+            integrations::zoom::disconnect_zoom,
+            integrations::zoom::set_zoom_enrich_sessions,
+            integrations::zoom::get_current_zoom_meeting,
+            integrations::zoom::list_zoom_recordings,
+            integrations::zoom::import_zoom_recording,
             // Overlay control
             toggle_overlay,
+            overlay::set_overlay_opacity,
+            overlay::set_overlay_click_through,
+            overlay::set_always_on_top,
+            overlay::move_overlay,
+            // History and settings windows
+            windows::open_window,
+            windows::close_window,
+            windows::list_open_windows,
+            // Usage dashboard
+            metrics::get_usage_dashboard,
+            // Event delivery
+            events::get_event_stats,
+            // Logs and diagnostics
+            logging::get_recent_logs,
+            logging::export_diagnostics_bundle,
+            // Launch at login
+            autostart::get_launch_at_login,
+            // Stealth mode
+            stealth::set_stealth_mode,
+            stealth::toggle_stealth_mode,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running VenkyAI");
+        .build(tauri::generate_context!())
+        .expect("error while running VenkyAI")
+        .run(|app, event| {
+            // Tray Quit and OS shutdown both end here
+            if let tauri::RunEvent::Exit = event {
+                shutdown::finalize(app);
+            }
+        });
+}
+
+/// Exclude the overlay, or every window in stealth mode, from screen capture at the OS level
+/// (macOS/Windows)
+fn apply_content_protection(app: &tauri::AppHandle, cfg: &config::AppConfig) {
+    for (label, window) in app.webview_windows() {
+        let protected = cfg.stealth_mode || (label == "overlay" && cfg.protect_overlay);
+        if let Err(e) = window.set_content_protected(protected) {
+            log::error!("Failed to set content protection of {}: {}", label, e);
+        }
+    }
 }
 
 #[tauri::command]
-fn toggle_overlay(app: tauri::AppHandle) -> Result<bool, String> {
+fn toggle_overlay(app: tauri::AppHandle) -> error::CommandResult<bool> {
     let window = app
         .get_webview_window("overlay")
-        .ok_or("Overlay window not found")?;
+        .ok_or_else(|| error::VenkyError::Other("Overlay window not found".to_string()))?;
 
     let visible = window.is_visible().unwrap_or(false);
+    let failed =
+        |e: tauri::Error| error::VenkyError::Other(format!("Failed to toggle overlay: {}", e));
     if visible {
-        window.hide().map_err(|e| e.to_string())?;
+        window.hide().map_err(failed)?;
     } else {
-        window.show().map_err(|e| e.to_string())?;
-        window.set_focus().map_err(|e| e.to_string())?;
+        window.show().map_err(failed)?;
+        window.set_focus().map_err(failed)?;
     }
 
     let _ = app.emit("overlay-visibility", !visible);
@@ -0,0 +1,220 @@
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+use crate::ai::live_engine::{LiveEngineState, LiveEngineStatus};
+use crate::config::{AppConfig, ConfigDiagnostic, ConfigDiagnosticsState};
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+const LOG_DIR: &str = "logs";
+const LOG_PREFIX: &str = "venkyai";
+/// Days of log files kept
+const MAX_LOG_FILES: usize = 7;
+/// Log lines included in a diagnostics bundle
+const BUNDLE_LOG_LINES: usize = 2000;
+
+/// Keeps the background log writer alive (and flushing) for the life of the app
+static WRITER_GUARD: OnceCell<WorkerGuard> = OnceCell::new();
+
+/// One line of a log file
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// How lines are written by tracing's JSON formatter
+#[derive(Debug, Deserialize)]
+struct JsonLine {
+    timestamp: String,
+    level: String,
+    #[serde(default)]
+    target: String,
+    #[serde(default)]
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// What a bug report needs, minus anything secret
+#[derive(Debug, Serialize)]
+struct DiagnosticsBundle {
+    app_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    created_at: String,
+    /// Settings with API keys blanked
+    config: AppConfig,
+    config_diagnostics: Vec<ConfigDiagnostic>,
+    live_engine: LiveEngineStatus,
+    logs: Vec<LogEntry>,
+}
+
+fn log_dir(app_data: &Path) -> PathBuf {
+    app_data.join(LOG_DIR)
+}
+
+/// Log to stderr and to daily JSON files under app data; `RUST_LOG` sets the level
+/// (default info). `log` macros are forwarded, so existing call sites keep working.
+pub fn init(app_data: &Path) {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir(app_data));
+    let file_layer = match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let _ = WRITER_GUARD.set(guard);
+            Some(
+                fmt::layer()
+                    .json()
+                    .with_current_span(false)
+                    .with_span_list(false)
+                    .with_writer(writer),
+            )
+        }
+        Err(e) => {
+            eprintln!("Failed to open log files: {}", e);
+            None
+        }
+    };
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    if let Err(e) = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer)
+        .try_init()
+    {
+        eprintln!("Failed to set up logging: {}", e);
+    }
+}
+
+fn severity(level: &str) -> u8 {
+    match level.to_uppercase().as_str() {
+        "ERROR" => 4,
+        "WARN" => 3,
+        "INFO" => 2,
+        "DEBUG" => 1,
+        _ => 0,
+    }
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let line: JsonLine = serde_json::from_str(line).ok()?;
+    let message = match line.fields.get("message") {
+        Some(serde_json::Value::String(message)) => message.clone(),
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    // Records forwarded from `log` carry their real target as a field
+    let target = match line.fields.get("log.target") {
+        Some(serde_json::Value::String(target)) => target.clone(),
+        _ => line.target,
+    };
+    Some(LogEntry {
+        timestamp: line.timestamp,
+        level: line.level,
+        target,
+        message,
+    })
+}
+
+/// The newest `limit` entries at `min_level` or above, newest first
+fn read_recent(
+    app_data: &Path,
+    min_level: &str,
+    limit: usize,
+) -> Result<Vec<LogEntry>, VenkyError> {
+    let dir = log_dir(app_data);
+    let Ok(read_dir) = std::fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    // Daily files are named <prefix>.<date>.log, so names sort oldest to newest
+    let mut files: Vec<PathBuf> = read_dir
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(LOG_PREFIX))
+        })
+        .collect();
+    files.sort();
+
+    let min = severity(min_level);
+    let mut entries = Vec::new();
+    for file in files.iter().rev() {
+        let content = std::fs::read_to_string(file).map_err(|e| {
+            VenkyError::Storage(format!("Failed to read {}: {}", file.display(), e))
+        })?;
+        for entry in content.lines().rev().filter_map(parse_line) {
+            if severity(&entry.level) >= min {
+                entries.push(entry);
+                if entries.len() >= limit {
+                    return Ok(entries);
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, VenkyError> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))
+}
+
+/// Latest log entries, newest first. `level` is the lowest level shown (default "info").
+#[tauri::command]
+pub fn get_recent_logs(
+    app: AppHandle,
+    level: Option<String>,
+    limit: Option<usize>,
+) -> CommandResult<Vec<LogEntry>> {
+    read_recent(
+        &app_data_dir(&app)?,
+        level.as_deref().unwrap_or("info"),
+        limit.unwrap_or(200),
+    )
+}
+
+/// Write app version, platform, settings, config problems, live engine status and recent
+/// logs to one JSON file to attach to a bug report. Settings leave out API keys, proxy
+/// credentials and text the user wrote, such as redaction patterns.
+#[tauri::command]
+pub fn export_diagnostics_bundle(app: AppHandle, path: String) -> CommandResult<()> {
+    let mut config = app.state::<ConfigState>().lock().without_secrets();
+    // May name customers or their data
+    config.redaction_patterns.clear();
+    config.consent_disclosure_text.clear();
+    let config_diagnostics = app.state::<ConfigDiagnosticsState>().lock().clone();
+    let live_engine = app.state::<LiveEngineState>().lock().status();
+    let bundle = DiagnosticsBundle {
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        config,
+        config_diagnostics,
+        live_engine,
+        logs: read_recent(&app_data_dir(&app)?, "debug", BUNDLE_LOG_LINES)?,
+    };
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| VenkyError::Other(format!("Serialize error: {}", e)))?;
+    Ok(std::fs::write(&path, json)
+        .map_err(|e| VenkyError::Storage(format!("Failed to write {}: {}", path, e)))?)
+}
@@ -1,5 +1,20 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Release builds get no console on Windows, so borrow the terminal the CLI was run from
+    #[cfg(windows)]
+    if std::env::args().len() > 1 {
+        // Fails harmlessly when launched from Explorer or the login item
+        unsafe {
+            windows_sys::Win32::System::Console::AttachConsole(
+                windows_sys::Win32::System::Console::ATTACH_PARENT_PROCESS,
+            );
+        }
+    }
+
+    // `venkyai transcribe|summarize|export ...` runs headless and exits
+    if let Some(code) = venky_ai_lib::cli::run_from_args() {
+        std::process::exit(code);
+    }
     venky_ai_lib::run()
 }
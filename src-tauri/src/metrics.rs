@@ -0,0 +1,209 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager};
+
+use crate::error::{CommandResult, VenkyError};
+use crate::session::storage::Storage;
+use crate::session::storage_actor::StorageActor;
+
+pub const TOKENS: &str = "tokens";
+pub const CRM_SYNC: &str = "crm_sync";
+pub const CRM_SYNC_FAILED: &str = "crm_sync_failed";
+
+/// Set once at startup so AI and CRM code can record usage without a handle of their own
+static APP: OnceCell<AppHandle> = OnceCell::new();
+
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// Count `amount` of `kind` against `provider`; a no-op outside the app (e.g. the CLI)
+pub fn record(kind: &str, provider: &str, amount: i64) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    if amount <= 0 {
+        return;
+    }
+    let (kind, provider) = (kind.to_string(), provider.to_string());
+    app.state::<StorageActor>().send(move |s| {
+        if let Err(e) = s.record_usage_event(&kind, &provider, amount) {
+            log::warn!("{}", e);
+        }
+    });
+}
+
+pub fn record_tokens(provider: &str, tokens: u64) {
+    record(TOKENS, provider, tokens as i64);
+}
+
+/// How far back the dashboard looks; the last 30 days and less are counted per day, longer
+/// ranges per week
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageRange {
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl UsageRange {
+    fn days(self) -> i64 {
+        match self {
+            UsageRange::Week => 7,
+            UsageRange::Month => 30,
+            UsageRange::Quarter => 91,
+            UsageRange::Year => 365,
+        }
+    }
+
+    fn weekly(self) -> bool {
+        matches!(self, UsageRange::Quarter | UsageRange::Year)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UsageBucket {
+    /// First day of the bucket, YYYY-MM-DD (local time)
+    pub start: String,
+    pub sessions: u32,
+    /// Minutes of ended sessions
+    pub minutes_transcribed: f64,
+    /// Tokens spent, by provider
+    pub tokens: BTreeMap<String, u64>,
+    pub suggestions_generated: u32,
+    pub suggestions_accepted: u32,
+    pub crm_syncs: u32,
+    pub crm_sync_failures: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageDashboard {
+    pub range: UsageRange,
+    /// "day" or "week"
+    pub bucket: &'static str,
+    /// Oldest first, including empty days/weeks
+    pub buckets: Vec<UsageBucket>,
+    pub totals: UsageBucket,
+}
+
+fn local_date(timestamp: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Local).date_naive())
+}
+
+fn bucket_start(date: NaiveDate, weekly: bool) -> NaiveDate {
+    if weekly {
+        date - Duration::days(date.weekday().num_days_from_monday() as i64)
+    } else {
+        date
+    }
+}
+
+fn bucket_for<'a>(
+    buckets: &'a mut BTreeMap<NaiveDate, UsageBucket>,
+    timestamp: &str,
+    weekly: bool,
+) -> Option<&'a mut UsageBucket> {
+    local_date(timestamp).and_then(|date| buckets.get_mut(&bucket_start(date, weekly)))
+}
+
+fn minutes_between(start: &str, end: &str) -> Option<f64> {
+    let start = DateTime::parse_from_rfc3339(start).ok()?;
+    let end = DateTime::parse_from_rfc3339(end).ok()?;
+    Some(((end - start).num_seconds().max(0)) as f64 / 60.0)
+}
+
+fn add(totals: &mut UsageBucket, bucket: &UsageBucket) {
+    totals.sessions += bucket.sessions;
+    totals.minutes_transcribed += bucket.minutes_transcribed;
+    for (provider, tokens) in &bucket.tokens {
+        *totals.tokens.entry(provider.clone()).or_default() += tokens;
+    }
+    totals.suggestions_generated += bucket.suggestions_generated;
+    totals.suggestions_accepted += bucket.suggestions_accepted;
+    totals.crm_syncs += bucket.crm_syncs;
+    totals.crm_sync_failures += bucket.crm_sync_failures;
+}
+
+fn dashboard(storage: &Storage, range: UsageRange) -> Result<UsageDashboard, VenkyError> {
+    let weekly = range.weekly();
+    let today = Local::now().date_naive();
+    let first = bucket_start(today - Duration::days(range.days() - 1), weekly);
+    let since = first
+        .and_hms_opt(0, 0, 0)
+        .and_then(|t| t.and_local_timezone(Local).earliest())
+        .map(|t| t.with_timezone(&chrono::Utc).to_rfc3339())
+        .ok_or_else(|| VenkyError::Other("Invalid date range".to_string()))?;
+
+    let mut buckets: BTreeMap<NaiveDate, UsageBucket> = BTreeMap::new();
+    let step = if weekly { 7 } else { 1 };
+    let mut day = first;
+    while day <= today {
+        buckets.insert(
+            day,
+            UsageBucket {
+                start: day.format("%Y-%m-%d").to_string(),
+                ..Default::default()
+            },
+        );
+        day += Duration::days(step);
+    }
+
+    for (start, end) in storage.session_times_since(&since)? {
+        if let Some(bucket) = bucket_for(&mut buckets, &start, weekly) {
+            bucket.sessions += 1;
+            if let Some(minutes) = end.as_deref().and_then(|end| minutes_between(&start, end)) {
+                bucket.minutes_transcribed += minutes;
+            }
+        }
+    }
+    for (created_at, accepted) in storage.suggestion_times_since(&since)? {
+        if let Some(bucket) = bucket_for(&mut buckets, &created_at, weekly) {
+            bucket.suggestions_generated += 1;
+            if accepted {
+                bucket.suggestions_accepted += 1;
+            }
+        }
+    }
+    for event in storage.usage_events_since(&since)? {
+        let Some(bucket) = bucket_for(&mut buckets, &event.created_at, weekly) else {
+            continue;
+        };
+        match event.kind.as_str() {
+            TOKENS => *bucket.tokens.entry(event.provider).or_default() += event.amount as u64,
+            CRM_SYNC => bucket.crm_syncs += 1,
+            CRM_SYNC_FAILED => bucket.crm_sync_failures += 1,
+            _ => {}
+        }
+    }
+
+    let buckets: Vec<UsageBucket> = buckets.into_values().collect();
+    let mut totals = UsageBucket {
+        start: first.format("%Y-%m-%d").to_string(),
+        ..Default::default()
+    };
+    for bucket in &buckets {
+        add(&mut totals, bucket);
+    }
+    Ok(UsageDashboard {
+        range,
+        bucket: if weekly { "week" } else { "day" },
+        buckets,
+        totals,
+    })
+}
+
+/// Sessions, transcribed minutes, tokens per provider, suggestions and CRM syncs over
+/// `range`, per day or week
+#[tauri::command]
+pub async fn get_usage_dashboard(
+    storage: tauri::State<'_, StorageActor>,
+    range: UsageRange,
+) -> CommandResult<UsageDashboard> {
+    Ok(storage.call(move |s| dashboard(s, range)).await?)
+}
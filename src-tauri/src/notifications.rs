@@ -0,0 +1,98 @@
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::config::AppConfig;
+use crate::error::VenkyError;
+use crate::session::storage_actor::StorageActor;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+const REMINDER_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Whether notifications are on, and on for the event `pick` selects; stealth mode silences
+/// them all
+fn enabled(app: &AppHandle, pick: fn(&AppConfig) -> bool) -> bool {
+    let cfg = app.state::<ConfigState>();
+    let cfg = cfg.lock();
+    cfg.desktop_notifications && !cfg.stealth_mode && pick(&cfg)
+}
+
+fn show(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("Failed to show notification \"{}\": {}", title, e);
+    }
+}
+
+pub fn meeting_detected(app: &AppHandle, window_title: &str) {
+    if enabled(app, |c| c.notify_meeting_detected) {
+        show(app, "Meeting detected", window_title);
+    }
+}
+
+pub fn session_auto_started(app: &AppHandle, session_title: &str) {
+    if enabled(app, |c| c.notify_session_auto_started) {
+        show(
+            app,
+            "Recording started",
+            &format!("VenkyAI started a session for {}", session_title),
+        );
+    }
+}
+
+pub fn summary_ready(app: &AppHandle, session_title: &str) {
+    if enabled(app, |c| c.notify_summary_ready) {
+        show(
+            app,
+            "Summary ready",
+            &format!("The summary of {} is ready", session_title),
+        );
+    }
+}
+
+pub fn crm_sync_failed(app: &AppHandle, message: &str) {
+    if enabled(app, |c| c.notify_crm_sync_failed) {
+        show(app, "CRM sync failed", message);
+    }
+}
+
+/// Notify once about each open action item that is due today or overdue
+async fn remind_due_action_items(app: &AppHandle) -> Result<(), VenkyError> {
+    if !enabled(app, |c| c.notify_action_item_reminders) {
+        return Ok(());
+    }
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let storage = app.state::<StorageActor>();
+    let day = today.clone();
+    let due = storage.call(move |s| s.due_action_items(&day)).await?;
+    for item in due {
+        let title = match item.due_date.as_deref() {
+            Some(date) if date.starts_with(&today) => "Action item due today",
+            _ => "Action item overdue",
+        };
+        let body = match item.owner.as_deref() {
+            Some(owner) if !owner.trim().is_empty() => format!("{} ({})", item.description, owner),
+            _ => item.description.clone(),
+        };
+        show(app, title, &body);
+        storage
+            .call(move |s| s.mark_action_item_reminded(&item.id))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Check for due action items now and every 15 minutes
+pub fn start_reminders(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = remind_due_action_items(&app).await {
+                log::warn!("Action item reminders failed: {}", e);
+            }
+            tokio::time::sleep(REMINDER_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,233 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+const OVERLAY: &str = "overlay";
+const GEOMETRY_FILE: &str = "overlay-window.json";
+/// Moves and resizes arrive many times a second while dragging; save once they settle
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+/// Gap between the overlay and the screen edge when snapped to a corner, in logical pixels
+const CORNER_MARGIN: f64 = 16.0;
+
+/// Bumped on every move/resize so only the last one in a burst is saved
+static GEOMETRY_CHANGES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Overlay position and size in physical pixels, kept across launches
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+fn window(app: &AppHandle) -> Result<WebviewWindow, VenkyError> {
+    app.get_webview_window(OVERLAY)
+        .ok_or_else(|| VenkyError::Other("Overlay window not found".to_string()))
+}
+
+fn geometry_path(app: &AppHandle) -> Result<PathBuf, VenkyError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(GEOMETRY_FILE))
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))
+}
+
+fn save_geometry(app: &AppHandle) -> Result<(), VenkyError> {
+    let window = window(app)?;
+    let position = window
+        .outer_position()
+        .map_err(|e| VenkyError::Other(e.to_string()))?;
+    let size = window
+        .inner_size()
+        .map_err(|e| VenkyError::Other(e.to_string()))?;
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+    let json = serde_json::to_string(&geometry)
+        .map_err(|e| VenkyError::Other(format!("Serialize error: {}", e)))?;
+    let path = geometry_path(app)?;
+    std::fs::write(&path, json)
+        .map_err(|e| VenkyError::Storage(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+fn schedule_save(app: &AppHandle) {
+    let change = GEOMETRY_CHANGES.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DELAY).await;
+        if GEOMETRY_CHANGES.load(Ordering::SeqCst) != change {
+            return;
+        }
+        if let Err(e) = save_geometry(&app) {
+            log::warn!("Failed to save overlay position: {}", e);
+        }
+    });
+}
+
+/// Put the overlay back where it was last left, unless that spot is no longer on any screen
+fn restore_geometry(app: &AppHandle, window: &WebviewWindow) -> Result<(), VenkyError> {
+    let path = geometry_path(app)?;
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let geometry: WindowGeometry = serde_json::from_str(&content)
+        .map_err(|e| VenkyError::Other(format!("Failed to parse {}: {}", path.display(), e)))?;
+
+    let monitors = window
+        .available_monitors()
+        .map_err(|e| VenkyError::Other(e.to_string()))?;
+    let on_screen = monitors.iter().any(|m| {
+        let (pos, size) = (m.position(), m.size());
+        geometry.x >= pos.x
+            && geometry.y >= pos.y
+            && geometry.x < pos.x + size.width as i32
+            && geometry.y < pos.y + size.height as i32
+    });
+    if !on_screen {
+        log::info!("Saved overlay position is off-screen; using the default");
+        return Ok(());
+    }
+    window
+        .set_size(PhysicalSize::new(geometry.width, geometry.height))
+        .map_err(|e| VenkyError::Other(e.to_string()))?;
+    window
+        .set_position(PhysicalPosition::new(geometry.x, geometry.y))
+        .map_err(|e| VenkyError::Other(e.to_string()))
+}
+
+/// Apply the overlay settings from `cfg`; opacity is left to the frontend
+pub fn apply_settings(app: &AppHandle, cfg: &AppConfig) {
+    let Ok(window) = window(app) else {
+        return;
+    };
+    if let Err(e) = window.set_always_on_top(cfg.overlay_always_on_top) {
+        log::error!("Failed to set overlay always-on-top: {}", e);
+    }
+    let _ = app.emit("overlay-opacity", cfg.overlay_opacity);
+}
+
+/// Restore the saved geometry and settings at launch, and save the geometry whenever the
+/// overlay is moved or resized
+pub fn init(app: &AppHandle, cfg: &AppConfig) {
+    let Ok(window) = window(app) else {
+        return;
+    };
+    if let Err(e) = restore_geometry(app, &window) {
+        log::warn!("Failed to restore overlay position: {}", e);
+    }
+    apply_settings(app, cfg);
+
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if matches!(
+            event,
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)
+        ) {
+            schedule_save(&handle);
+        }
+    });
+}
+
+fn update_config(
+    app: &AppHandle,
+    config: &ConfigState,
+    change: impl FnOnce(&mut AppConfig),
+) -> Result<AppConfig, VenkyError> {
+    let mut cfg = config.lock().clone();
+    change(&mut cfg);
+    cfg.validate()?;
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))?;
+    cfg.save(&app_data)?;
+    *config.lock() = cfg.clone();
+    Ok(cfg)
+}
+
+/// Set how opaque the overlay is (0.2-1.0); the frontend gets "overlay-opacity"
+#[tauri::command]
+pub fn set_overlay_opacity(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+    opacity: f64,
+) -> CommandResult<()> {
+    let cfg = update_config(&app, &config, |c| c.overlay_opacity = opacity)?;
+    let _ = app.emit("overlay-opacity", cfg.overlay_opacity);
+    Ok(())
+}
+
+/// Let clicks pass through the overlay to the window below. Not saved, so a restart always
+/// brings back a clickable overlay.
+#[tauri::command]
+pub fn set_overlay_click_through(app: AppHandle, enabled: bool) -> CommandResult<()> {
+    window(&app)?
+        .set_ignore_cursor_events(enabled)
+        .map_err(|e| VenkyError::Other(format!("Failed to set click-through: {}", e)))?;
+    let _ = app.emit("overlay-click-through", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_always_on_top(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+    enabled: bool,
+) -> CommandResult<()> {
+    window(&app)?
+        .set_always_on_top(enabled)
+        .map_err(|e| VenkyError::Other(format!("Failed to set always-on-top: {}", e)))?;
+    update_config(&app, &config, |c| c.overlay_always_on_top = enabled)?;
+    Ok(())
+}
+
+/// Snap the overlay to a corner of the screen it is on
+#[tauri::command]
+pub fn move_overlay(app: AppHandle, corner: OverlayCorner) -> CommandResult<()> {
+    let window = window(&app)?;
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| VenkyError::Other(e.to_string()))?
+        .ok_or_else(|| VenkyError::Other("The overlay is not on any screen".to_string()))?;
+    let area = monitor.work_area();
+    let size = window
+        .outer_size()
+        .map_err(|e| VenkyError::Other(e.to_string()))?;
+    let margin = (CORNER_MARGIN * monitor.scale_factor()).round() as i32;
+
+    let left = area.position.x + margin;
+    let right = area.position.x + area.size.width as i32 - size.width as i32 - margin;
+    let top = area.position.y + margin;
+    let bottom = area.position.y + area.size.height as i32 - size.height as i32 - margin;
+    let (x, y) = match corner {
+        OverlayCorner::TopLeft => (left, top),
+        OverlayCorner::TopRight => (right, top),
+        OverlayCorner::BottomLeft => (left, bottom),
+        OverlayCorner::BottomRight => (right, bottom),
+    };
+    Ok(window
+        .set_position(PhysicalPosition::new(x.max(left), y.max(top)))
+        .map_err(|e| VenkyError::Other(format!("Failed to move overlay: {}", e)))?)
+}
@@ -0,0 +1,175 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+/// Used until the user creates a profile of their own
+const DEFAULT_PROFILE: &str = "default";
+const MAX_NAME_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+}
+
+/// Profiles are full configs in `profiles/<name>.json`; the active one also lives in
+/// config.json, which is what the app loads at startup
+fn profiles_dir(app: &AppHandle) -> Result<PathBuf, VenkyError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))?
+        .join("profiles");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| VenkyError::Storage(format!("Failed to create profiles dir: {}", e)))?;
+    Ok(dir)
+}
+
+fn profile_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.json", name))
+}
+
+/// Profile names become file names, so only letters, digits, spaces, `-` and `_` are allowed
+fn check_name(name: &str) -> Result<String, VenkyError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+        return Err(VenkyError::Other(format!(
+            "Profile names must be 1 to {} characters",
+            MAX_NAME_CHARS
+        )));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+    {
+        return Err(VenkyError::Other(
+            "Profile names can only contain letters, digits, spaces, - and _".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn active_profile(dir: &Path) -> String {
+    std::fs::read_to_string(dir.join("active"))
+        .map(|name| name.trim().to_string())
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+fn write_profile(dir: &Path, name: &str, config: &AppConfig) -> Result<(), VenkyError> {
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| VenkyError::Other(format!("Failed to serialize profile: {}", e)))?;
+    std::fs::write(profile_path(dir, name), content)
+        .map_err(|e| VenkyError::Storage(format!("Failed to save profile {}: {}", name, e)))
+}
+
+fn read_profile(dir: &Path, name: &str) -> Result<AppConfig, VenkyError> {
+    let content = std::fs::read_to_string(profile_path(dir, name))
+        .map_err(|e| VenkyError::Storage(format!("Failed to read profile {}: {}", name, e)))?;
+    let (config, diagnostics) = AppConfig::from_json(&content)?;
+    for diagnostic in diagnostics {
+        log::warn!("Profile {}: {}", name, diagnostic.message);
+    }
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> CommandResult<Vec<ProfileInfo>> {
+    let dir = profiles_dir(&app)?;
+    let active = active_profile(&dir);
+
+    let mut names: Vec<String> = std::fs::read_dir(&dir)
+        .map_err(|e| VenkyError::Storage(format!("Failed to list profiles: {}", e)))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            path.file_stem()?.to_str().map(str::to_string)
+        })
+        .collect();
+    if !names.contains(&active) {
+        names.push(active.clone());
+    }
+    names.sort();
+
+    Ok(names
+        .into_iter()
+        .map(|name| ProfileInfo {
+            active: name == active,
+            name,
+        })
+        .collect())
+}
+
+/// Save the running settings to the active profile, then load `name`'s provider keys,
+/// hotkeys and auto-start preferences. Emits "active-profile-changed".
+#[tauri::command]
+pub fn switch_profile(
+    app: AppHandle,
+    config_state: tauri::State<'_, ConfigState>,
+    name: String,
+) -> CommandResult<AppConfig> {
+    let dir = profiles_dir(&app)?;
+    let name = check_name(&name)?;
+    let active = active_profile(&dir);
+    if name == active {
+        return Ok(config_state.lock().clone());
+    }
+    if !profile_path(&dir, &name).exists() {
+        return Err(VenkyError::Other(format!("Profile {} not found", name)));
+    }
+
+    let config = read_profile(&dir, &name)?;
+    config.validate()?;
+    write_profile(&dir, &active, &config_state.lock())?;
+
+    let app_data = dir.parent().unwrap_or(dir.as_path());
+    config.save(app_data)?;
+    std::fs::write(dir.join("active"), &name)
+        .map_err(|e| VenkyError::Storage(format!("Failed to save active profile: {}", e)))?;
+    crate::config::apply(&app, &config_state, config.clone());
+
+    log::info!("Switched settings profile from {} to {}", active, name);
+    let _ = app.emit("active-profile-changed", &name);
+    Ok(config)
+}
+
+/// Copy a profile (the running settings, for the active one) under a new name
+#[tauri::command]
+pub fn duplicate_profile(
+    app: AppHandle,
+    config_state: tauri::State<'_, ConfigState>,
+    source: String,
+    name: String,
+) -> CommandResult<ProfileInfo> {
+    let dir = profiles_dir(&app)?;
+    let source = check_name(&source)?;
+    let name = check_name(&name)?;
+    if name == active_profile(&dir) || profile_path(&dir, &name).exists() {
+        return Err(VenkyError::Other(format!(
+            "A profile named {} already exists",
+            name
+        )));
+    }
+
+    let config = if source == active_profile(&dir) {
+        config_state.lock().clone()
+    } else {
+        read_profile(&dir, &source)?
+    };
+    write_profile(&dir, &name, &config)?;
+    Ok(ProfileInfo {
+        name,
+        active: false,
+    })
+}
@@ -0,0 +1,356 @@
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use crate::config::AppConfig;
+use crate::error::CommandResult;
+use crate::session::manager::{Session, SessionManager};
+use crate::session::storage::RedactionCount;
+use crate::session::storage_actor::StorageActor;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+static EMAIL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b").unwrap());
+/// 13-19 digits, optionally grouped by spaces or dashes; only Luhn-valid numbers are masked
+static CARD: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(?:\d[ -]?){12,18}\d\b").unwrap());
+/// Digit runs with phone punctuation; only those with a plausible digit count are masked
+static PHONE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\+?\(?\d[\d ().-]{6,}\d").unwrap());
+
+/// Set once at startup so redaction can find the session a request is made for
+static APP: OnceCell<AppHandle> = OnceCell::new();
+
+tokio::task_local! {
+    /// Session the current task's cloud requests belong to
+    static SESSION: String;
+}
+
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// Run `fut` with its cloud requests counted in `session_id`'s redaction report. Without
+/// this, requests are counted against the focused live session.
+pub async fn for_session<F: Future>(session_id: &str, fut: F) -> F::Output {
+    SESSION.scope(session_id.to_string(), fut).await
+}
+
+fn digits(text: &str) -> Vec<u32> {
+    text.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+fn is_card(text: &str) -> bool {
+    let digits = digits(text);
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2, d * 2) {
+            (1, doubled) if doubled > 9 => doubled - 9,
+            (1, doubled) => doubled,
+            _ => d,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+/// Ten or more digits, or eight or more after a `+`, so years and times are left alone
+fn is_phone(text: &str) -> bool {
+    let count = digits(text).len();
+    (10..=15).contains(&count) || (text.starts_with('+') && (8..=15).contains(&count))
+}
+
+fn any(_: &str) -> bool {
+    true
+}
+
+struct Rule {
+    kind: &'static str,
+    regex: Regex,
+    accept: fn(&str) -> bool,
+}
+
+/// Masks personal data in text bound for the cloud with placeholders like `[EMAIL_1]`.
+/// A value always gets the same placeholder, so replies can be mapped back locally.
+pub struct Redactor {
+    rules: Vec<Rule>,
+    /// (original, placeholder)
+    replacements: Vec<(String, String)>,
+    /// Masked occurrences per kind not yet added to the report
+    counts: BTreeMap<&'static str, u32>,
+    session_id: Option<String>,
+}
+
+/// Participant names of a session, whole and by part, longest first so full names win
+async fn names_regex(session_id: &str) -> Option<Regex> {
+    let app = APP.get()?;
+    let id = session_id.to_string();
+    let participants = app
+        .state::<StorageActor>()
+        .call(move |s| s.get_session_participants(&id))
+        .await
+        .ok()?;
+    let mut names: Vec<String> = Vec::new();
+    for participant in participants {
+        let name = participant.name.trim();
+        // Email-only participants are covered by the email rule
+        if name.is_empty() || name.contains('@') {
+            continue;
+        }
+        names.push(name.to_string());
+        names.extend(
+            name.split_whitespace()
+                .filter(|part| part.chars().count() >= 3)
+                .map(str::to_string),
+        );
+    }
+    if names.is_empty() {
+        return None;
+    }
+    names.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    names.dedup();
+    let alternatives = names
+        .iter()
+        .map(|n| regex::escape(n))
+        .collect::<Vec<_>>()
+        .join("|");
+    Regex::new(&format!(r"(?i)\b(?:{})\b", alternatives)).ok()
+}
+
+fn focused_session() -> Option<String> {
+    let app = APP.get()?;
+    let state = app.state::<SessionState>();
+    let focused = state.lock().focused_id.clone();
+    focused
+}
+
+impl Redactor {
+    /// A redactor for the current task's session; it changes nothing when redaction is off
+    pub async fn new(cfg: &AppConfig) -> Self {
+        let session_id = SESSION
+            .try_with(|id| id.clone())
+            .ok()
+            .or_else(focused_session);
+        Self::build(cfg, session_id).await
+    }
+
+    /// A redactor for text taken from `session_id`
+    pub async fn for_session_id(cfg: &AppConfig, session_id: &str) -> Self {
+        Self::build(cfg, Some(session_id.to_string())).await
+    }
+
+    async fn build(cfg: &AppConfig, session_id: Option<String>) -> Self {
+        let mut rules = Vec::new();
+        if cfg.redact_pii {
+            if cfg.redact_emails {
+                rules.push(Rule {
+                    kind: "email",
+                    regex: EMAIL.clone(),
+                    accept: any,
+                });
+            }
+            // Before phone numbers, which would also match most card numbers
+            if cfg.redact_credit_cards {
+                rules.push(Rule {
+                    kind: "card",
+                    regex: CARD.clone(),
+                    accept: is_card,
+                });
+            }
+            if cfg.redact_phone_numbers {
+                rules.push(Rule {
+                    kind: "phone",
+                    regex: PHONE.clone(),
+                    accept: is_phone,
+                });
+            }
+            for pattern in &cfg.redaction_patterns {
+                match Regex::new(pattern) {
+                    Ok(regex) => rules.push(Rule {
+                        kind: "custom",
+                        regex,
+                        accept: any,
+                    }),
+                    Err(e) => log::warn!("Skipping redaction pattern {}: {}", pattern, e),
+                }
+            }
+            if cfg.redact_names {
+                let names = match session_id.as_deref() {
+                    Some(id) => names_regex(id).await,
+                    None => None,
+                };
+                if let Some(regex) = names {
+                    rules.push(Rule {
+                        kind: "name",
+                        regex,
+                        accept: any,
+                    });
+                }
+            }
+        }
+        Self {
+            rules,
+            replacements: Vec::new(),
+            counts: BTreeMap::new(),
+            session_id,
+        }
+    }
+
+    fn placeholder(&mut self, kind: &'static str, original: &str) -> String {
+        *self.counts.entry(kind).or_insert(0) += 1;
+        if let Some((_, placeholder)) = self
+            .replacements
+            .iter()
+            .find(|(o, _)| o.eq_ignore_ascii_case(original))
+        {
+            return placeholder.clone();
+        }
+        let prefix = format!("[{}_", kind.to_uppercase());
+        let n = self
+            .replacements
+            .iter()
+            .filter(|(_, p)| p.starts_with(&prefix))
+            .count();
+        let placeholder = format!("{}{}]", prefix, n + 1);
+        self.replacements
+            .push((original.to_string(), placeholder.clone()));
+        placeholder
+    }
+
+    pub fn redact(&mut self, text: &str) -> String {
+        let mut text = text.to_string();
+        for i in 0..self.rules.len() {
+            let (regex, accept, kind) = {
+                let rule = &self.rules[i];
+                (rule.regex.clone(), rule.accept, rule.kind)
+            };
+            let mut out = String::with_capacity(text.len());
+            let mut last = 0;
+            for m in regex.find_iter(&text) {
+                if !accept(m.as_str()) {
+                    continue;
+                }
+                out.push_str(&text[last..m.start()]);
+                out.push_str(&self.placeholder(kind, m.as_str()));
+                last = m.end();
+            }
+            out.push_str(&text[last..]);
+            text = out;
+        }
+        text
+    }
+
+    /// Mask a session's title, summary and transcript before it leaves the device
+    pub fn redact_session(&mut self, session: &mut Session) {
+        session.title = self.redact(&session.title);
+        session.description = session.description.as_deref().map(|d| self.redact(d));
+        session.context = session.context.as_deref().map(|c| self.redact(c));
+        session.summary = session.summary.as_deref().map(|s| self.redact(s));
+        for entry in &mut session.transcript {
+            entry.speaker = self.redact(&entry.speaker);
+            entry.text = self.redact(&entry.text);
+        }
+    }
+
+    /// Put the original values back into a reply
+    pub fn restore(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for (original, placeholder) in &self.replacements {
+            text = text.replace(placeholder, original);
+        }
+        text
+    }
+
+    /// Like `restore`, for a JSON reply: originals are escaped as JSON string content
+    pub fn restore_json(&self, json: &str) -> String {
+        let mut json = json.to_string();
+        for (original, placeholder) in &self.replacements {
+            let escaped = serde_json::to_string(original).unwrap_or_default();
+            json = json.replace(placeholder, escaped.trim_matches('"'));
+        }
+        json
+    }
+
+    /// Add what was masked so far to the session's redaction report
+    pub fn report(&mut self) {
+        let counts = std::mem::take(&mut self.counts);
+        let (Some(session_id), Some(app)) = (self.session_id.as_deref(), APP.get()) else {
+            return;
+        };
+        if counts.is_empty() {
+            return;
+        }
+        let counts: Vec<RedactionCount> = counts
+            .into_iter()
+            .map(|(kind, count)| RedactionCount {
+                kind: kind.to_string(),
+                count,
+            })
+            .collect();
+        let session_id = session_id.to_string();
+        app.state::<StorageActor>().send(move |s| {
+            if let Err(e) = s.add_redaction_counts(&session_id, &counts) {
+                log::warn!("{}", e);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RedactionReport {
+    pub session_id: String,
+    pub total: u32,
+    pub counts: Vec<RedactionCount>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RedactionPreview {
+    pub text: String,
+    pub counts: Vec<RedactionCount>,
+}
+
+/// What was masked in a session's requests to OpenAI and the CRM, by kind
+#[tauri::command]
+pub async fn get_redaction_report(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<RedactionReport> {
+    let id = session_id.clone();
+    let counts = storage.call(move |s| s.get_redaction_counts(&id)).await?;
+    Ok(RedactionReport {
+        session_id,
+        total: counts.iter().map(|c| c.count).sum(),
+        counts,
+    })
+}
+
+/// `text` as it would be sent with the current settings, for trying out custom patterns
+#[tauri::command]
+pub async fn preview_redaction(
+    config: tauri::State<'_, ConfigState>,
+    text: String,
+) -> CommandResult<RedactionPreview> {
+    let cfg = config.lock().clone();
+    let mut redactor = Redactor::new(&cfg).await;
+    let text = redactor.redact(&text);
+    Ok(RedactionPreview {
+        text,
+        counts: redactor
+            .counts
+            .into_iter()
+            .map(|(kind, count)| RedactionCount {
+                kind: kind.to_string(),
+                count,
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,200 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::manager::TIMELINE_SPEAKER;
+use super::storage::ActionItem;
+use super::storage_actor::StorageActor;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+const EXTRACTION_PROMPT: &str = "You extract action items from meeting transcripts. \
+    Respond with a JSON object of the form \
+    {\"action_items\": [{\"owner\": string or null, \"description\": string, \"due_date\": \"YYYY-MM-DD\" or null}]}. \
+    Only include concrete tasks someone committed to or was asked to do. \
+    Use the speaker's name as owner when it is clear who will do it. \
+    Resolve relative due dates (\"next Friday\") against the meeting date. \
+    Return an empty list if there are none.";
+
+#[derive(Debug, Deserialize)]
+struct ExtractedItems {
+    #[serde(default)]
+    action_items: Vec<ExtractedItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractedItem {
+    owner: Option<String>,
+    description: String,
+    due_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionItemsExtracted {
+    pub session_id: String,
+    pub items: Vec<ActionItem>,
+}
+
+/// Extract action items from a saved session's transcript and store them, replacing the
+/// session's earlier open items that have no ticket
+pub async fn extract(
+    cfg: &AppConfig,
+    storage: &StorageActor,
+    session_id: &str,
+) -> Result<Vec<ActionItem>, VenkyError> {
+    let id = session_id.to_string();
+    let (session, kept) = storage
+        .call(move |s| {
+            let kept: Vec<ActionItem> = s
+                .list_action_items(Some(&id), None)?
+                .into_iter()
+                .filter(|item| item.status == "done" || item.issue_key.is_some())
+                .collect();
+            Ok((s.get_session(&id)?, kept))
+        })
+        .await?;
+
+    let transcript = session
+        .transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER)
+        .map(|e| format!("{}: {}", e.speaker, e.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if transcript.trim().is_empty() {
+        return Err(VenkyError::Other(
+            "No transcript to extract action items from".to_string(),
+        ));
+    }
+
+    let question = format!(
+        "Meeting: {}\nDate: {}\n\nTranscript:\n{}",
+        session.title, session.start_time, transcript
+    );
+    let raw = crate::redaction::for_session(
+        session_id,
+        crate::ai::llm::generate_json(cfg, EXTRACTION_PROMPT, &question),
+    )
+    .await?;
+    let parsed: ExtractedItems = serde_json::from_str(&raw)
+        .map_err(|e| VenkyError::Provider(format!("Model returned invalid action items: {}", e)))?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let items: Vec<ActionItem> = parsed
+        .action_items
+        .into_iter()
+        .filter(|item| !item.description.trim().is_empty())
+        // Don't bring back items that were already ticked off or ticketed
+        .filter(|item| {
+            !kept.iter().any(|existing| {
+                existing
+                    .description
+                    .eq_ignore_ascii_case(item.description.trim())
+            })
+        })
+        .map(|item| ActionItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            owner: item
+                .owner
+                .map(|o| o.trim().to_string())
+                .filter(|o| !o.is_empty()),
+            description: item.description.trim().to_string(),
+            due_date: item
+                .due_date
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            status: "open".to_string(),
+            created_at: now.clone(),
+            completed_at: None,
+            issue_key: None,
+            issue_url: None,
+        })
+        .collect();
+
+    let (id, stored) = (session_id.to_string(), items.clone());
+    storage
+        .call(move |s| s.replace_open_action_items(&id, &stored))
+        .await?;
+    Ok(items)
+}
+
+/// Tell notifiers and webhook subscribers about newly extracted items
+fn announce(app: &AppHandle, session_id: &str, items: &[ActionItem]) {
+    crate::integrations::notify::notify_action_items(app, session_id, items);
+    for item in items {
+        crate::integrations::webhooks::dispatch(
+            app,
+            crate::integrations::webhooks::ACTION_ITEM_CREATED,
+            serde_json::json!({ "session_id": session_id, "action_item": item }),
+        );
+    }
+}
+
+/// After a session ends, extract its action items in the background and emit "action-items-extracted"
+pub fn extract_if_enabled(app: &AppHandle, session_id: &str) {
+    let cfg = app.state::<ConfigState>().lock().clone();
+    if !cfg.extract_action_items {
+        return;
+    }
+
+    let storage = app.state::<StorageActor>().inner().clone();
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        match extract(&cfg, &storage, &session_id).await {
+            Ok(items) => {
+                log::info!(
+                    "Extracted {} action items from session {}",
+                    items.len(),
+                    session_id
+                );
+                announce(&app, &session_id, &items);
+                let _ = app.emit(
+                    "action-items-extracted",
+                    ActionItemsExtracted { session_id, items },
+                );
+            }
+            Err(e) => log::warn!("Action item extraction for {} failed: {}", session_id, e),
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn extract_action_items(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<ActionItem>> {
+    let cfg = config.lock().clone();
+    let items = extract(&cfg, &storage, &session_id).await?;
+    announce(&app, &session_id, &items);
+    Ok(items)
+}
+
+/// Action items across all sessions, open ones first and soonest due first
+#[tauri::command]
+pub async fn list_action_items(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: Option<String>,
+    status: Option<String>,
+) -> CommandResult<Vec<ActionItem>> {
+    Ok(storage
+        .call(move |s| s.list_action_items(session_id.as_deref(), status.as_deref()))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn complete_action_item(
+    storage: tauri::State<'_, StorageActor>,
+    id: String,
+    done: Option<bool>,
+) -> CommandResult<()> {
+    Ok(storage
+        .call(move |s| s.set_action_item_done(&id, done.unwrap_or(true)))
+        .await?)
+}
@@ -0,0 +1,160 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::manager::TIMELINE_SPEAKER;
+use super::storage::Chapter;
+use super::storage_actor::StorageActor;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+const CHAPTERING_PROMPT: &str = "You split meeting transcripts into chapters by topic. \
+    The transcript lines are numbered. Respond with a JSON object of the form \
+    {\"chapters\": [{\"title\": string, \"start_line\": number}]}. \
+    Chapters are consecutive and in order; the first starts at line 0. \
+    Start a new chapter only when the conversation clearly moves to another topic, \
+    roughly every 5 to 15 minutes. Titles are specific and under 8 words.";
+
+#[derive(Debug, Deserialize)]
+struct DetectedChapters {
+    #[serde(default)]
+    chapters: Vec<DetectedChapter>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DetectedChapter {
+    title: String,
+    start_line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChaptersGenerated {
+    pub session_id: String,
+    pub chapters: Vec<Chapter>,
+}
+
+/// Split a session's transcript into titled chapters and store them, replacing earlier ones
+pub async fn generate(
+    cfg: &AppConfig,
+    storage: &StorageActor,
+    session_id: &str,
+) -> Result<Vec<Chapter>, VenkyError> {
+    let id = session_id.to_string();
+    let session = storage.call(move |s| s.get_session(&id)).await?;
+
+    let entries = session
+        .transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER)
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return Err(VenkyError::Other(
+            "No transcript to split into chapters".to_string(),
+        ));
+    }
+
+    let transcript = entries
+        .iter()
+        .enumerate()
+        .map(|(i, e)| format!("{}. {}: {}", i, e.speaker, e.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let question = format!("Meeting: {}\n\nTranscript:\n{}", session.title, transcript);
+    let raw = crate::redaction::for_session(
+        session_id,
+        crate::ai::llm::generate_json(cfg, CHAPTERING_PROMPT, &question),
+    )
+    .await?;
+    let parsed: DetectedChapters = serde_json::from_str(&raw)
+        .map_err(|e| VenkyError::Provider(format!("Model returned invalid chapters: {}", e)))?;
+
+    // Keep chapters in transcript order and drop ones starting on the same line
+    let mut starts = parsed
+        .chapters
+        .into_iter()
+        .filter(|c| !c.title.trim().is_empty() && c.start_line < entries.len())
+        .collect::<Vec<_>>();
+    starts.sort_by_key(|c| c.start_line);
+    starts.dedup_by_key(|c| c.start_line);
+    if let Some(first) = starts.first_mut() {
+        first.start_line = 0;
+    }
+
+    let last_timestamp = &entries[entries.len() - 1].timestamp;
+    let chapters: Vec<Chapter> = starts
+        .iter()
+        .enumerate()
+        .map(|(i, c)| Chapter {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            seq: i as u32,
+            title: c.title.trim().to_string(),
+            start_time: entries[c.start_line].timestamp.clone(),
+            end_time: starts
+                .get(i + 1)
+                .map(|next| entries[next.start_line].timestamp.clone())
+                .unwrap_or_else(|| last_timestamp.clone()),
+        })
+        .collect();
+
+    let (id, stored) = (session_id.to_string(), chapters.clone());
+    storage
+        .call(move |s| s.replace_chapters(&id, &stored))
+        .await?;
+    Ok(chapters)
+}
+
+/// After a session ends, chapter it in the background and emit "chapters-generated"
+pub fn generate_if_enabled(app: &AppHandle, session_id: &str) {
+    let cfg = app.state::<ConfigState>().lock().clone();
+    if !cfg.generate_chapters {
+        return;
+    }
+
+    let storage = app.state::<StorageActor>().inner().clone();
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        match generate(&cfg, &storage, &session_id).await {
+            Ok(chapters) => {
+                log::info!(
+                    "Split session {} into {} chapters",
+                    session_id,
+                    chapters.len()
+                );
+                let _ = app.emit(
+                    "chapters-generated",
+                    ChaptersGenerated {
+                        session_id,
+                        chapters,
+                    },
+                );
+            }
+            Err(e) => log::warn!("Chaptering session {} failed: {}", session_id, e),
+        }
+    });
+}
+
+/// Chapter a session on demand; works for a live session too since its transcript is saved as it grows
+#[tauri::command]
+pub async fn generate_chapters(
+    config: tauri::State<'_, ConfigState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<Chapter>> {
+    let cfg = config.lock().clone();
+    generate(&cfg, &storage, &session_id).await
+}
+
+#[tauri::command]
+pub async fn get_session_chapters(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<Chapter>> {
+    Ok(storage
+        .call(move |s| s.get_session_chapters(&session_id))
+        .await?)
+}
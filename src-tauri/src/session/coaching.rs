@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio_util::sync::CancellationToken;
+
+use super::manager::{Session, SessionManager, TranscriptEntry, TIMELINE_SPEAKER};
+use super::storage::CoachingMetrics;
+use crate::config::AppConfig;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Span of recent transcript the live metrics cover
+const LIVE_WINDOW_SECS: i64 = 2 * 60;
+/// Longest pause after an unfinished sentence that still counts as being cut off
+const INTERRUPTION_GAP_SECS: i64 = 3;
+
+static FILLER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(um+|uh+|erm+|hmm+|like|you know|i mean|sort of|kind of|basically|literally)\b",
+    )
+    .unwrap()
+});
+
+/// Lines the user said, as opposed to the other side of the call
+pub fn is_user(entry: &TranscriptEntry) -> bool {
+    entry.source.as_deref() == Some("mic")
+        || matches!(entry.speaker.to_lowercase().as_str(), "you" | "user" | "me")
+}
+
+fn parse(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Metrics over the last `window_secs` of the transcript, or the whole session when None
+pub fn compute(session: &Session, window_secs: Option<i64>) -> CoachingMetrics {
+    let now = Utc::now();
+    let since = window_secs.map(|secs| now - chrono::Duration::seconds(secs));
+
+    let entries = session
+        .transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER)
+        .filter(|e| match since {
+            Some(since) => parse(&e.timestamp).map(|t| t >= since).unwrap_or(false),
+            None => true,
+        })
+        .collect::<Vec<_>>();
+
+    let mut user_words = 0u32;
+    let mut total_words = 0u32;
+    let mut filler_count = 0u32;
+    let mut interruption_count = 0u32;
+    let mut user_minutes = HashSet::new();
+
+    for (i, entry) in entries.iter().enumerate() {
+        let words = entry.text.split_whitespace().count() as u32;
+        total_words += words;
+        if !is_user(entry) {
+            continue;
+        }
+
+        user_words += words;
+        filler_count += FILLER.find_iter(&entry.text).count() as u32;
+        if let Some(t) = parse(&entry.timestamp) {
+            user_minutes.insert(t.timestamp() / 60);
+        }
+
+        // The other side was mid-sentence when the user started talking
+        if let Some(prev) = i.checked_sub(1).map(|p| entries[p]) {
+            let unfinished = !prev.text.trim_end().ends_with(['.', '?', '!']);
+            let gap = parse(&prev.timestamp)
+                .zip(parse(&entry.timestamp))
+                .map(|(a, b)| (b - a).num_seconds());
+            if !is_user(prev) && unfinished && gap.is_some_and(|g| g <= INTERRUPTION_GAP_SECS) {
+                interruption_count += 1;
+            }
+        }
+    }
+
+    // Live pace is over the window (or the session so far, if shorter); the final
+    // figure only counts the minutes in which the user actually spoke
+    let minutes = match since {
+        Some(since) => {
+            let start = parse(&session.start_time).map_or(since, |s| s.max(since));
+            (now - start).num_seconds().max(1) as f64 / 60.0
+        }
+        None => user_minutes.len().max(1) as f64,
+    };
+
+    CoachingMetrics {
+        session_id: session.id.clone(),
+        user_talk_ratio: if total_words > 0 {
+            user_words as f64 / total_words as f64
+        } else {
+            0.0
+        },
+        words_per_minute: user_words as f64 / minutes,
+        filler_count,
+        interruption_count,
+        computed_at: now.to_rfc3339(),
+    }
+}
+
+/// Emit "coaching-metrics" for the focused session every few seconds
+pub async fn coaching_loop(app: AppHandle, cancel: CancellationToken) {
+    let secs = app.state::<ConfigState>().lock().coaching_interval_secs;
+    let mut interval = tokio::time::interval(Duration::from_secs(secs.max(1) as u64));
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+
+        let session_state = app.state::<SessionState>();
+        let metrics = {
+            let mgr = session_state.lock();
+            mgr.focused()
+                .filter(|s| s.transcript.iter().any(|e| e.speaker != TIMELINE_SPEAKER))
+                .map(|s| compute(s, Some(LIVE_WINDOW_SECS)))
+        };
+        if let Some(metrics) = metrics {
+            let _ = app.emit("coaching-metrics", metrics);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::manager::{mark_timeline, Session, SessionManager};
+use super::storage::ConsentRecord;
+use super::storage_actor::StorageActor;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Sessions waiting for recording consent, checked before any audio capture starts
+#[derive(Debug, Default)]
+pub struct ConsentGate {
+    pending: HashSet<String>,
+    /// Automatic capture held back until consent, with the meeting window it was scoped to
+    deferred_capture: Option<Option<String>>,
+}
+
+pub type ConsentState = Arc<Mutex<ConsentGate>>;
+
+impl ConsentGate {
+    /// Fails while any live session still needs consent confirmed
+    pub fn check(&self) -> Result<(), VenkyError> {
+        if self.pending.is_empty() {
+            Ok(())
+        } else {
+            Err(VenkyError::Other(
+                "Recording consent hasn't been confirmed for this session".to_string(),
+            ))
+        }
+    }
+
+    /// Start automatic capture once consent is confirmed instead of now
+    pub fn defer_capture(&mut self, window_title: Option<&str>) {
+        self.deferred_capture = Some(window_title.map(str::to_string));
+    }
+}
+
+/// Emitted as "consent-disclosure" for the UI to read the notice aloud
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsentDisclosure {
+    pub session_id: String,
+    pub text: String,
+}
+
+/// In compliance mode, log a pending consent record for a new session, block capture until
+/// it is confirmed and, when enabled, put the disclosure notice in the transcript
+pub fn on_session_start(app: &AppHandle, session: &Session) {
+    let cfg = app.state::<ConfigState>().lock().clone();
+    if !cfg.consent_mode {
+        return;
+    }
+
+    let record = ConsentRecord {
+        session_id: session.id.clone(),
+        jurisdiction: cfg.consent_jurisdiction.clone(),
+        participants_notified: false,
+        disclosure_played: cfg.consent_disclosure,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        confirmed_at: None,
+    };
+    let stored = record.clone();
+    app.state::<StorageActor>().send(move |s| {
+        if let Err(e) = s.save_consent_record(&stored) {
+            log::error!("Failed to log consent for {}: {}", stored.session_id, e);
+        }
+    });
+    app.state::<ConsentState>()
+        .lock()
+        .pending
+        .insert(session.id.clone());
+
+    if cfg.consent_disclosure {
+        mark_timeline(
+            &app.state::<SessionState>(),
+            &format!("Disclosure: {}", cfg.consent_disclosure_text),
+        );
+        let _ = app.emit(
+            "consent-disclosure",
+            ConsentDisclosure {
+                session_id: session.id.clone(),
+                text: cfg.consent_disclosure_text,
+            },
+        );
+    }
+    let _ = app.emit("consent-required", record);
+}
+
+/// A session that ends unconfirmed no longer holds capture back
+pub fn on_session_end(app: &AppHandle, session_id: &str) {
+    let consent = app.state::<ConsentState>();
+    let mut gate = consent.lock();
+    gate.pending.remove(session_id);
+    if gate.pending.is_empty() {
+        gate.deferred_capture = None;
+    }
+}
+
+/// Confirm recording consent for the given session, or the focused one, and start any
+/// automatic capture that was waiting for it
+#[tauri::command]
+pub async fn confirm_consent(
+    app: AppHandle,
+    consent: tauri::State<'_, ConsentState>,
+    session_state: tauri::State<'_, SessionState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: Option<String>,
+    participants_notified: bool,
+) -> CommandResult<ConsentRecord> {
+    let id = session_id
+        .or_else(|| session_state.lock().focused_id.clone())
+        .ok_or_else(|| VenkyError::Other("No active session".to_string()))?;
+
+    let session_id = id.clone();
+    let mut record = storage
+        .call(move |s| s.get_consent_record(&session_id))
+        .await?
+        .ok_or_else(|| VenkyError::Other(format!("Session {} has no consent record", id)))?;
+    if record.confirmed_at.is_some() {
+        return Ok(record);
+    }
+    record.participants_notified = participants_notified;
+    record.confirmed_at = Some(chrono::Utc::now().to_rfc3339());
+    let row = record.clone();
+    storage.call(move |s| s.save_consent_record(&row)).await?;
+
+    mark_timeline(&session_state, "Recording consent confirmed");
+    let _ = app.emit("consent-confirmed", &record);
+
+    let deferred = {
+        let mut gate = consent.lock();
+        gate.pending.remove(&id);
+        if gate.pending.is_empty() {
+            gate.deferred_capture.take()
+        } else {
+            None
+        }
+    };
+    if let Some(window_title) = deferred {
+        crate::ai::live_engine::start_auto_capture(&app, window_title.as_deref());
+    }
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn get_consent_record(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Option<ConsentRecord>> {
+    Ok(storage
+        .call(move |s| s.get_consent_record(&session_id))
+        .await?)
+}
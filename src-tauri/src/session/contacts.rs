@@ -0,0 +1,156 @@
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use super::manager::TIMELINE_SPEAKER;
+use super::storage_actor::StorageActor;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+use crate::integrations::CRMContact;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+const EXTRACTION_PROMPT: &str = "You extract contact details of people from meeting transcripts. \
+    Respond with a JSON object of the form \
+    {\"contacts\": [{\"first_name\": string, \"last_name\": string, \"email\": string or null, \
+    \"company\": string or null, \"title\": string or null, \"phone\": string or null}]}. \
+    Include the people who spoke and anyone named with details worth keeping, such as a new \
+    decision maker. Leave out the user (speaker \"You\"). Take emails, phone numbers, companies \
+    and roles only from what was said or from the known participants; never guess them. \
+    Return an empty list if there are none.";
+
+#[derive(Debug, Deserialize)]
+struct ExtractedContacts {
+    #[serde(default)]
+    contacts: Vec<ExtractedContact>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractedContact {
+    #[serde(default)]
+    first_name: String,
+    #[serde(default)]
+    last_name: String,
+    email: Option<String>,
+    company: Option<String>,
+    title: Option<String>,
+    phone: Option<String>,
+}
+
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Pull the people in a saved session's transcript into contacts ready to review and sync.
+/// Participants already linked to a CRM record keep that id.
+pub async fn extract(
+    cfg: &AppConfig,
+    storage: &StorageActor,
+    session_id: &str,
+) -> Result<Vec<CRMContact>, VenkyError> {
+    let id = session_id.to_string();
+    let (session, participants) = storage
+        .call(move |s| Ok((s.get_session(&id)?, s.get_session_participants(&id)?)))
+        .await?;
+
+    let transcript = session
+        .transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER)
+        .map(|e| format!("{}: {}", e.speaker, e.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if transcript.trim().is_empty() {
+        return Err(VenkyError::Other(
+            "No transcript to extract contacts from".to_string(),
+        ));
+    }
+
+    let known = participants
+        .iter()
+        .map(|p| {
+            let mut line = format!("- {}", p.name);
+            if let Some(ref email) = p.email {
+                line.push_str(&format!(" <{}>", email));
+            }
+            if let Some(ref role) = p.role {
+                line.push_str(&format!(" ({})", role));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let question = format!(
+        "Meeting: {}\nKnown participants:\n{}\n\nTranscript:\n{}",
+        session.title,
+        if known.is_empty() { "(none)" } else { &known },
+        transcript
+    );
+    let raw = crate::redaction::for_session(
+        session_id,
+        crate::ai::llm::generate_json(cfg, EXTRACTION_PROMPT, &question),
+    )
+    .await?;
+    let parsed: ExtractedContacts = serde_json::from_str(&raw)
+        .map_err(|e| VenkyError::Provider(format!("Model returned invalid contacts: {}", e)))?;
+
+    let mut contacts: Vec<CRMContact> = Vec::new();
+    for c in parsed.contacts {
+        let first_name = c.first_name.trim().to_string();
+        let last_name = c.last_name.trim().to_string();
+        if first_name.is_empty() && last_name.is_empty() {
+            continue;
+        }
+        let email = clean(c.email).unwrap_or_default();
+        let full_name = format!("{} {}", first_name, last_name);
+        let full_name = full_name.trim();
+
+        // The same person can come back twice, e.g. once by name and once with an email
+        let duplicate = contacts.iter().any(|other| {
+            (!email.is_empty() && other.email.eq_ignore_ascii_case(&email))
+                || format!("{} {}", other.first_name, other.last_name)
+                    .trim()
+                    .eq_ignore_ascii_case(full_name)
+        });
+        if duplicate {
+            continue;
+        }
+
+        let participant = participants.iter().find(|p| {
+            p.name.trim().eq_ignore_ascii_case(full_name)
+                || (!email.is_empty()
+                    && p.email
+                        .as_deref()
+                        .is_some_and(|e| e.eq_ignore_ascii_case(&email)))
+        });
+        contacts.push(CRMContact {
+            id: participant.and_then(|p| p.crm_contact_id.clone()),
+            email: if email.is_empty() {
+                participant
+                    .and_then(|p| p.email.clone())
+                    .unwrap_or_default()
+            } else {
+                email
+            },
+            first_name,
+            last_name,
+            company: clean(c.company),
+            phone: clean(c.phone),
+            title: clean(c.title).or_else(|| participant.and_then(|p| p.role.clone())),
+        });
+    }
+    Ok(contacts)
+}
+
+/// Contacts mentioned in a session, for the user to review before syncing them to the CRM
+#[tauri::command]
+pub async fn extract_contacts(
+    config: tauri::State<'_, ConfigState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<CRMContact>> {
+    let cfg = config.lock().clone();
+    extract(&cfg, &storage, &session_id).await
+}
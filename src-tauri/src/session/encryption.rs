@@ -0,0 +1,295 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::storage::Storage;
+use super::storage_actor::StorageActor;
+use crate::error::{CommandResult, VenkyError};
+use crate::integrations::calendar::CalendarConfig;
+use crate::integrations::email::EmailConfig;
+use crate::integrations::keychain;
+use crate::integrations::zoom::ZoomConfig;
+use crate::integrations::CRMConfig;
+
+pub type DatabaseLockState = Arc<Mutex<DatabaseLock>>;
+
+/// Keychain account holding the database key, so the app can open it unattended
+const KEYCHAIN_ACCOUNT: &str = "database-key";
+/// The key being replaced while the database is re-encrypted
+const PREVIOUS_KEYCHAIN_ACCOUNT: &str = "database-key-previous";
+const MIN_PASSPHRASE_CHARS: usize = 8;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    /// The database couldn't be opened at launch and waits for `unlock_database`
+    pub locked: bool,
+    /// Why it couldn't be opened
+    pub error: Option<String>,
+}
+
+/// Set when the database couldn't be opened at launch. Until it is unlocked the app runs
+/// on an empty in-memory database, so nothing is read from or written to the file.
+pub struct DatabaseLock {
+    path: PathBuf,
+    error: Option<String>,
+}
+
+impl DatabaseLock {
+    pub fn new(path: &Path, error: Option<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            error,
+        }
+    }
+
+    fn check(&self) -> Result<(), VenkyError> {
+        match self.error {
+            Some(_) => Err(VenkyError::Storage(
+                "The database is locked; unlock it first".to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The key to open the database with, if it is encrypted
+pub fn database_key() -> Result<Option<String>, VenkyError> {
+    keychain::get_secret(KEYCHAIN_ACCOUNT)
+}
+
+/// Open the database with the keychain's key. When that fails, try the key from before a
+/// re-encryption that was cut short and then no key, and point the keychain at the one
+/// that works.
+pub fn open_database(path: &Path) -> Result<Storage, VenkyError> {
+    let (current, keychain_error) = match database_key() {
+        Ok(key) => (key, None),
+        Err(e) => (None, Some(e)),
+    };
+    let previous = keychain::get_secret(PREVIOUS_KEYCHAIN_ACCOUNT).unwrap_or_else(|e| {
+        log::warn!("{}", e);
+        None
+    });
+
+    let mut candidates = vec![current.clone()];
+    for key in [previous, None] {
+        if !candidates.contains(&key) {
+            candidates.push(key);
+        }
+    }
+
+    let mut first_error = None;
+    for key in candidates {
+        match Storage::new(path, key.as_deref()) {
+            Ok(storage) => {
+                if key != current && keychain_error.is_none() {
+                    log::warn!(
+                        "Database opened with the key from before an unfinished re-encryption"
+                    );
+                    if let Err(e) = keychain::set_secret(KEYCHAIN_ACCOUNT, key.as_deref()) {
+                        log::error!("Failed to repair the database key: {}", e);
+                    }
+                }
+                if let Err(e) = keychain::set_secret(PREVIOUS_KEYCHAIN_ACCOUNT, None) {
+                    log::warn!("{}", e);
+                }
+                return Ok(storage);
+            }
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    Err(keychain_error
+        .or(first_error)
+        .unwrap_or_else(|| VenkyError::Storage("Failed to open database".to_string())))
+}
+
+/// Stand-in for a database that couldn't be opened, so the app can start and ask for it
+/// to be unlocked
+pub fn locked_placeholder() -> Result<Storage, VenkyError> {
+    Storage::new(Path::new(":memory:"), None)
+}
+
+/// A passphrase to encrypt with, or a random key when none is given
+fn new_key(passphrase: Option<String>) -> Result<String, VenkyError> {
+    match passphrase {
+        Some(passphrase) if passphrase.chars().count() < MIN_PASSPHRASE_CHARS => {
+            Err(VenkyError::Other(format!(
+                "The passphrase must be at least {} characters",
+                MIN_PASSPHRASE_CHARS
+            )))
+        }
+        Some(passphrase) => Ok(passphrase),
+        None => Ok(format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        )),
+    }
+}
+
+/// Re-encrypt the database with `key` (None decrypts it), keeping the keychain in step.
+/// The outgoing key stays in the keychain until the file has been replaced, so a crash
+/// part way is sorted out by `open_database` at the next launch.
+fn rekey(storage: &mut Storage, key: Option<&str>) -> Result<(), VenkyError> {
+    let previous = database_key()?;
+    keychain::set_secret(PREVIOUS_KEYCHAIN_ACCOUNT, previous.as_deref())?;
+    if key.is_some() {
+        keychain::set_secret(KEYCHAIN_ACCOUNT, key)?;
+    }
+    if let Err(e) = storage.set_encryption(key) {
+        if let Err(restore) = keychain::set_secret(KEYCHAIN_ACCOUNT, previous.as_deref()) {
+            log::error!("Failed to restore the database key: {}", restore);
+        }
+        return Err(e);
+    }
+    if key.is_none() {
+        keychain::set_secret(KEYCHAIN_ACCOUNT, None)?;
+    }
+    if let Err(e) = keychain::set_secret(PREVIOUS_KEYCHAIN_ACCOUNT, None) {
+        log::warn!("{}", e);
+    }
+    Ok(())
+}
+
+async fn status(
+    storage: &StorageActor,
+    lock: &DatabaseLockState,
+) -> Result<EncryptionStatus, VenkyError> {
+    let error = lock.lock().error.clone();
+    let enabled = storage.call(|s| Ok(s.is_encrypted())).await?;
+    Ok(EncryptionStatus {
+        enabled,
+        locked: error.is_some(),
+        error,
+    })
+}
+
+#[tauri::command]
+pub async fn get_database_encryption(
+    storage: tauri::State<'_, StorageActor>,
+    lock: tauri::State<'_, DatabaseLockState>,
+) -> CommandResult<EncryptionStatus> {
+    Ok(status(&storage, &lock).await?)
+}
+
+/// Open a database that couldn't be opened at launch, with its passphrase, or with the
+/// keychain again when none is given (e.g. once the keychain is reachable). A passphrase
+/// that works is saved to the keychain. Emits "database-unlocked".
+#[tauri::command]
+pub async fn unlock_database(
+    app: AppHandle,
+    storage: tauri::State<'_, StorageActor>,
+    lock: tauri::State<'_, DatabaseLockState>,
+    passphrase: Option<String>,
+) -> CommandResult<EncryptionStatus> {
+    let path = {
+        let lock = lock.lock();
+        if lock.error.is_none() {
+            return Err(VenkyError::Other(
+                "The database is already open".to_string(),
+            ));
+        }
+        lock.path.clone()
+    };
+    let opened = match passphrase.as_deref() {
+        Some(passphrase) => Storage::new(&path, Some(passphrase))?,
+        None => open_database(&path)?,
+    };
+    if let Some(passphrase) = passphrase.as_deref() {
+        if let Err(e) = keychain::set_secret(KEYCHAIN_ACCOUNT, Some(passphrase)) {
+            log::error!(
+                "Unlocked, but the key can't be kept for the next launch: {}",
+                e
+            );
+        }
+    }
+
+    // Connections were loaded from the stand-in at launch
+    let (calendar, crm, email, zoom) = storage
+        .call(move |s| {
+            *s = opened;
+            Ok((
+                CalendarConfig::load(s),
+                CRMConfig::load(s),
+                EmailConfig::load(s),
+                ZoomConfig::load(s),
+            ))
+        })
+        .await?;
+    *app.state::<Arc<Mutex<CalendarConfig>>>().lock() = calendar;
+    *app.state::<Arc<Mutex<CRMConfig>>>().lock() = crm;
+    *app.state::<Arc<Mutex<EmailConfig>>>().lock() = email;
+    *app.state::<Arc<Mutex<ZoomConfig>>>().lock() = zoom;
+    lock.lock().error = None;
+    log::info!("Database unlocked");
+    let _ = app.emit("database-unlocked", ());
+    Ok(status(&storage, &lock).await?)
+}
+
+/// Encrypt the database with SQLCipher, under `passphrase` or a random key. Either way
+/// the key is kept in the OS keychain; a passphrase also lets the file be opened elsewhere.
+#[tauri::command]
+pub async fn enable_database_encryption(
+    storage: tauri::State<'_, StorageActor>,
+    lock: tauri::State<'_, DatabaseLockState>,
+    passphrase: Option<String>,
+) -> CommandResult<EncryptionStatus> {
+    lock.lock().check()?;
+    let key = new_key(passphrase)?;
+    storage
+        .call(move |s| {
+            if s.is_encrypted() {
+                return Err(VenkyError::Other(
+                    "The database is already encrypted".to_string(),
+                ));
+            }
+            rekey(s, Some(&key))
+        })
+        .await?;
+    Ok(status(&storage, &lock).await?)
+}
+
+#[tauri::command]
+pub async fn disable_database_encryption(
+    storage: tauri::State<'_, StorageActor>,
+    lock: tauri::State<'_, DatabaseLockState>,
+) -> CommandResult<EncryptionStatus> {
+    lock.lock().check()?;
+    storage
+        .call(|s| {
+            if !s.is_encrypted() {
+                return Err(VenkyError::Other(
+                    "The database is not encrypted".to_string(),
+                ));
+            }
+            rekey(s, None)
+        })
+        .await?;
+    Ok(status(&storage, &lock).await?)
+}
+
+/// Re-encrypt all existing data under a new passphrase or random key
+#[tauri::command]
+pub async fn rotate_database_key(
+    storage: tauri::State<'_, StorageActor>,
+    lock: tauri::State<'_, DatabaseLockState>,
+    passphrase: Option<String>,
+) -> CommandResult<EncryptionStatus> {
+    lock.lock().check()?;
+    let key = new_key(passphrase)?;
+    storage
+        .call(move |s| {
+            if !s.is_encrypted() {
+                return Err(VenkyError::Other(
+                    "The database is not encrypted".to_string(),
+                ));
+            }
+            rekey(s, Some(&key))
+        })
+        .await?;
+    Ok(status(&storage, &lock).await?)
+}
@@ -0,0 +1,462 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::manager::{Session, TIMELINE_SPEAKER};
+use super::storage::{ActionItem, Chapter, Highlight, Participant, Storage};
+use super::storage_actor::StorageActor;
+
+use crate::error::{CommandResult, VenkyError};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Pdf,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            "pdf" => Some(ExportFormat::Pdf),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Everything kept about a session, as written to an export
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionExport {
+    pub session: Session,
+    pub participants: Vec<Participant>,
+    pub chapters: Vec<Chapter>,
+    pub highlights: Vec<Highlight>,
+    pub action_items: Vec<ActionItem>,
+    pub exported_at: String,
+}
+
+pub fn gather(storage: &Storage, session_id: &str) -> Result<SessionExport, VenkyError> {
+    Ok(SessionExport {
+        session: storage.get_session(session_id)?,
+        participants: storage.get_session_participants(session_id)?,
+        chapters: storage.get_session_chapters(session_id)?,
+        highlights: storage.get_session_highlights(session_id)?,
+        action_items: storage.list_action_items(Some(session_id), None)?,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Time since the session started, as "M:SS" or "H:MM:SS"
+pub fn offset(start: &str, timestamp: &str) -> String {
+    let secs = match (
+        chrono::DateTime::parse_from_rfc3339(start),
+        chrono::DateTime::parse_from_rfc3339(timestamp),
+    ) {
+        (Ok(start), Ok(at)) => (at - start).num_seconds().max(0),
+        _ => 0,
+    };
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+fn local_time(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%b %-d, %Y %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+pub fn to_markdown(export: &SessionExport) -> String {
+    let session = &export.session;
+    let start = &session.start_time;
+    let mut md = format!("# {}\n\n", session.title);
+
+    let mut meta = vec![session.purpose.as_str().to_string(), local_time(start)];
+    if let Some(ref end) = session.end_time {
+        meta.push(format!("{} long", offset(start, end)));
+    }
+    md.push_str(&format!("_{}_\n\n", meta.join(" · ")));
+    if let Some(ref description) = session.description {
+        md.push_str(&format!("{}\n\n", description));
+    }
+
+    if !export.participants.is_empty() {
+        md.push_str("## Participants\n\n");
+        for p in &export.participants {
+            let mut line = p.name.clone();
+            if let Some(ref role) = p.role {
+                line.push_str(&format!(" ({})", role));
+            }
+            if let Some(ref email) = p.email {
+                line.push_str(&format!(" <{}>", email));
+            }
+            md.push_str(&format!("- {}\n", line));
+        }
+        md.push('\n');
+    }
+
+    if let Some(ref summary) = session.summary {
+        md.push_str(&format!("## Summary\n\n{}\n\n", summary.trim()));
+    }
+
+    if !export.action_items.is_empty() {
+        md.push_str("## Action Items\n\n");
+        for item in &export.action_items {
+            let check = if item.status == "done" { "x" } else { " " };
+            let mut line = format!("- [{}] {}", check, item.description);
+            if let Some(ref owner) = item.owner {
+                line.push_str(&format!(" — {}", owner));
+            }
+            if let Some(ref due) = item.due_date {
+                line.push_str(&format!(" (due {})", due));
+            }
+            md.push_str(&format!("{}\n", line));
+        }
+        md.push('\n');
+    }
+
+    if !export.highlights.is_empty() {
+        md.push_str("## Highlights\n\n");
+        for h in &export.highlights {
+            md.push_str(&format!(
+                "- {} {}\n",
+                offset(start, &h.timestamp),
+                h.note.as_deref().unwrap_or("(highlight)")
+            ));
+        }
+        md.push('\n');
+    }
+
+    if !export.chapters.is_empty() {
+        md.push_str("## Chapters\n\n");
+        for c in &export.chapters {
+            md.push_str(&format!("- {} {}\n", offset(start, &c.start_time), c.title));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Transcript\n");
+    let mut chapters = export.chapters.iter().peekable();
+    for entry in &session.transcript {
+        while let Some(chapter) = chapters.next_if(|c| c.start_time <= entry.timestamp) {
+            md.push_str(&format!("\n### {}\n", chapter.title));
+        }
+        let at = offset(start, &entry.timestamp);
+        if entry.speaker == TIMELINE_SPEAKER {
+            md.push_str(&format!("\n_{} — {}_\n", at, entry.text));
+        } else {
+            md.push_str(&format!("\n**{} {}:** {}\n", at, entry.speaker, entry.text));
+        }
+    }
+    md
+}
+
+pub fn to_json(export: &SessionExport) -> Result<String, VenkyError> {
+    serde_json::to_string_pretty(export)
+        .map_err(|e| VenkyError::Other(format!("Serialize error: {}", e)))
+}
+
+/// The document for `export` in `format`
+pub fn render(export: &SessionExport, format: ExportFormat) -> Result<Vec<u8>, VenkyError> {
+    Ok(match format {
+        ExportFormat::Markdown => to_markdown(export).into_bytes(),
+        ExportFormat::Json => to_json(export)?.into_bytes(),
+        ExportFormat::Pdf => pdf::from_markdown(&to_markdown(export)),
+    })
+}
+
+/// File name for a bulk export: start date and title, safe on every platform
+pub fn file_name(session: &Session, format: ExportFormat) -> String {
+    let date = session.start_time.get(..10).unwrap_or("session");
+    let title: String = session
+        .title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let title = title
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let short_id = session.id.get(..8).unwrap_or(&session.id);
+    format!("{}-{}-{}.{}", date, title, short_id, format.extension())
+}
+
+fn write(path: &Path, bytes: &[u8]) -> Result<(), VenkyError> {
+    std::fs::write(path, bytes)
+        .map_err(|e| VenkyError::Storage(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// Write every session to `dir`, one file each; returns how many were written
+pub fn export_all(
+    storage: &Storage,
+    format: ExportFormat,
+    dir: &Path,
+) -> Result<usize, VenkyError> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| VenkyError::Storage(format!("Failed to create {}: {}", dir.display(), e)))?;
+    let sessions = storage.get_all_sessions()?;
+    for summary in &sessions {
+        let export = gather(storage, &summary.id)?;
+        let path = dir.join(file_name(&export.session, format));
+        write(&path, &render(&export, format)?)?;
+    }
+    Ok(sessions.len())
+}
+
+/// Write a session's metadata, summary, action items, highlights, chapters and transcript
+/// to `path` as Markdown, PDF or JSON
+#[tauri::command]
+pub async fn export_session(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+    format: ExportFormat,
+    path: String,
+) -> CommandResult<()> {
+    let export = storage.call(move |s| gather(s, &session_id)).await?;
+    write(Path::new(&path), &render(&export, format)?)
+}
+
+/// Back up every session into `dir`, one file per session; returns how many were written
+#[tauri::command]
+pub async fn export_all_sessions(
+    storage: tauri::State<'_, StorageActor>,
+    format: ExportFormat,
+    dir: String,
+) -> CommandResult<usize> {
+    Ok(storage
+        .call(move |s| export_all(s, format, Path::new(&dir)))
+        .await?)
+}
+
+/// A minimal PDF writer for the exported Markdown: Helvetica, wrapped and paginated, with
+/// headings in bold. No dependency is worth it for one text document.
+mod pdf {
+    const PAGE_WIDTH: f64 = 612.0;
+    const PAGE_HEIGHT: f64 = 792.0;
+    const MARGIN: f64 = 56.0;
+    const BODY_SIZE: f64 = 10.0;
+
+    /// Average Helvetica glyph width as a fraction of the font size
+    fn char_width(c: char, size: f64) -> f64 {
+        let em = match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '\'' | '!' | '|' => 0.28,
+            ' ' | 'f' | 't' | 'r' | 'I' | '(' | ')' | '-' => 0.33,
+            'm' | 'w' | 'M' | 'W' | '@' => 0.83,
+            c if c.is_uppercase() => 0.67,
+            _ => 0.55,
+        };
+        em * size
+    }
+
+    fn text_width(text: &str, size: f64) -> f64 {
+        text.chars().map(|c| char_width(c, size)).sum()
+    }
+
+    fn wrap(text: &str, size: f64, width: f64) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for word in text.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+            if text_width(&candidate, size) > width && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                line = word.to_string();
+            } else {
+                line = candidate;
+            }
+        }
+        if !line.is_empty() || lines.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// A PDF string literal in WinAnsiEncoding; characters it lacks become '?'
+    fn escape(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            let code: u32 = match c {
+                '\u{2022}' => 0x95,
+                '\u{2013}' => 0x96,
+                '\u{2014}' => 0x97,
+                '\u{2018}' => 0x91,
+                '\u{2019}' => 0x92,
+                '\u{201C}' => 0x93,
+                '\u{201D}' => 0x94,
+                '\u{2026}' => 0x85,
+                c if (c as u32) < 0x100 => c as u32,
+                _ => '?' as u32,
+            };
+            match code {
+                0x28 | 0x29 | 0x5C => {
+                    out.push('\\');
+                    out.push(code as u8 as char);
+                }
+                0x20..=0x7E => out.push(code as u8 as char),
+                _ => out.push_str(&format!("\\{:03o}", code)),
+            }
+        }
+        out
+    }
+
+    fn strip_markup(text: &str) -> String {
+        text.replace("**", "")
+            .trim_matches('_')
+            .replace("- [x] ", "\u{2022} [done] ")
+            .replace("- [ ] ", "\u{2022} ")
+    }
+
+    struct Writer {
+        pages: Vec<String>,
+        current: String,
+        y: f64,
+    }
+
+    impl Writer {
+        fn new() -> Self {
+            Writer {
+                pages: Vec::new(),
+                current: String::new(),
+                y: PAGE_HEIGHT - MARGIN,
+            }
+        }
+
+        fn line(&mut self, text: &str, bold: bool, size: f64, indent: f64) {
+            let leading = size * 1.35;
+            if self.y - leading < MARGIN {
+                self.pages.push(std::mem::take(&mut self.current));
+                self.y = PAGE_HEIGHT - MARGIN;
+            }
+            self.y -= leading;
+            self.current.push_str(&format!(
+                "BT /{} {} Tf {:.1} {:.1} Td ({}) Tj ET\n",
+                if bold { "F2" } else { "F1" },
+                size,
+                MARGIN + indent,
+                self.y,
+                escape(text)
+            ));
+        }
+
+        fn gap(&mut self, points: f64) {
+            self.y -= points;
+        }
+
+        fn paragraph(&mut self, text: &str, bold: bool, size: f64, indent: f64) {
+            let width = PAGE_WIDTH - 2.0 * MARGIN - indent;
+            for line in wrap(text, size, width) {
+                self.line(&line, bold, size, indent);
+            }
+        }
+
+        fn finish(mut self) -> Vec<String> {
+            if !self.current.is_empty() || self.pages.is_empty() {
+                self.pages.push(self.current);
+            }
+            self.pages
+        }
+    }
+
+    pub fn from_markdown(markdown: &str) -> Vec<u8> {
+        let mut writer = Writer::new();
+        for raw in markdown.lines() {
+            if raw.trim().is_empty() {
+                writer.gap(BODY_SIZE * 0.6);
+            } else if let Some(title) = raw.strip_prefix("# ") {
+                writer.paragraph(title, true, 18.0, 0.0);
+            } else if let Some(heading) = raw.strip_prefix("## ") {
+                writer.gap(6.0);
+                writer.paragraph(heading, true, 14.0, 0.0);
+            } else if let Some(heading) = raw.strip_prefix("### ") {
+                writer.paragraph(heading, true, 12.0, 0.0);
+            } else if raw.starts_with("- ") {
+                let item = strip_markup(raw);
+                let item = match item.strip_prefix("- ") {
+                    Some(rest) => format!("\u{2022} {}", rest),
+                    None => item,
+                };
+                writer.paragraph(&item, false, BODY_SIZE, 8.0);
+            } else {
+                writer.paragraph(&strip_markup(raw), false, BODY_SIZE, 0.0);
+            }
+        }
+        document(&writer.finish())
+    }
+
+    /// Assemble pages into a PDF file with its cross-reference table
+    fn document(pages: &[String]) -> Vec<u8> {
+        // 1: catalog, 2: page tree, 3-4: fonts, then a page and its content per page
+        let page_ids: Vec<usize> = (0..pages.len()).map(|i| 5 + i * 2).collect();
+        let mut objects: Vec<String> = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids
+                    .iter()
+                    .map(|id| format!("{} 0 R", id))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                pages.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        for (content, page_id) in pages.iter().zip(&page_ids) {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                page_id + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                content.len(),
+                content
+            ));
+        }
+
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+        }
+        let xref = out.len();
+        out.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            out.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        out.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        ));
+        out.into_bytes()
+    }
+}
@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::manager::{Session, SessionPurpose, SessionStatus, TranscriptEntry};
+use super::storage::Participant;
+use super::storage_actor::StorageActor;
+use crate::error::{CommandResult, VenkyError};
+use crate::integrations::zoom::parse_vtt;
+
+const UNKNOWN_SPEAKER: &str = "Others";
+/// Speaking pace used to space out lines that carry no time of their own
+const WORDS_PER_SEC: f64 = 2.5;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    /// WebVTT, as Zoom records it
+    Vtt,
+    /// Teams' .vtt download, with speakers as voice tags
+    Teams,
+    Srt,
+    /// Otter's .txt export ("Name  0:12" above each paragraph) or its .srt
+    Otter,
+    /// Fireflies' JSON (from the API or a download) or its .srt
+    Fireflies,
+    /// One line per utterance, optionally "Name: text" and/or a leading "[0:12]"
+    Text,
+}
+
+impl TranscriptFormat {
+    fn from_extension(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .as_deref()
+        {
+            Some("vtt") => TranscriptFormat::Vtt,
+            Some("srt") => TranscriptFormat::Srt,
+            Some("json") => TranscriptFormat::Fireflies,
+            _ => TranscriptFormat::Text,
+        }
+    }
+}
+
+/// One utterance as read from the file; `offset` is seconds from the start of the meeting
+struct Line {
+    offset: Option<f64>,
+    speaker: Option<String>,
+    text: String,
+}
+
+fn spoken_secs(text: &str) -> f64 {
+    text.split_whitespace().count() as f64 / WORDS_PER_SEC
+}
+
+/// Seconds in a "1:02:03", "02:03" or "[02:03]" clock time
+fn clock_seconds(time: &str) -> Option<f64> {
+    let time = time.trim_matches(['[', ']', '(', ')']);
+    if !time.contains(':') {
+        return None;
+    }
+    let mut secs = 0.0;
+    for part in time.split(':') {
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            return None;
+        }
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(secs)
+}
+
+/// "Name: text" split into its speaker and text
+fn split_speaker(text: &str) -> (Option<String>, String) {
+    match text.split_once(": ") {
+        Some((speaker, rest))
+            if !speaker.trim().is_empty() && speaker.split_whitespace().count() <= 4 =>
+        {
+            (Some(speaker.trim().to_string()), rest.trim().to_string())
+        }
+        _ => (None, text.trim().to_string()),
+    }
+}
+
+/// A paragraph header such as Otter's "Jane Doe  0:12" or "Jane Doe - 00:12"
+fn header(line: &str) -> Option<(String, f64)> {
+    let (name, time) = line.rsplit_once(char::is_whitespace)?;
+    let offset = clock_seconds(time)?;
+    let name = name.trim().trim_end_matches(['-', '–', '|', ',']).trim();
+    if name.is_empty() || name.len() > 60 || name.contains(':') {
+        return None;
+    }
+    Some((name.to_string(), offset))
+}
+
+/// Plain text transcripts. With `headers`, a "Name  0:12" line starts a paragraph that runs
+/// to the next blank line; otherwise every line stands alone.
+fn parse_text(content: &str, headers: bool) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut paragraph: Option<Line> = None;
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() {
+            lines.extend(paragraph.take());
+            continue;
+        }
+        if headers {
+            if let Some((speaker, offset)) = header(line) {
+                lines.extend(paragraph.take());
+                paragraph = Some(Line {
+                    offset: Some(offset),
+                    speaker: Some(speaker),
+                    text: String::new(),
+                });
+                continue;
+            }
+            if let Some(ref mut paragraph) = paragraph {
+                if !paragraph.text.is_empty() {
+                    paragraph.text.push(' ');
+                }
+                paragraph.text.push_str(line);
+                continue;
+            }
+        }
+        let (offset, rest) = match line.split_once(char::is_whitespace) {
+            Some((first, rest)) if clock_seconds(first).is_some() => {
+                (clock_seconds(first), rest.trim())
+            }
+            _ => (None, line),
+        };
+        let (speaker, text) = split_speaker(rest);
+        lines.push(Line {
+            offset,
+            speaker,
+            text,
+        });
+    }
+    lines.extend(paragraph);
+    lines
+}
+
+fn parse_cues(content: &str) -> Vec<Line> {
+    parse_vtt(content)
+        .into_iter()
+        .map(|(offset, speaker, text)| Line {
+            offset: Some(offset),
+            speaker,
+            text,
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct FirefliesSentence {
+    #[serde(default)]
+    speaker_name: Option<String>,
+    text: String,
+    #[serde(default)]
+    start_time: Option<f64>,
+}
+
+/// Fireflies' sentences, whether the file holds the API response, the transcript object
+/// or just the list
+fn parse_fireflies(content: &str) -> Result<Vec<Line>, VenkyError> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| VenkyError::Other(format!("Invalid Fireflies JSON: {}", e)))?;
+    let sentences = value
+        .pointer("/data/transcript/sentences")
+        .or_else(|| value.get("sentences"))
+        .unwrap_or(&value)
+        .clone();
+    let sentences: Vec<FirefliesSentence> = serde_json::from_value(sentences)
+        .map_err(|e| VenkyError::Other(format!("Invalid Fireflies transcript: {}", e)))?;
+    Ok(sentences
+        .into_iter()
+        .map(|s| Line {
+            offset: s.start_time,
+            speaker: s.speaker_name,
+            text: s.text.trim().to_string(),
+        })
+        .collect())
+}
+
+fn parse(content: &str, format: TranscriptFormat) -> Result<Vec<Line>, VenkyError> {
+    let is_cues = content.contains("-->");
+    Ok(match format {
+        TranscriptFormat::Vtt | TranscriptFormat::Teams | TranscriptFormat::Srt => {
+            parse_cues(content)
+        }
+        TranscriptFormat::Fireflies if content.trim_start().starts_with(['{', '[']) => {
+            parse_fireflies(content)?
+        }
+        TranscriptFormat::Otter | TranscriptFormat::Fireflies if is_cues => parse_cues(content),
+        TranscriptFormat::Otter | TranscriptFormat::Fireflies => parse_text(content, true),
+        TranscriptFormat::Text => parse_text(content, false),
+    })
+}
+
+/// Transcript entries stamped from `start`; untimed lines follow the previous one by the time
+/// it would take to say it
+fn to_entries(
+    lines: Vec<Line>,
+    start: chrono::DateTime<chrono::FixedOffset>,
+) -> Vec<TranscriptEntry> {
+    let mut next = 0.0;
+    lines
+        .into_iter()
+        .filter(|l| !l.text.is_empty())
+        .map(|line| {
+            let offset = line.offset.unwrap_or(next);
+            next = offset + spoken_secs(&line.text);
+            TranscriptEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                timestamp: (start + chrono::Duration::milliseconds((offset * 1000.0) as i64))
+                    .to_rfc3339(),
+                speaker: line.speaker.unwrap_or_else(|| UNKNOWN_SPEAKER.to_string()),
+                text: line.text,
+                source: Some("import".to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Seconds from the first line to the end of the last
+fn duration_secs(lines: &[Line]) -> f64 {
+    let mut end: f64 = 0.0;
+    for line in lines {
+        end = line.offset.unwrap_or(end) + spoken_secs(&line.text);
+    }
+    end
+}
+
+/// Create an ended session from a transcript exported by Zoom, Teams, Otter, Fireflies or
+/// written as plain text, with its speakers as participants. `format` defaults to one
+/// guessed from the file extension, `start_time` (RFC 3339) to the file's modification time
+/// less the meeting's length.
+#[tauri::command]
+pub async fn import_transcript(
+    storage: tauri::State<'_, StorageActor>,
+    path: String,
+    format: Option<TranscriptFormat>,
+    title: Option<String>,
+    start_time: Option<String>,
+) -> CommandResult<Session> {
+    let path = Path::new(&path);
+    let bytes = std::fs::read(path)
+        .map_err(|e| VenkyError::Storage(format!("Failed to read {}: {}", path.display(), e)))?;
+    if bytes.starts_with(b"PK") {
+        return Err(VenkyError::Other(
+            "Word documents can't be imported; download the transcript as .vtt or .txt".to_string(),
+        ));
+    }
+    let content = String::from_utf8_lossy(&bytes);
+    let format = format.unwrap_or_else(|| TranscriptFormat::from_extension(path));
+    let lines = parse(content.trim_start_matches('\u{feff}'), format)?;
+    if lines.iter().all(|l| l.text.is_empty()) {
+        return Err(VenkyError::Other(
+            "No transcript lines found in the file".to_string(),
+        ));
+    }
+
+    let duration = chrono::Duration::seconds(duration_secs(&lines).ceil() as i64);
+    let start = match start_time {
+        Some(ref start_time) => chrono::DateTime::parse_from_rfc3339(start_time)
+            .map_err(|e| VenkyError::Other(format!("Invalid start time: {}", e)))?,
+        None => {
+            let modified = std::fs::metadata(path)
+                .and_then(|m| m.modified())
+                .map(chrono::DateTime::<chrono::Utc>::from)
+                .unwrap_or_else(|_| chrono::Utc::now());
+            (modified - duration).fixed_offset()
+        }
+    };
+    let title = title
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().replace(['_', '-'], " "))
+        })
+        .unwrap_or_else(|| "Imported transcript".to_string());
+
+    let transcript = to_entries(lines, start);
+    let session = Session {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: title.trim().to_string(),
+        description: None,
+        meeting_window: None,
+        purpose: SessionPurpose::Meeting,
+        context: None,
+        prompt_template_id: None,
+        status: SessionStatus::Ended,
+        start_time: start.to_rfc3339(),
+        end_time: Some((start + duration).to_rfc3339()),
+        transcript: Vec::new(),
+        suggestions: Vec::new(),
+        summary: None,
+    };
+
+    Ok(storage
+        .call(move |s| {
+            s.save_session(&session)?;
+            s.update_transcript(&session.id, &transcript)?;
+            let mut seen = HashSet::new();
+            for entry in &transcript {
+                if entry.speaker == UNKNOWN_SPEAKER || !seen.insert(entry.speaker.to_lowercase()) {
+                    continue;
+                }
+                s.add_participant(&Participant {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    session_id: session.id.clone(),
+                    name: entry.speaker.clone(),
+                    email: None,
+                    role: None,
+                    crm_contact_id: None,
+                    source: "speaker".to_string(),
+                })?;
+            }
+            s.get_session(&session.id)
+        })
+        .await?)
+}
@@ -0,0 +1,199 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::manager::TIMELINE_SPEAKER;
+use super::storage::InterviewQa;
+use super::storage_actor::StorageActor;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+const QA_PROMPT: &str = "You review job interview transcripts. The transcript lines are numbered. \
+    Find every question the interviewer asked the candidate and pair it with the candidate's answer. \
+    Respond with a JSON object of the form \
+    {\"pairs\": [{\"question_line\": number, \"question\": string, \"answer\": string or null}]}. \
+    Use the line where the question was asked. Rephrase the question cleanly but keep its meaning; \
+    give the answer as the candidate said it, lightly cleaned of filler words. \
+    Use null when the candidate didn't answer. Skip small talk and logistics.";
+
+#[derive(Debug, Deserialize)]
+struct DetectedPairs {
+    #[serde(default)]
+    pairs: Vec<DetectedPair>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DetectedPair {
+    question_line: usize,
+    question: String,
+    answer: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterviewQaExtracted {
+    pub session_id: String,
+    pub pairs: Vec<InterviewQa>,
+}
+
+/// Pair each interviewer question with the answer given and the AI suggestion shown for it,
+/// replacing the session's earlier log
+pub async fn extract(
+    cfg: &AppConfig,
+    storage: &StorageActor,
+    session_id: &str,
+) -> Result<Vec<InterviewQa>, VenkyError> {
+    let id = session_id.to_string();
+    let (session, suggestions) = storage
+        .call(move |s| Ok((s.get_session(&id)?, s.get_session_suggestions(&id)?)))
+        .await?;
+
+    let entries = session
+        .transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER)
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return Err(VenkyError::Other(
+            "No transcript to extract questions from".to_string(),
+        ));
+    }
+
+    let transcript = entries
+        .iter()
+        .enumerate()
+        .map(|(i, e)| format!("{}. {}: {}", i, e.speaker, e.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let question = format!(
+        "Interview: {}\n\nTranscript:\n{}",
+        session.title, transcript
+    );
+    let raw = crate::redaction::for_session(
+        session_id,
+        crate::ai::llm::generate_json(cfg, QA_PROMPT, &question),
+    )
+    .await?;
+    let parsed: DetectedPairs = serde_json::from_str(&raw).map_err(|e| {
+        VenkyError::Provider(format!("Model returned an invalid interview log: {}", e))
+    })?;
+
+    let mut detected = parsed
+        .pairs
+        .into_iter()
+        .filter(|p| !p.question.trim().is_empty() && p.question_line < entries.len())
+        .collect::<Vec<_>>();
+    detected.sort_by_key(|p| p.question_line);
+
+    let asked_at = |p: &DetectedPair| entries[p.question_line].timestamp.clone();
+    let pairs: Vec<InterviewQa> = detected
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            // The suggestion shown after the question was asked and before the next one
+            let from = asked_at(p);
+            let until = detected.get(i + 1).map(asked_at);
+            let suggested_answer = suggestions
+                .iter()
+                .find(|(created_at, _)| {
+                    *created_at >= from && until.as_ref().map(|u| created_at < u).unwrap_or(true)
+                })
+                .map(|(_, text)| text.clone());
+
+            InterviewQa {
+                id: uuid::Uuid::new_v4().to_string(),
+                session_id: session_id.to_string(),
+                seq: i as u32,
+                asked_at: from,
+                question: p.question.trim().to_string(),
+                answer: p
+                    .answer
+                    .as_ref()
+                    .map(|a| a.trim().to_string())
+                    .filter(|a| !a.is_empty()),
+                suggested_answer,
+            }
+        })
+        .collect();
+
+    let (id, stored) = (session_id.to_string(), pairs.clone());
+    storage
+        .call(move |s| s.replace_interview_qa(&id, &stored))
+        .await?;
+    Ok(pairs)
+}
+
+/// After an interview ends, build its question/answer log in the background and emit "interview-qa-extracted"
+pub fn extract_in_background(app: &AppHandle, session_id: &str) {
+    let cfg = app.state::<ConfigState>().lock().clone();
+    let storage = app.state::<StorageActor>().inner().clone();
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        match extract(&cfg, &storage, &session_id).await {
+            Ok(pairs) => {
+                log::info!(
+                    "Extracted {} interview questions from session {}",
+                    pairs.len(),
+                    session_id
+                );
+                let _ = app.emit(
+                    "interview-qa-extracted",
+                    InterviewQaExtracted { session_id, pairs },
+                );
+            }
+            Err(e) => log::warn!("Interview log for {} failed: {}", session_id, e),
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn extract_interview_qa(
+    config: tauri::State<'_, ConfigState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<InterviewQa>> {
+    let cfg = config.lock().clone();
+    extract(&cfg, &storage, &session_id).await
+}
+
+#[tauri::command]
+pub async fn get_interview_qa(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<InterviewQa>> {
+    Ok(storage
+        .call(move |s| s.get_interview_qa(&session_id))
+        .await?)
+}
+
+/// The interview log as Markdown, for self-review outside the app
+#[tauri::command]
+pub async fn export_interview_qa(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<String> {
+    let (session, pairs) = storage
+        .call(move |s| {
+            Ok((
+                s.get_session(&session_id)?,
+                s.get_interview_qa(&session_id)?,
+            ))
+        })
+        .await?;
+
+    let mut md = format!("# {}\n\n_{}_\n", session.title, session.start_time);
+    for qa in &pairs {
+        md.push_str(&format!("\n## Q{}: {}\n\n", qa.seq + 1, qa.question));
+        md.push_str(&format!(
+            "**Your answer:** {}\n\n",
+            qa.answer.as_deref().unwrap_or("_(no answer)_")
+        ));
+        if let Some(ref suggested) = qa.suggested_answer {
+            md.push_str(&format!("**Suggested answer:** {}\n", suggested));
+        }
+    }
+    Ok(md)
+}
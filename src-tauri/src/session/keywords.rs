@@ -0,0 +1,153 @@
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::manager::{TranscriptEntry, TIMELINE_SPEAKER};
+use super::storage::KeywordWatch;
+use super::storage_actor::StorageActor;
+
+use crate::error::{CommandResult, VenkyError};
+
+/// Characters of context kept on each side of a match in the alert snippet
+const SNIPPET_CONTEXT_CHARS: usize = 80;
+
+/// Emitted as "keyword-alert" when a watched word or phrase comes up
+#[derive(Debug, Clone, Serialize)]
+pub struct KeywordAlert {
+    pub session_id: String,
+    pub watch_id: String,
+    pub label: String,
+    pub matched: String,
+    pub snippet: String,
+    pub entry_id: String,
+    pub speaker: String,
+    pub timestamp: String,
+    pub response: Option<String>,
+}
+
+/// Plain patterns match as whole words; both kinds ignore case
+pub fn compile(watch: &KeywordWatch) -> Result<Regex, VenkyError> {
+    let pattern = if watch.is_regex {
+        watch.pattern.clone()
+    } else {
+        format!(r"\b{}\b", regex::escape(watch.pattern.trim()))
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| VenkyError::Other(format!("Invalid pattern for {}: {}", watch.label, e)))
+}
+
+/// The match with some surrounding text, cut on char boundaries
+fn snippet(text: &str, start: usize, end: usize) -> String {
+    let from = text[..start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT_CHARS)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let to = text[end..]
+        .char_indices()
+        .nth(SNIPPET_CONTEXT_CHARS)
+        .map(|(i, _)| end + i)
+        .unwrap_or(text.len());
+
+    let mut snippet = text[from..to].trim().to_string();
+    if from > 0 {
+        snippet.insert(0, '…');
+    }
+    if to < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// Emit a "keyword-alert" for every enabled watch the new transcript entry matches
+pub fn check_entry(app: &AppHandle, session_id: &str, entry: &TranscriptEntry) {
+    if entry.speaker == TIMELINE_SPEAKER {
+        return;
+    }
+
+    let (app, session_id, entry) = (app.clone(), session_id.to_string(), entry.clone());
+    tauri::async_runtime::spawn(async move {
+        let storage = app.state::<StorageActor>();
+        let watches = match storage.call(|s| s.get_keyword_watches()).await {
+            Ok(watches) => watches,
+            Err(e) => {
+                log::error!("Failed to load keyword watches: {}", e);
+                return;
+            }
+        };
+
+        for watch in watches.iter().filter(|w| w.enabled) {
+            let re = match compile(watch) {
+                Ok(re) => re,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    continue;
+                }
+            };
+            let Some(m) = re.find(&entry.text) else {
+                continue;
+            };
+
+            let _ = app.emit(
+                "keyword-alert",
+                KeywordAlert {
+                    session_id: session_id.clone(),
+                    watch_id: watch.id.clone(),
+                    label: watch.label.clone(),
+                    matched: m.as_str().to_string(),
+                    snippet: snippet(&entry.text, m.start(), m.end()),
+                    entry_id: entry.id.clone(),
+                    speaker: entry.speaker.clone(),
+                    timestamp: entry.timestamp.clone(),
+                    response: watch.response.clone(),
+                },
+            );
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_keyword_watches(
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<Vec<KeywordWatch>> {
+    Ok(storage.call(|s| s.get_keyword_watches()).await?)
+}
+
+/// Create (empty id) or update a keyword watch; regex patterns are checked before saving
+#[tauri::command]
+pub async fn save_keyword_watch(
+    storage: tauri::State<'_, StorageActor>,
+    mut watch: KeywordWatch,
+) -> CommandResult<KeywordWatch> {
+    if watch.pattern.trim().is_empty() {
+        return Err(VenkyError::Other(
+            "A keyword watch needs a word, phrase or pattern".to_string(),
+        ));
+    }
+    if watch.label.trim().is_empty() {
+        watch.label = watch.pattern.trim().to_string();
+    }
+    watch.response = watch
+        .response
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    compile(&watch)?;
+    if watch.id.is_empty() {
+        watch.id = uuid::Uuid::new_v4().to_string();
+    }
+
+    let row = watch.clone();
+    storage.call(move |s| s.save_keyword_watch(&row)).await?;
+    Ok(watch)
+}
+
+#[tauri::command]
+pub async fn delete_keyword_watch(
+    storage: tauri::State<'_, StorageActor>,
+    id: String,
+) -> CommandResult<()> {
+    Ok(storage.call(move |s| s.delete_keyword_watch(&id)).await?)
+}
@@ -1,19 +1,31 @@
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
 
-use super::storage::Storage;
+use super::storage::Highlight;
+use super::storage_actor::StorageActor;
 use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
 
-type StorageState = Arc<Mutex<Storage>>;
 type ConfigState = Arc<Mutex<AppConfig>>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
     pub id: String,
     pub title: String,
-    pub purpose: String,
+    /// One-line description, set when the session is titled from its content
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Title of the meeting window a session was auto-started for, which stays put when
+    /// the session is retitled
+    #[serde(default)]
+    pub meeting_window: Option<String>,
+    pub purpose: SessionPurpose,
     pub context: Option<String>, // Added for resume/profile info
+    /// Prompt template whose instructions steer the suggestions for this session
+    pub prompt_template_id: Option<String>,
     pub status: SessionStatus,
     pub start_time: String,
     pub end_time: Option<String>,
@@ -22,52 +34,212 @@ pub struct Session {
     pub summary: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionPurpose {
+    Meeting,
+    Interview,
+    Sales,
+    Casual,
+    #[serde(other)]
+    Custom,
+}
+
+impl SessionPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionPurpose::Meeting => "meeting",
+            SessionPurpose::Interview => "interview",
+            SessionPurpose::Sales => "sales",
+            SessionPurpose::Casual => "casual",
+            SessionPurpose::Custom => "custom",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "meeting" => SessionPurpose::Meeting,
+            "interview" => SessionPurpose::Interview,
+            "sales" => SessionPurpose::Sales,
+            "casual" => SessionPurpose::Casual,
+            _ => SessionPurpose::Custom,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SessionStatus {
     Active,
     Paused,
     Ended,
+    /// The app quit while the session was running; it carries on at the next launch
+    Interrupted,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptEntry {
+    #[serde(default)]
+    pub id: String,
     pub timestamp: String,
     pub speaker: String,
     pub text: String,
+    /// Audio stream the entry was transcribed from ("mic", "system"), if known
+    #[serde(default)]
+    pub source: Option<String>,
 }
 
 pub struct SessionManager {
-    pub current_session: Option<Session>,
+    /// Sessions in progress, by id
+    pub sessions: HashMap<String, Session>,
+    /// The session live transcription, suggestions and highlights go to
+    pub focused_id: Option<String>,
+    storage: StorageActor,
+    app: AppHandle,
+}
+
+/// Payload of "transcript-save-failed", for an entry that is shown but wasn't saved
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptSaveFailed {
+    pub session_id: String,
+    pub entry_id: String,
+    pub error: String,
 }
 
 impl SessionManager {
-    pub fn new() -> Self {
-        Self {
-            current_session: None,
+    /// Picks up sessions a crash cut off, so their transcripts carry on. Sessions the app
+    /// was quit in the middle of wait for `resume_session` or `end_session` instead.
+    pub fn new(storage: StorageActor, app: AppHandle) -> Self {
+        let mut recovered = match storage.call_blocking(|s| s.get_unfinished_sessions()) {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                log::error!("Failed to look for unfinished sessions: {}", e);
+                Vec::new()
+            }
+        };
+        recovered.retain(|s| s.status != SessionStatus::Interrupted);
+        for session in &recovered {
+            log::info!(
+                "Recovered unfinished session {} ({} transcript entries)",
+                session.id,
+                session.transcript.len()
+            );
         }
+
+        let mut mgr = Self {
+            sessions: recovered.into_iter().map(|s| (s.id.clone(), s)).collect(),
+            focused_id: None,
+            storage,
+            app,
+        };
+        mgr.refocus();
+        mgr
+    }
+
+    pub fn focused(&self) -> Option<&Session> {
+        self.focused_id
+            .as_ref()
+            .and_then(|id| self.sessions.get(id))
+    }
+
+    pub fn focused_mut(&mut self) -> Option<&mut Session> {
+        self.focused_id
+            .as_ref()
+            .and_then(|id| self.sessions.get_mut(id))
+    }
+
+    /// Focus the most recently started session, if any is left
+    fn refocus(&mut self) {
+        self.focused_id = self
+            .sessions
+            .values()
+            .max_by(|a, b| a.start_time.cmp(&b.start_time))
+            .map(|s| s.id.clone());
+    }
+
+    /// Append to the focused session's transcript, writing the entry through to storage
+    pub fn push_entry(&mut self, entry: TranscriptEntry) -> Result<(), VenkyError> {
+        let id = self
+            .focused_id
+            .clone()
+            .ok_or_else(|| VenkyError::Other("No active session".to_string()))?;
+        self.push_entry_to(&id, entry)
+    }
+
+    /// Append to a specific active session's transcript. The write is queued on the storage
+    /// thread, so a failure to save can't be returned; it emits "transcript-save-failed".
+    pub fn push_entry_to(
+        &mut self,
+        session_id: &str,
+        entry: TranscriptEntry,
+    ) -> Result<(), VenkyError> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| VenkyError::Other(format!("Session {} is not active", session_id)))?;
+
+        session.transcript.push(entry.clone());
+        let session_id = session_id.to_string();
+        let app = self.app.clone();
+        self.storage.send(move |s| {
+            if let Err(error) = s.append_transcript_entry(&session_id, &entry) {
+                log::error!("Failed to save transcript entry: {}", error);
+                let _ = app.emit(
+                    "transcript-save-failed",
+                    TranscriptSaveFailed {
+                        session_id,
+                        entry_id: entry.id,
+                        error: error.to_string(),
+                    },
+                );
+            }
+        });
+        Ok(())
     }
 }
 
 type SessionState = Arc<Mutex<SessionManager>>;
 
+/// Speaker used for non-speech events (capture paused/resumed) in the transcript timeline
+pub const TIMELINE_SPEAKER: &str = "system";
+
+/// Record an event in the focused session's timeline, if there is one
+pub fn mark_timeline(session_state: &SessionState, text: &str) {
+    let mut mgr = session_state.lock();
+    if mgr.focused_id.is_none() {
+        return;
+    }
+    let entry = TranscriptEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        speaker: TIMELINE_SPEAKER.to_string(),
+        text: text.to_string(),
+        source: None,
+    };
+    if let Err(e) = mgr.push_entry(entry) {
+        log::error!("Failed to persist timeline event: {}", e);
+    }
+}
+
+/// Start a session and focus it; sessions already running carry on in the background
 #[tauri::command]
 pub fn create_session(
+    app: tauri::AppHandle,
     session_state: tauri::State<'_, SessionState>,
     title: String,
-    purpose: String,
+    purpose: SessionPurpose,
     context: Option<String>,
-) -> Result<Session, String> {
+    prompt_template_id: Option<String>,
+) -> CommandResult<Session> {
     let mut mgr = session_state.lock();
 
-    if mgr.current_session.is_some() {
-        return Err("A session is already active. End it before starting a new one.".to_string());
-    }
-
     let session = Session {
         id: uuid::Uuid::new_v4().to_string(),
         title,
+        description: None,
+        meeting_window: None,
         purpose,
         context,
+        prompt_template_id,
         status: SessionStatus::Active,
         start_time: chrono::Utc::now().to_rfc3339(),
         end_time: None,
@@ -76,74 +248,323 @@ pub fn create_session(
         summary: None,
     };
 
-    mgr.current_session = Some(session.clone());
+    // Saved up front (without an end time) so the session can be recovered after a crash.
+    // Queued ahead of its transcript entries, which need the row to exist.
+    let row = session.clone();
+    mgr.storage.send(move |s| {
+        if let Err(e) = s.save_session(&row) {
+            log::error!("Failed to save session {}: {}", row.id, e);
+        }
+    });
+    let first = mgr.sessions.is_empty();
+    mgr.sessions.insert(session.id.clone(), session.clone());
+    mgr.focused_id = Some(session.id.clone());
+    drop(mgr);
+
+    // The screen is recorded once, for the session that was running first
+    if first {
+        crate::capture::screen_recording::start_if_enabled(&app, &session.id);
+    }
+    let _ = app.emit("session-focused", &session.id);
+    super::consent::on_session_start(&app, &session);
+    crate::api_server::publish(&app, "session-started", &session);
+    crate::integrations::webhooks::dispatch(
+        &app,
+        crate::integrations::webhooks::SESSION_STARTED,
+        serde_json::json!({ "session": &session }),
+    );
     Ok(session)
 }
 
+/// End the given session, or the focused one; focus moves to the most recent remaining session
 #[tauri::command]
-pub fn end_session(
-    session_state: tauri::State<'_, SessionState>,
-    storage_state: tauri::State<'_, StorageState>,
-) -> Result<Session, String> {
-    let mut mgr = session_state.lock();
+pub async fn end_session(
+    app: tauri::AppHandle,
+    session_id: Option<String>,
+) -> CommandResult<Session> {
+    let (id, live) = {
+        let state = app.state::<SessionState>();
+        let mut mgr = state.lock();
+
+        let id = session_id
+            .or_else(|| mgr.focused_id.clone())
+            .ok_or_else(|| VenkyError::Other("No active session".to_string()))?;
+        let live = mgr.sessions.remove(&id).map(|mut session| {
+            session.status = SessionStatus::Ended;
+            session.end_time = Some(chrono::Utc::now().to_rfc3339());
+            session
+        });
+
+        if live.is_some() && mgr.focused_id.as_deref() == Some(id.as_str()) {
+            mgr.refocus();
+            if let Some(ref id) = mgr.focused_id {
+                let _ = app.emit("session-focused", id);
+            }
+        }
+        let last = mgr.sessions.is_empty();
+        (id, live.map(|session| (session, last)))
+    };
 
-    let session = mgr
-        .current_session
-        .as_mut()
-        .ok_or_else(|| "No active session".to_string())?;
+    // Save to storage, off the IPC thread; the work below reads the saved session back
+    let storage = app.state::<StorageActor>().inner().clone();
+    let (finished, last) = match live {
+        Some((finished, last)) => {
+            let row = finished.clone();
+            if let Err(e) = storage.call(move |s| s.save_session(&row)).await {
+                log::error!("Failed to save session {}: {}", finished.id, e);
+            }
+            (finished, Some(last))
+        }
+        // A session the app was quit in the middle of ends when the app did; its
+        // recordings were kept on the way out
+        None => {
+            let finished = storage
+                .call(move |s| {
+                    s.end_interrupted_session(&id)?;
+                    s.get_session(&id)
+                })
+                .await?;
+            (finished, None)
+        }
+    };
+    let coaching = super::coaching::compute(&finished, None);
+    if let Err(e) = storage
+        .call(move |s| s.save_coaching_metrics(&coaching))
+        .await
+    {
+        log::error!("Failed to save coaching metrics for {}: {}", finished.id, e);
+    }
 
-    session.status = SessionStatus::Ended;
-    session.end_time = Some(chrono::Utc::now().to_rfc3339());
+    // Audio capture is shared, so it stops with the last session; each session that used it
+    // gets its own part of the recording then
+    match last {
+        Some(true) => {
+            crate::capture::recording::save_if_enabled(&app, std::slice::from_ref(&finished));
+        }
+        Some(false) => crate::capture::recording::hold_until_capture_stops(&app, &finished),
+        None => {}
+    }
+    crate::capture::screen_recording::stop_and_save(&app, &finished.id);
+    super::consent::on_session_end(&app, &finished.id);
+    super::action_items::extract_if_enabled(&app, &finished.id);
+    super::chapters::generate_if_enabled(&app, &finished.id);
+    super::titles::retitle_if_enabled(&app, &finished.id);
+    if finished.purpose == SessionPurpose::Interview {
+        super::interview::extract_in_background(&app, &finished.id);
+    }
+    crate::integrations::crm::auto_sync::sync_if_enabled(&app, &finished.id);
+    crate::integrations::notify::notify_session_ended(&app, &finished.id);
+    crate::api_server::publish(&app, "session-ended", &finished);
+    Ok(finished)
+}
 
-    let finished = session.clone();
+/// Sessions the app was quit in the middle of, oldest first, for `resume_session` or
+/// `end_session`
+#[tauri::command]
+pub async fn get_interrupted_sessions(
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<Vec<Session>> {
+    let sessions = storage.call(|s| s.get_unfinished_sessions()).await?;
+    Ok(sessions
+        .into_iter()
+        .filter(|s| s.status == SessionStatus::Interrupted)
+        .collect())
+}
 
-    // Save to storage
-    let storage = storage_state.lock();
-    storage.save_session(&finished).ok();
+/// Carry on with a session the app was quit in the middle of and focus it; emits
+/// "session-focused". Capture is started again separately.
+#[tauri::command]
+pub async fn resume_session(app: AppHandle, session_id: String) -> CommandResult<Session> {
+    let storage = app.state::<StorageActor>().inner().clone();
+    let id = session_id.clone();
+    let mut session = storage.call(move |s| s.get_session(&id)).await?;
+    if session.status != SessionStatus::Interrupted {
+        return Err(VenkyError::Other(format!(
+            "Session {} was not interrupted",
+            session_id
+        )));
+    }
+    let id = session_id.clone();
+    storage.call(move |s| s.mark_session_resumed(&id)).await?;
+    session.status = SessionStatus::Active;
+    log::info!("Resumed interrupted session {}", session.id);
 
-    mgr.current_session = None;
-    Ok(finished)
+    {
+        let state = app.state::<SessionState>();
+        let mut mgr = state.lock();
+        mgr.sessions.insert(session.id.clone(), session.clone());
+        mgr.focused_id = Some(session.id.clone());
+    }
+    let _ = app.emit("session-focused", &session.id);
+    Ok(session)
 }
 
+/// The focused session
 #[tauri::command]
 pub fn get_current_session(
     session_state: tauri::State<'_, SessionState>,
 ) -> Option<Session> {
-    session_state.lock().current_session.clone()
+    session_state.lock().focused().cloned()
 }
 
+/// All sessions in progress, oldest first
 #[tauri::command]
-pub fn add_transcript_entry(
+pub fn get_active_sessions(session_state: tauri::State<'_, SessionState>) -> Vec<Session> {
+    let mut sessions = session_state
+        .lock()
+        .sessions
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    sessions.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+    sessions
+}
+
+/// Send live transcription and suggestions to another active session; emits "session-focused"
+#[tauri::command]
+pub fn focus_session(
+    app: AppHandle,
     session_state: tauri::State<'_, SessionState>,
-    speaker: String,
-    text: String,
-) -> Result<TranscriptEntry, String> {
+    session_id: String,
+) -> CommandResult<Session> {
     let mut mgr = session_state.lock();
-
     let session = mgr
-        .current_session
-        .as_mut()
-        .ok_or_else(|| "No active session".to_string())?;
+        .sessions
+        .get(&session_id)
+        .cloned()
+        .ok_or_else(|| VenkyError::Other(format!("Session {} is not active", session_id)))?;
+    mgr.focused_id = Some(session_id);
+    drop(mgr);
+
+    let _ = app.emit("session-focused", &session.id);
+    Ok(session)
+}
 
+#[tauri::command]
+pub fn add_transcript_entry(
+    app: AppHandle,
+    session_state: tauri::State<'_, SessionState>,
+    speaker: String,
+    text: String,
+    source: Option<String>,
+) -> CommandResult<TranscriptEntry> {
     let entry = TranscriptEntry {
+        id: uuid::Uuid::new_v4().to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
         speaker,
         text,
+        source,
     };
 
-    session.transcript.push(entry.clone());
+    let session_id = {
+        let mut mgr = session_state.lock();
+        mgr.push_entry(entry.clone())?;
+        mgr.focused_id.clone().unwrap_or_default()
+    };
+    if entry.speaker != TIMELINE_SPEAKER {
+        crate::capture::privacy::check_text(&app, "transcript", &entry.text);
+        crate::api_server::publish(
+            &app,
+            "transcript",
+            serde_json::json!({ "session_id": &session_id, "entry": &entry }),
+        );
+        crate::integrations::webhooks::dispatch(
+            &app,
+            crate::integrations::webhooks::TRANSCRIPTION_CHUNK,
+            serde_json::json!({ "session_id": &session_id, "entry": &entry }),
+        );
+    }
+    super::keywords::check_entry(&app, &session_id, &entry);
+    super::objections::check_entry(&app, &session_id, &entry);
     Ok(entry)
 }
 
+/// Correct a transcript line of the live session or a saved one; the original is kept in the edit log
+#[tauri::command]
+pub async fn update_transcript_entry(
+    session_state: tauri::State<'_, SessionState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+    entry_id: String,
+    new_text: Option<String>,
+    new_speaker: Option<String>,
+) -> CommandResult<TranscriptEntry> {
+    // Queued behind any pending writes of the entry, so the edit applies to the saved line
+    let (sid, eid) = (session_id.clone(), entry_id.clone());
+    let updated = storage
+        .call(move |s| {
+            s.edit_transcript_entry(&sid, &eid, new_text.as_deref(), new_speaker.as_deref())
+        })
+        .await?;
+
+    let mut mgr = session_state.lock();
+    if let Some(session) = mgr.sessions.get_mut(&session_id) {
+        if let Some(entry) = session.transcript.iter_mut().find(|e| e.id == entry_id) {
+            *entry = updated.clone();
+        }
+    }
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn delete_transcript_entry(
+    session_state: tauri::State<'_, SessionState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+    entry_id: String,
+) -> CommandResult<()> {
+    let (sid, eid) = (session_id.clone(), entry_id.clone());
+    storage
+        .call(move |s| s.delete_transcript_entry(&sid, &eid))
+        .await?;
+
+    let mut mgr = session_state.lock();
+    if let Some(session) = mgr.sessions.get_mut(&session_id) {
+        session.transcript.retain(|e| e.id != entry_id);
+    }
+    Ok(())
+}
+
+/// Bookmark the current moment of the focused session and emit "highlight-marked"
+pub async fn add_highlight(
+    app: &AppHandle,
+    note: Option<String>,
+) -> Result<Highlight, VenkyError> {
+    let session_id = app
+        .state::<SessionState>()
+        .lock()
+        .focused_id
+        .clone()
+        .ok_or_else(|| VenkyError::Other("No active session".to_string()))?;
+
+    let highlight = Highlight {
+        id: uuid::Uuid::new_v4().to_string(),
+        session_id,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        note: note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+    };
+    let stored = highlight.clone();
+    app.state::<StorageActor>()
+        .call(move |s| s.add_highlight(&stored))
+        .await?;
+
+    let _ = app.emit("highlight-marked", &highlight);
+    Ok(highlight)
+}
+
+#[tauri::command]
+pub async fn mark_highlight(app: AppHandle, note: Option<String>) -> CommandResult<Highlight> {
+    add_highlight(&app, note).await
+}
+
 #[tauri::command]
 pub fn get_session_transcript(
     session_state: tauri::State<'_, SessionState>,
-) -> Result<Vec<TranscriptEntry>, String> {
+) -> CommandResult<Vec<TranscriptEntry>> {
     let mgr = session_state.lock();
     let session = mgr
-        .current_session
-        .as_ref()
-        .ok_or_else(|| "No active session".to_string())?;
+        .focused()
+        .ok_or_else(|| VenkyError::Other("No active session".to_string()))?;
     Ok(session.transcript.clone())
 }
 
@@ -151,56 +572,37 @@ pub fn get_session_transcript(
 pub async fn generate_summary(
     session_state: tauri::State<'_, SessionState>,
     config_state: tauri::State<'_, ConfigState>,
-) -> Result<String, String> {
-    let transcript_text = {
-        let mgr = session_state.lock();
-        let session = mgr
-            .current_session
-            .as_ref()
-            .ok_or_else(|| "No active session".to_string())?;
-
-        session
-            .transcript
-            .iter()
-            .map(|e| format!("[{}] {}: {}", e.timestamp, e.speaker, e.text))
-            .collect::<Vec<_>>()
-            .join("\n")
-    };
-
-    if transcript_text.is_empty() {
-        return Err("No transcript to summarize".to_string());
-    }
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<String> {
+    let session = session_state
+        .lock()
+        .focused()
+        .cloned()
+        .ok_or_else(|| VenkyError::Other("No active session".to_string()))?;
+
+    let (id, purpose) = (session.id.clone(), session.purpose);
+    let (highlights, chapters, template) = storage
+        .call(move |s| {
+            Ok((
+                s.get_session_highlights(&id)?,
+                s.get_session_chapters(&id)?,
+                super::summary::template_for(s, purpose, None)?,
+            ))
+        })
+        .await?;
 
     let cfg = config_state.lock().clone();
-
-    let question = format!(
-        "Summarize the following meeting transcript into key points, action items, and decisions:\n\n{}",
-        transcript_text
-    );
-
-    let context = crate::ai::AIContext {
-        transcript: Some(transcript_text),
-        screen_description: None,
-        custom_prompt: Some("Generate a concise meeting summary with: 1) Key Points 2) Action Items 3) Decisions Made".to_string()),
-        screen_base64: None,
-    };
-
-    let response = match cfg.llm_provider {
-        crate::config::LLMProvider::OpenAI => {
-            crate::ai::openai::generate(&cfg, &question, &context).await?
-        }
-        crate::config::LLMProvider::Ollama => {
-            crate::ai::ollama::generate(&cfg, &question, &context).await?
-        }
-    };
+    let summary =
+        super::summary::summarize(&cfg, &session, &highlights, &chapters, &template, |_, _| {})
+            .await?;
 
     // Save summary to session
     {
         let mut mgr = session_state.lock();
-        if let Some(ref mut session) = mgr.current_session {
-            session.summary = Some(response.content.clone());
+        if let Some(live) = mgr.sessions.get_mut(&session.id) {
+            live.summary = Some(summary.clone());
         }
     }
 
-    Ok(response.content)
+    Ok(summary)
 }
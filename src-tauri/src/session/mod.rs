@@ -1,2 +1,23 @@
+pub mod action_items;
+pub mod chapters;
+pub mod coaching;
+pub mod consent;
+pub mod contacts;
+pub mod encryption;
+pub mod export;
+pub mod import;
+pub mod interview;
+pub mod keywords;
 pub mod manager;
+pub mod objections;
+pub mod retention;
+pub mod sentiment;
+pub mod stats;
 pub mod storage;
+pub mod storage_actor;
+pub mod subtitles;
+pub mod summary;
+pub mod teleprompter;
+pub mod titles;
+pub mod type_out;
+pub mod vault;
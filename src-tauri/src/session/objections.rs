@@ -0,0 +1,192 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::coaching::is_user;
+use super::manager::{SessionManager, SessionPurpose, TranscriptEntry, TIMELINE_SPEAKER};
+use super::storage::KeywordWatch;
+use super::storage_actor::StorageActor;
+use crate::config::AppConfig;
+use crate::error::VenkyError;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Common sales objections, by category
+static OBJECTION_CUES: Lazy<Vec<(&'static str, Regex)>> = Lazy::new(|| {
+    [
+        (
+            "price",
+            r"too (expensive|pricey|much)|over (our|the) budget|(can't|cannot|can not) afford|(no|don't have the|out of) budget|cheaper",
+        ),
+        (
+            "competitor",
+            r"(we|we're) already (use|using|have|work with)|happy with (our|what we have|the current)|locked into|(current|existing) (vendor|provider|tool)",
+        ),
+        (
+            "timing",
+            r"not (the right|a good) time|not a priority|next (quarter|year)|maybe later|circle back|revisit (this|it) later",
+        ),
+        (
+            "authority",
+            r"(check|talk|run it by|speak) with (my|our|the) (boss|manager|team|ceo|cfo|legal|procurement)|not my (call|decision)|need (approval|sign-off|buy-in)",
+        ),
+        (
+            "need",
+            r"(don't|do not) (really )?(need|see the need)|not sure (we|it) (need|would help)|doesn't (fit|apply to) us",
+        ),
+        (
+            "trust",
+            r"never heard of you|(how|why) (should|would) we trust|too risky|(concerned|worried) about (security|risk|support)",
+        ),
+    ]
+    .into_iter()
+    .map(|(category, pattern)| (category, Regex::new(&format!(r"(?i)\b({})", pattern)).unwrap()))
+    .collect()
+});
+
+const HANDLING_PROMPT: &str =
+    "You coach a salesperson during a live call. The prospect just raised \
+    an objection. Write a short, battlecard-style rebuttal the salesperson can say right away: \
+    acknowledge the concern, reframe it, and end with a question that moves the deal forward. \
+    Use the battlecards and call context when they are relevant; never invent prices or features. \
+    Respond with a JSON object of the form {\"handling\": string}.";
+
+#[derive(Debug, Deserialize)]
+struct GeneratedHandling {
+    handling: String,
+}
+
+/// Emitted as "objection-detected"
+#[derive(Debug, Clone, Serialize)]
+pub struct ObjectionDetected {
+    pub session_id: String,
+    pub entry_id: String,
+    /// "price", "competitor", "timing", "authority", "need" or "trust"
+    pub category: String,
+    /// The transcript line that raised the objection
+    pub objection: String,
+    pub handling: String,
+    /// "battlecard" when a keyword watch's response was used, "generated" otherwise
+    pub source: String,
+    /// Keyword watch the battlecard came from
+    pub watch_id: Option<String>,
+}
+
+/// Objection category raised in `text`, if any
+pub fn detect(text: &str) -> Option<&'static str> {
+    OBJECTION_CUES
+        .iter()
+        .find(|(_, re)| re.is_match(text))
+        .map(|(category, _)| *category)
+}
+
+/// Keyword watches that carry a prepared response
+async fn battlecards(app: &AppHandle) -> Vec<KeywordWatch> {
+    let storage = app.state::<StorageActor>();
+    match storage.call(|s| s.get_keyword_watches()).await {
+        Ok(watches) => watches
+            .into_iter()
+            .filter(|w| w.enabled && w.response.is_some())
+            .collect(),
+        Err(e) => {
+            log::error!("Failed to load battlecards: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+async fn generate_handling(
+    cfg: &AppConfig,
+    objection: &str,
+    context: Option<&str>,
+    battlecards: &[KeywordWatch],
+) -> Result<String, VenkyError> {
+    let mut question = format!("Objection: {}", objection);
+    if let Some(context) = context.filter(|c| !c.trim().is_empty()) {
+        question.push_str(&format!("\n\nCall context:\n{}", context));
+    }
+    if !battlecards.is_empty() {
+        question.push_str("\n\nBattlecards:");
+        for card in battlecards {
+            question.push_str(&format!(
+                "\n- {}: {}",
+                card.label,
+                card.response.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+
+    let raw = crate::ai::llm::generate_json(cfg, HANDLING_PROMPT, &question).await?;
+    let parsed: GeneratedHandling = serde_json::from_str(&raw)
+        .map_err(|e| VenkyError::Provider(format!("Model returned invalid handling: {}", e)))?;
+    let handling = parsed.handling.trim().to_string();
+    if handling.is_empty() {
+        return Err(VenkyError::Provider(
+            "Model returned an empty rebuttal".to_string(),
+        ));
+    }
+    Ok(handling)
+}
+
+/// Look for an objection from the prospect in a sales session and emit "objection-detected" with handling
+pub fn check_entry(app: &AppHandle, session_id: &str, entry: &TranscriptEntry) {
+    if entry.speaker == TIMELINE_SPEAKER || is_user(entry) {
+        return;
+    }
+    let cfg = app.state::<ConfigState>().lock().clone();
+    if !cfg.objection_handling {
+        return;
+    }
+
+    let session_state = app.state::<SessionState>();
+    let context = {
+        let mgr = session_state.lock();
+        match mgr.sessions.get(session_id) {
+            Some(s) if s.purpose == SessionPurpose::Sales => s.context.clone(),
+            _ => return,
+        }
+    };
+    let Some(category) = detect(&entry.text) else {
+        return;
+    };
+
+    let mut event = ObjectionDetected {
+        session_id: session_id.to_string(),
+        entry_id: entry.id.clone(),
+        category: category.to_string(),
+        objection: entry.text.clone(),
+        handling: String::new(),
+        source: "battlecard".to_string(),
+        watch_id: None,
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let cards = battlecards(&app).await;
+        // A battlecard whose watch matches the objection beats a generated answer
+        let prepared = cards.iter().find(|w| {
+            super::keywords::compile(w)
+                .map(|re| re.is_match(&event.objection))
+                .unwrap_or(false)
+        });
+        if let Some(card) = prepared {
+            event.handling = card.response.clone().unwrap_or_default();
+            event.watch_id = Some(card.id.clone());
+            let _ = app.emit("objection-detected", event);
+            return;
+        }
+
+        match generate_handling(&cfg, &event.objection, context.as_deref(), &cards).await {
+            Ok(handling) => {
+                event.handling = handling;
+                event.source = "generated".to_string();
+                let _ = app.emit("objection-detected", event);
+            }
+            Err(e) => log::warn!("Objection handling for {} failed: {}", event.session_id, e),
+        }
+    });
+}
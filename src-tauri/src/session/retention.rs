@@ -0,0 +1,159 @@
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::manager::SessionManager;
+use super::storage::Storage;
+use super::storage_actor::StorageActor;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// How often the retention policy is applied
+const RETENTION_CHECK_INTERVAL_SECS: u64 = 60 * 60;
+
+pub fn remove_files(paths: &[String]) {
+    for path in paths {
+        match std::fs::remove_file(path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!("Failed to delete {}: {}", path, e),
+        }
+    }
+}
+
+pub fn ensure_not_active(
+    session_state: &SessionState,
+    session_id: Option<&str>,
+) -> Result<(), VenkyError> {
+    let mgr = session_state.lock();
+    let active = match session_id {
+        Some(id) => mgr.sessions.contains_key(id),
+        None => !mgr.sessions.is_empty(),
+    };
+    if active {
+        return Err(VenkyError::Other(
+            "End the active session first".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Delete a saved session with its transcript, audio, screenshots and screen recording
+#[tauri::command]
+pub async fn delete_session(
+    session_state: tauri::State<'_, SessionState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<()> {
+    ensure_not_active(&session_state, Some(&session_id))?;
+
+    let paths = storage.call(move |s| s.delete_session(&session_id)).await?;
+    remove_files(&paths);
+    Ok(())
+}
+
+/// Delete every session and all recorded data (audio, screenshots, recordings).
+/// Settings and prompt/summary templates are kept.
+#[tauri::command]
+pub async fn purge_all_data(
+    app: AppHandle,
+    session_state: tauri::State<'_, SessionState>,
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<()> {
+    ensure_not_active(&session_state, None)?;
+
+    storage
+        .call(|s| {
+            for id in s.ended_session_ids(None)? {
+                let paths = s.delete_session(&id)?;
+                remove_files(&paths);
+            }
+            Ok(())
+        })
+        .await?;
+
+    // Files that never made it into the database, e.g. after a crash mid-write
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))?;
+    for dir in [
+        crate::capture::history::captures_dir(&app)?,
+        crate::capture::recording::recordings_dir(&app_data),
+    ] {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return Err(VenkyError::Storage(format!(
+                    "Failed to delete {}: {}",
+                    dir.display(),
+                    e
+                )));
+            }
+        }
+    }
+
+    log::info!("All session data purged");
+    Ok(())
+}
+
+/// Apply the configured retention periods once, on the storage thread
+fn enforce_retention(cfg: &AppConfig, s: &Storage) -> Result<(), VenkyError> {
+    let cutoff =
+        |days: u32| (chrono::Utc::now() - chrono::Duration::days(days as i64)).to_rfc3339();
+
+    if cfg.session_retention_days > 0 {
+        let expired = s.ended_session_ids(Some(&cutoff(cfg.session_retention_days)))?;
+        for id in &expired {
+            let paths = s.delete_session(id)?;
+            remove_files(&paths);
+        }
+        if !expired.is_empty() {
+            log::info!("Retention: deleted {} expired sessions", expired.len());
+        }
+    }
+
+    if cfg.audio_retention_days > 0 {
+        let expired = s.session_audio_before(&cutoff(cfg.audio_retention_days))?;
+        let paths = expired.iter().map(|a| a.path.clone()).collect::<Vec<_>>();
+        remove_files(&paths);
+
+        let mut session_ids = expired
+            .into_iter()
+            .map(|a| a.session_id)
+            .collect::<Vec<_>>();
+        session_ids.sort();
+        session_ids.dedup();
+        for id in &session_ids {
+            s.delete_session_audio(id)?;
+        }
+        if !paths.is_empty() {
+            log::info!("Retention: deleted {} expired audio files", paths.len());
+        }
+    }
+
+    Ok(())
+}
+
+/// Periodically delete sessions and audio past their retention period
+pub async fn retention_loop(app: AppHandle) {
+    let mut interval = tokio::time::interval(Duration::from_secs(RETENTION_CHECK_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+
+        let cfg = app.state::<ConfigState>().lock().clone();
+        if cfg.session_retention_days == 0 && cfg.audio_retention_days == 0 {
+            continue;
+        }
+
+        let storage = app.state::<StorageActor>();
+        let applied = storage.call(move |s| enforce_retention(&cfg, s)).await;
+        if let Err(e) = applied {
+            log::error!("Failed to apply retention policy: {}", e);
+        }
+    }
+}
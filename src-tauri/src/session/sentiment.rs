@@ -0,0 +1,148 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio_util::sync::CancellationToken;
+
+use super::coaching::is_user;
+use super::manager::{SessionManager, TIMELINE_SPEAKER};
+use crate::config::AppConfig;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// New words from the other side needed before the mood is checked again
+const MIN_NEW_WORDS: usize = 20;
+/// Recent lines from the other side sent for each check
+const CONTEXT_LINES: usize = 20;
+/// Score change between checks that counts as a shift rather than noise
+const TREND_THRESHOLD: f64 = 0.2;
+
+const SENTIMENT_PROMPT: &str = "You read the other party's side of a live meeting transcript \
+    and judge how they feel about the conversation right now, weighting the latest lines most. \
+    Respond with a JSON object of the form {\"sentiment\": \"positive\" | \"neutral\" | \"negative\", \
+    \"score\": number from -1 to 1, \"engagement\": \"high\" | \"medium\" | \"low\", \
+    \"course_correction\": string or null}. Give a one-sentence course_correction the user \
+    could act on only when the sentiment is negative or engagement is low.";
+
+#[derive(Debug, Deserialize)]
+struct Reading {
+    sentiment: String,
+    #[serde(default)]
+    score: f64,
+    #[serde(default)]
+    engagement: Option<String>,
+    #[serde(default)]
+    course_correction: Option<String>,
+}
+
+/// Emitted as "sentiment-update"
+#[derive(Debug, Clone, Serialize)]
+pub struct SentimentUpdate {
+    pub session_id: String,
+    /// "positive", "neutral" or "negative"
+    pub sentiment: String,
+    /// -1.0 (negative) to 1.0 (positive)
+    pub score: f64,
+    /// "improving", "steady" or "declining" compared with the previous check
+    pub trend: String,
+    pub engagement: Option<String>,
+    /// Suggested change of approach when the other side is cooling off
+    pub course_correction: Option<String>,
+    pub analyzed_at: String,
+}
+
+fn trend(previous: Option<f64>, score: f64) -> &'static str {
+    match previous {
+        Some(prev) if score - prev >= TREND_THRESHOLD => "improving",
+        Some(prev) if prev - score >= TREND_THRESHOLD => "declining",
+        _ => "steady",
+    }
+}
+
+/// Periodically gauge the other side's sentiment in the focused session and emit "sentiment-update"
+pub async fn sentiment_loop(app: AppHandle, cancel: CancellationToken) {
+    let secs = app.state::<ConfigState>().lock().sentiment_interval_secs;
+    let mut interval = tokio::time::interval(Duration::from_secs(secs.max(1) as u64));
+    // Per session: transcript entries already seen and the last score
+    let mut seen: HashMap<String, (usize, Option<f64>)> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+
+        let session_state = app.state::<SessionState>();
+        let due = {
+            let mgr = session_state.lock();
+            mgr.focused().and_then(|session| {
+                let (last_count, _) = seen.get(&session.id).copied().unwrap_or_default();
+                let others = session
+                    .transcript
+                    .iter()
+                    .filter(|e| e.speaker != TIMELINE_SPEAKER && !is_user(e));
+                let new_words = others
+                    .clone()
+                    .skip(last_count)
+                    .map(|e| e.text.split_whitespace().count())
+                    .sum::<usize>();
+                let lines = others
+                    .map(|e| format!("{}: {}", e.speaker, e.text))
+                    .collect::<Vec<_>>();
+                (new_words >= MIN_NEW_WORDS).then(|| (session.id.clone(), lines))
+            })
+        };
+        let Some((session_id, lines)) = due else {
+            continue;
+        };
+
+        let previous = seen.get(&session_id).and_then(|(_, score)| *score);
+        seen.insert(session_id.clone(), (lines.len(), previous));
+
+        let cfg = app.state::<ConfigState>().lock().clone();
+        let recent = lines[lines.len().saturating_sub(CONTEXT_LINES)..].join("\n");
+        let reading = match crate::redaction::for_session(
+            &session_id,
+            crate::ai::llm::generate_json(&cfg, SENTIMENT_PROMPT, &recent),
+        )
+        .await
+        {
+            Ok(raw) => match serde_json::from_str::<Reading>(&raw) {
+                Ok(reading) => reading,
+                Err(e) => {
+                    log::warn!("Model returned invalid sentiment: {}", e);
+                    continue;
+                }
+            },
+            Err(e) => {
+                log::warn!("Sentiment check for {} failed: {}", session_id, e);
+                continue;
+            }
+        };
+
+        let sentiment = reading.sentiment.to_lowercase();
+        let score = reading.score.clamp(-1.0, 1.0);
+        let trend = trend(previous, score);
+        seen.insert(session_id.clone(), (lines.len(), Some(score)));
+
+        // Only nudge the user when things are going the wrong way
+        let cooling = sentiment == "negative" || trend == "declining";
+        let _ = app.emit(
+            "sentiment-update",
+            SentimentUpdate {
+                session_id,
+                sentiment,
+                score,
+                trend: trend.to_string(),
+                engagement: reading.engagement,
+                course_correction: reading
+                    .course_correction
+                    .filter(|c| cooling && !c.trim().is_empty()),
+                analyzed_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+
+use super::manager::{Session, SessionManager, TIMELINE_SPEAKER};
+use super::storage::CoachingMetrics;
+use super::storage_actor::StorageActor;
+
+use crate::error::CommandResult;
+
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Average speaking rate used to turn word counts into talk time
+const WORDS_PER_MINUTE: f64 = 150.0;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerStats {
+    pub speaker: String,
+    pub words: u32,
+    pub entries: u32,
+    pub questions: u32,
+    /// Estimated from the word count at an average speaking rate
+    pub talk_time_secs: u32,
+    /// Share of all spoken words, 0.0-1.0
+    pub talk_ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Monologue {
+    pub speaker: String,
+    pub words: u32,
+    pub started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionStats {
+    pub session_id: String,
+    pub duration_secs: i64,
+    pub total_words: u32,
+    pub question_count: u32,
+    pub speakers: Vec<SpeakerStats>,
+    /// Longest run of consecutive entries from one speaker
+    pub longest_monologue: Option<Monologue>,
+    pub suggestions_shown: u32,
+    pub suggestions_accepted: u32,
+    /// None when no suggestions were shown
+    pub suggestion_acceptance_rate: Option<f64>,
+    /// Final coaching metrics, or the running values for a live session
+    pub coaching: Option<CoachingMetrics>,
+}
+
+fn count_questions(text: &str) -> u32 {
+    text.split_inclusive(['.', '!', '?'])
+        .filter(|s| s.trim_end().ends_with('?'))
+        .count() as u32
+}
+
+fn compute(
+    session: &Session,
+    suggestions_shown: u32,
+    suggestions_accepted: u32,
+    coaching: Option<CoachingMetrics>,
+) -> SessionStats {
+    let parse = |ts: &str| chrono::DateTime::parse_from_rfc3339(ts).ok();
+    let duration_secs = match (
+        parse(&session.start_time),
+        session.end_time.as_deref().and_then(parse),
+    ) {
+        (Some(start), Some(end)) => (end - start).num_seconds(),
+        (Some(start), None) => {
+            (chrono::Utc::now() - start.with_timezone(&chrono::Utc)).num_seconds()
+        }
+        _ => 0,
+    };
+
+    let mut speakers: Vec<SpeakerStats> = Vec::new();
+    let mut longest: Option<Monologue> = None;
+    let mut current: Option<Monologue> = None;
+
+    for entry in session
+        .transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER)
+    {
+        let words = entry.text.split_whitespace().count() as u32;
+        let questions = count_questions(&entry.text);
+
+        match speakers.iter_mut().find(|s| s.speaker == entry.speaker) {
+            Some(stats) => {
+                stats.words += words;
+                stats.entries += 1;
+                stats.questions += questions;
+            }
+            None => speakers.push(SpeakerStats {
+                speaker: entry.speaker.clone(),
+                words,
+                entries: 1,
+                questions,
+                talk_time_secs: 0,
+                talk_ratio: 0.0,
+            }),
+        }
+
+        match current.as_mut() {
+            Some(run) if run.speaker == entry.speaker => run.words += words,
+            _ => {
+                if let Some(run) = current.take() {
+                    if longest
+                        .as_ref()
+                        .map(|l| run.words > l.words)
+                        .unwrap_or(true)
+                    {
+                        longest = Some(run);
+                    }
+                }
+                current = Some(Monologue {
+                    speaker: entry.speaker.clone(),
+                    words,
+                    started_at: entry.timestamp.clone(),
+                });
+            }
+        }
+    }
+    if let Some(run) = current {
+        if longest
+            .as_ref()
+            .map(|l| run.words > l.words)
+            .unwrap_or(true)
+        {
+            longest = Some(run);
+        }
+    }
+
+    let total_words: u32 = speakers.iter().map(|s| s.words).sum();
+    for stats in &mut speakers {
+        stats.talk_time_secs = (stats.words as f64 / WORDS_PER_MINUTE * 60.0).round() as u32;
+        if total_words > 0 {
+            stats.talk_ratio = stats.words as f64 / total_words as f64;
+        }
+    }
+    speakers.sort_by(|a, b| b.words.cmp(&a.words));
+
+    SessionStats {
+        session_id: session.id.clone(),
+        duration_secs: duration_secs.max(0),
+        total_words,
+        question_count: speakers.iter().map(|s| s.questions).sum(),
+        speakers,
+        longest_monologue: longest,
+        suggestions_shown,
+        suggestions_accepted,
+        suggestion_acceptance_rate: (suggestions_shown > 0)
+            .then(|| suggestions_accepted as f64 / suggestions_shown as f64),
+        coaching,
+    }
+}
+
+/// Talk-time, question and suggestion stats for the live session or a saved one
+#[tauri::command]
+pub async fn get_session_stats(
+    session_state: tauri::State<'_, SessionState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<SessionStats> {
+    let live = session_state.lock().sessions.get(&session_id).cloned();
+
+    Ok(storage
+        .call(move |s| {
+            let (session, coaching) = match live {
+                Some(session) => {
+                    let coaching = super::coaching::compute(&session, None);
+                    (session, Some(coaching))
+                }
+                None => (
+                    s.get_session(&session_id)?,
+                    s.get_coaching_metrics(&session_id)?,
+                ),
+            };
+            let (shown, accepted) = s.suggestion_counts(&session_id)?;
+            Ok(compute(&session, shown, accepted, coaching))
+        })
+        .await?)
+}
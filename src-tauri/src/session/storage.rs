@@ -1,17 +1,20 @@
-use parking_lot::Mutex;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use super::manager::Session;
+use super::manager::{Session, SessionPurpose, SessionStatus, TranscriptEntry};
+use super::storage_actor::StorageActor;
+
+use crate::error::{CommandResult, VenkyError};
 
 pub struct Storage {
     conn: Connection,
+    path: PathBuf,
+    /// SQLCipher key the database is encrypted with, if any
+    key: Option<String>,
 }
 
-type StorageState = Arc<Mutex<Storage>>;
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PromptTemplate {
     pub id: String,
@@ -20,19 +23,278 @@ pub struct PromptTemplate {
     pub category: String,
 }
 
+/// Shape of a generated summary: which sections, in what tone, how long
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryTemplate {
+    pub id: String,
+    pub name: String,
+    pub sections: Vec<String>,
+    pub tone: String,
+    pub length: SummaryLength,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryLength {
+    Short,
+    Medium,
+    Long,
+}
+
+impl SummaryLength {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SummaryLength::Short => "short",
+            SummaryLength::Medium => "medium",
+            SummaryLength::Long => "long",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "short" => SummaryLength::Short,
+            "long" => SummaryLength::Long,
+            _ => SummaryLength::Medium,
+        }
+    }
+}
+
+/// Id of the built-in summary template used when a purpose has none selected
+pub const DEFAULT_SUMMARY_TEMPLATE_ID: &str = "standard";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionAudio {
+    pub session_id: String,
+    pub source: String,
+    pub path: String,
+    pub created_at: String,
+    /// When the first sample was recorded, for lining the transcript up with the file
+    #[serde(default)]
+    pub started_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCapture {
+    pub id: String,
+    pub session_id: String,
+    pub path: String,
+    pub timestamp: String,
+    pub ocr_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Participant {
+    pub id: String,
+    pub session_id: String,
+    pub name: String,
+    pub email: Option<String>,
+    pub role: Option<String>,
+    pub crm_contact_id: Option<String>,
+    /// Where the participant came from: "manual", "speaker", "calendar" or "zoom"
+    pub source: String,
+}
+
+/// How many values of one kind ("email", "phone", ...) were masked for a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionCount {
+    pub kind: String,
+    pub count: u32,
+}
+
+/// Recording consent logged for a session started in compliance mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsentRecord {
+    pub session_id: String,
+    /// Jurisdiction setting in force when the session started
+    pub jurisdiction: String,
+    /// The user confirmed every participant was told about the recording
+    pub participants_notified: bool,
+    pub disclosure_played: bool,
+    pub created_at: String,
+    /// Unset while audio capture is still blocked
+    pub confirmed_at: Option<String>,
+}
+
+/// An earlier summarized session a person took part in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PastMeeting {
+    pub session_id: String,
+    pub title: String,
+    pub start_time: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionItem {
+    pub id: String,
+    pub session_id: String,
+    pub owner: Option<String>,
+    pub description: String,
+    /// As stated in the meeting, ideally YYYY-MM-DD
+    pub due_date: Option<String>,
+    /// "open" or "done"
+    pub status: String,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    /// Jira issue key or Linear identifier of the ticket filed for this item
+    #[serde(default)]
+    pub issue_key: Option<String>,
+    #[serde(default)]
+    pub issue_url: Option<String>,
+}
+
+/// A moment of a session bookmarked by the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Highlight {
+    pub id: String,
+    pub session_id: String,
+    pub timestamp: String,
+    pub note: Option<String>,
+}
+
+/// A word, phrase or regex to alert on when it comes up in a live transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeywordWatch {
+    pub id: String,
+    pub label: String,
+    pub pattern: String,
+    /// Treat `pattern` as a regular expression instead of a plain phrase
+    pub is_regex: bool,
+    /// Canned response or battlecard shown with the alert
+    pub response: Option<String>,
+    pub enabled: bool,
+}
+
+/// An outbound webhook POSTed signed JSON for the events it subscribes to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookSubscription {
+    pub id: String,
+    pub url: String,
+    /// HMAC-SHA256 key the payloads are signed with
+    pub secret: String,
+    /// e.g. "session-started", "summary-ready"
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub created_at: String,
+    pub last_delivery_at: Option<String>,
+    /// HTTP status or error of the last delivery
+    pub last_status: Option<String>,
+}
+
+/// A titled topic section of a session's transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub id: String,
+    pub session_id: String,
+    pub seq: u32,
+    pub title: String,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+/// A session with everything sync carries between devices; recordings, audio and
+/// screenshots stay on the machine that made them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub session: Session,
+    pub participants: Vec<Participant>,
+    pub chapters: Vec<Chapter>,
+    pub highlights: Vec<Highlight>,
+    pub action_items: Vec<ActionItem>,
+    /// `sessions.updated_at` when the record was read
+    pub updated_at: String,
+}
+
+/// An interviewer question paired with the answer given and the suggestion shown for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterviewQa {
+    pub id: String,
+    pub session_id: String,
+    pub seq: u32,
+    pub asked_at: String,
+    pub question: String,
+    pub answer: Option<String>,
+    pub suggested_answer: Option<String>,
+}
+
+/// Speaking habits of the user over a session; live values cover a recent window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoachingMetrics {
+    pub session_id: String,
+    /// Share of spoken words that were the user's, 0.0-1.0
+    pub user_talk_ratio: f64,
+    pub words_per_minute: f64,
+    pub filler_count: u32,
+    /// Times the user cut in before the other side finished a sentence
+    pub interruption_count: u32,
+    pub computed_at: String,
+}
+
+/// One correction or deletion of a transcript line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptEdit {
+    pub id: String,
+    pub session_id: String,
+    pub entry_id: String,
+    /// "update" or "delete"
+    pub action: String,
+    pub old_speaker: String,
+    pub old_text: String,
+    pub new_speaker: Option<String>,
+    pub new_text: Option<String>,
+    pub edited_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchFilters {
+    pub purpose: Option<SessionPurpose>,
+    /// RFC 3339 bounds on the session start time
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Only match transcript lines from this speaker
+    pub speaker: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub session_id: String,
+    pub session_title: String,
+    /// "session" for a title/summary hit, "transcript" for a transcript line
+    pub kind: String,
+    pub timestamp: String,
+    pub speaker: Option<String>,
+    /// Matching text with the hits wrapped in [ ]
+    pub snippet: String,
+    /// bm25 score, lower is a better match
+    pub rank: f64,
+}
+
+/// Something countable that isn't kept with a session, e.g. tokens spent on a request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageEvent {
+    /// "tokens", "crm_sync" or "crm_sync_failed"
+    pub kind: String,
+    pub provider: String,
+    pub amount: i64,
+    pub created_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionSummary {
     pub id: String,
     pub title: String,
+    pub description: Option<String>,
+    pub purpose: SessionPurpose,
     pub start_time: String,
     pub end_time: Option<String>,
     pub summary: Option<String>,
 }
 
 impl Storage {
-    pub fn new(db_path: &Path) -> Result<Self, String> {
-        let conn =
-            Connection::open(db_path).map_err(|e| format!("Failed to open database: {}", e))?;
+    /// Open (or create) the database, with the SQLCipher key if it is encrypted
+    pub fn new(db_path: &Path, key: Option<&str>) -> Result<Self, VenkyError> {
+        let conn = open_connection(db_path, key)?;
 
         conn.execute_batch(
             "
@@ -52,13 +314,235 @@ impl Storage {
                 category TEXT NOT NULL DEFAULT 'general'
             );
 
+            CREATE TABLE IF NOT EXISTS keyword_watches (
+                id TEXT PRIMARY KEY,
+                label TEXT NOT NULL,
+                pattern TEXT NOT NULL,
+                is_regex INTEGER NOT NULL DEFAULT 0,
+                response TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1
+            );
+
+            CREATE TABLE IF NOT EXISTS redaction_counts (
+                session_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                count INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (session_id, kind)
+            );
+
+            CREATE TABLE IF NOT EXISTS consent_records (
+                session_id TEXT PRIMARY KEY,
+                jurisdiction TEXT NOT NULL,
+                participants_notified INTEGER NOT NULL DEFAULT 0,
+                disclosure_played INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                confirmed_at TEXT
+            );
+
+            CREATE TABLE IF NOT EXISTS webhooks (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                secret TEXT NOT NULL,
+                events TEXT NOT NULL DEFAULT '[]',
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL,
+                last_delivery_at TEXT,
+                last_status TEXT
+            );
+
+            CREATE TABLE IF NOT EXISTS summary_templates (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                sections_json TEXT NOT NULL,
+                tone TEXT NOT NULL,
+                length TEXT NOT NULL DEFAULT 'medium'
+            );
+
             CREATE TABLE IF NOT EXISTS settings (
                 key TEXT PRIMARY KEY,
                 value TEXT NOT NULL
             );
+
+            CREATE TABLE IF NOT EXISTS session_audio (
+                session_id TEXT NOT NULL,
+                source TEXT NOT NULL,
+                path TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (session_id, source)
+            );
+
+            CREATE TABLE IF NOT EXISTS captures (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                path TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                ocr_text TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_captures_session ON captures (session_id, timestamp);
+
+            CREATE TABLE IF NOT EXISTS transcript_entries (
+                session_id TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                timestamp TEXT NOT NULL,
+                speaker TEXT NOT NULL,
+                text TEXT NOT NULL,
+                source TEXT,
+                id TEXT,
+                PRIMARY KEY (session_id, seq)
+            );
+
+            CREATE TABLE IF NOT EXISTS transcript_edits (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                entry_id TEXT NOT NULL,
+                action TEXT NOT NULL,
+                old_speaker TEXT NOT NULL,
+                old_text TEXT NOT NULL,
+                new_speaker TEXT,
+                new_text TEXT,
+                edited_at TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_transcript_edits_session ON transcript_edits (session_id, edited_at);
+
+            CREATE INDEX IF NOT EXISTS idx_transcript_entries_time ON transcript_entries (session_id, timestamp);
+            CREATE INDEX IF NOT EXISTS idx_transcript_entries_speaker ON transcript_entries (speaker);
+
+            CREATE TABLE IF NOT EXISTS action_items (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                owner TEXT,
+                description TEXT NOT NULL,
+                due_date TEXT,
+                status TEXT NOT NULL DEFAULT 'open',
+                created_at TEXT NOT NULL,
+                completed_at TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_action_items_session ON action_items (session_id);
+            CREATE INDEX IF NOT EXISTS idx_action_items_status ON action_items (status, due_date);
+
+            CREATE TABLE IF NOT EXISTS suggestions (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                text TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                accepted INTEGER NOT NULL DEFAULT 0
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_suggestions_session ON suggestions (session_id);
+
+            CREATE TABLE IF NOT EXISTS chapters (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                title TEXT NOT NULL,
+                start_time TEXT NOT NULL,
+                end_time TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_chapters_session ON chapters (session_id, seq);
+
+            CREATE TABLE IF NOT EXISTS interview_qa (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                asked_at TEXT NOT NULL,
+                question TEXT NOT NULL,
+                answer TEXT,
+                suggested_answer TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_interview_qa_session ON interview_qa (session_id, seq);
+
+            CREATE TABLE IF NOT EXISTS coaching_metrics (
+                session_id TEXT PRIMARY KEY,
+                user_talk_ratio REAL NOT NULL,
+                words_per_minute REAL NOT NULL,
+                filler_count INTEGER NOT NULL,
+                interruption_count INTEGER NOT NULL,
+                computed_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS highlights (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                note TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_highlights_session ON highlights (session_id, timestamp);
+
+            CREATE TABLE IF NOT EXISTS participants (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                email TEXT,
+                role TEXT,
+                crm_contact_id TEXT,
+                source TEXT NOT NULL DEFAULT 'manual',
+                UNIQUE (session_id, name)
+            );
+
+            CREATE TABLE IF NOT EXISTS session_recordings (
+                session_id TEXT PRIMARY KEY,
+                path TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS usage_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                created_at TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_usage_events_created ON usage_events (created_at);
+
+            -- Version of each session last pushed to or pulled from the sync target
+            CREATE TABLE IF NOT EXISTS sync_state (
+                session_id TEXT PRIMARY KEY,
+                updated_at TEXT NOT NULL
+            );
             ",
         )
-        .map_err(|e| format!("Failed to create tables: {}", e))?;
+        .map_err(|e| VenkyError::Storage(format!("Failed to create tables: {}", e)))?;
+
+        // Columns added after the first release
+        add_column_if_missing(
+            &conn,
+            "sessions",
+            "purpose",
+            "TEXT NOT NULL DEFAULT 'meeting'",
+        )?;
+        add_column_if_missing(&conn, "sessions", "context", "TEXT")?;
+        add_column_if_missing(&conn, "sessions", "prompt_template_id", "TEXT")?;
+        add_column_if_missing(&conn, "sessions", "description", "TEXT")?;
+        add_column_if_missing(&conn, "transcript_entries", "source", "TEXT")?;
+        add_column_if_missing(&conn, "transcript_entries", "id", "TEXT")?;
+        add_column_if_missing(&conn, "action_items", "issue_key", "TEXT")?;
+        add_column_if_missing(&conn, "action_items", "issue_url", "TEXT")?;
+        add_column_if_missing(&conn, "action_items", "reminded_at", "TEXT")?;
+        add_column_if_missing(&conn, "session_audio", "started_at", "TEXT")?;
+        add_column_if_missing(&conn, "session_recordings", "started_at", "TEXT")?;
+        add_column_if_missing(&conn, "sessions", "updated_at", "TEXT")?;
+        add_column_if_missing(&conn, "sessions", "interrupted_at", "TEXT")?;
+        add_column_if_missing(&conn, "sessions", "meeting_window", "TEXT")?;
+        create_sync_triggers(&conn)?;
+        conn.execute_batch(
+            "
+            UPDATE transcript_entries SET id = lower(hex(randomblob(16))) WHERE id IS NULL;
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_transcript_entries_id ON transcript_entries (id);
+            ",
+        )
+        .map_err(|e| {
+            VenkyError::Storage(format!("Failed to migrate transcript entry ids: {}", e))
+        })?;
+
+        create_search_index(&conn)?;
+        migrate_transcript_json(&conn)?;
 
         // Insert default prompt templates if none exist
         let count: i64 = conn
@@ -83,115 +567,2642 @@ impl Storage {
             }
         }
 
-        Ok(Self { conn })
+        let summary_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM summary_templates", [], |row| {
+                row.get(0)
+            })
+            .unwrap_or(0);
+
+        if summary_count == 0 {
+            let defaults = vec![
+                (
+                    DEFAULT_SUMMARY_TEMPLATE_ID,
+                    "Standard",
+                    vec!["Key Points", "Action Items", "Decisions Made"],
+                    "neutral and concise",
+                    "medium",
+                ),
+                (
+                    "interview",
+                    "Interview Debrief",
+                    vec![
+                        "Questions Asked",
+                        "How I Answered",
+                        "Strengths",
+                        "Areas to Improve",
+                        "Follow-ups",
+                    ],
+                    "candid and constructive",
+                    "medium",
+                ),
+                (
+                    "sales",
+                    "Sales Call Recap",
+                    vec![
+                        "Customer Pain Points",
+                        "Objections",
+                        "Budget & Timeline",
+                        "Next Steps",
+                    ],
+                    "crisp, CRM-ready",
+                    "short",
+                ),
+            ];
+
+            for (id, name, sections, tone, length) in defaults {
+                conn.execute(
+                    "INSERT INTO summary_templates (id, name, sections_json, tone, length) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![id, name, serde_json::to_string(&sections).unwrap_or_default(), tone, length],
+                ).ok();
+            }
+        }
+
+        Ok(Self {
+            conn,
+            path: db_path.to_path_buf(),
+            key: key.map(str::to_string),
+        })
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Rewrite the database encrypted with `key`, or as plain SQLite when None, and reopen
+    /// it. Every page is copied, so this also re-encrypts existing data under a new key.
+    pub fn set_encryption(&mut self, key: Option<&str>) -> Result<(), VenkyError> {
+        let rekeyed = self.path.with_extension("db.rekey");
+        self.export_copy(&rekeyed, key)
+            .map_err(|e| VenkyError::Storage(format!("Failed to re-encrypt database: {}", e)))?;
+
+        // Close the old file before it is replaced; Windows won't rename over an open file
+        self.conn = Connection::open_in_memory()
+            .map_err(|e| VenkyError::Storage(format!("Failed to re-encrypt database: {}", e)))?;
+        if let Err(e) = std::fs::rename(&rekeyed, &self.path) {
+            let _ = std::fs::remove_file(&rekeyed);
+            self.conn = open_connection(&self.path, self.key.as_deref())?;
+            return Err(VenkyError::Storage(format!(
+                "Failed to replace database: {}",
+                e
+            )));
+        }
+        self.conn = open_connection(&self.path, key)?;
+        self.key = key.map(str::to_string);
+        Ok(())
+    }
+
+    /// Copy the whole database to a new file at `dest`, encrypted with `key` (None leaves
+    /// the copy unencrypted)
+    fn export_copy(&self, dest: &Path, key: Option<&str>) -> rusqlite::Result<()> {
+        let _ = std::fs::remove_file(dest);
+        let export = || -> rusqlite::Result<()> {
+            self.conn.execute(
+                "ATTACH DATABASE ?1 AS exported KEY ?2",
+                params![dest.to_string_lossy(), key.unwrap_or("")],
+            )?;
+            let version: i64 = self
+                .conn
+                .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+            self.conn
+                .query_row("SELECT sqlcipher_export('exported')", [], |_| Ok(()))?;
+            self.conn
+                .execute_batch(&format!("PRAGMA exported.user_version = {}", version))?;
+            self.conn.execute("DETACH DATABASE exported", [])?;
+            Ok(())
+        };
+        let result = export();
+        if result.is_err() {
+            let _ = self.conn.execute("DETACH DATABASE exported", []);
+            let _ = std::fs::remove_file(dest);
+        }
+        result
+    }
+
+    /// Fold the write-ahead log back into the database file, so nothing is left in it when
+    /// the app exits
+    pub fn checkpoint(&self) -> Result<(), VenkyError> {
+        self.conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+            .map_err(|e| VenkyError::Storage(format!("Failed to checkpoint database: {}", e)))
+    }
+
+    /// Write a consistent copy of the database to `dest` encrypted with `key`, e.g. for a
+    /// backup, so no readable copy is left on disk if the app stops part way
+    pub fn snapshot(&self, dest: &Path, key: &str) -> Result<(), VenkyError> {
+        self.export_copy(dest, Some(key))
+            .map_err(|e| VenkyError::Storage(format!("Failed to copy database: {}", e)))
+    }
+
+    /// Replace the database with the one at `src`, opened with `src_key`, bringing its
+    /// tables up to date and encrypting it with the current key. The old file is put back
+    /// if that fails.
+    pub fn replace_with(&mut self, src: &Path, src_key: Option<&str>) -> Result<(), VenkyError> {
+        // Make sure the new database opens before the current one is touched
+        Storage::new(src, src_key)?;
+
+        let previous = self.path.with_extension("db.pre-restore");
+        let key = self.key.clone();
+        // Close the old file before it is moved; Windows won't rename an open file
+        self.conn = Connection::open_in_memory()
+            .map_err(|e| VenkyError::Storage(format!("Failed to replace database: {}", e)))?;
+        if let Err(e) = std::fs::rename(&self.path, &previous) {
+            self.conn = open_connection(&self.path, key.as_deref())?;
+            return Err(VenkyError::Storage(format!(
+                "Failed to replace database: {}",
+                e
+            )));
+        }
+
+        let restore = || -> Result<Storage, VenkyError> {
+            std::fs::copy(src, &self.path)
+                .map_err(|e| VenkyError::Storage(format!("Failed to replace database: {}", e)))?;
+            let mut restored = Storage::new(&self.path, src_key)?;
+            if src_key != key.as_deref() {
+                restored.set_encryption(key.as_deref())?;
+            }
+            Ok(restored)
+        };
+        match restore() {
+            Ok(restored) => {
+                *self = restored;
+                let _ = std::fs::remove_file(&previous);
+                Ok(())
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&self.path);
+                if let Err(undo) = std::fs::rename(&previous, &self.path) {
+                    log::error!("Failed to put the previous database back: {}", undo);
+                }
+                self.conn = open_connection(&self.path, key.as_deref())?;
+                Err(e)
+            }
+        }
     }
 
-    pub fn save_session(&self, session: &Session) -> Result<(), String> {
-        let transcript_json =
-            serde_json::to_string(&session.transcript).unwrap_or_else(|_| "[]".to_string());
+    /// Point stored file paths under `old_root` at the same files under `new_root`, for
+    /// data restored on another machine
+    pub fn rebase_file_paths(&self, old_root: &str, new_root: &str) -> Result<(), VenkyError> {
+        if old_root == new_root {
+            return Ok(());
+        }
+        let old_len = old_root.chars().count() as i64;
+        for table in ["session_audio", "captures", "session_recordings"] {
+            self.conn
+                .execute(
+                    &format!(
+                        "UPDATE {} SET path = ?2 || substr(path, ?3 + 1) WHERE substr(path, 1, ?3) = ?1",
+                        table
+                    ),
+                    params![old_root, new_root, old_len],
+                )
+                .map_err(|e| VenkyError::Storage(format!("Update error: {}", e)))?;
+        }
+        Ok(())
+    }
 
+    /// Save the session row; transcript entries are written as they arrive (see `append_transcript_entry`)
+    pub fn save_session(&self, session: &Session) -> Result<(), VenkyError> {
         self.conn
             .execute(
-                "INSERT OR REPLACE INTO sessions (id, title, start_time, end_time, summary, transcript_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                "INSERT INTO sessions (id, title, start_time, end_time, summary, purpose, context, prompt_template_id, description, meeting_window) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT(id) DO UPDATE SET title = excluded.title, start_time = excluded.start_time, end_time = excluded.end_time, summary = excluded.summary, purpose = excluded.purpose, context = excluded.context, prompt_template_id = excluded.prompt_template_id, description = excluded.description, meeting_window = excluded.meeting_window",
                 params![
                     session.id,
                     session.title,
                     session.start_time,
                     session.end_time,
                     session.summary,
-                    transcript_json,
+                    session.purpose.as_str(),
+                    session.context,
+                    session.prompt_template_id,
+                    session.description,
+                    session.meeting_window,
                 ],
             )
-            .map_err(|e| format!("Failed to save session: {}", e))?;
+            .map_err(|e| VenkyError::Storage(format!("Failed to save session: {}", e)))?;
+
+        self.conn
+            .execute(
+                "DELETE FROM session_fts WHERE session_id = ?1",
+                params![session.id],
+            )
+            .and_then(|_| {
+                self.conn.execute(
+                    "INSERT INTO session_fts (session_id, title, summary) VALUES (?1, ?2, ?3)",
+                    params![session.id, session.title, session.summary],
+                )
+            })
+            .map_err(|e| VenkyError::Storage(format!("Failed to index session: {}", e)))?;
 
         Ok(())
     }
-}
 
-#[tauri::command]
-pub fn get_all_sessions(storage: tauri::State<'_, StorageState>) -> Result<Vec<SessionSummary>, String> {
-    let s = storage.lock();
-    let mut stmt = s
-        .conn
-        .prepare("SELECT id, title, start_time, end_time, summary FROM sessions ORDER BY start_time DESC")
-        .map_err(|e| format!("Query error: {}", e))?;
-
-    let sessions = stmt
-        .query_map([], |row| {
-            Ok(SessionSummary {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                start_time: row.get(2)?,
-                end_time: row.get(3)?,
-                summary: row.get(4)?,
-            })
-        })
-        .map_err(|e| format!("Query error: {}", e))?
-        .filter_map(|r| r.ok())
-        .collect();
+    /// Full-text search over session titles, summaries and transcript lines, best matches first
+    pub fn search(
+        &self,
+        query: &str,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SearchResult>, VenkyError> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let limit = filters.limit.unwrap_or(50).clamp(1, 500) as i64;
+        let purpose = filters.purpose.map(|p| p.as_str());
 
-    Ok(sessions)
-}
+        let mut results = Vec::new();
 
-#[tauri::command]
-pub fn get_prompt_templates(
-    storage: tauri::State<'_, StorageState>,
-) -> Result<Vec<PromptTemplate>, String> {
-    let s = storage.lock();
-    let mut stmt = s
-        .conn
-        .prepare("SELECT id, name, template, category FROM prompt_templates ORDER BY name")
-        .map_err(|e| format!("Query error: {}", e))?;
-
-    let templates = stmt
-        .query_map([], |row| {
-            Ok(PromptTemplate {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                template: row.get(2)?,
-                category: row.get(3)?,
-            })
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT e.session_id, s.title, e.timestamp, e.speaker,
+                        snippet(transcript_fts, 0, '[', ']', '…', 12), bm25(transcript_fts)
+                 FROM transcript_fts
+                 JOIN transcript_entries e ON e.rowid = transcript_fts.rowid
+                 JOIN sessions s ON s.id = e.session_id
+                 WHERE transcript_fts MATCH ?1
+                   AND (?2 IS NULL OR s.purpose = ?2)
+                   AND (?3 IS NULL OR s.start_time >= ?3)
+                   AND (?4 IS NULL OR s.start_time <= ?4)
+                   AND (?5 IS NULL OR e.speaker = ?5)
+                 ORDER BY bm25(transcript_fts)
+                 LIMIT ?6",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    query,
+                    purpose,
+                    filters.from,
+                    filters.to,
+                    filters.speaker,
+                    limit
+                ],
+                |row| {
+                    Ok(SearchResult {
+                        session_id: row.get(0)?,
+                        session_title: row.get(1)?,
+                        kind: "transcript".to_string(),
+                        timestamp: row.get(2)?,
+                        speaker: Some(row.get(3)?),
+                        snippet: row.get(4)?,
+                        rank: row.get(5)?,
+                    })
+                },
+            )
+            .map_err(|e| VenkyError::Storage(format!("Search failed: {}", e)))?;
+        results.extend(rows.filter_map(|r| r.ok()));
+
+        // Titles and summaries have no speaker
+        if filters.speaker.is_none() {
+            let mut stmt = self
+                .conn
+                .prepare(
+                    "SELECT f.session_id, s.title, s.start_time,
+                            snippet(session_fts, -1, '[', ']', '…', 12), bm25(session_fts)
+                     FROM session_fts f
+                     JOIN sessions s ON s.id = f.session_id
+                     WHERE session_fts MATCH ?1
+                       AND (?2 IS NULL OR s.purpose = ?2)
+                       AND (?3 IS NULL OR s.start_time >= ?3)
+                       AND (?4 IS NULL OR s.start_time <= ?4)
+                     ORDER BY bm25(session_fts)
+                     LIMIT ?5",
+                )
+                .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+            let rows = stmt
+                .query_map(
+                    params![query, purpose, filters.from, filters.to, limit],
+                    |row| {
+                        Ok(SearchResult {
+                            session_id: row.get(0)?,
+                            session_title: row.get(1)?,
+                            kind: "session".to_string(),
+                            timestamp: row.get(2)?,
+                            speaker: None,
+                            snippet: row.get(3)?,
+                            rank: row.get(4)?,
+                        })
+                    },
+                )
+                .map_err(|e| VenkyError::Storage(format!("Search failed: {}", e)))?;
+            results.extend(rows.filter_map(|r| r.ok()));
+        }
+
+        results.sort_by(|a, b| a.rank.total_cmp(&b.rank));
+        results.truncate(limit as usize);
+        Ok(results)
+    }
+
+    /// Load a saved session with its full transcript
+    pub fn get_session(&self, session_id: &str) -> Result<Session, VenkyError> {
+        let (title, start_time, end_time, summary, purpose, context, prompt_template_id, description, interrupted_at, meeting_window) = self
+            .conn
+            .query_row(
+                "SELECT title, start_time, end_time, summary, purpose, context, prompt_template_id, description, interrupted_at, meeting_window FROM sessions WHERE id = ?1",
+                params![session_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, Option<String>>(5)?,
+                        row.get::<_, Option<String>>(6)?,
+                        row.get::<_, Option<String>>(7)?,
+                        row.get::<_, Option<String>>(8)?,
+                        row.get::<_, Option<String>>(9)?,
+                    ))
+                },
+            )
+            .map_err(|e| VenkyError::Other(format!("Session not found: {}", e)))?;
+
+        Ok(Session {
+            id: session_id.to_string(),
+            title,
+            description,
+            meeting_window,
+            purpose: SessionPurpose::parse(&purpose),
+            context,
+            prompt_template_id,
+            status: match (&end_time, &interrupted_at) {
+                (Some(_), _) => SessionStatus::Ended,
+                (None, Some(_)) => SessionStatus::Interrupted,
+                (None, None) => SessionStatus::Active,
+            },
+            start_time,
+            end_time,
+            transcript: self.load_transcript(session_id)?,
+            suggestions: Vec::new(),
+            summary,
         })
-        .map_err(|e| format!("Query error: {}", e))?
-        .filter_map(|r| r.ok())
-        .collect();
+    }
 
-    Ok(templates)
-}
+    /// Sessions that were never ended, i.e. the app exited mid-session, oldest first
+    pub fn get_unfinished_sessions(&self) -> Result<Vec<Session>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id FROM sessions WHERE end_time IS NULL ORDER BY start_time")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
 
-#[tauri::command]
-pub fn save_prompt_template(
-    storage: tauri::State<'_, StorageState>,
-    name: String,
-    template: String,
-    category: String,
-) -> Result<PromptTemplate, String> {
-    let s = storage.lock();
-    let id = uuid::Uuid::new_v4().to_string();
+        ids.iter().map(|id| self.get_session(id)).collect()
+    }
 
-    s.conn
-        .execute(
-            "INSERT INTO prompt_templates (id, name, template, category) VALUES (?1, ?2, ?3, ?4)",
-            params![id, name, template, category],
-        )
-        .map_err(|e| format!("Failed to save template: {}", e))?;
+    /// Record that the app quit while the session was still running
+    pub fn mark_session_interrupted(&self, session_id: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "UPDATE sessions SET interrupted_at = ?2 WHERE id = ?1 AND end_time IS NULL",
+                params![session_id, chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Update error: {}", e)))?;
+        Ok(())
+    }
 
-    Ok(PromptTemplate {
-        id,
-        name,
-        template,
-        category,
-    })
-}
+    /// End a session the app was quit in the middle of, at the time it was quit
+    pub fn end_interrupted_session(&self, session_id: &str) -> Result<(), VenkyError> {
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE sessions SET end_time = interrupted_at, interrupted_at = NULL WHERE id = ?1 AND end_time IS NULL AND interrupted_at IS NOT NULL",
+                params![session_id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Update error: {}", e)))?;
+        if updated == 0 {
+            return Err(VenkyError::Other(format!(
+                "Session {} is not active",
+                session_id
+            )));
+        }
+        Ok(())
+    }
 
-#[tauri::command]
-pub fn delete_prompt_template(
-    storage: tauri::State<'_, StorageState>,
-    id: String,
-) -> Result<(), String> {
-    let s = storage.lock();
-    s.conn
-        .execute("DELETE FROM prompt_templates WHERE id = ?1", params![id])
-        .map_err(|e| format!("Failed to delete template: {}", e))?;
-    Ok(())
+    /// Clear the interrupted mark of a session picked up again after a restart
+    pub fn mark_session_resumed(&self, session_id: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "UPDATE sessions SET interrupted_at = NULL WHERE id = ?1 AND interrupted_at IS NOT NULL",
+                params![session_id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Update error: {}", e)))?;
+        Ok(())
+    }
+
+    /// Write one transcript entry as it arrives so a crash doesn't lose the session
+    pub fn append_transcript_entry(
+        &self,
+        session_id: &str,
+        entry: &TranscriptEntry,
+    ) -> Result<(), VenkyError> {
+        // Deleted entries leave gaps, so the transcript length isn't the next seq
+        let next_seq: i64 = self
+            .conn
+            .query_row(
+                "SELECT COALESCE(MAX(seq) + 1, 0) FROM transcript_entries WHERE session_id = ?1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        insert_transcript_entry(&self.conn, session_id, next_seq as usize, entry)
+    }
+
+    pub fn get_transcript_entry(
+        &self,
+        session_id: &str,
+        entry_id: &str,
+    ) -> Result<Option<TranscriptEntry>, VenkyError> {
+        self.conn
+            .query_row(
+                "SELECT id, timestamp, speaker, text, source FROM transcript_entries WHERE session_id = ?1 AND id = ?2",
+                params![session_id, entry_id],
+                |row| {
+                    Ok(TranscriptEntry {
+                        id: row.get(0)?,
+                        timestamp: row.get(1)?,
+                        speaker: row.get(2)?,
+                        text: row.get(3)?,
+                        source: row.get(4)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))
+    }
+
+    /// Change an entry's text and/or speaker, logging the previous values in `transcript_edits`
+    pub fn edit_transcript_entry(
+        &self,
+        session_id: &str,
+        entry_id: &str,
+        text: Option<&str>,
+        speaker: Option<&str>,
+    ) -> Result<TranscriptEntry, VenkyError> {
+        let old = self
+            .get_transcript_entry(session_id, entry_id)?
+            .ok_or_else(|| VenkyError::Other("Transcript entry not found".to_string()))?;
+        let updated = TranscriptEntry {
+            text: text.map(str::to_string).unwrap_or_else(|| old.text.clone()),
+            speaker: speaker
+                .map(str::to_string)
+                .unwrap_or_else(|| old.speaker.clone()),
+            ..old.clone()
+        };
+
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| VenkyError::Storage(format!("Failed to edit transcript entry: {}", e)))?;
+
+        tx.execute(
+            "UPDATE transcript_entries SET speaker = ?1, text = ?2 WHERE id = ?3",
+            params![updated.speaker, updated.text, entry_id],
+        )
+        .and_then(|_| {
+            tx.execute(
+                "UPDATE transcript_fts SET text = ?1 WHERE rowid = (SELECT rowid FROM transcript_entries WHERE id = ?2)",
+                params![updated.text, entry_id],
+            )
+        })
+        .map_err(|e| VenkyError::Storage(format!("Failed to edit transcript entry: {}", e)))?;
+        record_transcript_edit(&tx, session_id, &old, Some(&updated))?;
+
+        tx.commit()
+            .map_err(|e| VenkyError::Storage(format!("Failed to edit transcript entry: {}", e)))?;
+        Ok(updated)
+    }
+
+    /// Remove an entry, logging its content in `transcript_edits`
+    pub fn delete_transcript_entry(
+        &self,
+        session_id: &str,
+        entry_id: &str,
+    ) -> Result<(), VenkyError> {
+        let old = self
+            .get_transcript_entry(session_id, entry_id)?
+            .ok_or_else(|| VenkyError::Other("Transcript entry not found".to_string()))?;
+
+        let tx = self.conn.unchecked_transaction().map_err(|e| {
+            VenkyError::Storage(format!("Failed to delete transcript entry: {}", e))
+        })?;
+
+        tx.execute(
+            "DELETE FROM transcript_fts WHERE rowid = (SELECT rowid FROM transcript_entries WHERE id = ?1)",
+            params![entry_id],
+        )
+        .and_then(|_| {
+            tx.execute(
+                "DELETE FROM transcript_entries WHERE id = ?1",
+                params![entry_id],
+            )
+        })
+        .map_err(|e| VenkyError::Storage(format!("Failed to delete transcript entry: {}", e)))?;
+        record_transcript_edit(&tx, session_id, &old, None)?;
+
+        tx.commit()
+            .map_err(|e| VenkyError::Storage(format!("Failed to delete transcript entry: {}", e)))
+    }
+
+    pub fn get_transcript_edits(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<TranscriptEdit>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, session_id, entry_id, action, old_speaker, old_text, new_speaker, new_text, edited_at FROM transcript_edits WHERE session_id = ?1 ORDER BY edited_at")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let edits = stmt
+            .query_map(params![session_id], |row| {
+                Ok(TranscriptEdit {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    entry_id: row.get(2)?,
+                    action: row.get(3)?,
+                    old_speaker: row.get(4)?,
+                    old_text: row.get(5)?,
+                    new_speaker: row.get(6)?,
+                    new_text: row.get(7)?,
+                    edited_at: row.get(8)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(edits)
+    }
+
+    pub fn load_transcript(&self, session_id: &str) -> Result<Vec<TranscriptEntry>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, timestamp, speaker, text, source FROM transcript_entries WHERE session_id = ?1 ORDER BY seq",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let entries = stmt
+            .query_map(params![session_id], |row| {
+                Ok(TranscriptEntry {
+                    id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    speaker: row.get(2)?,
+                    text: row.get(3)?,
+                    source: row.get(4)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    pub fn update_transcript(
+        &self,
+        session_id: &str,
+        transcript: &[TranscriptEntry],
+    ) -> Result<(), VenkyError> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| VenkyError::Storage(format!("Failed to update transcript: {}", e)))?;
+
+        tx.execute(
+            "DELETE FROM transcript_fts WHERE rowid IN (SELECT rowid FROM transcript_entries WHERE session_id = ?1)",
+            params![session_id],
+        )
+        .and_then(|_| {
+            tx.execute(
+                "DELETE FROM transcript_entries WHERE session_id = ?1",
+                params![session_id],
+            )
+        })
+        .map_err(|e| VenkyError::Storage(format!("Failed to update transcript: {}", e)))?;
+        for (seq, entry) in transcript.iter().enumerate() {
+            insert_transcript_entry(&tx, session_id, seq, entry)?;
+        }
+
+        tx.commit()
+            .map_err(|e| VenkyError::Storage(format!("Failed to update transcript: {}", e)))
+    }
+
+    pub fn add_session_audio(
+        &self,
+        session_id: &str,
+        source: &str,
+        path: &str,
+        started_at: Option<&str>,
+    ) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO session_audio (session_id, source, path, created_at, started_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![session_id, source, path, chrono::Utc::now().to_rfc3339(), started_at],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save session audio: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get_session_audio(&self, session_id: &str) -> Result<Vec<SessionAudio>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT session_id, source, path, created_at, started_at FROM session_audio WHERE session_id = ?1 ORDER BY source")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let audio = stmt
+            .query_map(params![session_id], |row| {
+                Ok(SessionAudio {
+                    session_id: row.get(0)?,
+                    source: row.get(1)?,
+                    path: row.get(2)?,
+                    created_at: row.get(3)?,
+                    started_at: row.get(4)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(audio)
+    }
+
+    /// Delete a session and every row linked to it. Returns the paths of its files on disk
+    /// (audio, screenshots, screen recording) for the caller to remove.
+    pub fn delete_session(&self, session_id: &str) -> Result<Vec<String>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT path FROM session_audio WHERE session_id = ?1
+                 UNION ALL SELECT path FROM captures WHERE session_id = ?1
+                 UNION ALL SELECT path FROM session_recordings WHERE session_id = ?1",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+        let paths = stmt
+            .query_map(params![session_id], |row| row.get(0))
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| VenkyError::Storage(format!("Failed to delete session: {}", e)))?;
+        tx.execute(
+            "DELETE FROM transcript_fts WHERE rowid IN (SELECT rowid FROM transcript_entries WHERE session_id = ?1)",
+            params![session_id],
+        )
+        .map_err(|e| VenkyError::Storage(format!("Failed to delete session: {}", e)))?;
+        for table in [
+            "transcript_entries",
+            "transcript_edits",
+            "session_audio",
+            "captures",
+            "session_recordings",
+            "participants",
+            "highlights",
+            "suggestions",
+            "action_items",
+            "chapters",
+            "interview_qa",
+            "coaching_metrics",
+            "redaction_counts",
+            "consent_records",
+            "session_fts",
+        ] {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE session_id = ?1", table),
+                params![session_id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to delete session: {}", e)))?;
+        }
+        tx.execute("DELETE FROM sessions WHERE id = ?1", params![session_id])
+            .map_err(|e| VenkyError::Storage(format!("Failed to delete session: {}", e)))?;
+        tx.commit()
+            .map_err(|e| VenkyError::Storage(format!("Failed to delete session: {}", e)))?;
+
+        Ok(paths)
+    }
+
+    /// Ended sessions that started before `cutoff` (RFC 3339); all of them when `cutoff` is None
+    pub fn ended_session_ids(&self, cutoff: Option<&str>) -> Result<Vec<String>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id FROM sessions WHERE end_time IS NOT NULL AND (?1 IS NULL OR start_time < ?1)",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let ids = stmt
+            .query_map(params![cutoff], |row| row.get(0))
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(ids)
+    }
+
+    /// When each session last changed, keyed by id; None while the session is still running
+    pub fn session_versions(&self) -> Result<HashMap<String, Option<String>>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, updated_at, end_time IS NOT NULL FROM sessions")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let versions = stmt
+            .query_map([], |row| {
+                let updated_at: Option<String> = row.get(1)?;
+                let ended: bool = row.get(2)?;
+                Ok((row.get(0)?, ended.then(|| updated_at.unwrap_or_default())))
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(versions)
+    }
+
+    pub fn get_session_record(&self, session_id: &str) -> Result<SessionRecord, VenkyError> {
+        let updated_at: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT updated_at FROM sessions WHERE id = ?1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| VenkyError::Other(format!("Session not found: {}", e)))?;
+
+        Ok(SessionRecord {
+            session: self.get_session(session_id)?,
+            participants: self.get_session_participants(session_id)?,
+            chapters: self.get_session_chapters(session_id)?,
+            highlights: self.get_session_highlights(session_id)?,
+            action_items: self.list_action_items(Some(session_id), None)?,
+            updated_at: updated_at.unwrap_or_default(),
+        })
+    }
+
+    /// Replace a session and its transcript, participants, chapters, highlights and action
+    /// items with a copy pulled from another device, keeping that copy's `updated_at`
+    pub fn put_session_record(&self, record: &SessionRecord) -> Result<(), VenkyError> {
+        let session = &record.session;
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| VenkyError::Storage(format!("Failed to save synced session: {}", e)))?;
+        let err = |e: rusqlite::Error| {
+            VenkyError::Storage(format!("Failed to save synced session: {}", e))
+        };
+
+        tx.execute(
+            "INSERT INTO sessions (id, title, start_time, end_time, summary, purpose, context, prompt_template_id, description, meeting_window, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+             ON CONFLICT(id) DO UPDATE SET title = excluded.title, start_time = excluded.start_time, end_time = excluded.end_time, summary = excluded.summary, purpose = excluded.purpose, context = excluded.context, prompt_template_id = excluded.prompt_template_id, description = excluded.description, meeting_window = excluded.meeting_window, updated_at = excluded.updated_at",
+            params![
+                session.id,
+                session.title,
+                session.start_time,
+                session.end_time,
+                session.summary,
+                session.purpose.as_str(),
+                session.context,
+                session.prompt_template_id,
+                session.description,
+                session.meeting_window,
+                record.updated_at,
+            ],
+        )
+        .map_err(err)?;
+        tx.execute(
+            "DELETE FROM session_fts WHERE session_id = ?1",
+            params![session.id],
+        )
+        .map_err(err)?;
+        tx.execute(
+            "INSERT INTO session_fts (session_id, title, summary) VALUES (?1, ?2, ?3)",
+            params![session.id, session.title, session.summary],
+        )
+        .map_err(err)?;
+
+        tx.execute(
+            "DELETE FROM transcript_fts WHERE rowid IN (SELECT rowid FROM transcript_entries WHERE session_id = ?1)",
+            params![session.id],
+        )
+        .map_err(err)?;
+        for table in [
+            "transcript_entries",
+            "participants",
+            "chapters",
+            "highlights",
+            "action_items",
+        ] {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE session_id = ?1", table),
+                params![session.id],
+            )
+            .map_err(err)?;
+        }
+
+        for (seq, entry) in session.transcript.iter().enumerate() {
+            insert_transcript_entry(&tx, &session.id, seq, entry)?;
+        }
+        for p in &record.participants {
+            tx.execute(
+                "INSERT OR IGNORE INTO participants (id, session_id, name, email, role, crm_contact_id, source) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![p.id, session.id, p.name, p.email, p.role, p.crm_contact_id, p.source],
+            )
+            .map_err(err)?;
+        }
+        for c in &record.chapters {
+            tx.execute(
+                "INSERT INTO chapters (id, session_id, seq, title, start_time, end_time) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![c.id, session.id, c.seq, c.title, c.start_time, c.end_time],
+            )
+            .map_err(err)?;
+        }
+        for h in &record.highlights {
+            tx.execute(
+                "INSERT INTO highlights (id, session_id, timestamp, note) VALUES (?1, ?2, ?3, ?4)",
+                params![h.id, session.id, h.timestamp, h.note],
+            )
+            .map_err(err)?;
+        }
+        // Reminders are left to the device the meeting was recorded on
+        for item in &record.action_items {
+            tx.execute(
+                "INSERT INTO action_items (id, session_id, owner, description, due_date, status, created_at, completed_at, issue_key, issue_url, reminded_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    item.id,
+                    session.id,
+                    item.owner,
+                    item.description,
+                    item.due_date,
+                    item.status,
+                    item.created_at,
+                    item.completed_at,
+                    item.issue_key,
+                    item.issue_url,
+                    record.updated_at,
+                ],
+            )
+            .map_err(err)?;
+        }
+
+        // The inserts above bumped updated_at through the triggers
+        tx.execute(
+            "UPDATE sessions SET updated_at = ?2 WHERE id = ?1",
+            params![session.id, record.updated_at],
+        )
+        .map_err(err)?;
+        tx.commit().map_err(err)
+    }
+
+    /// Session versions as of the last sync, keyed by id
+    pub fn get_sync_state(&self) -> Result<HashMap<String, String>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT session_id, updated_at FROM sync_state")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let state = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(state)
+    }
+
+    /// Record the version both sides now agree on; None forgets the session
+    pub fn set_sync_state(
+        &self,
+        session_id: &str,
+        updated_at: Option<&str>,
+    ) -> Result<(), VenkyError> {
+        match updated_at {
+            Some(updated_at) => self.conn.execute(
+                "INSERT OR REPLACE INTO sync_state (session_id, updated_at) VALUES (?1, ?2)",
+                params![session_id, updated_at],
+            ),
+            None => self.conn.execute(
+                "DELETE FROM sync_state WHERE session_id = ?1",
+                params![session_id],
+            ),
+        }
+        .map_err(|e| VenkyError::Storage(format!("Failed to save sync state: {}", e)))?;
+        Ok(())
+    }
+
+    /// Saved session audio created before `cutoff` (RFC 3339)
+    pub fn session_audio_before(&self, cutoff: &str) -> Result<Vec<SessionAudio>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT session_id, source, path, created_at, started_at FROM session_audio WHERE created_at < ?1")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let audio = stmt
+            .query_map(params![cutoff], |row| {
+                Ok(SessionAudio {
+                    session_id: row.get(0)?,
+                    source: row.get(1)?,
+                    path: row.get(2)?,
+                    created_at: row.get(3)?,
+                    started_at: row.get(4)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(audio)
+    }
+
+    pub fn delete_session_audio(&self, session_id: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "DELETE FROM session_audio WHERE session_id = ?1",
+                params![session_id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to delete session audio: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get_summary_templates(&self) -> Result<Vec<SummaryTemplate>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, name, sections_json, tone, length FROM summary_templates ORDER BY name",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let templates = stmt
+            .query_map([], summary_template_from_row)
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(templates)
+    }
+
+    pub fn get_summary_template(&self, id: &str) -> Result<Option<SummaryTemplate>, VenkyError> {
+        self.conn
+            .query_row(
+                "SELECT id, name, sections_json, tone, length FROM summary_templates WHERE id = ?1",
+                params![id],
+                summary_template_from_row,
+            )
+            .optional()
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))
+    }
+
+    pub fn save_summary_template(&self, template: &SummaryTemplate) -> Result<(), VenkyError> {
+        let sections_json = serde_json::to_string(&template.sections)
+            .map_err(|e| VenkyError::Storage(format!("Serialize error: {}", e)))?;
+
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO summary_templates (id, name, sections_json, tone, length) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    template.id,
+                    template.name,
+                    sections_json,
+                    template.tone,
+                    template.length.as_str()
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save summary template: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn delete_summary_template(&self, id: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute("DELETE FROM summary_templates WHERE id = ?1", params![id])
+            .map_err(|e| {
+                VenkyError::Storage(format!("Failed to delete summary template: {}", e))
+            })?;
+        Ok(())
+    }
+
+    pub fn get_keyword_watches(&self) -> Result<Vec<KeywordWatch>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, label, pattern, is_regex, response, enabled FROM keyword_watches ORDER BY label",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let watches = stmt
+            .query_map([], |row| {
+                Ok(KeywordWatch {
+                    id: row.get(0)?,
+                    label: row.get(1)?,
+                    pattern: row.get(2)?,
+                    is_regex: row.get(3)?,
+                    response: row.get(4)?,
+                    enabled: row.get(5)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(watches)
+    }
+
+    pub fn save_keyword_watch(&self, watch: &KeywordWatch) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO keyword_watches (id, label, pattern, is_regex, response, enabled) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    watch.id,
+                    watch.label,
+                    watch.pattern,
+                    watch.is_regex,
+                    watch.response,
+                    watch.enabled
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save keyword watch: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn delete_keyword_watch(&self, id: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute("DELETE FROM keyword_watches WHERE id = ?1", params![id])
+            .map_err(|e| VenkyError::Storage(format!("Failed to delete keyword watch: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get_webhooks(&self) -> Result<Vec<WebhookSubscription>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, url, secret, events, enabled, created_at, last_delivery_at, last_status FROM webhooks ORDER BY created_at",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let webhooks = stmt
+            .query_map([], |row| {
+                let events: String = row.get(3)?;
+                Ok(WebhookSubscription {
+                    id: row.get(0)?,
+                    url: row.get(1)?,
+                    secret: row.get(2)?,
+                    events: serde_json::from_str(&events).unwrap_or_default(),
+                    enabled: row.get(4)?,
+                    created_at: row.get(5)?,
+                    last_delivery_at: row.get(6)?,
+                    last_status: row.get(7)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(webhooks)
+    }
+
+    pub fn save_webhook(&self, webhook: &WebhookSubscription) -> Result<(), VenkyError> {
+        let events = serde_json::to_string(&webhook.events)
+            .map_err(|e| VenkyError::Storage(format!("Failed to save webhook: {}", e)))?;
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO webhooks (id, url, secret, events, enabled, created_at, last_delivery_at, last_status) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    webhook.id,
+                    webhook.url,
+                    webhook.secret,
+                    events,
+                    webhook.enabled,
+                    webhook.created_at,
+                    webhook.last_delivery_at,
+                    webhook.last_status
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save webhook: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn delete_webhook(&self, id: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute("DELETE FROM webhooks WHERE id = ?1", params![id])
+            .map_err(|e| VenkyError::Storage(format!("Failed to delete webhook: {}", e)))?;
+        Ok(())
+    }
+
+    /// Note the outcome of the latest delivery attempt
+    pub fn record_webhook_delivery(&self, id: &str, status: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "UPDATE webhooks SET last_delivery_at = ?1, last_status = ?2 WHERE id = ?3",
+                params![chrono::Utc::now().to_rfc3339(), status, id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to update webhook: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>, VenkyError> {
+        self.conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))
+    }
+
+    pub fn set_setting(&self, key: &str, value: Option<&str>) -> Result<(), VenkyError> {
+        let result = match value {
+            Some(value) => self.conn.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            ),
+            None => self
+                .conn
+                .execute("DELETE FROM settings WHERE key = ?1", params![key]),
+        };
+        result.map_err(|e| VenkyError::Storage(format!("Failed to save setting: {}", e)))?;
+        Ok(())
+    }
+
+    /// Replace a session's title and description, keeping the search index in step
+    pub fn set_session_title(
+        &self,
+        session_id: &str,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "UPDATE sessions SET title = ?1, description = ?2 WHERE id = ?3",
+                params![title, description, session_id],
+            )
+            .and_then(|_| {
+                self.conn.execute(
+                    "UPDATE session_fts SET title = ?1 WHERE session_id = ?2",
+                    params![title, session_id],
+                )
+            })
+            .map_err(|e| VenkyError::Storage(format!("Failed to save session title: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn set_session_context(
+        &self,
+        session_id: &str,
+        context: Option<&str>,
+    ) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "UPDATE sessions SET context = ?1 WHERE id = ?2",
+                params![context, session_id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save session context: {}", e)))?;
+        Ok(())
+    }
+
+    /// Record the meeting window an auto-started session follows
+    pub fn set_session_meeting_window(
+        &self,
+        session_id: &str,
+        meeting_window: &str,
+    ) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "UPDATE sessions SET meeting_window = ?1 WHERE id = ?2",
+                params![meeting_window, session_id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save meeting window: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn set_session_summary(&self, session_id: &str, summary: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "UPDATE sessions SET summary = ?1 WHERE id = ?2",
+                params![summary, session_id],
+            )
+            .and_then(|_| {
+                self.conn.execute(
+                    "UPDATE session_fts SET summary = ?1 WHERE session_id = ?2",
+                    params![summary, session_id],
+                )
+            })
+            .map_err(|e| VenkyError::Storage(format!("Failed to save summary: {}", e)))?;
+        Ok(())
+    }
+
+    /// Every session, newest first
+    pub fn get_all_sessions(&self) -> Result<Vec<SessionSummary>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, title, start_time, end_time, summary, purpose, description FROM sessions ORDER BY start_time DESC")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let sessions = stmt
+            .query_map([], |row| {
+                Ok(SessionSummary {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    start_time: row.get(2)?,
+                    end_time: row.get(3)?,
+                    summary: row.get(4)?,
+                    purpose: SessionPurpose::parse(&row.get::<_, String>(5)?),
+                    description: row.get(6)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(sessions)
+    }
+
+    pub fn record_usage_event(
+        &self,
+        kind: &str,
+        provider: &str,
+        amount: i64,
+    ) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "INSERT INTO usage_events (kind, provider, amount, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![kind, provider, amount, chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to record usage: {}", e)))?;
+        Ok(())
+    }
+
+    /// Usage events recorded at or after `since` (RFC 3339)
+    pub fn usage_events_since(&self, since: &str) -> Result<Vec<UsageEvent>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT kind, provider, amount, created_at FROM usage_events
+                 WHERE created_at >= ?1 ORDER BY created_at",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let events = stmt
+            .query_map(params![since], |row| {
+                Ok(UsageEvent {
+                    kind: row.get(0)?,
+                    provider: row.get(1)?,
+                    amount: row.get(2)?,
+                    created_at: row.get(3)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(events)
+    }
+
+    /// (start_time, end_time) of sessions started at or after `since`
+    pub fn session_times_since(
+        &self,
+        since: &str,
+    ) -> Result<Vec<(String, Option<String>)>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT start_time, end_time FROM sessions WHERE start_time >= ?1")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let times = stmt
+            .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(times)
+    }
+
+    /// (created_at, accepted) of suggestions made at or after `since`
+    pub fn suggestion_times_since(&self, since: &str) -> Result<Vec<(String, bool)>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT created_at, accepted FROM suggestions WHERE created_at >= ?1")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let times = stmt
+            .query_map(params![since], |row| {
+                Ok((row.get(0)?, row.get::<_, i64>(1)? != 0))
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(times)
+    }
+
+    /// The latest ended sessions, newest first
+    pub fn recent_sessions(&self, limit: usize) -> Result<Vec<SessionSummary>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, title, start_time, end_time, summary, purpose, description FROM sessions \
+                 WHERE end_time IS NOT NULL ORDER BY start_time DESC LIMIT ?1",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let sessions = stmt
+            .query_map(params![limit as i64], |row| {
+                Ok(SessionSummary {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    start_time: row.get(2)?,
+                    end_time: row.get(3)?,
+                    summary: row.get(4)?,
+                    purpose: SessionPurpose::parse(&row.get::<_, String>(5)?),
+                    description: row.get(6)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(sessions)
+    }
+
+    pub fn get_prompt_templates(&self) -> Result<Vec<PromptTemplate>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, name, template, category FROM prompt_templates ORDER BY name")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let templates = stmt
+            .query_map([], |row| {
+                Ok(PromptTemplate {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    template: row.get(2)?,
+                    category: row.get(3)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(templates)
+    }
+
+    /// Insert a template, or replace the one with the same id
+    pub fn put_prompt_template(&self, template: &PromptTemplate) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO prompt_templates (id, name, template, category) VALUES (?1, ?2, ?3, ?4)",
+                params![template.id, template.name, template.template, template.category],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save template: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get_prompt_template(&self, id: &str) -> Result<Option<PromptTemplate>, VenkyError> {
+        self.conn
+            .query_row(
+                "SELECT id, name, template, category FROM prompt_templates WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok(PromptTemplate {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        template: row.get(2)?,
+                        category: row.get(3)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))
+    }
+
+    pub fn set_session_recording(
+        &self,
+        session_id: &str,
+        path: &str,
+        started_at: Option<&str>,
+    ) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO session_recordings (session_id, path, created_at, started_at) VALUES (?1, ?2, ?3, ?4)",
+                params![session_id, path, chrono::Utc::now().to_rfc3339(), started_at],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save session recording: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get_session_recording(&self, session_id: &str) -> Result<Option<String>, VenkyError> {
+        self.conn
+            .query_row(
+                "SELECT path FROM session_recordings WHERE session_id = ?1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))
+    }
+
+    /// When the session's screen recording started, if it has one and that was recorded
+    pub fn get_session_recording_started_at(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<String>, VenkyError> {
+        self.conn
+            .query_row(
+                "SELECT started_at FROM session_recordings WHERE session_id = ?1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map(Option::flatten)
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))
+    }
+
+    pub fn add_capture(
+        &self,
+        id: &str,
+        session_id: &str,
+        path: &str,
+        timestamp: &str,
+    ) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "INSERT INTO captures (id, session_id, path, timestamp) VALUES (?1, ?2, ?3, ?4)",
+                params![id, session_id, path, timestamp],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save capture: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn set_capture_ocr_text(&self, id: &str, ocr_text: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "UPDATE captures SET ocr_text = ?1 WHERE id = ?2",
+                params![ocr_text, id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to update capture: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get_session_captures(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<SessionCapture>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, session_id, path, timestamp, ocr_text FROM captures WHERE session_id = ?1 ORDER BY timestamp")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let captures = stmt
+            .query_map(params![session_id], |row| {
+                Ok(SessionCapture {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    path: row.get(2)?,
+                    timestamp: row.get(3)?,
+                    ocr_text: row.get(4)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(captures)
+    }
+
+    /// Replace a session's open action items with a fresh extraction; completed and ticketed
+    /// ones are kept
+    pub fn replace_open_action_items(
+        &self,
+        session_id: &str,
+        items: &[ActionItem],
+    ) -> Result<(), VenkyError> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| VenkyError::Storage(format!("Failed to save action items: {}", e)))?;
+
+        tx.execute(
+            "DELETE FROM action_items WHERE session_id = ?1 AND status = 'open' AND issue_key IS NULL",
+            params![session_id],
+        )
+        .map_err(|e| VenkyError::Storage(format!("Failed to save action items: {}", e)))?;
+        for item in items {
+            tx.execute(
+                "INSERT INTO action_items (id, session_id, owner, description, due_date, status, created_at, completed_at, issue_key, issue_url) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    item.id,
+                    item.session_id,
+                    item.owner,
+                    item.description,
+                    item.due_date,
+                    item.status,
+                    item.created_at,
+                    item.completed_at,
+                    item.issue_key,
+                    item.issue_url,
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save action items: {}", e)))?;
+        }
+
+        tx.commit()
+            .map_err(|e| VenkyError::Storage(format!("Failed to save action items: {}", e)))
+    }
+
+    pub fn list_action_items(
+        &self,
+        session_id: Option<&str>,
+        status: Option<&str>,
+    ) -> Result<Vec<ActionItem>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, session_id, owner, description, due_date, status, created_at, completed_at, issue_key, issue_url
+                 FROM action_items
+                 WHERE (?1 IS NULL OR session_id = ?1) AND (?2 IS NULL OR status = ?2)
+                 ORDER BY status DESC, due_date IS NULL, due_date, created_at",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let items = stmt
+            .query_map(params![session_id, status], |row| {
+                Ok(ActionItem {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    owner: row.get(2)?,
+                    description: row.get(3)?,
+                    due_date: row.get(4)?,
+                    status: row.get(5)?,
+                    created_at: row.get(6)?,
+                    completed_at: row.get(7)?,
+                    issue_key: row.get(8)?,
+                    issue_url: row.get(9)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(items)
+    }
+
+    /// Open action items due on or before `today` (YYYY-MM-DD) that nobody was reminded of yet
+    pub fn due_action_items(&self, today: &str) -> Result<Vec<ActionItem>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, session_id, owner, description, due_date, status, created_at, completed_at, issue_key, issue_url
+                 FROM action_items
+                 WHERE status = 'open' AND reminded_at IS NULL
+                   AND due_date GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]*'
+                   AND substr(due_date, 1, 10) <= ?1
+                 ORDER BY due_date, created_at",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let items = stmt
+            .query_map(params![today], |row| {
+                Ok(ActionItem {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    owner: row.get(2)?,
+                    description: row.get(3)?,
+                    due_date: row.get(4)?,
+                    status: row.get(5)?,
+                    created_at: row.get(6)?,
+                    completed_at: row.get(7)?,
+                    issue_key: row.get(8)?,
+                    issue_url: row.get(9)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(items)
+    }
+
+    /// Remember that a reminder was shown for an action item so it is only shown once
+    pub fn mark_action_item_reminded(&self, id: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "UPDATE action_items SET reminded_at = ?1 WHERE id = ?2",
+                params![chrono::Utc::now().to_rfc3339(), id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to update action item: {}", e)))?;
+        Ok(())
+    }
+
+    /// Record the ticket filed for an action item
+    pub fn set_action_item_issue(&self, id: &str, key: &str, url: &str) -> Result<(), VenkyError> {
+        let changed = self
+            .conn
+            .execute(
+                "UPDATE action_items SET issue_key = ?1, issue_url = ?2 WHERE id = ?3",
+                params![key, url, id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to update action item: {}", e)))?;
+        if changed == 0 {
+            return Err(VenkyError::Other("Action item not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn set_action_item_done(&self, id: &str, done: bool) -> Result<(), VenkyError> {
+        let changed = self
+            .conn
+            .execute(
+                "UPDATE action_items SET status = ?1, completed_at = ?2 WHERE id = ?3",
+                params![
+                    if done { "done" } else { "open" },
+                    done.then(|| chrono::Utc::now().to_rfc3339()),
+                    id
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to update action item: {}", e)))?;
+        if changed == 0 {
+            return Err(VenkyError::Other("Action item not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn add_suggestion(&self, id: &str, session_id: &str, text: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "INSERT INTO suggestions (id, session_id, text, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![id, session_id, text, chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save suggestion: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn set_suggestion_accepted(&self, id: &str) -> Result<(), VenkyError> {
+        let changed = self
+            .conn
+            .execute(
+                "UPDATE suggestions SET accepted = 1 WHERE id = ?1",
+                params![id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to update suggestion: {}", e)))?;
+        if changed == 0 {
+            return Err(VenkyError::Other("Suggestion not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn get_suggestion_text(&self, id: &str) -> Result<String, VenkyError> {
+        self.conn
+            .query_row(
+                "SELECT text FROM suggestions WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .ok_or_else(|| VenkyError::Other("Suggestion not found".to_string()))
+    }
+
+    /// (created_at, text) of the suggestions shown during a session, oldest first
+    pub fn get_session_suggestions(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<(String, String)>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT created_at, text FROM suggestions WHERE session_id = ?1 ORDER BY created_at")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let suggestions = stmt
+            .query_map(params![session_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(suggestions)
+    }
+
+    /// (shown, accepted) suggestion counts for a session
+    pub fn suggestion_counts(&self, session_id: &str) -> Result<(u32, u32), VenkyError> {
+        self.conn
+            .query_row(
+                "SELECT COUNT(*), COALESCE(SUM(accepted), 0) FROM suggestions WHERE session_id = ?1",
+                params![session_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))
+    }
+
+    pub fn add_highlight(&self, highlight: &Highlight) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "INSERT INTO highlights (id, session_id, timestamp, note) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    highlight.id,
+                    highlight.session_id,
+                    highlight.timestamp,
+                    highlight.note
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save highlight: {}", e)))?;
+        Ok(())
+    }
+
+    /// Replace a session's chapters with a fresh chaptering pass
+    pub fn replace_chapters(
+        &self,
+        session_id: &str,
+        chapters: &[Chapter],
+    ) -> Result<(), VenkyError> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| VenkyError::Storage(format!("Failed to save chapters: {}", e)))?;
+
+        tx.execute(
+            "DELETE FROM chapters WHERE session_id = ?1",
+            params![session_id],
+        )
+        .map_err(|e| VenkyError::Storage(format!("Failed to save chapters: {}", e)))?;
+        for chapter in chapters {
+            tx.execute(
+                "INSERT INTO chapters (id, session_id, seq, title, start_time, end_time) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    chapter.id,
+                    chapter.session_id,
+                    chapter.seq,
+                    chapter.title,
+                    chapter.start_time,
+                    chapter.end_time,
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save chapters: {}", e)))?;
+        }
+
+        tx.commit()
+            .map_err(|e| VenkyError::Storage(format!("Failed to save chapters: {}", e)))
+    }
+
+    pub fn get_session_chapters(&self, session_id: &str) -> Result<Vec<Chapter>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, session_id, seq, title, start_time, end_time FROM chapters WHERE session_id = ?1 ORDER BY seq")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let chapters = stmt
+            .query_map(params![session_id], |row| {
+                Ok(Chapter {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    seq: row.get(2)?,
+                    title: row.get(3)?,
+                    start_time: row.get(4)?,
+                    end_time: row.get(5)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(chapters)
+    }
+
+    pub fn replace_interview_qa(
+        &self,
+        session_id: &str,
+        pairs: &[InterviewQa],
+    ) -> Result<(), VenkyError> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| VenkyError::Storage(format!("Failed to save interview log: {}", e)))?;
+
+        tx.execute(
+            "DELETE FROM interview_qa WHERE session_id = ?1",
+            params![session_id],
+        )
+        .map_err(|e| VenkyError::Storage(format!("Failed to save interview log: {}", e)))?;
+        for qa in pairs {
+            tx.execute(
+                "INSERT INTO interview_qa (id, session_id, seq, asked_at, question, answer, suggested_answer) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    qa.id,
+                    qa.session_id,
+                    qa.seq,
+                    qa.asked_at,
+                    qa.question,
+                    qa.answer,
+                    qa.suggested_answer,
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save interview log: {}", e)))?;
+        }
+
+        tx.commit()
+            .map_err(|e| VenkyError::Storage(format!("Failed to save interview log: {}", e)))
+    }
+
+    pub fn get_interview_qa(&self, session_id: &str) -> Result<Vec<InterviewQa>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, session_id, seq, asked_at, question, answer, suggested_answer FROM interview_qa WHERE session_id = ?1 ORDER BY seq")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let pairs = stmt
+            .query_map(params![session_id], |row| {
+                Ok(InterviewQa {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    seq: row.get(2)?,
+                    asked_at: row.get(3)?,
+                    question: row.get(4)?,
+                    answer: row.get(5)?,
+                    suggested_answer: row.get(6)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(pairs)
+    }
+
+    pub fn save_coaching_metrics(&self, metrics: &CoachingMetrics) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO coaching_metrics (session_id, user_talk_ratio, words_per_minute, filler_count, interruption_count, computed_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    metrics.session_id,
+                    metrics.user_talk_ratio,
+                    metrics.words_per_minute,
+                    metrics.filler_count,
+                    metrics.interruption_count,
+                    metrics.computed_at
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save coaching metrics: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get_coaching_metrics(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<CoachingMetrics>, VenkyError> {
+        self.conn
+            .query_row(
+                "SELECT session_id, user_talk_ratio, words_per_minute, filler_count, interruption_count, computed_at FROM coaching_metrics WHERE session_id = ?1",
+                params![session_id],
+                |row| {
+                    Ok(CoachingMetrics {
+                        session_id: row.get(0)?,
+                        user_talk_ratio: row.get(1)?,
+                        words_per_minute: row.get(2)?,
+                        filler_count: row.get(3)?,
+                        interruption_count: row.get(4)?,
+                        computed_at: row.get(5)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))
+    }
+
+    pub fn get_session_highlights(&self, session_id: &str) -> Result<Vec<Highlight>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, session_id, timestamp, note FROM highlights WHERE session_id = ?1 ORDER BY timestamp")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let highlights = stmt
+            .query_map(params![session_id], |row| {
+                Ok(Highlight {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    timestamp: row.get(2)?,
+                    note: row.get(3)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(highlights)
+    }
+
+    /// Add a participant; one with the same name already in the session is kept as is
+    pub fn add_participant(&self, participant: &Participant) -> Result<bool, VenkyError> {
+        let inserted = self
+            .conn
+            .execute(
+                "INSERT OR IGNORE INTO participants (id, session_id, name, email, role, crm_contact_id, source) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    participant.id,
+                    participant.session_id,
+                    participant.name,
+                    participant.email,
+                    participant.role,
+                    participant.crm_contact_id,
+                    participant.source,
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save participant: {}", e)))?;
+        Ok(inserted > 0)
+    }
+
+    /// Link a participant to the CRM contact they were matched to
+    pub fn set_participant_crm_contact(
+        &self,
+        id: &str,
+        crm_contact_id: &str,
+    ) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "UPDATE participants SET crm_contact_id = ?1 WHERE id = ?2",
+                params![crm_contact_id, id],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to update participant: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn remove_participant(&self, id: &str) -> Result<(), VenkyError> {
+        self.conn
+            .execute("DELETE FROM participants WHERE id = ?1", params![id])
+            .map_err(|e| VenkyError::Storage(format!("Failed to remove participant: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get_session_participants(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<Participant>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, session_id, name, email, role, crm_contact_id, source FROM participants WHERE session_id = ?1 ORDER BY name")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let participants = stmt
+            .query_map(params![session_id], |row| {
+                Ok(Participant {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    name: row.get(2)?,
+                    email: row.get(3)?,
+                    role: row.get(4)?,
+                    crm_contact_id: row.get(5)?,
+                    source: row.get(6)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(participants)
+    }
+
+    pub fn add_redaction_counts(
+        &self,
+        session_id: &str,
+        counts: &[RedactionCount],
+    ) -> Result<(), VenkyError> {
+        for c in counts {
+            self.conn
+                .execute(
+                    "INSERT INTO redaction_counts (session_id, kind, count) VALUES (?1, ?2, ?3) \
+                     ON CONFLICT(session_id, kind) DO UPDATE SET count = count + excluded.count",
+                    params![session_id, c.kind, c.count],
+                )
+                .map_err(|e| {
+                    VenkyError::Storage(format!("Failed to save redaction report: {}", e))
+                })?;
+        }
+        Ok(())
+    }
+
+    pub fn get_redaction_counts(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<RedactionCount>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT kind, count FROM redaction_counts WHERE session_id = ?1 ORDER BY kind")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let counts = stmt
+            .query_map(params![session_id], |row| {
+                Ok(RedactionCount {
+                    kind: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(counts)
+    }
+
+    pub fn save_consent_record(&self, record: &ConsentRecord) -> Result<(), VenkyError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO consent_records (session_id, jurisdiction, participants_notified, disclosure_played, created_at, confirmed_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    record.session_id,
+                    record.jurisdiction,
+                    record.participants_notified,
+                    record.disclosure_played,
+                    record.created_at,
+                    record.confirmed_at
+                ],
+            )
+            .map_err(|e| VenkyError::Storage(format!("Failed to save consent record: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get_consent_record(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<ConsentRecord>, VenkyError> {
+        self.conn
+            .query_row(
+                "SELECT session_id, jurisdiction, participants_notified, disclosure_played, created_at, confirmed_at FROM consent_records WHERE session_id = ?1",
+                params![session_id],
+                |row| {
+                    Ok(ConsentRecord {
+                        session_id: row.get(0)?,
+                        jurisdiction: row.get(1)?,
+                        participants_notified: row.get(2)?,
+                        disclosure_played: row.get(3)?,
+                        created_at: row.get(4)?,
+                        confirmed_at: row.get(5)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))
+    }
+
+    /// The most recent summarized sessions, other than `exclude_session_id`, that a person
+    /// with this email or CRM contact took part in
+    pub fn past_meetings_with(
+        &self,
+        email: Option<&str>,
+        crm_contact_id: Option<&str>,
+        exclude_session_id: &str,
+        limit: usize,
+    ) -> Result<Vec<PastMeeting>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT DISTINCT s.id, s.title, s.start_time, s.summary FROM sessions s \
+                 JOIN participants p ON p.session_id = s.id \
+                 WHERE s.id != ?1 AND s.summary IS NOT NULL AND s.summary != '' \
+                 AND ((?2 IS NOT NULL AND lower(p.email) = lower(?2)) OR (?3 IS NOT NULL AND p.crm_contact_id = ?3)) \
+                 ORDER BY s.start_time DESC LIMIT ?4",
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let meetings = stmt
+            .query_map(
+                params![exclude_session_id, email, crm_contact_id, limit as i64],
+                |row| {
+                    Ok(PastMeeting {
+                        session_id: row.get(0)?,
+                        title: row.get(1)?,
+                        start_time: row.get(2)?,
+                        summary: row.get(3)?,
+                    })
+                },
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(meetings)
+    }
+
+    /// Distinct speaker labels in a session's transcript
+    pub fn get_transcript_speakers(&self, session_id: &str) -> Result<Vec<String>, VenkyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT speaker FROM transcript_entries WHERE session_id = ?1")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+
+        let speakers = stmt
+            .query_map(params![session_id], |row| row.get(0))
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(speakers)
+    }
+}
+
+fn insert_transcript_entry(
+    conn: &Connection,
+    session_id: &str,
+    seq: usize,
+    entry: &TranscriptEntry,
+) -> Result<(), VenkyError> {
+    // Drop the search row of an entry being replaced
+    let replaced: Option<i64> = conn
+        .query_row(
+            "SELECT rowid FROM transcript_entries WHERE session_id = ?1 AND seq = ?2",
+            params![session_id, seq as i64],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+    if let Some(rowid) = replaced {
+        conn.execute(
+            "DELETE FROM transcript_fts WHERE rowid = ?1",
+            params![rowid],
+        )
+        .map_err(|e| VenkyError::Storage(format!("Failed to update search index: {}", e)))?;
+    }
+
+    // Entries from before ids existed (e.g. migrated JSON transcripts) get one here
+    let id = if entry.id.is_empty() {
+        uuid::Uuid::new_v4().to_string()
+    } else {
+        entry.id.clone()
+    };
+
+    conn.execute(
+        "INSERT OR REPLACE INTO transcript_entries (session_id, seq, timestamp, speaker, text, source, id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![session_id, seq as i64, entry.timestamp, entry.speaker, entry.text, entry.source, id],
+    )
+    .map_err(|e| VenkyError::Storage(format!("Failed to save transcript entry: {}", e)))?;
+
+    conn.execute(
+        "INSERT INTO transcript_fts (rowid, text) VALUES (?1, ?2)",
+        params![conn.last_insert_rowid(), entry.text],
+    )
+    .map_err(|e| VenkyError::Storage(format!("Failed to update search index: {}", e)))?;
+    Ok(())
+}
+
+fn summary_template_from_row(row: &rusqlite::Row) -> rusqlite::Result<SummaryTemplate> {
+    let sections_json: String = row.get(2)?;
+    Ok(SummaryTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        sections: serde_json::from_str(&sections_json).unwrap_or_default(),
+        tone: row.get(3)?,
+        length: SummaryLength::parse(&row.get::<_, String>(4)?),
+    })
+}
+
+fn record_transcript_edit(
+    conn: &Connection,
+    session_id: &str,
+    old: &TranscriptEntry,
+    new: Option<&TranscriptEntry>,
+) -> Result<(), VenkyError> {
+    conn.execute(
+        "INSERT INTO transcript_edits (id, session_id, entry_id, action, old_speaker, old_text, new_speaker, new_text, edited_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            uuid::Uuid::new_v4().to_string(),
+            session_id,
+            old.id,
+            if new.is_some() { "update" } else { "delete" },
+            old.speaker,
+            old.text,
+            new.map(|e| e.speaker.as_str()),
+            new.map(|e| e.text.as_str()),
+            chrono::Utc::now().to_rfc3339(),
+        ],
+    )
+    .map_err(|e| VenkyError::Storage(format!("Failed to record transcript edit: {}", e)))?;
+    Ok(())
+}
+
+/// SQL for the current time in the format `sessions.updated_at` uses
+const NOW_SQL: &str = "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')";
+
+/// Keep `sessions.updated_at` current whenever a session or the records synced with it
+/// change, so sync can tell which side edited a session
+fn create_sync_triggers(conn: &Connection) -> Result<(), VenkyError> {
+    let mut sql = format!(
+        "
+        UPDATE sessions SET updated_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', COALESCE(end_time, start_time)), {now})
+            WHERE updated_at IS NULL;
+        CREATE TRIGGER IF NOT EXISTS sessions_sync_insert AFTER INSERT ON sessions
+            WHEN NEW.updated_at IS NULL
+            BEGIN UPDATE sessions SET updated_at = {now} WHERE id = NEW.id; END;
+        CREATE TRIGGER IF NOT EXISTS sessions_sync_update AFTER UPDATE ON sessions
+            WHEN NEW.updated_at IS OLD.updated_at
+            BEGIN UPDATE sessions SET updated_at = {now} WHERE id = NEW.id; END;
+        ",
+        now = NOW_SQL
+    );
+    // Reminder bookkeeping on action items isn't worth a sync
+    for (table, columns) in [
+        ("transcript_entries", ""),
+        ("participants", ""),
+        ("chapters", ""),
+        ("highlights", ""),
+        (
+            "action_items",
+            " OF owner, description, due_date, status, completed_at, issue_key, issue_url",
+        ),
+    ] {
+        let update = format!("UPDATE{}", columns);
+        for (name, event, row) in [
+            ("insert", "INSERT", "NEW"),
+            ("update", update.as_str(), "NEW"),
+            ("delete", "DELETE", "OLD"),
+        ] {
+            sql.push_str(&format!(
+                "
+                CREATE TRIGGER IF NOT EXISTS {table}_sync_{name} AFTER {event} ON {table}
+                    BEGIN UPDATE sessions SET updated_at = {now} WHERE id = {row}.session_id; END;
+                ",
+                table = table,
+                name = name,
+                event = event,
+                row = row,
+                now = NOW_SQL
+            ));
+        }
+    }
+
+    conn.execute_batch(&sql)
+        .map_err(|e| VenkyError::Storage(format!("Failed to create sync triggers: {}", e)))
+}
+
+/// FTS5 tables for `search`: transcript lines keyed by their `transcript_entries` rowid,
+/// and session titles/summaries. Built from existing rows the first time.
+fn create_search_index(conn: &Connection) -> Result<(), VenkyError> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = 'transcript_fts'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|count| count > 0)
+        .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+    if exists {
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        "
+        CREATE VIRTUAL TABLE transcript_fts USING fts5(text, tokenize = 'porter unicode61');
+        CREATE VIRTUAL TABLE IF NOT EXISTS session_fts USING fts5(
+            session_id UNINDEXED, title, summary, tokenize = 'porter unicode61'
+        );
+
+        INSERT INTO transcript_fts (rowid, text) SELECT rowid, text FROM transcript_entries;
+        DELETE FROM session_fts;
+        INSERT INTO session_fts (session_id, title, summary) SELECT id, title, summary FROM sessions;
+        ",
+    )
+    .map_err(|e| VenkyError::Storage(format!("Failed to create search index: {}", e)))
+}
+
+/// Turn free text into an FTS5 query matching all of its words, so punctuation
+/// in what the user typed can't be parsed as query syntax
+fn fts_query(input: &str) -> Option<String> {
+    let terms = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Move transcripts stored as a JSON blob on the session row into `transcript_entries`.
+/// Migrated rows have the blob cleared, so this only does work once per session.
+fn migrate_transcript_json(conn: &Connection) -> Result<(), VenkyError> {
+    let pending = {
+        let mut stmt = conn
+            .prepare("SELECT id, transcript_json FROM sessions WHERE transcript_json IS NOT NULL")
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect::<Vec<_>>();
+        rows
+    };
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| VenkyError::Storage(format!("Failed to migrate transcripts: {}", e)))?;
+    for (session_id, json) in &pending {
+        let transcript: Vec<TranscriptEntry> = match serde_json::from_str(json) {
+            Ok(transcript) => transcript,
+            Err(e) => {
+                log::error!(
+                    "Skipping unreadable transcript of session {}: {}",
+                    session_id,
+                    e
+                );
+                continue;
+            }
+        };
+
+        // Sessions recovered mid-way may already have incrementally written entries
+        let existing: i64 = tx
+            .query_row(
+                "SELECT COUNT(*) FROM transcript_entries WHERE session_id = ?1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+        if existing == 0 {
+            for (seq, entry) in transcript.iter().enumerate() {
+                insert_transcript_entry(&tx, session_id, seq, entry)?;
+            }
+        }
+
+        tx.execute(
+            "UPDATE sessions SET transcript_json = NULL WHERE id = ?1",
+            params![session_id],
+        )
+        .map_err(|e| VenkyError::Storage(format!("Failed to migrate transcripts: {}", e)))?;
+    }
+    tx.commit()
+        .map_err(|e| VenkyError::Storage(format!("Failed to migrate transcripts: {}", e)))?;
+
+    log::info!(
+        "Migrated {} session transcripts to transcript_entries",
+        pending.len()
+    );
+    Ok(())
+}
+
+/// Open the database file, unlocking it with the SQLCipher key if one is given
+fn open_connection(path: &Path, key: Option<&str>) -> Result<Connection, VenkyError> {
+    let conn = Connection::open(path)
+        .map_err(|e| VenkyError::Storage(format!("Failed to open database: {}", e)))?;
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", key)
+            .map_err(|e| VenkyError::Storage(format!("Failed to unlock database: {}", e)))?;
+    }
+    // The key is only checked on the first read; a wrong one reads as "not a database"
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|e| {
+            VenkyError::Storage(format!(
+                "Failed to open database (wrong encryption key?): {}",
+                e
+            ))
+        })?;
+    // Readers don't wait on the storage thread's writes, and commits skip most fsyncs
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+        .and_then(|_| conn.pragma_update(None, "synchronous", "NORMAL"))
+        .map_err(|e| VenkyError::Storage(format!("Failed to enable WAL mode: {}", e)))?;
+    Ok(conn)
+}
+
+/// ALTER TABLE ADD COLUMN for databases created before `column` existed
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    decl: &str,
+) -> Result<(), VenkyError> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| VenkyError::Storage(format!("Query error: {}", e)))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl),
+            [],
+        )
+        .map_err(|e| {
+            VenkyError::Storage(format!("Failed to migrate {}.{}: {}", table, column, e))
+        })?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_all_sessions(
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<Vec<SessionSummary>> {
+    Ok(storage.call(|s| s.get_all_sessions()).await?)
+}
+
+#[tauri::command]
+pub async fn get_session_by_id(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Session> {
+    Ok(storage.call(move |s| s.get_session(&session_id)).await?)
+}
+
+/// Mark a suggestion as used by the user (copied, read out, typed) and start the teleprompter
+#[tauri::command]
+pub async fn accept_suggestion(
+    app: tauri::AppHandle,
+    storage: tauri::State<'_, StorageActor>,
+    suggestion_id: String,
+) -> CommandResult<()> {
+    let id = suggestion_id.clone();
+    let text = storage
+        .call(move |s| {
+            s.set_suggestion_accepted(&id)?;
+            s.get_suggestion_text(&id)
+        })
+        .await?;
+    super::teleprompter::play_if_enabled(&app, &suggestion_id, &text);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_session_highlights(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<Highlight>> {
+    Ok(storage
+        .call(move |s| s.get_session_highlights(&session_id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_transcript_edits(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<TranscriptEdit>> {
+    Ok(storage
+        .call(move |s| s.get_transcript_edits(&session_id))
+        .await?)
+}
+
+/// Speaker labels that stand for a stream or the app rather than a person
+const GENERIC_SPEAKERS: &[&str] = &["you", "others", "user", "assistant", "unknown"];
+
+#[tauri::command]
+pub async fn add_participant(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+    name: String,
+    email: Option<String>,
+    role: Option<String>,
+    crm_contact_id: Option<String>,
+) -> CommandResult<Participant> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(VenkyError::Other(
+            "Participant name is required".to_string(),
+        ));
+    }
+
+    let participant = Participant {
+        id: uuid::Uuid::new_v4().to_string(),
+        session_id,
+        name,
+        email,
+        role,
+        crm_contact_id,
+        source: "manual".to_string(),
+    };
+    let row = participant.clone();
+    if !storage.call(move |s| s.add_participant(&row)).await? {
+        return Err(VenkyError::Other(format!(
+            "{} is already a participant",
+            participant.name
+        )));
+    }
+    Ok(participant)
+}
+
+#[tauri::command]
+pub async fn remove_participant(
+    storage: tauri::State<'_, StorageActor>,
+    participant_id: String,
+) -> CommandResult<()> {
+    Ok(storage
+        .call(move |s| s.remove_participant(&participant_id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_session_participants(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<Participant>> {
+    Ok(storage
+        .call(move |s| s.get_session_participants(&session_id))
+        .await?)
+}
+
+/// Add a participant for every named speaker in the transcript (e.g. from diarization
+/// or a meeting app's captions) and return the session's participants
+#[tauri::command]
+pub async fn populate_participants_from_speakers(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<Participant>> {
+    Ok(storage
+        .call(move |s| {
+            for speaker in s.get_transcript_speakers(&session_id)? {
+                let name = speaker.trim();
+                if name.is_empty()
+                    || name == super::manager::TIMELINE_SPEAKER
+                    || GENERIC_SPEAKERS.contains(&name.to_lowercase().as_str())
+                {
+                    continue;
+                }
+
+                s.add_participant(&Participant {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    session_id: session_id.clone(),
+                    name: name.to_string(),
+                    email: None,
+                    role: None,
+                    crm_contact_id: None,
+                    source: "speaker".to_string(),
+                })?;
+            }
+            s.get_session_participants(&session_id)
+        })
+        .await?)
+}
+
+/// "Find the meeting where we discussed pricing tiers"
+#[tauri::command]
+pub async fn search_sessions(
+    storage: tauri::State<'_, StorageActor>,
+    query: String,
+    filters: Option<SearchFilters>,
+) -> CommandResult<Vec<SearchResult>> {
+    Ok(storage
+        .call(move |s| s.search(&query, &filters.unwrap_or_default()))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_session_captures(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+) -> CommandResult<Vec<SessionCapture>> {
+    Ok(storage
+        .call(move |s| s.get_session_captures(&session_id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_prompt_templates(
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<Vec<PromptTemplate>> {
+    Ok(storage.call(|s| s.get_prompt_templates()).await?)
+}
+
+#[tauri::command]
+pub async fn save_prompt_template(
+    storage: tauri::State<'_, StorageActor>,
+    name: String,
+    template: String,
+    category: String,
+) -> CommandResult<PromptTemplate> {
+    let template = PromptTemplate {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        template,
+        category,
+    };
+    let row = template.clone();
+    storage
+        .call(move |s| {
+            s.conn
+                .execute(
+                    "INSERT INTO prompt_templates (id, name, template, category) VALUES (?1, ?2, ?3, ?4)",
+                    params![row.id, row.name, row.template, row.category],
+                )
+                .map_err(|e| VenkyError::Storage(format!("Failed to save template: {}", e)))
+        })
+        .await?;
+    Ok(template)
+}
+
+#[tauri::command]
+pub async fn delete_prompt_template(
+    storage: tauri::State<'_, StorageActor>,
+    id: String,
+) -> CommandResult<()> {
+    Ok(storage
+        .call(move |s| {
+            s.conn
+                .execute("DELETE FROM prompt_templates WHERE id = ?1", params![id])
+                .map_err(|e| VenkyError::Storage(format!("Failed to delete template: {}", e)))?;
+            Ok(())
+        })
+        .await?)
 }
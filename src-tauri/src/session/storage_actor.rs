@@ -0,0 +1,69 @@
+use std::sync::mpsc;
+
+use super::storage::Storage;
+use crate::error::VenkyError;
+
+type Job = Box<dyn FnOnce(&mut Storage) + Send>;
+
+/// Runs storage work on a dedicated thread, so commands and the IPC threads never wait
+/// on SQLite. Jobs run one at a time in the order they were sent, so a queued write is
+/// always visible to work sent after it.
+#[derive(Clone)]
+pub struct StorageActor {
+    jobs: mpsc::Sender<Job>,
+}
+
+impl StorageActor {
+    /// Start the storage thread, which owns `storage` from then on
+    pub fn spawn(mut storage: Storage) -> Result<Self, VenkyError> {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        std::thread::Builder::new()
+            .name("storage".to_string())
+            .spawn(move || {
+                for job in queue {
+                    job(&mut storage);
+                }
+            })
+            .map_err(|e| VenkyError::Storage(format!("Failed to start storage thread: {}", e)))?;
+        Ok(Self { jobs })
+    }
+
+    /// Run `f` on the storage thread and wait for its result without blocking this thread
+    pub async fn call<T, F>(&self, f: F) -> Result<T, VenkyError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Storage) -> Result<T, VenkyError> + Send + 'static,
+    {
+        let (reply, result) = tokio::sync::oneshot::channel();
+        self.send(move |s| {
+            let _ = reply.send(f(s));
+        });
+        result
+            .await
+            .map_err(|_| VenkyError::Storage("Storage thread stopped".to_string()))?
+    }
+
+    /// Run `f` on the storage thread and block until it is done, for setup code and
+    /// `spawn_blocking` jobs; on an async worker it would park the thread. Never call it
+    /// from inside a storage job.
+    pub fn call_blocking<T, F>(&self, f: F) -> Result<T, VenkyError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Storage) -> Result<T, VenkyError> + Send + 'static,
+    {
+        let (reply, result) = mpsc::sync_channel(1);
+        self.send(move |s| {
+            let _ = reply.send(f(s));
+        });
+        result
+            .recv()
+            .map_err(|_| VenkyError::Storage("Storage thread stopped".to_string()))?
+    }
+
+    /// Queue `f` without waiting for it, for writes whose failure is only worth a log line
+    pub fn send(&self, f: impl FnOnce(&mut Storage) + Send + 'static) {
+        if self.jobs.send(Box::new(f)).is_err() {
+            log::error!("Storage thread stopped; a write was dropped");
+        }
+    }
+}
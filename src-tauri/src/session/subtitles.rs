@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+
+use super::manager::{TranscriptEntry, TIMELINE_SPEAKER};
+use super::storage::Storage;
+use super::storage_actor::StorageActor;
+
+use crate::error::{CommandResult, VenkyError};
+
+/// Longest cue text; longer entries are split into several cues
+const MAX_CUE_CHARS: usize = 84;
+/// Reading speed used to estimate how long an entry was spoken
+const WORDS_PER_SEC: f64 = 2.5;
+const MIN_CUE_SECS: f64 = 1.0;
+const MAX_CUE_SECS: f64 = 7.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+/// Which file the subtitles line up with
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleTrack {
+    Screen,
+    Mic,
+    System,
+}
+
+struct Cue {
+    start: f64,
+    end: f64,
+    text: String,
+}
+
+/// Start of the recording the cues are timed against: the requested one, else the screen
+/// recording, else the first audio recording, else the session start
+fn origin(
+    storage: &Storage,
+    session_id: &str,
+    track: Option<SubtitleTrack>,
+) -> Result<String, VenkyError> {
+    let audio = storage.get_session_audio(session_id)?;
+    let audio_start = |source: &str| {
+        audio
+            .iter()
+            .find(|a| a.source == source)
+            .and_then(|a| a.started_at.clone())
+    };
+    let started_at = match track {
+        Some(SubtitleTrack::Screen) => storage.get_session_recording_started_at(session_id)?,
+        Some(SubtitleTrack::Mic) => audio_start("mic"),
+        Some(SubtitleTrack::System) => audio_start("system"),
+        None => storage
+            .get_session_recording_started_at(session_id)?
+            .or_else(|| audio.iter().find_map(|a| a.started_at.clone())),
+    };
+    match (started_at, track) {
+        (Some(started_at), _) => Ok(started_at),
+        (None, Some(track)) => Err(VenkyError::Other(format!(
+            "The session has no {:?} recording with a known start time",
+            track
+        ))),
+        (None, None) => Ok(storage.get_session(session_id)?.start_time),
+    }
+}
+
+fn spoken_secs(text: &str) -> f64 {
+    let words = text.split_whitespace().count() as f64;
+    (words / WORDS_PER_SEC).clamp(MIN_CUE_SECS, MAX_CUE_SECS)
+}
+
+/// Seconds into the recording an entry began. Re-transcribed entries carry their offset
+/// ("+12s"); imported ones are stamped when they began; live ones when their audio chunk was
+/// transcribed, i.e. around when they ended.
+fn entry_start(
+    entry: &TranscriptEntry,
+    origin: &chrono::DateTime<chrono::FixedOffset>,
+) -> Option<f64> {
+    if let Some(offset) = entry.timestamp.strip_prefix('+') {
+        return offset.trim_end_matches('s').parse().ok();
+    }
+    let at = chrono::DateTime::parse_from_rfc3339(&entry.timestamp).ok()?;
+    let secs = (at - *origin).num_milliseconds() as f64 / 1000.0;
+    let live = matches!(entry.source.as_deref(), None | Some("mic") | Some("system"));
+    Some(if live {
+        secs - spoken_secs(&entry.text)
+    } else {
+        secs
+    })
+}
+
+/// Break `text` into pieces of at most MAX_CUE_CHARS at word boundaries
+fn split_text(text: &str) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut piece = String::new();
+    for word in text.split_whitespace() {
+        if !piece.is_empty() && piece.len() + 1 + word.len() > MAX_CUE_CHARS {
+            pieces.push(std::mem::take(&mut piece));
+        }
+        if !piece.is_empty() {
+            piece.push(' ');
+        }
+        piece.push_str(word);
+    }
+    if !piece.is_empty() {
+        pieces.push(piece);
+    }
+    pieces
+}
+
+fn cues(transcript: &[TranscriptEntry], origin: &str) -> Result<Vec<Cue>, VenkyError> {
+    let origin = chrono::DateTime::parse_from_rfc3339(origin)
+        .map_err(|e| VenkyError::Other(format!("Invalid recording start time: {}", e)))?;
+    let mut timed: Vec<(f64, &TranscriptEntry)> = transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER && !e.text.trim().is_empty())
+        .filter_map(|e| entry_start(e, &origin).map(|start| (start, e)))
+        // Speech from before the recording started has nothing to line up with
+        .filter(|(start, entry)| start + spoken_secs(&entry.text) > 0.0)
+        .collect();
+    timed.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut cues = Vec::new();
+    for (i, (start, entry)) in timed.iter().enumerate() {
+        let start = start.max(0.0);
+        let mut end = start + spoken_secs(&entry.text);
+        if let Some((next, _)) = timed.get(i + 1) {
+            end = end.min(next.max(start + 0.5));
+        }
+        let speaker = if entry.speaker.is_empty() {
+            String::new()
+        } else {
+            format!("{}: ", entry.speaker)
+        };
+        let pieces = split_text(&format!("{}{}", speaker, entry.text.trim()));
+        let total_chars: usize = pieces.iter().map(|p| p.len()).sum();
+        let mut at = start;
+        for piece in pieces {
+            let share = (end - start) * piece.len() as f64 / total_chars.max(1) as f64;
+            cues.push(Cue {
+                start: at,
+                end: at + share,
+                text: piece,
+            });
+            at += share;
+        }
+    }
+    Ok(cues)
+}
+
+fn timecode(secs: f64, separator: char) -> String {
+    let ms = (secs.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+fn render(cues: &[Cue], format: SubtitleFormat) -> String {
+    let mut out = String::new();
+    if format == SubtitleFormat::Vtt {
+        out.push_str("WEBVTT\n\n");
+    }
+    let separator = match format {
+        SubtitleFormat::Srt => ',',
+        SubtitleFormat::Vtt => '.',
+    };
+    for (i, cue) in cues.iter().enumerate() {
+        if format == SubtitleFormat::Srt {
+            out.push_str(&format!("{}\n", i + 1));
+        }
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            timecode(cue.start, separator),
+            timecode(cue.end, separator),
+            cue.text
+        ));
+    }
+    out
+}
+
+/// Write the transcript as SRT or WebVTT subtitles timed to the session's screen or audio
+/// recording (`track`, by default the screen recording when there is one). Returns the
+/// number of cues written.
+#[tauri::command]
+pub async fn export_subtitles(
+    storage: tauri::State<'_, StorageActor>,
+    session_id: String,
+    format: SubtitleFormat,
+    path: String,
+    track: Option<SubtitleTrack>,
+) -> CommandResult<usize> {
+    let (transcript, origin) = storage
+        .call(move |s| {
+            Ok((
+                s.get_session(&session_id)?.transcript,
+                origin(s, &session_id, track)?,
+            ))
+        })
+        .await?;
+    let cues = cues(&transcript, &origin)?;
+    if cues.is_empty() {
+        return Err(VenkyError::Other(
+            "The session has no transcript to subtitle".to_string(),
+        ));
+    }
+    std::fs::write(&path, render(&cues, format))
+        .map_err(|e| VenkyError::Storage(format!("Failed to write {}: {}", path, e)))?;
+    Ok(cues.len())
+}
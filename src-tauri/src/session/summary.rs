@@ -0,0 +1,367 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::manager::{Session, SessionManager, SessionPurpose, TIMELINE_SPEAKER};
+use super::storage::{
+    Chapter, Highlight, Storage, SummaryLength, SummaryTemplate, DEFAULT_SUMMARY_TEMPLATE_ID,
+};
+use super::storage_actor::StorageActor;
+use crate::config::{AppConfig, LLMProvider};
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Transcripts longer than this (in chars) are condensed chunk by chunk before the final summary
+const CHUNK_CHARS: usize = 12_000;
+
+const CHUNK_NOTES_PROMPT: &str = "You are condensing one part of a longer meeting transcript. \
+    Write terse bullet notes of everything that matters: topics, facts and figures, questions, \
+    commitments (with who made them), decisions and open issues. Keep speaker names. No preamble.";
+
+/// Emitted as "summary-progress" while summarizing a long transcript
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryProgress {
+    pub session_id: String,
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// Settings key holding the summary template chosen for a session purpose
+fn purpose_setting_key(purpose: SessionPurpose) -> String {
+    format!("summary_template.{}", purpose.as_str())
+}
+
+/// The explicitly requested template, else the one selected for the purpose, else the built-in default
+pub fn template_for(
+    storage: &Storage,
+    purpose: SessionPurpose,
+    template_id: Option<&str>,
+) -> Result<SummaryTemplate, VenkyError> {
+    if let Some(id) = template_id {
+        return storage
+            .get_summary_template(id)?
+            .ok_or_else(|| VenkyError::Other(format!("Summary template {} not found", id)));
+    }
+
+    if let Some(id) = storage.get_setting(&purpose_setting_key(purpose))? {
+        if let Some(template) = storage.get_summary_template(&id)? {
+            return Ok(template);
+        }
+    }
+
+    Ok(storage
+        .get_summary_template(DEFAULT_SUMMARY_TEMPLATE_ID)?
+        .unwrap_or_else(|| SummaryTemplate {
+            id: DEFAULT_SUMMARY_TEMPLATE_ID.to_string(),
+            name: "Standard".to_string(),
+            sections: vec![
+                "Key Points".to_string(),
+                "Action Items".to_string(),
+                "Decisions Made".to_string(),
+            ],
+            tone: "neutral and concise".to_string(),
+            length: SummaryLength::Medium,
+        }))
+}
+
+fn system_prompt(template: &SummaryTemplate) -> String {
+    let length = match template.length {
+        SummaryLength::Short => "Keep it under 150 words.",
+        SummaryLength::Medium => "Keep it under 400 words.",
+        SummaryLength::Long => "Be thorough; up to 1000 words.",
+    };
+    let sections = template
+        .sections
+        .iter()
+        .enumerate()
+        .map(|(i, s)| format!("{}) {}", i + 1, s))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        "You are VenkyAI, summarizing a meeting for the user. \
+         Write the summary in markdown with these sections, in order: {}. \
+         Leave out a section if nothing in the transcript belongs in it. \
+         Tone: {}. {}",
+        sections, template.tone, length
+    )
+}
+
+/// Split transcript lines into chunks of at most `max_chars` (a longer single line gets its own chunk)
+fn chunk_lines(lines: &[String], max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        if !current.is_empty() && current.len() + line.len() + 1 > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+async fn complete(
+    cfg: &AppConfig,
+    system_prompt: &str,
+    question: &str,
+) -> Result<String, VenkyError> {
+    let context = crate::ai::AIContext {
+        transcript: None,
+        screen_description: None,
+        custom_prompt: None,
+        screen_base64: None,
+    };
+
+    let response = match cfg.llm_provider {
+        LLMProvider::OpenAI => {
+            crate::ai::openai::generate_with_system(cfg, system_prompt, question, &context).await?
+        }
+        LLMProvider::Ollama => {
+            crate::ai::ollama::generate_with_system(cfg, system_prompt, question).await?
+        }
+    };
+    Ok(response.content)
+}
+
+/// Summarize a session's transcript following `template`, calling out the user's highlights
+/// and following its chapters when it has been chaptered.
+/// Long transcripts are map-reduced: each chunk is condensed to notes, then the notes are
+/// summarized. `on_progress(completed, total)` is called after each LLM call.
+pub async fn summarize(
+    cfg: &AppConfig,
+    session: &Session,
+    highlights: &[Highlight],
+    chapters: &[Chapter],
+    template: &SummaryTemplate,
+    on_progress: impl Fn(usize, usize),
+) -> Result<String, VenkyError> {
+    let lines = session
+        .transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER)
+        .map(|e| format!("[{}] {}: {}", e.timestamp, e.speaker, e.text))
+        .collect::<Vec<_>>();
+
+    if lines.is_empty() {
+        return Err(VenkyError::Other("No transcript to summarize".to_string()));
+    }
+
+    let chunks = chunk_lines(&lines, CHUNK_CHARS);
+    let total = if chunks.len() > 1 {
+        chunks.len() + 1
+    } else {
+        1
+    };
+
+    let mut question = if chunks.len() > 1 {
+        let mut notes = Vec::with_capacity(chunks.len());
+        for (i, chunk) in chunks.iter().enumerate() {
+            let question = format!("Part {} of {}:\n\n{}", i + 1, chunks.len(), chunk);
+            notes.push(
+                crate::redaction::for_session(
+                    &session.id,
+                    complete(cfg, CHUNK_NOTES_PROMPT, &question),
+                )
+                .await?,
+            );
+            on_progress(i + 1, total);
+        }
+        format!(
+            "Summarize the following {} from notes taken on consecutive parts of its transcript:\n\n{}",
+            session.purpose.as_str(),
+            notes.join("\n\n---\n\n")
+        )
+    } else {
+        format!(
+            "Summarize the following {} transcript:\n\n{}",
+            session.purpose.as_str(),
+            chunks.concat()
+        )
+    };
+    if !chapters.is_empty() {
+        question.push_str(
+            "\n\nThe meeting was split into these chapters; include a Chapters section listing each with its start time and a one-line recap:\n",
+        );
+        for c in chapters {
+            question.push_str(&format!("- [{}] {}\n", c.start_time, c.title));
+        }
+    }
+    if !highlights.is_empty() {
+        question.push_str(
+            "\n\nThe user highlighted these moments; call out what was said around each in a Highlights section:\n",
+        );
+        for h in highlights {
+            question.push_str(&format!(
+                "- [{}] {}\n",
+                h.timestamp,
+                h.note.as_deref().unwrap_or("(no note)")
+            ));
+        }
+    }
+
+    let summary = crate::redaction::for_session(
+        &session.id,
+        complete(cfg, &system_prompt(template), &question),
+    )
+    .await?;
+    on_progress(total, total);
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn get_summary_templates(
+    storage: tauri::State<'_, StorageActor>,
+) -> CommandResult<Vec<SummaryTemplate>> {
+    Ok(storage.call(|s| s.get_summary_templates()).await?)
+}
+
+/// Create (empty id) or update a summary template
+#[tauri::command]
+pub async fn save_summary_template(
+    storage: tauri::State<'_, StorageActor>,
+    mut template: SummaryTemplate,
+) -> CommandResult<SummaryTemplate> {
+    if template.name.trim().is_empty() {
+        return Err(VenkyError::Other("Template name is required".to_string()));
+    }
+    template.sections.retain(|s| !s.trim().is_empty());
+    if template.sections.is_empty() {
+        return Err(VenkyError::Other(
+            "A summary template needs at least one section".to_string(),
+        ));
+    }
+    if template.id.is_empty() {
+        template.id = uuid::Uuid::new_v4().to_string();
+    }
+
+    let row = template.clone();
+    storage.call(move |s| s.save_summary_template(&row)).await?;
+    Ok(template)
+}
+
+#[tauri::command]
+pub async fn delete_summary_template(
+    storage: tauri::State<'_, StorageActor>,
+    id: String,
+) -> CommandResult<()> {
+    if id == DEFAULT_SUMMARY_TEMPLATE_ID {
+        return Err(VenkyError::Other(
+            "The standard summary template can't be deleted".to_string(),
+        ));
+    }
+    Ok(storage
+        .call(move |s| s.delete_summary_template(&id))
+        .await?)
+}
+
+/// Choose the summary template used for sessions of `purpose`; `None` goes back to the default
+#[tauri::command]
+pub async fn set_purpose_summary_template(
+    storage: tauri::State<'_, StorageActor>,
+    purpose: SessionPurpose,
+    template_id: Option<String>,
+) -> CommandResult<()> {
+    Ok(storage
+        .call(move |s| {
+            if let Some(ref id) = template_id {
+                if s.get_summary_template(id)?.is_none() {
+                    return Err(VenkyError::Other(format!(
+                        "Summary template {} not found",
+                        id
+                    )));
+                }
+            }
+            s.set_setting(&purpose_setting_key(purpose), template_id.as_deref())
+        })
+        .await?)
+}
+
+/// Summarize the live session or a saved one with a given (or the purpose's) template and store
+/// the result, emitting "summary-progress" along the way
+pub async fn summarize_session(
+    app: &AppHandle,
+    session_id: &str,
+    template_id: Option<&str>,
+) -> Result<String, VenkyError> {
+    let session_state = app.state::<SessionState>();
+    let storage = app.state::<StorageActor>();
+    let live = session_state.lock().sessions.get(session_id).cloned();
+
+    let (id, template_id) = (session_id.to_string(), template_id.map(str::to_string));
+    let (session, highlights, chapters, template) = storage
+        .call(move |s| {
+            let session = match live {
+                Some(session) => session,
+                None => s.get_session(&id)?,
+            };
+            let highlights = s.get_session_highlights(&id)?;
+            let chapters = s.get_session_chapters(&id)?;
+            let template = template_for(s, session.purpose, template_id.as_deref())?;
+            Ok((session, highlights, chapters, template))
+        })
+        .await?;
+
+    let cfg = app.state::<ConfigState>().lock().clone();
+    let summary = summarize(
+        &cfg,
+        &session,
+        &highlights,
+        &chapters,
+        &template,
+        |completed, total| {
+            let _ = app.emit(
+                "summary-progress",
+                SummaryProgress {
+                    session_id: session_id.to_string(),
+                    completed,
+                    total,
+                },
+            );
+        },
+    )
+    .await?;
+
+    {
+        let mut mgr = session_state.lock();
+        if let Some(live) = mgr.sessions.get_mut(session_id) {
+            live.summary = Some(summary.clone());
+        }
+    }
+    let (id, stored) = (session_id.to_string(), summary.clone());
+    storage
+        .call(move |s| s.set_session_summary(&id, &stored))
+        .await?;
+    crate::integrations::webhooks::dispatch(
+        app,
+        crate::integrations::webhooks::SUMMARY_READY,
+        serde_json::json!({ "session_id": session_id, "summary": &summary }),
+    );
+    crate::notifications::summary_ready(app, &session.title);
+    crate::session::vault::export_if_enabled(app, session_id);
+
+    Ok(summary)
+}
+
+/// Summarize the live session or a saved one with a given (or the purpose's) template and store the result
+#[tauri::command]
+pub async fn generate_summary_for_session(
+    app: AppHandle,
+    session_id: String,
+    template_id: Option<String>,
+) -> CommandResult<String> {
+    summarize_session(&app, &session_id, template_id.as_deref()).await
+}
+
+/// Summarize a saved session with its purpose's template, persisting the result.
+/// Emits "summary-progress" as chunks of a long transcript are processed.
+#[tauri::command]
+pub async fn generate_summary_by_id(app: AppHandle, session_id: String) -> CommandResult<String> {
+    summarize_session(&app, &session_id, None).await
+}
@@ -0,0 +1,77 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::AppConfig;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+/// Bumped whenever playback starts or stops, so an older playback notices and quits
+static PLAYBACK: AtomicU64 = AtomicU64::new(0);
+
+/// Emitted as "teleprompter-line", one per sentence of the accepted answer
+#[derive(Debug, Clone, Serialize)]
+pub struct TeleprompterLine {
+    pub suggestion_id: String,
+    pub index: usize,
+    pub total: usize,
+    pub text: String,
+    /// How long the line should stay up at the configured speaking speed
+    pub duration_ms: u64,
+}
+
+fn sentences(text: &str) -> Vec<String> {
+    text.split_inclusive(['.', '!', '?', '\n'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Emit `text` sentence by sentence at the configured words per minute, replacing any
+/// answer that is still playing
+pub fn play_if_enabled(app: &AppHandle, suggestion_id: &str, text: &str) {
+    let (enabled, wpm) = {
+        let config_state = app.state::<ConfigState>();
+        let cfg = config_state.lock();
+        (cfg.teleprompter, cfg.teleprompter_wpm.max(1))
+    };
+    if !enabled {
+        return;
+    }
+    let generation = PLAYBACK.fetch_add(1, Ordering::SeqCst) + 1;
+    let lines = sentences(text);
+    let total = lines.len();
+
+    let app = app.clone();
+    let suggestion_id = suggestion_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        for (index, text) in lines.into_iter().enumerate() {
+            if PLAYBACK.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            let words = text.split_whitespace().count().max(1) as u64;
+            let duration_ms = words * 60_000 / wpm as u64;
+            let _ = app.emit(
+                "teleprompter-line",
+                TeleprompterLine {
+                    suggestion_id: suggestion_id.clone(),
+                    index,
+                    total,
+                    text,
+                    duration_ms,
+                },
+            );
+            tokio::time::sleep(Duration::from_millis(duration_ms)).await;
+        }
+    });
+}
+
+/// Stop the answer currently scrolling in the overlay
+#[tauri::command]
+pub fn stop_teleprompter() {
+    PLAYBACK.fetch_add(1, Ordering::SeqCst);
+}
@@ -0,0 +1,199 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::manager::{Session, SessionManager, TranscriptEntry, TIMELINE_SPEAKER};
+use super::storage_actor::StorageActor;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// How often live sessions are checked for enough transcript to title them
+const TITLE_CHECK_INTERVAL_SECS: u64 = 60;
+/// Transcript span a live session needs before it is titled
+const MIN_TRANSCRIPT_SECS: i64 = 3 * 60;
+/// Only the start of very long transcripts is sent; the topic is settled by then
+const MAX_TRANSCRIPT_CHARS: usize = 16_000;
+
+const TITLE_PROMPT: &str = "You name meeting recordings. From the transcript, write a short, specific \
+    title (at most 8 words, no quotes, no date) and a one-sentence description of what the meeting is about. \
+    Respond with a JSON object of the form {\"title\": string, \"description\": string}.";
+
+#[derive(Debug, Deserialize)]
+struct GeneratedTitle {
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Emitted as "session-title-updated"
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionTitle {
+    pub session_id: String,
+    pub title: String,
+    pub description: Option<String>,
+}
+
+/// Title a live or saved session from its transcript, store it and emit "session-title-updated"
+pub async fn retitle(
+    app: &AppHandle,
+    cfg: &AppConfig,
+    session_id: &str,
+) -> Result<SessionTitle, VenkyError> {
+    let live = app
+        .state::<SessionState>()
+        .lock()
+        .sessions
+        .get(session_id)
+        .cloned();
+    let storage = app.state::<StorageActor>();
+    let session = match live {
+        Some(session) => session,
+        None => {
+            let id = session_id.to_string();
+            storage.call(move |s| s.get_session(&id)).await?
+        }
+    };
+
+    let mut transcript = String::new();
+    for e in session
+        .transcript
+        .iter()
+        .filter(|e| e.speaker != TIMELINE_SPEAKER)
+    {
+        if transcript.len() > MAX_TRANSCRIPT_CHARS {
+            break;
+        }
+        transcript.push_str(&format!("{}: {}\n", e.speaker, e.text));
+    }
+    if transcript.trim().is_empty() {
+        return Err(VenkyError::Other(
+            "No transcript to title the session from".to_string(),
+        ));
+    }
+
+    let question = format!(
+        "Current title: {}\nPurpose: {}\n\nTranscript:\n{}",
+        session.title,
+        session.purpose.as_str(),
+        transcript
+    );
+    let raw = crate::redaction::for_session(
+        session_id,
+        crate::ai::llm::generate_json(cfg, TITLE_PROMPT, &question),
+    )
+    .await?;
+    let generated: GeneratedTitle = serde_json::from_str(&raw)
+        .map_err(|e| VenkyError::Provider(format!("Model returned an invalid title: {}", e)))?;
+
+    let title = generated.title.trim().trim_matches('"').trim().to_string();
+    if title.is_empty() {
+        return Err(VenkyError::Provider(
+            "Model returned an empty title".to_string(),
+        ));
+    }
+    let description = generated
+        .description
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+
+    let (id, stored_title, stored_description) =
+        (session_id.to_string(), title.clone(), description.clone());
+    storage
+        .call(move |s| s.set_session_title(&id, &stored_title, stored_description.as_deref()))
+        .await?;
+    if let Some(live) = app
+        .state::<SessionState>()
+        .lock()
+        .sessions
+        .get_mut(session_id)
+    {
+        live.title = title.clone();
+        live.description = description.clone();
+    }
+
+    let updated = SessionTitle {
+        session_id: session_id.to_string(),
+        title,
+        description,
+    };
+    let _ = app.emit("session-title-updated", &updated);
+    Ok(updated)
+}
+
+/// After a session ends, title it from the full transcript in the background
+pub fn retitle_if_enabled(app: &AppHandle, session_id: &str) {
+    let cfg = app.state::<ConfigState>().lock().clone();
+    if !cfg.auto_title_sessions {
+        return;
+    }
+
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = retitle(&app, &cfg, &session_id).await {
+            log::warn!("Titling session {} failed: {}", session_id, e);
+        }
+    });
+}
+
+/// Seconds between the first and last transcript lines
+fn transcript_span_secs(session: &Session) -> i64 {
+    let parse = |e: &TranscriptEntry| chrono::DateTime::parse_from_rfc3339(&e.timestamp).ok();
+    match (
+        session.transcript.first().and_then(parse),
+        session.transcript.last().and_then(parse),
+    ) {
+        (Some(first), Some(last)) => (last - first).num_seconds(),
+        _ => 0,
+    }
+}
+
+/// Title live sessions once a few minutes of transcript exist
+pub async fn title_loop(app: AppHandle) {
+    let mut interval = tokio::time::interval(Duration::from_secs(TITLE_CHECK_INTERVAL_SECS));
+    // Sessions already tried, so a failing provider isn't asked again every minute
+    let mut attempted: HashSet<String> = HashSet::new();
+
+    loop {
+        interval.tick().await;
+
+        let cfg = app.state::<ConfigState>().lock().clone();
+        if !cfg.auto_title_sessions {
+            continue;
+        }
+
+        let due = app
+            .state::<SessionState>()
+            .lock()
+            .sessions
+            .values()
+            .filter(|s| s.description.is_none() && !attempted.contains(&s.id))
+            .filter(|s| transcript_span_secs(s) >= MIN_TRANSCRIPT_SECS)
+            .map(|s| s.id.clone())
+            .collect::<Vec<_>>();
+
+        for id in due {
+            attempted.insert(id.clone());
+            match retitle(&app, &cfg, &id).await {
+                Ok(t) => log::info!("Titled session {}: {}", id, t.title),
+                Err(e) => log::warn!("Titling session {} failed: {}", id, e),
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn refresh_session_title(
+    app: AppHandle,
+    config: tauri::State<'_, ConfigState>,
+    session_id: String,
+) -> CommandResult<SessionTitle> {
+    let cfg = config.lock().clone();
+    retitle(&app, &cfg, &session_id).await
+}
@@ -0,0 +1,171 @@
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::storage_actor::StorageActor;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+/// Bumped whenever typing starts or is stopped, so an older type-out notices and quits
+static TYPING: AtomicU64 = AtomicU64::new(0);
+
+/// Time to click into the field the answer should go in before the first key
+const START_DELAY_MS: u64 = 2000;
+/// Characters per word when converting words per minute to a key interval
+const CHARS_PER_WORD: u64 = 5;
+
+/// Emitted as "type-out-progress" as the answer is typed
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeOutProgress {
+    pub suggestion_id: String,
+    pub typed: usize,
+    pub total: usize,
+    /// "typing", "done", "stopped" or "error"
+    pub state: &'static str,
+    pub error: Option<String>,
+}
+
+/// Small xorshift so key intervals vary like a person typing
+struct Jitter(u64);
+
+impl Jitter {
+    fn new() -> Self {
+        let seed = chrono::Utc::now().timestamp_subsec_nanos() as u64;
+        Self(seed | 1)
+    }
+
+    /// A factor between 0.6 and 1.4
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        0.6 + (self.0 % 1000) as f64 / 1250.0
+    }
+}
+
+/// How long to wait after typing `c`; sentence ends and line breaks take longer
+fn pause_after(c: char, base_ms: u64, jitter: &mut Jitter) -> Duration {
+    let factor = match c {
+        '.' | '!' | '?' | '\n' => 6.0,
+        ',' | ';' | ':' => 3.0,
+        ' ' => 1.5,
+        _ => 1.0,
+    };
+    Duration::from_millis((base_ms as f64 * factor * jitter.next()) as u64)
+}
+
+/// Type `text` into the focused window, stopping early once `generation` is superseded.
+/// Returns how many characters were typed.
+fn type_text(
+    app: &AppHandle,
+    suggestion_id: &str,
+    text: &str,
+    wpm: u32,
+    generation: u64,
+) -> Result<usize, VenkyError> {
+    let mut enigo = Enigo::new(&Settings::default())
+        .map_err(|e| VenkyError::Other(format!("Keyboard input isn't available: {}", e)))?;
+    let chars: Vec<char> = text.chars().collect();
+    let total = chars.len();
+    let base_ms = 60_000 / (wpm.max(1) as u64 * CHARS_PER_WORD);
+    let mut jitter = Jitter::new();
+
+    std::thread::sleep(Duration::from_millis(START_DELAY_MS));
+    for (i, c) in chars.iter().enumerate() {
+        if TYPING.load(Ordering::SeqCst) != generation {
+            return Ok(i);
+        }
+        let typed = if *c == '\n' {
+            // Shift+Enter is a line break in chat boxes, where Enter alone would send
+            enigo
+                .key(Key::Shift, Direction::Press)
+                .and_then(|_| enigo.key(Key::Return, Direction::Click))
+                .and_then(|_| enigo.key(Key::Shift, Direction::Release))
+        } else {
+            enigo.text(&c.to_string())
+        };
+        typed.map_err(|e| VenkyError::Other(format!("Failed to type: {}", e)))?;
+
+        if i % 20 == 0 {
+            let _ = app.emit(
+                "type-out-progress",
+                TypeOutProgress {
+                    suggestion_id: suggestion_id.to_string(),
+                    typed: i + 1,
+                    total,
+                    state: "typing",
+                    error: None,
+                },
+            );
+        }
+        std::thread::sleep(pause_after(*c, base_ms, &mut jitter));
+    }
+    Ok(total)
+}
+
+/// Stop typing right away; bound to the stop-typing hotkey
+pub fn stop() {
+    TYPING.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Type an accepted suggestion into the focused window at `wpm` words per minute (default
+/// from settings), after a short delay to click into the right field. Replaces any
+/// type-out still running.
+#[tauri::command]
+pub async fn type_out_answer(
+    app: AppHandle,
+    storage: tauri::State<'_, StorageActor>,
+    id: String,
+    wpm: Option<u32>,
+) -> CommandResult<()> {
+    let wpm = wpm.unwrap_or_else(|| app.state::<ConfigState>().lock().type_out_wpm);
+    if !(10..=300).contains(&wpm) {
+        return Err(VenkyError::Other(
+            "Typing speed must be between 10 and 300 words per minute".to_string(),
+        ));
+    }
+    let suggestion_id = id.clone();
+    let text = storage
+        .call(move |s| {
+            s.set_suggestion_accepted(&suggestion_id)?;
+            s.get_suggestion_text(&suggestion_id)
+        })
+        .await?;
+    let text = text.replace("**", "").replace('`', "").trim().to_string();
+    let generation = TYPING.fetch_add(1, Ordering::SeqCst) + 1;
+
+    std::thread::spawn(move || {
+        let total = text.chars().count();
+        let (typed, state, error) = match type_text(&app, &id, &text, wpm, generation) {
+            Ok(typed) if typed == total => (typed, "done", None),
+            Ok(typed) => (typed, "stopped", None),
+            Err(e) => {
+                log::warn!("Type-out failed: {}", e);
+                (0, "error", Some(e.to_string()))
+            }
+        };
+        let _ = app.emit(
+            "type-out-progress",
+            TypeOutProgress {
+                suggestion_id: id,
+                typed,
+                total,
+                state,
+                error,
+            },
+        );
+    });
+    Ok(())
+}
+
+/// Stop the answer being typed out
+#[tauri::command]
+pub fn stop_typing() {
+    stop();
+}
@@ -0,0 +1,501 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use super::export::{gather, offset, SessionExport};
+use super::manager::TIMELINE_SPEAKER;
+use super::storage::{ActionItem, Participant, SessionSummary, Storage};
+use super::storage_actor::StorageActor;
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+/// Folder inside an Obsidian vault that exported notes go into
+const OBSIDIAN_DIR: &str = "VenkyAI";
+const ACTION_ITEMS_NOTE: &str = "VenkyAI Action Items";
+const TAG: &str = "venkyai";
+/// Earlier meetings with the same people linked from a session's note
+const EARLIER_MEETINGS: usize = 5;
+
+/// Which notes app the vault belongs to; Logseq wants page properties and outline blocks
+/// instead of YAML frontmatter and plain Markdown
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VaultFlavor {
+    Obsidian,
+    Logseq,
+}
+
+/// `text` without the characters that break file names or wiki-links
+fn note_name(text: &str) -> String {
+    let cleaned: String = text
+        .chars()
+        .map(|c| {
+            if c.is_control() || "\\/:*?\"<>|#^[]".contains(c) {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn link(name: &str) -> String {
+    format!("[[{}]]", name)
+}
+
+fn local_date(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .unwrap_or_else(|_| timestamp.get(..10).unwrap_or(timestamp).to_string())
+}
+
+/// Lowercased names of the notes under `dir`, skipping hidden folders such as `.obsidian`
+fn existing_notes(dir: &Path, notes: &mut HashSet<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            if !entry.file_name().to_string_lossy().starts_with('.') {
+                existing_notes(&path, notes);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            if let Some(stem) = path.file_stem() {
+                notes.insert(stem.to_string_lossy().to_lowercase());
+            }
+        }
+    }
+}
+
+fn write(path: &Path, content: &str) -> Result<(), VenkyError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            VenkyError::Storage(format!("Failed to create {}: {}", dir.display(), e))
+        })?;
+    }
+    std::fs::write(path, content)
+        .map_err(|e| VenkyError::Storage(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// A titled part of a session note; `list` sections are bullet lists, the rest paragraphs
+struct Section {
+    heading: &'static str,
+    lines: Vec<String>,
+    list: bool,
+}
+
+struct Vault {
+    flavor: VaultFlavor,
+    meetings_dir: PathBuf,
+    people_dir: PathBuf,
+    index_dir: PathBuf,
+    /// Note name of every session, by session id
+    names: HashMap<String, String>,
+    /// Notes already in the vault, so people the user has notes on aren't duplicated
+    notes: HashSet<String>,
+}
+
+impl Vault {
+    fn open(
+        root: &Path,
+        flavor: VaultFlavor,
+        sessions: &[SessionSummary],
+    ) -> Result<Self, VenkyError> {
+        if !root.is_dir() {
+            return Err(VenkyError::Other(format!(
+                "Vault folder {} doesn't exist",
+                root.display()
+            )));
+        }
+        let (meetings_dir, people_dir, index_dir) = match flavor {
+            VaultFlavor::Obsidian => {
+                let base = root.join(OBSIDIAN_DIR);
+                (base.join("Meetings"), base.join("People"), base)
+            }
+            VaultFlavor::Logseq => {
+                let pages = root.join("pages");
+                (pages.clone(), pages.clone(), pages)
+            }
+        };
+
+        // Oldest first, so a session keeps its name when a later one gets the same title
+        let mut ordered: Vec<&SessionSummary> = sessions.iter().collect();
+        ordered.sort_by(|a, b| (&a.start_time, &a.id).cmp(&(&b.start_time, &b.id)));
+        let mut names = HashMap::new();
+        let mut taken = HashSet::new();
+        for session in ordered {
+            let mut name = note_name(&format!(
+                "{} {}",
+                local_date(&session.start_time),
+                session.title
+            ));
+            if !taken.insert(name.to_lowercase()) {
+                name = format!("{} ({})", name, session.id.get(..8).unwrap_or(&session.id));
+                taken.insert(name.to_lowercase());
+            }
+            names.insert(session.id.clone(), name);
+        }
+
+        let mut notes = HashSet::new();
+        existing_notes(root, &mut notes);
+        Ok(Self {
+            flavor,
+            meetings_dir,
+            people_dir,
+            index_dir,
+            names,
+            notes,
+        })
+    }
+
+    fn task(&self, item: &ActionItem) -> String {
+        let done = item.status == "done";
+        let mut line = match (self.flavor, done) {
+            (VaultFlavor::Obsidian, false) => format!("[ ] {}", item.description),
+            (VaultFlavor::Obsidian, true) => format!("[x] {}", item.description),
+            (VaultFlavor::Logseq, false) => format!("TODO {}", item.description),
+            (VaultFlavor::Logseq, true) => format!("DONE {}", item.description),
+        };
+        if let Some(owner) = item.owner.as_deref().map(note_name) {
+            if !owner.is_empty() {
+                line.push_str(&format!(" — {}", link(&owner)));
+            }
+        }
+        if let Some(ref due) = item.due_date {
+            line.push_str(&format!(" (due {})", due));
+        }
+        line
+    }
+
+    /// Summarized sessions before this one that any of its participants were in, newest first
+    fn earlier_meetings(
+        &self,
+        storage: &Storage,
+        export: &SessionExport,
+    ) -> Result<Vec<String>, VenkyError> {
+        let mut earlier = Vec::new();
+        for p in &export.participants {
+            if p.email.is_none() && p.crm_contact_id.is_none() {
+                continue;
+            }
+            earlier.extend(
+                storage
+                    .past_meetings_with(
+                        p.email.as_deref(),
+                        p.crm_contact_id.as_deref(),
+                        &export.session.id,
+                        EARLIER_MEETINGS,
+                    )?
+                    .into_iter()
+                    .filter(|m| m.start_time < export.session.start_time),
+            );
+        }
+        earlier.sort_by(|a, b| (&b.start_time, &b.session_id).cmp(&(&a.start_time, &a.session_id)));
+        earlier.dedup_by(|a, b| a.session_id == b.session_id);
+        Ok(earlier
+            .iter()
+            .filter_map(|m| self.names.get(&m.session_id))
+            .take(EARLIER_MEETINGS)
+            .map(|name| link(name))
+            .collect())
+    }
+
+    fn session_note(&self, export: &SessionExport, earlier: Vec<String>) -> String {
+        let session = &export.session;
+        let start = &session.start_time;
+        let participants: Vec<String> = export
+            .participants
+            .iter()
+            .map(|p| note_name(&p.name))
+            .filter(|name| !name.is_empty())
+            .map(|name| link(&name))
+            .collect();
+        let tags = [TAG, session.purpose.as_str()];
+
+        let sections = [
+            Section {
+                heading: "Summary",
+                lines: session
+                    .summary
+                    .iter()
+                    .map(|s| s.trim().to_string())
+                    .collect(),
+                list: false,
+            },
+            Section {
+                heading: "Action Items",
+                lines: export.action_items.iter().map(|i| self.task(i)).collect(),
+                list: true,
+            },
+            Section {
+                heading: "Highlights",
+                lines: export
+                    .highlights
+                    .iter()
+                    .map(|h| {
+                        format!(
+                            "{} {}",
+                            offset(start, &h.timestamp),
+                            h.note.as_deref().unwrap_or("(highlight)")
+                        )
+                    })
+                    .collect(),
+                list: true,
+            },
+            Section {
+                heading: "Chapters",
+                lines: export
+                    .chapters
+                    .iter()
+                    .map(|c| format!("{} {}", offset(start, &c.start_time), c.title))
+                    .collect(),
+                list: true,
+            },
+            Section {
+                heading: "Earlier Meetings",
+                lines: earlier,
+                list: true,
+            },
+            Section {
+                heading: "Transcript",
+                lines: session
+                    .transcript
+                    .iter()
+                    .map(|entry| {
+                        let at = offset(start, &entry.timestamp);
+                        if entry.speaker == TIMELINE_SPEAKER {
+                            format!("_{} — {}_", at, entry.text)
+                        } else {
+                            format!("**{} {}:** {}", at, entry.speaker, entry.text)
+                        }
+                    })
+                    .collect(),
+                list: false,
+            },
+        ];
+
+        let mut note = String::new();
+        match self.flavor {
+            VaultFlavor::Obsidian => {
+                note.push_str(&format!("---\ndate: {}\n", local_date(start)));
+                note.push_str(&format!("title: \"{}\"\n", session.title.replace('"', "'")));
+                if !participants.is_empty() {
+                    note.push_str("participants:\n");
+                    for p in &participants {
+                        note.push_str(&format!("  - \"{}\"\n", p));
+                    }
+                }
+                note.push_str("tags:\n");
+                for tag in tags {
+                    note.push_str(&format!("  - {}\n", tag));
+                }
+                note.push_str(&format!("venkyai_id: {}\n---\n\n", session.id));
+                note.push_str(&format!("# {}\n\n", session.title));
+                if let Some(ref description) = session.description {
+                    note.push_str(&format!("{}\n\n", description));
+                }
+                for section in sections.iter().filter(|s| !s.lines.is_empty()) {
+                    note.push_str(&format!("## {}\n\n", section.heading));
+                    for line in &section.lines {
+                        if section.list {
+                            note.push_str(&format!("- {}\n", line));
+                        } else {
+                            note.push_str(&format!("{}\n\n", line));
+                        }
+                    }
+                    if section.list {
+                        note.push('\n');
+                    }
+                }
+            }
+            VaultFlavor::Logseq => {
+                note.push_str(&format!("date:: {}\n", local_date(start)));
+                if !participants.is_empty() {
+                    note.push_str(&format!("participants:: {}\n", participants.join(", ")));
+                }
+                note.push_str(&format!("tags:: {}\n", tags.join(", ")));
+                note.push_str(&format!("venkyai-id:: {}\n\n", session.id));
+                if let Some(ref description) = session.description {
+                    note.push_str(&format!("- {}\n", description));
+                }
+                for section in sections.iter().filter(|s| !s.lines.is_empty()) {
+                    note.push_str(&format!("- ## {}\n", section.heading));
+                    for line in &section.lines {
+                        // Continuation lines of a multi-line block are indented past its bullet
+                        note.push_str(&format!("\t- {}\n", line.replace('\n', "\n\t  ")));
+                    }
+                }
+            }
+        }
+        note
+    }
+
+    /// A note for a person, unless the vault already has one by that name
+    fn write_person(
+        &mut self,
+        name: &str,
+        participant: Option<&Participant>,
+    ) -> Result<(), VenkyError> {
+        let name = note_name(name);
+        if name.is_empty() || !self.notes.insert(name.to_lowercase()) {
+            return Ok(());
+        }
+        let mut properties = Vec::new();
+        if let Some(p) = participant {
+            if let Some(ref email) = p.email {
+                properties.push(("email", email.clone()));
+            }
+            if let Some(ref role) = p.role {
+                properties.push(("role", role.clone()));
+            }
+        }
+        properties.push(("tags", "person".to_string()));
+        let note = match self.flavor {
+            VaultFlavor::Obsidian => {
+                let mut note = "---\n".to_string();
+                for (key, value) in &properties {
+                    note.push_str(&format!("{}: \"{}\"\n", key, value.replace('"', "'")));
+                }
+                note.push_str(&format!("---\n\n# {}\n", name));
+                note
+            }
+            VaultFlavor::Logseq => properties
+                .iter()
+                .map(|(key, value)| format!("{}:: {}\n", key, value))
+                .collect(),
+        };
+        write(&self.people_dir.join(format!("{}.md", name)), &note)
+    }
+
+    fn write_session(&mut self, storage: &Storage, session_id: &str) -> Result<(), VenkyError> {
+        let export = gather(storage, session_id)?;
+        let name = self
+            .names
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| VenkyError::Other(format!("Session not found: {}", session_id)))?;
+        let earlier = self.earlier_meetings(storage, &export)?;
+        write(
+            &self.meetings_dir.join(format!("{}.md", name)),
+            &self.session_note(&export, earlier),
+        )?;
+        self.notes.insert(name.to_lowercase());
+
+        for p in &export.participants {
+            self.write_person(&p.name, Some(p))?;
+        }
+        for owner in export
+            .action_items
+            .iter()
+            .filter_map(|i| i.owner.as_deref())
+        {
+            self.write_person(owner, None)?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the note listing every open action item under the session it came from
+    fn write_action_index(&self, storage: &Storage) -> Result<(), VenkyError> {
+        let mut by_session: Vec<(&String, Vec<ActionItem>)> = Vec::new();
+        for item in storage.list_action_items(None, Some("open"))? {
+            let Some(name) = self.names.get(&item.session_id) else {
+                continue;
+            };
+            match by_session.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, items)) => items.push(item),
+                None => by_session.push((name, vec![item])),
+            }
+        }
+
+        let mut note = match self.flavor {
+            VaultFlavor::Obsidian => {
+                format!("---\ntags:\n  - {}\n---\n\n# Open Action Items\n", TAG)
+            }
+            VaultFlavor::Logseq => format!("tags:: {}\n\n", TAG),
+        };
+        for (name, items) in &by_session {
+            match self.flavor {
+                VaultFlavor::Obsidian => {
+                    note.push_str(&format!("\n## {}\n\n", link(name)));
+                    for item in items {
+                        note.push_str(&format!("- {}\n", self.task(item)));
+                    }
+                }
+                VaultFlavor::Logseq => {
+                    note.push_str(&format!("- {}\n", link(name)));
+                    for item in items {
+                        note.push_str(&format!("\t- {}\n", self.task(item)));
+                    }
+                }
+            }
+        }
+        write(
+            &self.index_dir.join(format!("{}.md", ACTION_ITEMS_NOTE)),
+            &note,
+        )
+    }
+}
+
+/// Write the note for `session_id`, or for every session, into the configured vault along
+/// with notes for the people involved and the open action item index. Returns how many
+/// session notes were written.
+pub fn export(
+    storage: &Storage,
+    cfg: &AppConfig,
+    session_id: Option<&str>,
+) -> Result<usize, VenkyError> {
+    let root = cfg.vault_path.trim();
+    if root.is_empty() {
+        return Err(VenkyError::Other("No vault folder is set".to_string()));
+    }
+    let sessions = storage.get_all_sessions()?;
+    let mut vault = Vault::open(Path::new(root), cfg.vault_flavor, &sessions)?;
+    let ids: Vec<&str> = match session_id {
+        Some(id) => vec![id],
+        None => sessions.iter().map(|s| s.id.as_str()).collect(),
+    };
+    for id in &ids {
+        vault.write_session(storage, id)?;
+    }
+    vault.write_action_index(storage)?;
+    Ok(ids.len())
+}
+
+/// Update the session's note in the vault if auto export is on
+pub fn export_if_enabled(app: &AppHandle, session_id: &str) {
+    let cfg = app.state::<ConfigState>().lock().clone();
+    if !cfg.vault_auto_export {
+        return;
+    }
+    let session_id = session_id.to_string();
+    app.state::<StorageActor>().send(move |s| {
+        if let Err(e) = export(s, &cfg, Some(&session_id)) {
+            log::warn!("Vault export failed: {}", e);
+        }
+    });
+}
+
+/// Export one session (or all of them when `session_id` is omitted) as linked notes into
+/// the Obsidian vault or Logseq graph set in the config
+#[tauri::command]
+pub async fn export_to_vault(
+    config: tauri::State<'_, ConfigState>,
+    storage: tauri::State<'_, StorageActor>,
+    session_id: Option<String>,
+) -> CommandResult<usize> {
+    let cfg = config.lock().clone();
+    Ok(storage
+        .call(move |s| export(s, &cfg, session_id.as_deref()))
+        .await?)
+}
@@ -0,0 +1,274 @@
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use crate::config::{AppConfig, ConfigDiagnostic, DiagnosticKind, CONFIG_VERSION};
+use crate::error::{CommandResult, VenkyError};
+use crate::session::storage::{KeywordWatch, PromptTemplate, SummaryTemplate};
+use crate::session::storage_actor::StorageActor;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+const FORMAT: &str = "venkyai-settings";
+const BUNDLE_VERSION: u32 = 1;
+pub const KDF_ROUNDS: u32 = 600_000;
+/// Rounds a file may ask for: fewer weakens the key, more would stall opening it
+const KDF_ROUNDS_ALLOWED: std::ops::RangeInclusive<u32> = 100_000..=6_000_000;
+pub const MIN_PASSPHRASE_CHARS: usize = 8;
+/// Settings that name this machine's files and programs or decide where its traffic goes,
+/// what it trusts and what it serves; they are never taken from a bundle
+const MACHINE_SETTINGS: [&str; 6] = [
+    "piper_path",
+    "http_proxy",
+    "http_ca_cert_path",
+    "vault_path",
+    "api_server",
+    "api_server_port",
+];
+
+/// What a bundle carries. Integration credentials (CRM, calendar, Zoom) stay in this
+/// machine's keychain and are never exported.
+#[derive(Debug, Serialize, Deserialize)]
+struct Contents {
+    /// Kept as JSON so settings from other versions can be checked one by one on import
+    config: serde_json::Value,
+    /// False when the API keys in `config` were blanked on export
+    includes_secrets: bool,
+    prompt_templates: Vec<PromptTemplate>,
+    summary_templates: Vec<SummaryTemplate>,
+    /// Keyword and phrase detection rules
+    keyword_watches: Vec<KeywordWatch>,
+    exported_at: String,
+}
+
+/// The file on disk: `Contents` as JSON, encrypted with a key derived from the passphrase
+#[derive(Debug, Serialize, Deserialize)]
+struct Bundle {
+    format: String,
+    version: u32,
+    kdf_rounds: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSummary {
+    pub prompt_templates: usize,
+    pub summary_templates: usize,
+    pub keyword_watches: usize,
+    /// Whether the bundle's API keys replaced the ones on this machine
+    pub secrets_imported: bool,
+    /// Settings in the bundle that were unknown or invalid here and were skipped
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+/// Reject a round count read from a file before deriving a key with it
+pub fn check_kdf_rounds(rounds: u32) -> Result<(), VenkyError> {
+    if KDF_ROUNDS_ALLOWED.contains(&rounds) {
+        Ok(())
+    } else {
+        Err(VenkyError::Other(format!(
+            "Unsupported key derivation rounds: {}",
+            rounds
+        )))
+    }
+}
+
+pub fn derive_key(passphrase: &str, salt: &[u8], rounds: u32) -> Key {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, rounds, &mut key);
+    key.into()
+}
+
+fn encrypt(contents: &Contents, passphrase: &str) -> Result<Bundle, VenkyError> {
+    let plaintext = serde_json::to_vec(contents)
+        .map_err(|e| VenkyError::Other(format!("Serialize error: {}", e)))?;
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt, KDF_ROUNDS));
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| VenkyError::Other("Failed to encrypt settings".to_string()))?;
+
+    let b64 = base64::engine::general_purpose::STANDARD;
+    Ok(Bundle {
+        format: FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        kdf_rounds: KDF_ROUNDS,
+        salt: b64.encode(salt),
+        nonce: b64.encode(nonce),
+        ciphertext: b64.encode(ciphertext),
+    })
+}
+
+fn decrypt(bundle: &Bundle, passphrase: &str) -> Result<Contents, VenkyError> {
+    if bundle.format != FORMAT {
+        return Err(VenkyError::Other(
+            "Not a VenkyAI settings bundle".to_string(),
+        ));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(VenkyError::Other(
+            "This settings bundle is from a newer version of VenkyAI".to_string(),
+        ));
+    }
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let decode = |field: &str| {
+        b64.decode(field)
+            .map_err(|e| VenkyError::Other(format!("Corrupt settings bundle: {}", e)))
+    };
+    let salt = decode(&bundle.salt)?;
+    let nonce = decode(&bundle.nonce)?;
+    if nonce.len() != 12 {
+        return Err(VenkyError::Other(
+            "Corrupt settings bundle: bad nonce".to_string(),
+        ));
+    }
+    let ciphertext = decode(&bundle.ciphertext)?;
+    check_kdf_rounds(bundle.kdf_rounds)?;
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt, bundle.kdf_rounds));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| {
+            VenkyError::Other("Wrong passphrase, or the bundle was modified".to_string())
+        })?;
+    serde_json::from_slice(&plaintext)
+        .map_err(|e| VenkyError::Other(format!("Corrupt settings bundle: {}", e)))
+}
+
+/// Write config, prompt and summary templates and detection rules to an encrypted file.
+/// API keys and proxy credentials are only included when `include_secrets` is set.
+#[tauri::command]
+pub async fn export_settings(
+    config: tauri::State<'_, ConfigState>,
+    storage: tauri::State<'_, StorageActor>,
+    path: String,
+    passphrase: String,
+    include_secrets: bool,
+) -> CommandResult<()> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(VenkyError::Other(format!(
+            "The passphrase must be at least {} characters",
+            MIN_PASSPHRASE_CHARS
+        )));
+    }
+
+    let cfg = if include_secrets {
+        config.lock().clone()
+    } else {
+        config.lock().without_secrets()
+    };
+    let config = serde_json::to_value(&cfg)
+        .map_err(|e| VenkyError::Other(format!("Serialize error: {}", e)))?;
+    let contents = storage
+        .call(move |s| {
+            Ok(Contents {
+                config,
+                includes_secrets: include_secrets,
+                prompt_templates: s.get_prompt_templates()?,
+                summary_templates: s.get_summary_templates()?,
+                keyword_watches: s.get_keyword_watches()?,
+                exported_at: chrono::Utc::now().to_rfc3339(),
+            })
+        })
+        .await?;
+
+    // Deriving the key takes a while, so it stays off the async runtime
+    let bundle = tokio::task::spawn_blocking(move || encrypt(&contents, &passphrase))
+        .await
+        .map_err(|e| VenkyError::Other(format!("Key derivation failed: {}", e)))??;
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| VenkyError::Other(format!("Serialize error: {}", e)))?;
+    Ok(std::fs::write(&path, json)
+        .map_err(|e| VenkyError::Storage(format!("Failed to write {}: {}", path, e)))?)
+}
+
+/// Apply a bundle from `export_settings`. Templates and rules are added, replacing those
+/// with the same id; the bundle's settings replace the current ones, keeping this machine's
+/// API keys when the bundle has none, its machine-specific settings and any setting that
+/// can't be used here.
+#[tauri::command]
+pub async fn import_settings(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+) -> CommandResult<ImportSummary> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| VenkyError::Storage(format!("Failed to read {}: {}", path, e)))?;
+    let bundle: Bundle = serde_json::from_str(&content)
+        .map_err(|_| VenkyError::Other("Not a VenkyAI settings bundle".to_string()))?;
+    let contents = tokio::task::spawn_blocking(move || decrypt(&bundle, &passphrase))
+        .await
+        .map_err(|e| VenkyError::Other(format!("Key derivation failed: {}", e)))??;
+
+    let serde_json::Value::Object(mut fields) = contents.config.clone() else {
+        return Err(VenkyError::Other(
+            "Corrupt settings bundle: settings must be a JSON object".to_string(),
+        ));
+    };
+    // Blanked on export, so this machine's key stays
+    if !contents.includes_secrets {
+        fields.remove("openai_api_key");
+    }
+    let mut skipped = Vec::new();
+    for key in MACHINE_SETTINGS {
+        if fields.remove(key).is_some() {
+            skipped.push(ConfigDiagnostic::new(
+                DiagnosticKind::MachineSpecific,
+                Some(key),
+                format!("{} is set on each machine and was not imported", key),
+            ));
+        }
+    }
+    // Checked the way config.json is, one setting at a time: settings this version doesn't
+    // know or can't use keep their current value and are reported
+    let config_state = app.state::<ConfigState>();
+    let (mut config, mut diagnostics) = config_state.lock().merge_json(fields);
+    diagnostics.extend(skipped);
+    config.config_version = CONFIG_VERSION;
+
+    let (prompt_templates, summary_templates, keyword_watches) = (
+        contents.prompt_templates.clone(),
+        contents.summary_templates.clone(),
+        contents.keyword_watches.clone(),
+    );
+    app.state::<StorageActor>()
+        .call(move |s| {
+            for template in &prompt_templates {
+                s.put_prompt_template(template)?;
+            }
+            for template in &summary_templates {
+                s.save_summary_template(template)?;
+            }
+            for watch in &keyword_watches {
+                s.save_keyword_watch(watch)?;
+            }
+            Ok(())
+        })
+        .await?;
+
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))?;
+    config.save(&app_data)?;
+    crate::config::apply(&app, &config_state, config);
+
+    log::info!("Imported settings exported at {}", contents.exported_at);
+    Ok(ImportSummary {
+        prompt_templates: contents.prompt_templates.len(),
+        summary_templates: contents.summary_templates.len(),
+        keyword_watches: contents.keyword_watches.len(),
+        secrets_imported: contents.includes_secrets,
+        diagnostics,
+    })
+}
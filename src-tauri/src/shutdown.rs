@@ -0,0 +1,76 @@
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::ai::live_engine::LiveEngineState;
+use crate::api_server::ApiServerState;
+use crate::capture::{recording, screen_recording};
+use crate::session::manager::{Session, SessionManager};
+use crate::session::storage_actor::StorageActor;
+
+type SessionState = Arc<Mutex<SessionManager>>;
+
+/// Longest quitting waits for recordings to finish encoding
+const RECORDING_TIMEOUT: Duration = Duration::from_secs(10);
+
+static FINALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Wind down before the process exits, from the tray's Quit or an OS shutdown: stop the
+/// live loops and capture, keep the recordings, save sessions still running as interrupted
+/// and checkpoint the database. Those sessions wait to be resumed or ended at the next launch.
+pub fn finalize(app: &AppHandle) {
+    if FINALIZED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("Shutting down");
+
+    app.state::<LiveEngineState>().lock().stop();
+    app.state::<ApiServerState>().lock().stop();
+    crate::ai::tts::stop_speaking();
+    crate::session::type_out::stop();
+
+    let mut sessions: Vec<Session> = app
+        .state::<SessionState>()
+        .lock()
+        .sessions
+        .values()
+        .cloned()
+        .collect();
+    sessions.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+
+    // Audio capture is shared, so each session that used it keeps its own part of the recording
+    let mut finishing = Vec::new();
+    finishing.extend(recording::save_if_enabled(app, &sessions));
+    for session in &sessions {
+        finishing.extend(screen_recording::stop_and_save(app, &session.id));
+    }
+
+    let storage = app.state::<StorageActor>().inner().clone();
+    for session in sessions {
+        log::info!("Session {} interrupted by quit", session.id);
+        storage.send(move |s| {
+            if let Err(e) = s
+                .save_session(&session)
+                .and_then(|_| s.mark_session_interrupted(&session.id))
+            {
+                log::error!("Failed to save interrupted session {}: {}", session.id, e);
+            }
+        });
+    }
+
+    let deadline = Instant::now() + RECORDING_TIMEOUT;
+    while finishing.iter().any(|t| !t.is_finished()) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    if finishing.iter().any(|t| !t.is_finished()) {
+        log::warn!("Quitting before recordings finished saving");
+    }
+
+    // Queued behind every pending write, so the log is empty once it runs
+    if let Err(e) = tauri::async_runtime::block_on(storage.call(|s| s.checkpoint())) {
+        log::error!("{}", e);
+    }
+    log::info!("Shutdown complete");
+}
@@ -0,0 +1,109 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use regex::Regex;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::AppConfig;
+use crate::error::{CommandResult, VenkyError};
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+
+const SHARE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Titles of the toolbars and banners meeting apps show while the user's screen is shared
+static SHARE_INDICATOR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)(is sharing (your|a) (screen|window|tab)|you are (screen )?sharing|zoom share toolbar|sharing control bar|sharing toolbar|screen sharing meeting controls|webex share)",
+    )
+    .unwrap()
+});
+
+pub fn is_active(app: &AppHandle) -> bool {
+    app.state::<ConfigState>().lock().stealth_mode
+}
+
+/// Turn stealth mode on or off and save it; protection and notifications follow right away
+pub fn set(app: &AppHandle, enabled: bool) -> Result<(), VenkyError> {
+    let config_state = app.state::<ConfigState>();
+    let mut cfg = config_state.lock().clone();
+    if cfg.stealth_mode == enabled {
+        return Ok(());
+    }
+    cfg.stealth_mode = enabled;
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| VenkyError::Storage(format!("Failed to resolve app data dir: {}", e)))?;
+    cfg.save(&app_data)?;
+    crate::apply_content_protection(app, &cfg);
+    *config_state.lock() = cfg;
+    log::info!("Stealth mode {}", if enabled { "on" } else { "off" });
+    let _ = app.emit("stealth-mode", enabled);
+    Ok(())
+}
+
+/// Flip stealth mode; returns whether it is now on
+pub fn toggle(app: &AppHandle) -> Result<bool, VenkyError> {
+    let enabled = !is_active(app);
+    set(app, enabled)?;
+    Ok(enabled)
+}
+
+fn screen_share_visible() -> bool {
+    xcap::Window::all()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|w| w.title().ok())
+        .any(|title| SHARE_INDICATOR.is_match(&title))
+}
+
+/// In stealth mode with blanking on, hide the overlay while a screen share is detected and
+/// bring it back once the share ends (if it was showing before)
+pub async fn share_watch_loop(app: AppHandle) {
+    let mut was_sharing = false;
+    let mut hidden_by_share = false;
+    loop {
+        tokio::time::sleep(SHARE_CHECK_INTERVAL).await;
+        let watching = {
+            let cfg = app.state::<ConfigState>();
+            let cfg = cfg.lock();
+            cfg.stealth_mode && cfg.stealth_blank_on_share
+        };
+        let sharing = watching
+            && tokio::task::spawn_blocking(screen_share_visible)
+                .await
+                .unwrap_or(false);
+        if sharing == was_sharing {
+            continue;
+        }
+        was_sharing = sharing;
+        let _ = app.emit("screen-share-detected", sharing);
+
+        let Some(window) = app.get_webview_window("overlay") else {
+            continue;
+        };
+        if sharing && window.is_visible().unwrap_or(false) {
+            log::info!("Screen share detected; hiding the overlay");
+            let _ = window.hide();
+            let _ = app.emit("overlay-visibility", false);
+            hidden_by_share = true;
+        } else if !sharing && hidden_by_share {
+            let _ = window.show();
+            let _ = app.emit("overlay-visibility", true);
+            hidden_by_share = false;
+        }
+    }
+}
+
+#[tauri::command]
+pub fn set_stealth_mode(app: AppHandle, enabled: bool) -> CommandResult<()> {
+    set(&app, enabled)
+}
+
+/// Returns whether stealth mode is now on
+#[tauri::command]
+pub fn toggle_stealth_mode(app: AppHandle) -> CommandResult<bool> {
+    toggle(&app)
+}
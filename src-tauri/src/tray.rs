@@ -0,0 +1,284 @@
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::capture::audio::AudioEngineState;
+use crate::config::AppConfig;
+use crate::session::manager::SessionManager;
+use crate::session::storage_actor::StorageActor;
+use crate::windows::AppWindow;
+
+type ConfigState = Arc<Mutex<AppConfig>>;
+type SessionState = Arc<Mutex<SessionManager>>;
+
+const TRAY_ID: &str = "main";
+const RECENT_SESSIONS: usize = 5;
+/// Prefix of the menu ids of recent-session items, followed by the session id
+const OPEN_SESSION: &str = "open-session:";
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What the tray shows; the menu is only rebuilt when this changes
+#[derive(Debug, Clone, PartialEq)]
+struct TrayStatus {
+    recording: bool,
+    /// Focused session's title and minutes since it started
+    session: Option<(String, i64)>,
+    suggestions: bool,
+    /// (id, title) of recently ended sessions
+    recent: Vec<(String, String)>,
+    stealth: bool,
+}
+
+async fn status(app: &AppHandle) -> TrayStatus {
+    let recording = {
+        let engine = app.state::<AudioEngineState>();
+        let engine = engine.lock();
+        engine.is_mic_active() || engine.is_system_active()
+    };
+    let session = {
+        let state = app.state::<SessionState>();
+        let mgr = state.lock();
+        mgr.focused().map(|s| {
+            let minutes = chrono::DateTime::parse_from_rfc3339(&s.start_time)
+                .map(|start| (chrono::Utc::now() - start.with_timezone(&chrono::Utc)).num_minutes())
+                .unwrap_or(0);
+            (s.title.clone(), minutes.max(0))
+        })
+    };
+    let (suggestions, stealth) = {
+        let cfg = app.state::<ConfigState>();
+        let cfg = cfg.lock();
+        (cfg.live_suggestions, cfg.stealth_mode)
+    };
+    let recent = app
+        .state::<StorageActor>()
+        .call(|s| s.recent_sessions(RECENT_SESSIONS))
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| (s.id, s.title))
+        .collect();
+    TrayStatus {
+        recording,
+        session,
+        suggestions,
+        recent,
+        stealth,
+    }
+}
+
+fn elapsed(minutes: i64) -> String {
+    if minutes < 60 {
+        format!("{} min", minutes)
+    } else {
+        format!("{} h {:02} min", minutes / 60, minutes % 60)
+    }
+}
+
+fn status_label(status: &TrayStatus) -> String {
+    let recording = if status.recording {
+        "● Recording"
+    } else {
+        ""
+    };
+    match (&status.session, recording.is_empty()) {
+        (Some((title, minutes)), true) => format!("{} · {}", title, elapsed(*minutes)),
+        (Some((title, minutes)), false) => {
+            format!("{} — {} · {}", recording, title, elapsed(*minutes))
+        }
+        (None, false) => recording.to_string(),
+        (None, true) => "No active session".to_string(),
+    }
+}
+
+/// Hover text; in stealth mode it gives nothing away about what is being recorded
+fn tooltip(status: &TrayStatus) -> String {
+    if status.stealth {
+        "VenkyAI".to_string()
+    } else {
+        format!("VenkyAI — {}", status_label(status))
+    }
+}
+
+fn build_menu(app: &AppHandle, status: &TrayStatus) -> tauri::Result<Menu<Wry>> {
+    let status_item = MenuItem::with_id(app, "status", status_label(status), false, None::<&str>)?;
+    let session_label = if status.session.is_some() {
+        "Stop Session"
+    } else {
+        "Start Session"
+    };
+    let session_item = MenuItem::with_id(app, "toggle-session", session_label, true, None::<&str>)?;
+    let suggestions_label = if status.suggestions {
+        "Pause Suggestions"
+    } else {
+        "Resume Suggestions"
+    };
+    let suggestions_item = MenuItem::with_id(
+        app,
+        "toggle-suggestions",
+        suggestions_label,
+        true,
+        None::<&str>,
+    )?;
+
+    let recent_items = status
+        .recent
+        .iter()
+        .map(|(id, title)| {
+            MenuItem::with_id(
+                app,
+                format!("{}{}", OPEN_SESSION, id),
+                title,
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let recent_refs: Vec<&dyn IsMenuItem<Wry>> = recent_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let recent = Submenu::with_items(
+        app,
+        "Recent Sessions",
+        !recent_refs.is_empty(),
+        &recent_refs,
+    )?;
+
+    let history_item = MenuItem::with_id(app, "history", "Session History…", true, None::<&str>)?;
+    let settings_item = MenuItem::with_id(app, "settings", "Settings…", true, None::<&str>)?;
+    let show_item = MenuItem::with_id(app, "show", "Show VenkyAI", true, None::<&str>)?;
+    let hide_item = MenuItem::with_id(app, "hide", "Hide VenkyAI", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &status_item,
+            &PredefinedMenuItem::separator(app)?,
+            &session_item,
+            &suggestions_item,
+            &recent,
+            &history_item,
+            &settings_item,
+            &PredefinedMenuItem::separator(app)?,
+            &show_item,
+            &hide_item,
+            &quit_item,
+        ],
+    )
+}
+
+/// The app icon, with a red dot in the corner while audio is being captured
+fn icon(recording: bool) -> Image<'static> {
+    let icon_png_bytes = include_bytes!("../icons/32x32.png");
+    let mut rgba = image::load_from_memory(icon_png_bytes)
+        .expect("Failed to decode icon PNG")
+        .to_rgba8();
+    let (w, h) = rgba.dimensions();
+    if recording {
+        let r = w.min(h) as f32 * 0.22;
+        let (cx, cy) = (w as f32 - r - 1.0, h as f32 - r - 1.0);
+        for (x, y, pixel) in rgba.enumerate_pixels_mut() {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            if dx * dx + dy * dy <= r * r {
+                *pixel = image::Rgba([230, 40, 40, 255]);
+            }
+        }
+    }
+    Image::new_owned(rgba.into_raw(), w, h)
+}
+
+fn show_overlay(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("overlay") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn on_menu_event(app: &AppHandle, id: &str) {
+    let result = match id {
+        "show" => {
+            show_overlay(app);
+            Ok(())
+        }
+        "hide" => {
+            if let Some(window) = app.get_webview_window("overlay") {
+                let _ = window.hide();
+            }
+            Ok(())
+        }
+        "quit" => {
+            app.exit(0);
+            Ok(())
+        }
+        "history" => crate::windows::open(app, AppWindow::History).map(|_| ()),
+        "settings" => crate::windows::open(app, AppWindow::Settings).map(|_| ()),
+        "toggle-session" => crate::hotkeys::toggle_session(app),
+        "toggle-suggestions" => {
+            let enabled = app.state::<ConfigState>().lock().live_suggestions;
+            crate::ai::live_engine::set_live_suggestions(app, !enabled)
+        }
+        _ => match id.strip_prefix(OPEN_SESSION) {
+            Some(session_id) => crate::windows::open(app, AppWindow::History).map(|window| {
+                let _ = window.emit("open-session", session_id);
+            }),
+            None => Ok(()),
+        },
+    };
+    if let Err(e) = result {
+        log::warn!("Tray action {} failed: {}", id, e);
+    }
+    refresh(app);
+}
+
+/// Rebuild the menu and icon from the current state
+fn apply(app: &AppHandle, status: &TrayStatus) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app, status) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                log::error!("Failed to update tray menu: {}", e);
+            }
+        }
+        Err(e) => log::error!("Failed to build tray menu: {}", e),
+    }
+    let _ = tray.set_icon(Some(icon(status.recording)));
+    let _ = tray.set_tooltip(Some(tooltip(status)));
+}
+
+fn refresh(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move { apply(&app, &status(&app).await) });
+}
+
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let initial = tauri::async_runtime::block_on(status(app));
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(icon(initial.recording))
+        .tooltip(tooltip(&initial))
+        .menu(&build_menu(app, &initial)?)
+        .on_menu_event(|app, event| on_menu_event(app, event.id.as_ref()))
+        .build(app)?;
+
+    // Recording state, session time and recent sessions change without a menu click
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last = initial;
+        loop {
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+            let current = status(&app).await;
+            if current != last {
+                apply(&app, &current);
+                last = current;
+            }
+        }
+    });
+    Ok(())
+}
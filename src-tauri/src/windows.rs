@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::error::{CommandResult, VenkyError};
+
+/// Full-size windows opened on demand, so the overlay only carries the live view
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AppWindow {
+    History,
+    Settings,
+}
+
+impl AppWindow {
+    pub const ALL: [AppWindow; 2] = [AppWindow::History, AppWindow::Settings];
+
+    /// Window label; the frontend reads it to pick which view to render
+    pub fn label(self) -> &'static str {
+        match self {
+            AppWindow::History => "history",
+            AppWindow::Settings => "settings",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            AppWindow::History => "VenkyAI — Session History",
+            AppWindow::Settings => "VenkyAI — Settings",
+        }
+    }
+
+    fn size(self) -> (f64, f64) {
+        match self {
+            AppWindow::History => (1100.0, 750.0),
+            AppWindow::Settings => (760.0, 680.0),
+        }
+    }
+}
+
+/// Show `kind`, creating its window if it isn't open yet. Each kind has at most one window.
+pub fn open(app: &AppHandle, kind: AppWindow) -> Result<WebviewWindow, VenkyError> {
+    if let Some(window) = app.get_webview_window(kind.label()) {
+        let _ = window.unminimize();
+        window
+            .show()
+            .map_err(|e| VenkyError::Other(e.to_string()))?;
+        window
+            .set_focus()
+            .map_err(|e| VenkyError::Other(e.to_string()))?;
+        return Ok(window);
+    }
+
+    let (width, height) = kind.size();
+    let url = WebviewUrl::App(format!("index.html?window={}", kind.label()).into());
+    let window = WebviewWindowBuilder::new(app, kind.label(), url)
+        .title(kind.title())
+        .inner_size(width, height)
+        .min_inner_size(480.0, 400.0)
+        .center()
+        .build()
+        .map_err(|e| VenkyError::Other(format!("Failed to open {} window: {}", kind.label(), e)))?;
+    if crate::stealth::is_active(app) {
+        let _ = window.set_content_protected(true);
+    }
+
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            let _ = handle.emit("window-closed", kind);
+        }
+    });
+    let _ = app.emit("window-opened", kind);
+    Ok(window)
+}
+
+/// Open the session history or settings window, or bring it to the front
+#[tauri::command]
+pub fn open_window(app: AppHandle, kind: AppWindow) -> CommandResult<()> {
+    open(&app, kind).map(|_| ())
+}
+
+#[tauri::command]
+pub fn close_window(app: AppHandle, kind: AppWindow) -> CommandResult<()> {
+    if let Some(window) = app.get_webview_window(kind.label()) {
+        window.close().map_err(|e| {
+            VenkyError::Other(format!("Failed to close {} window: {}", kind.label(), e))
+        })?;
+    }
+    Ok(())
+}
+
+/// Which of the history and settings windows are open
+#[tauri::command]
+pub fn list_open_windows(app: AppHandle) -> Vec<AppWindow> {
+    AppWindow::ALL
+        .into_iter()
+        .filter(|kind| app.get_webview_window(kind.label()).is_some())
+        .collect()
+}